@@ -0,0 +1,245 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(8))]
+struct BaseA {
+    vftable: *const crate::base_vftables::BaseAVftable,
+    pub a: u64,
+}
+fn _BaseA_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], BaseA>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl BaseA {
+    pub fn vftable(&self) -> *const crate::base_vftables::BaseAVftable {
+        self.vftable as *const crate::base_vftables::BaseAVftable
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+    pub unsafe fn get_a(&self) -> u32 {
+        let f = std::ptr::addr_of!((* self.vftable()).get_a).read();
+        f(self as *const Self as _)
+    }
+}
+impl std::convert::AsRef<BaseA> for BaseA {
+    fn as_ref(&self) -> &BaseA {
+        self
+    }
+}
+impl std::convert::AsMut<BaseA> for BaseA {
+    fn as_mut(&mut self) -> &mut BaseA {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct BaseAVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::base_vftables::BaseA),
+    pub get_a: unsafe extern "C" fn(this: *const crate::base_vftables::BaseA) -> u32,
+}
+fn _BaseAVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], BaseAVftable>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl BaseAVftable {}
+impl std::convert::AsRef<BaseAVftable> for BaseAVftable {
+    fn as_ref(&self) -> &BaseAVftable {
+        self
+    }
+}
+impl std::convert::AsMut<BaseAVftable> for BaseAVftable {
+    fn as_mut(&mut self) -> &mut BaseAVftable {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct BaseB {
+    vftable: *const crate::base_vftables::BaseBVftable,
+    pub b: u64,
+}
+fn _BaseB_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], BaseB>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl BaseB {
+    pub fn vftable(&self) -> *const crate::base_vftables::BaseBVftable {
+        self.vftable as *const crate::base_vftables::BaseBVftable
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+    /// Returns the object that owns this one
+    pub unsafe fn owner(&self) -> *mut crate::base_vftables::BaseB {
+        let f = std::ptr::addr_of!((* self.vftable()).owner).read();
+        f(self as *const Self as _)
+    }
+}
+impl std::convert::AsRef<BaseB> for BaseB {
+    fn as_ref(&self) -> &BaseB {
+        self
+    }
+}
+impl std::convert::AsMut<BaseB> for BaseB {
+    fn as_mut(&mut self) -> &mut BaseB {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct BaseBVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::base_vftables::BaseB),
+    /// Returns the object that owns this one
+    pub owner: unsafe extern "C" fn(
+        this: *const crate::base_vftables::BaseB,
+    ) -> *mut crate::base_vftables::BaseB,
+}
+fn _BaseBVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], BaseBVftable>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl BaseBVftable {}
+impl std::convert::AsRef<BaseBVftable> for BaseBVftable {
+    fn as_ref(&self) -> &BaseBVftable {
+        self
+    }
+}
+impl std::convert::AsMut<BaseBVftable> for BaseBVftable {
+    fn as_mut(&mut self) -> &mut BaseBVftable {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct Derived {
+    pub base_a: crate::base_vftables::BaseA,
+    pub base_b: crate::base_vftables::BaseB,
+    pub derived: u64,
+}
+fn _Derived_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x28], Derived>([0u8; 0x28]);
+    }
+    unreachable!()
+}
+impl Derived {
+    pub fn vftable(&self) -> *const crate::base_vftables::DerivedVftable {
+        self.base_a.vftable() as *const crate::base_vftables::DerivedVftable
+    }
+    pub fn base_b_vftable(&self) -> *const crate::base_vftables::DerivedBaseBVftable {
+        self.base_b.vftable() as *const crate::base_vftables::DerivedBaseBVftable
+    }
+    pub unsafe fn base_b_destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.base_b_vftable()).destructor).read();
+        f(std::ptr::addr_of_mut!(self.base_b) as _)
+    }
+    /// Returns the `Derived` that owns this one
+    pub unsafe fn owner(&self) -> *mut crate::base_vftables::Derived {
+        let f = std::ptr::addr_of!((* self.base_b_vftable()).owner).read();
+        f(std::ptr::addr_of!(self.base_b) as _)
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+    pub unsafe fn get_a(&self) -> u32 {
+        let f = std::ptr::addr_of!((* self.vftable()).get_a).read();
+        f(self as *const Self as _)
+    }
+    pub unsafe fn derived_vfunc(&mut self, value: u32) {
+        let f = std::ptr::addr_of!((* self.vftable()).derived_vfunc).read();
+        f(self as *mut Self as _, value)
+    }
+}
+impl std::convert::AsRef<crate::base_vftables::BaseA> for Derived {
+    fn as_ref(&self) -> &crate::base_vftables::BaseA {
+        &self.base_a
+    }
+}
+impl std::convert::AsMut<crate::base_vftables::BaseA> for Derived {
+    fn as_mut(&mut self) -> &mut crate::base_vftables::BaseA {
+        &mut self.base_a
+    }
+}
+impl std::convert::AsRef<crate::base_vftables::BaseB> for Derived {
+    fn as_ref(&self) -> &crate::base_vftables::BaseB {
+        &self.base_b
+    }
+}
+impl std::convert::AsMut<crate::base_vftables::BaseB> for Derived {
+    fn as_mut(&mut self) -> &mut crate::base_vftables::BaseB {
+        &mut self.base_b
+    }
+}
+impl std::convert::AsRef<Derived> for Derived {
+    fn as_ref(&self) -> &Derived {
+        self
+    }
+}
+impl std::convert::AsMut<Derived> for Derived {
+    fn as_mut(&mut self) -> &mut Derived {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct DerivedBaseBVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::base_vftables::BaseB),
+    /// Returns the `Derived` that owns this one
+    pub owner: unsafe extern "C" fn(
+        this: *const crate::base_vftables::BaseB,
+    ) -> *mut crate::base_vftables::Derived,
+}
+fn _DerivedBaseBVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], DerivedBaseBVftable>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl DerivedBaseBVftable {}
+impl std::convert::AsRef<DerivedBaseBVftable> for DerivedBaseBVftable {
+    fn as_ref(&self) -> &DerivedBaseBVftable {
+        self
+    }
+}
+impl std::convert::AsMut<DerivedBaseBVftable> for DerivedBaseBVftable {
+    fn as_mut(&mut self) -> &mut DerivedBaseBVftable {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct DerivedVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::base_vftables::Derived),
+    pub get_a: unsafe extern "C" fn(this: *const crate::base_vftables::Derived) -> u32,
+    pub derived_vfunc: unsafe extern "C" fn(
+        this: *mut crate::base_vftables::Derived,
+        value: u32,
+    ),
+}
+fn _DerivedVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x18], DerivedVftable>([0u8; 0x18]);
+    }
+    unreachable!()
+}
+impl DerivedVftable {}
+impl std::convert::AsRef<DerivedVftable> for DerivedVftable {
+    fn as_ref(&self) -> &DerivedVftable {
+        self
+    }
+}
+impl std::convert::AsMut<DerivedVftable> for DerivedVftable {
+    fn as_mut(&mut self) -> &mut DerivedVftable {
+        self
+    }
+}
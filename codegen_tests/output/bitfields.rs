@@ -0,0 +1,69 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[derive(Copy, Clone, Default)]
+#[repr(C, align(4))]
+pub struct RenderFlags {
+    _field_0: u32,
+    _field_4: u8,
+    _field_5: [u8; 3],
+}
+fn _RenderFlags_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], RenderFlags>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl RenderFlags {
+    /// Whether the object is drawn
+    pub fn visible(&self) -> u32 {
+        (self._field_0 & 0x1) as u32
+    }
+    pub fn set_visible(&mut self, value: u32) {
+        self._field_0 = (self._field_0 & 0xFFFFFFFE) | ((value as u32) & 0x1);
+    }
+    pub fn layer(&self) -> u32 {
+        ((self._field_0 >> 1) & 0x1F) as u32
+    }
+    pub fn set_layer(&mut self, value: u32) {
+        self._field_0 = (self._field_0 & 0xFFFFFFC1) | (((value as u32) & 0x1F) << 1);
+    }
+    pub fn depth_bias(&self) -> i32 {
+        (((self._field_0 << 20) as i32) >> 28) as i32
+    }
+    pub fn set_depth_bias(&mut self, value: i32) {
+        self._field_0 = (self._field_0 & 0xFFFFF0FF) | (((value as u32) & 0xF) << 8);
+    }
+    pub fn material_id(&self) -> u32 {
+        ((self._field_0 >> 12) & 0xFFFFF) as u32
+    }
+    pub fn set_material_id(&mut self, value: u32) {
+        self._field_0 = (self._field_0 & 0xFFF) | (((value as u32) & 0xFFFFF) << 12);
+    }
+    pub fn cast_shadows(&self) -> bool {
+        self._field_4 & 0x1 != 0
+    }
+    pub fn set_cast_shadows(&mut self, value: bool) {
+        self._field_4 = (self._field_4 & 0xFE) | ((value as u8) & 0x1);
+    }
+    pub fn priority(&self) -> u8 {
+        ((self._field_4 >> 1) & 0x7F) as u8
+    }
+    pub fn set_priority(&mut self, value: u8) {
+        self._field_4 = (self._field_4 & 0x1) | (((value as u8) & 0x7F) << 1);
+    }
+}
+impl std::convert::AsRef<RenderFlags> for RenderFlags {
+    fn as_ref(&self) -> &RenderFlags {
+        self
+    }
+}
+impl std::convert::AsMut<RenderFlags> for RenderFlags {
+    fn as_mut(&mut self) -> &mut RenderFlags {
+        self
+    }
+}
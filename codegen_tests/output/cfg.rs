@@ -0,0 +1,92 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(8))]
+pub struct Player {
+    vftable: *const crate::cfg::PlayerVftable,
+    pub health: u32,
+    _field_c: [u8; 4],
+    pub name: *const u8,
+}
+fn _Player_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x18], Player>([0u8; 0x18]);
+    }
+    unreachable!()
+}
+impl Player {
+    pub fn vftable(&self) -> *const crate::cfg::PlayerVftable {
+        self.vftable as *const crate::cfg::PlayerVftable
+    }
+    pub unsafe fn respawn(&mut self) {
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
+            0x1100 as usize,
+        );
+        f(self as *mut Self as _)
+    }
+    pub unsafe fn kill(&mut self) {
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
+            0x140001000 as usize,
+        );
+        f(self as *mut Self as _)
+    }
+    pub unsafe fn update(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).update).read();
+        f(self as *mut Self as _)
+    }
+    pub unsafe fn destroy(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destroy).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<Player> for Player {
+    fn as_ref(&self) -> &Player {
+        self
+    }
+}
+impl std::convert::AsMut<Player> for Player {
+    fn as_mut(&mut self) -> &mut Player {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct PlayerVftable {
+    pub update: unsafe extern "C" fn(this: *mut crate::cfg::Player),
+    pub destroy: unsafe extern "C" fn(this: *mut crate::cfg::Player),
+}
+fn _PlayerVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], PlayerVftable>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl PlayerVftable {}
+impl std::convert::AsRef<PlayerVftable> for PlayerVftable {
+    fn as_ref(&self) -> &PlayerVftable {
+        self
+    }
+}
+impl std::convert::AsMut<PlayerVftable> for PlayerVftable {
+    fn as_mut(&mut self) -> &mut PlayerVftable {
+        self
+    }
+}
+#[repr(u32)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Team {
+    Red = 0,
+    Blue = 1,
+}
+fn _Team_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], Team>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+pub unsafe fn get_player_count() -> &'static mut u32 {
+    unsafe { &mut *(0x140001337 as *mut u32) }
+}
@@ -0,0 +1,67 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(8))]
+pub struct Header {
+    pub magic: u32,
+    pub count: u32,
+}
+fn _Header_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], Header>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl Header {}
+impl std::convert::AsRef<Header> for Header {
+    fn as_ref(&self) -> &Header {
+        self
+    }
+}
+impl std::convert::AsMut<Header> for Header {
+    fn as_mut(&mut self) -> &mut Header {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct Packet {
+    pub header: crate::constant_expressions::Header,
+    _field_8: [u8; 4],
+    pub payload: [u8; 12],
+    _field_18: [u8; 16],
+}
+fn _Packet_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x28], Packet>([0u8; 0x28]);
+    }
+    unreachable!()
+}
+impl Packet {}
+impl std::convert::AsRef<Packet> for Packet {
+    fn as_ref(&self) -> &Packet {
+        self
+    }
+}
+impl std::convert::AsMut<Packet> for Packet {
+    fn as_mut(&mut self) -> &mut Packet {
+        self
+    }
+}
+#[repr(u32)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum PacketFlags {
+    None = 0,
+    Compressed = 1,
+    Encrypted = 2,
+    All = 3,
+}
+fn _PacketFlags_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], PacketFlags>([0u8; 0x4]);
+    }
+    unreachable!()
+}
@@ -0,0 +1,54 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+pub const INVALID_ID: i32 = -1;
+/// The maximum number of players in a lobby
+pub const MAX_PLAYERS: u32 = 64;
+const PLAYERS_PER_TEAM: u32 = 32;
+pub const TEAMS: u8 = 2;
+#[repr(C, align(4))]
+pub struct Lobby {
+    pub teams: [crate::constants::Team; 2],
+}
+fn _Lobby_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x100], Lobby>([0u8; 0x100]);
+    }
+    unreachable!()
+}
+impl Lobby {}
+impl std::convert::AsRef<Lobby> for Lobby {
+    fn as_ref(&self) -> &Lobby {
+        self
+    }
+}
+impl std::convert::AsMut<Lobby> for Lobby {
+    fn as_mut(&mut self) -> &mut Lobby {
+        self
+    }
+}
+#[repr(C, align(4))]
+pub struct Team {
+    pub players: [u32; 32],
+}
+fn _Team_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x80], Team>([0u8; 0x80]);
+    }
+    unreachable!()
+}
+impl Team {}
+impl std::convert::AsRef<Team> for Team {
+    fn as_ref(&self) -> &Team {
+        self
+    }
+}
+impl std::convert::AsMut<Team> for Team {
+    fn as_mut(&mut self) -> &mut Team {
+        self
+    }
+}
@@ -0,0 +1,390 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(4))]
+struct BaseA_0 {
+    pub field1: i32,
+}
+fn _BaseA_0_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], BaseA_0>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl BaseA_0 {}
+impl std::convert::AsRef<BaseA_0> for BaseA_0 {
+    fn as_ref(&self) -> &BaseA_0 {
+        self
+    }
+}
+impl std::convert::AsMut<BaseA_0> for BaseA_0 {
+    fn as_mut(&mut self) -> &mut BaseA_0 {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct BaseB_1 {
+    vftable: *const crate::cpp_layouts::BaseB_1Vftable,
+    pub field2: i32,
+    _field_c: [u8; 4],
+}
+fn _BaseB_1_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], BaseB_1>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl BaseB_1 {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::BaseB_1Vftable {
+        self.vftable as *const crate::cpp_layouts::BaseB_1Vftable
+    }
+    pub unsafe fn func2(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).func2).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<BaseB_1> for BaseB_1 {
+    fn as_ref(&self) -> &BaseB_1 {
+        self
+    }
+}
+impl std::convert::AsMut<BaseB_1> for BaseB_1 {
+    fn as_mut(&mut self) -> &mut BaseB_1 {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct BaseB_1Vftable {
+    pub func2: unsafe extern "C" fn(this: *mut crate::cpp_layouts::BaseB_1),
+}
+fn _BaseB_1Vftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], BaseB_1Vftable>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl BaseB_1Vftable {}
+impl std::convert::AsRef<BaseB_1Vftable> for BaseB_1Vftable {
+    fn as_ref(&self) -> &BaseB_1Vftable {
+        self
+    }
+}
+impl std::convert::AsMut<BaseB_1Vftable> for BaseB_1Vftable {
+    fn as_mut(&mut self) -> &mut BaseB_1Vftable {
+        self
+    }
+}
+#[repr(C, align(8))]
+/// MSVC lays out `BaseB_1` first, as it has a vftable.
+struct Derived_A0_B1_0 {
+    pub base_b: crate::cpp_layouts::BaseB_1,
+    pub base_a: crate::cpp_layouts::BaseA_0,
+    pub derived_field: i32,
+}
+fn _Derived_A0_B1_0_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x18], Derived_A0_B1_0>([0u8; 0x18]);
+    }
+    unreachable!()
+}
+impl Derived_A0_B1_0 {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::BaseB_1Vftable {
+        self.base_b.vftable() as *const crate::cpp_layouts::BaseB_1Vftable
+    }
+    pub unsafe fn func2(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).func2).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<crate::cpp_layouts::BaseB_1> for Derived_A0_B1_0 {
+    fn as_ref(&self) -> &crate::cpp_layouts::BaseB_1 {
+        &self.base_b
+    }
+}
+impl std::convert::AsMut<crate::cpp_layouts::BaseB_1> for Derived_A0_B1_0 {
+    fn as_mut(&mut self) -> &mut crate::cpp_layouts::BaseB_1 {
+        &mut self.base_b
+    }
+}
+impl std::convert::AsRef<crate::cpp_layouts::BaseA_0> for Derived_A0_B1_0 {
+    fn as_ref(&self) -> &crate::cpp_layouts::BaseA_0 {
+        &self.base_a
+    }
+}
+impl std::convert::AsMut<crate::cpp_layouts::BaseA_0> for Derived_A0_B1_0 {
+    fn as_mut(&mut self) -> &mut crate::cpp_layouts::BaseA_0 {
+        &mut self.base_a
+    }
+}
+impl std::convert::AsRef<Derived_A0_B1_0> for Derived_A0_B1_0 {
+    fn as_ref(&self) -> &Derived_A0_B1_0 {
+        self
+    }
+}
+impl std::convert::AsMut<Derived_A0_B1_0> for Derived_A0_B1_0 {
+    fn as_mut(&mut self) -> &mut Derived_A0_B1_0 {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct Leaf {
+    pub middle: crate::cpp_layouts::MiddleUnpadded,
+    pub leaf: u8,
+    _field_e: [u8; 2],
+}
+fn _Leaf_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], Leaf>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl Leaf {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::RootVftable {
+        self.middle.vftable() as *const crate::cpp_layouts::RootVftable
+    }
+    pub unsafe fn describe(&self) -> u32 {
+        self.middle.describe()
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<crate::cpp_layouts::MiddleUnpadded> for Leaf {
+    fn as_ref(&self) -> &crate::cpp_layouts::MiddleUnpadded {
+        &self.middle
+    }
+}
+impl std::convert::AsMut<crate::cpp_layouts::MiddleUnpadded> for Leaf {
+    fn as_mut(&mut self) -> &mut crate::cpp_layouts::MiddleUnpadded {
+        &mut self.middle
+    }
+}
+impl std::convert::AsRef<Leaf> for Leaf {
+    fn as_ref(&self) -> &Leaf {
+        self
+    }
+}
+impl std::convert::AsMut<Leaf> for Leaf {
+    fn as_mut(&mut self) -> &mut Leaf {
+        self
+    }
+}
+#[repr(C, packed)]
+/// `Leaf` without its tail padding, as embedded in the types that inherit from it
+struct LeafUnpadded {
+    pub middle: crate::cpp_layouts::MiddleUnpadded,
+    pub leaf: u8,
+}
+fn _LeafUnpadded_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0xE], LeafUnpadded>([0u8; 0xE]);
+    }
+    unreachable!()
+}
+impl LeafUnpadded {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::RootVftable {
+        unsafe {
+            (*std::ptr::addr_of!(self.middle)).vftable()
+                as *const crate::cpp_layouts::RootVftable
+        }
+    }
+    pub unsafe fn describe(&self) -> u32 {
+        (*std::ptr::addr_of!(self.middle)).describe()
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<LeafUnpadded> for LeafUnpadded {
+    fn as_ref(&self) -> &LeafUnpadded {
+        self
+    }
+}
+impl std::convert::AsMut<LeafUnpadded> for LeafUnpadded {
+    fn as_mut(&mut self) -> &mut LeafUnpadded {
+        self
+    }
+}
+#[repr(C, align(8))]
+/// `flags` is placed in the tail padding of `Root`.
+struct Middle {
+    pub root: crate::cpp_layouts::RootUnpadded,
+    pub flags: u8,
+    _field_d: [u8; 3],
+}
+fn _Middle_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], Middle>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl Middle {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::RootVftable {
+        self.root.vftable() as *const crate::cpp_layouts::RootVftable
+    }
+    pub unsafe fn describe(&self) -> u32 {
+        self.root.describe()
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<crate::cpp_layouts::RootUnpadded> for Middle {
+    fn as_ref(&self) -> &crate::cpp_layouts::RootUnpadded {
+        &self.root
+    }
+}
+impl std::convert::AsMut<crate::cpp_layouts::RootUnpadded> for Middle {
+    fn as_mut(&mut self) -> &mut crate::cpp_layouts::RootUnpadded {
+        &mut self.root
+    }
+}
+impl std::convert::AsRef<Middle> for Middle {
+    fn as_ref(&self) -> &Middle {
+        self
+    }
+}
+impl std::convert::AsMut<Middle> for Middle {
+    fn as_mut(&mut self) -> &mut Middle {
+        self
+    }
+}
+#[repr(C, packed)]
+/// `Middle` without its tail padding, as embedded in the types that inherit from it
+struct MiddleUnpadded {
+    pub root: crate::cpp_layouts::RootUnpadded,
+    pub flags: u8,
+}
+fn _MiddleUnpadded_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0xD], MiddleUnpadded>([0u8; 0xD]);
+    }
+    unreachable!()
+}
+impl MiddleUnpadded {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::RootVftable {
+        unsafe {
+            (*std::ptr::addr_of!(self.root)).vftable()
+                as *const crate::cpp_layouts::RootVftable
+        }
+    }
+    pub unsafe fn describe(&self) -> u32 {
+        (*std::ptr::addr_of!(self.root)).describe()
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<MiddleUnpadded> for MiddleUnpadded {
+    fn as_ref(&self) -> &MiddleUnpadded {
+        self
+    }
+}
+impl std::convert::AsMut<MiddleUnpadded> for MiddleUnpadded {
+    fn as_mut(&mut self) -> &mut MiddleUnpadded {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct Root {
+    vftable: *const crate::cpp_layouts::RootVftable,
+    pub id: u32,
+    _field_c: [u8; 4],
+}
+fn _Root_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], Root>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl Root {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::RootVftable {
+        self.vftable as *const crate::cpp_layouts::RootVftable
+    }
+    pub unsafe fn describe(&self) -> u32 {
+        let f: unsafe extern "C" fn(this: *const Self) -> u32 = ::std::mem::transmute(
+            0x123 as usize,
+        );
+        f(self as *const Self as _)
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<Root> for Root {
+    fn as_ref(&self) -> &Root {
+        self
+    }
+}
+impl std::convert::AsMut<Root> for Root {
+    fn as_mut(&mut self) -> &mut Root {
+        self
+    }
+}
+#[repr(C, packed)]
+/// `Root` without its tail padding, as embedded in the types that inherit from it
+struct RootUnpadded {
+    vftable: *const crate::cpp_layouts::RootVftable,
+    pub id: u32,
+}
+fn _RootUnpadded_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0xC], RootUnpadded>([0u8; 0xC]);
+    }
+    unreachable!()
+}
+impl RootUnpadded {
+    pub fn vftable(&self) -> *const crate::cpp_layouts::RootVftable {
+        self.vftable as *const crate::cpp_layouts::RootVftable
+    }
+    pub unsafe fn describe(&self) -> u32 {
+        let f: unsafe extern "C" fn(this: *const Self) -> u32 = ::std::mem::transmute(
+            0x123 as usize,
+        );
+        f(self as *const Self as _)
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<RootUnpadded> for RootUnpadded {
+    fn as_ref(&self) -> &RootUnpadded {
+        self
+    }
+}
+impl std::convert::AsMut<RootUnpadded> for RootUnpadded {
+    fn as_mut(&mut self) -> &mut RootUnpadded {
+        self
+    }
+}
+#[repr(C, align(8))]
+struct RootVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::cpp_layouts::Root),
+}
+fn _RootVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], RootVftable>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl RootVftable {}
+impl std::convert::AsRef<RootVftable> for RootVftable {
+    fn as_ref(&self) -> &RootVftable {
+        self
+    }
+}
+impl std::convert::AsMut<RootVftable> for RootVftable {
+    fn as_mut(&mut self) -> &mut RootVftable {
+        self
+    }
+}
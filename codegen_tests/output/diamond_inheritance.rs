@@ -49,7 +49,7 @@ impl BaseA {
         self.base.vftable() as *const crate::diamond_inheritance::BaseAVftable
     }
     pub unsafe fn associated(&mut self) {
-        let f: unsafe extern "thiscall" fn(this: *mut Self) = ::std::mem::transmute(
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
             0x123 as usize,
         );
         f(self as *mut Self as _)
@@ -81,9 +81,7 @@ impl std::convert::AsMut<BaseA> for BaseA {
 }
 #[repr(C, align(8))]
 struct BaseAVftable {
-    pub destructor: unsafe extern "thiscall" fn(
-        this: *mut crate::diamond_inheritance::BaseA,
-    ),
+    pub destructor: unsafe extern "C" fn(this: *mut crate::diamond_inheritance::BaseA),
 }
 fn _BaseAVftable_size_check() {
     unsafe {
@@ -117,7 +115,7 @@ impl BaseB {
         self.base.vftable() as *const crate::diamond_inheritance::BaseBVftable
     }
     pub unsafe fn associated(&mut self) {
-        let f: unsafe extern "thiscall" fn(this: *mut Self) = ::std::mem::transmute(
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
             0x123 as usize,
         );
         f(self as *mut Self as _)
@@ -149,9 +147,7 @@ impl std::convert::AsMut<BaseB> for BaseB {
 }
 #[repr(C, align(8))]
 struct BaseBVftable {
-    pub destructor: unsafe extern "thiscall" fn(
-        this: *mut crate::diamond_inheritance::BaseB,
-    ),
+    pub destructor: unsafe extern "C" fn(this: *mut crate::diamond_inheritance::BaseB),
 }
 fn _BaseBVftable_size_check() {
     unsafe {
@@ -172,9 +168,7 @@ impl std::convert::AsMut<BaseBVftable> for BaseBVftable {
 }
 #[repr(C, align(8))]
 struct BaseVftable {
-    pub destructor: unsafe extern "thiscall" fn(
-        this: *mut crate::diamond_inheritance::Base,
-    ),
+    pub destructor: unsafe extern "C" fn(this: *mut crate::diamond_inheritance::Base),
 }
 fn _BaseVftable_size_check() {
     unsafe {
@@ -264,9 +258,7 @@ impl std::convert::AsMut<Derived> for Derived {
 }
 #[repr(C, align(8))]
 struct DerivedVftable {
-    pub destructor: unsafe extern "thiscall" fn(
-        this: *mut crate::diamond_inheritance::Derived,
-    ),
+    pub destructor: unsafe extern "C" fn(this: *mut crate::diamond_inheritance::Derived),
 }
 fn _DerivedVftable_size_check() {
     unsafe {
@@ -0,0 +1,76 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[derive(Default)]
+#[repr(C, align(8))]
+pub struct Callbacks {
+    pub on_tick: Option<unsafe extern "C" fn(delta: f32)>,
+}
+fn _Callbacks_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], Callbacks>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl Callbacks {}
+impl std::convert::AsRef<Callbacks> for Callbacks {
+    fn as_ref(&self) -> &Callbacks {
+        self
+    }
+}
+impl std::convert::AsMut<Callbacks> for Callbacks {
+    fn as_mut(&mut self) -> &mut Callbacks {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct Entity {
+    pub health: f32,
+    _field_4: [u8; 4],
+    pub on_damage: unsafe extern "C" fn(
+        _: *mut crate::function_pointers::Entity,
+        _: f32,
+    ),
+    pub on_death: Option<
+        unsafe extern "C" fn(
+            entity: *mut crate::function_pointers::Entity,
+            killer: *mut crate::function_pointers::Entity,
+        ) -> bool,
+    >,
+    pub callbacks: crate::function_pointers::Callbacks,
+}
+fn _Entity_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x20], Entity>([0u8; 0x20]);
+    }
+    unreachable!()
+}
+impl Entity {
+    pub unsafe fn set_damage_callback(
+        &mut self,
+        callback: unsafe extern "C" fn(_: *mut crate::function_pointers::Entity, _: f32),
+    ) {
+        let f: unsafe extern "C" fn(
+            this: *mut Self,
+            callback: unsafe extern "C" fn(
+                _: *mut crate::function_pointers::Entity,
+                _: f32,
+            ),
+        ) = ::std::mem::transmute(0x1000 as usize);
+        f(self as *mut Self as _, callback)
+    }
+}
+impl std::convert::AsRef<Entity> for Entity {
+    fn as_ref(&self) -> &Entity {
+        self
+    }
+}
+impl std::convert::AsMut<Entity> for Entity {
+    fn as_mut(&mut self) -> &mut Entity {
+        self
+    }
+}
@@ -0,0 +1,136 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(8))]
+/// A contiguous array of `T`s.
+pub struct Array<T> {
+    pub data: *mut T,
+    pub len: u32,
+    pub cap: u32,
+}
+impl<T> Array<T> {}
+impl<T> std::convert::AsRef<Array<T>> for Array<T> {
+    fn as_ref(&self) -> &Array<T> {
+        self
+    }
+}
+impl<T> std::convert::AsMut<Array<T>> for Array<T> {
+    fn as_mut(&mut self) -> &mut Array<T> {
+        self
+    }
+}
+fn _Array_mut_Item_size_check() {
+    unsafe {
+        ::std::mem::transmute::<
+            [u8; 0x10],
+            crate::generic_types::Array<*mut crate::generic_types::Item>,
+        >([0u8; 0x10]);
+    }
+    unreachable!()
+}
+fn _Array_u32_size_check() {
+    unsafe {
+        ::std::mem::transmute::<
+            [u8; 0x10],
+            crate::generic_types::Array<u32>,
+        >([0u8; 0x10]);
+    }
+    unreachable!()
+}
+#[repr(C, align(8))]
+pub struct Inventory {
+    pub items: crate::generic_types::Array<*mut crate::generic_types::Item>,
+    pub counts: crate::generic_types::Array<u32>,
+    pub ids: crate::generic_types::Pair_u32_u32,
+    pub owner: crate::generic_types::Pair_mut_Item_u64,
+}
+fn _Inventory_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x38], Inventory>([0u8; 0x38]);
+    }
+    unreachable!()
+}
+impl Inventory {}
+impl std::convert::AsRef<Inventory> for Inventory {
+    fn as_ref(&self) -> &Inventory {
+        self
+    }
+}
+impl std::convert::AsMut<Inventory> for Inventory {
+    fn as_mut(&mut self) -> &mut Inventory {
+        self
+    }
+}
+#[repr(C, align(4))]
+pub struct Item {
+    pub id: u32,
+}
+fn _Item_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], Item>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl Item {}
+impl std::convert::AsRef<Item> for Item {
+    fn as_ref(&self) -> &Item {
+        self
+    }
+}
+impl std::convert::AsMut<Item> for Item {
+    fn as_mut(&mut self) -> &mut Item {
+        self
+    }
+}
+#[repr(C, align(8))]
+#[allow(non_camel_case_types)]
+/// Each instantiation of this type is generated as its own type.
+pub struct Pair_mut_Item_u64 {
+    pub first: *mut crate::generic_types::Item,
+    pub second: u64,
+}
+fn _Pair_mut_Item_u64_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], Pair_mut_Item_u64>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl Pair_mut_Item_u64 {}
+impl std::convert::AsRef<Pair_mut_Item_u64> for Pair_mut_Item_u64 {
+    fn as_ref(&self) -> &Pair_mut_Item_u64 {
+        self
+    }
+}
+impl std::convert::AsMut<Pair_mut_Item_u64> for Pair_mut_Item_u64 {
+    fn as_mut(&mut self) -> &mut Pair_mut_Item_u64 {
+        self
+    }
+}
+#[repr(C, align(4))]
+#[allow(non_camel_case_types)]
+/// Each instantiation of this type is generated as its own type.
+pub struct Pair_u32_u32 {
+    pub first: u32,
+    pub second: u32,
+}
+fn _Pair_u32_u32_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], Pair_u32_u32>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl Pair_u32_u32 {}
+impl std::convert::AsRef<Pair_u32_u32> for Pair_u32_u32 {
+    fn as_ref(&self) -> &Pair_u32_u32 {
+        self
+    }
+}
+impl std::convert::AsMut<Pair_u32_u32> for Pair_u32_u32 {
+    fn as_mut(&mut self) -> &mut Pair_u32_u32 {
+        self
+    }
+}
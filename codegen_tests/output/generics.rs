@@ -0,0 +1,37 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C)]
+pub struct TArray<T> {
+    pub data: *mut T,
+    pub count: u32,
+    pub max: u32,
+}
+#[repr(C, align(8))]
+pub struct Entity {
+    pub children: crate::generics::TArray<*mut crate::generics::Entity>,
+    pub ids: crate::generics::TArray<u32>,
+    pub id: u32,
+    _field_24: [u8; 4],
+}
+fn _Entity_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x28], Entity>([0u8; 0x28]);
+    }
+    unreachable!()
+}
+impl Entity {}
+impl std::convert::AsRef<Entity> for Entity {
+    fn as_ref(&self) -> &Entity {
+        self
+    }
+}
+impl std::convert::AsMut<Entity> for Entity {
+    fn as_mut(&mut self) -> &mut Entity {
+        self
+    }
+}
@@ -0,0 +1,54 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(4))]
+pub struct World {
+    pub tick: u32,
+}
+fn _World_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], World>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl World {
+    pub unsafe fn get() -> Option<&'static mut Self> {
+        unsafe {
+            let ptr: *mut Self = *((crate::images::base("game.exe") + 0x200000)
+                as *mut *mut Self);
+            ptr.as_mut()
+        }
+    }
+}
+impl World {
+    pub unsafe fn update(&mut self, delta: f32) {
+        let f: unsafe extern "C" fn(this: *mut Self, delta: f32) = ::std::mem::transmute(
+            (crate::images::base("game.exe")
+                + crate::versions::address([0x1000, 0x1100])) as usize,
+        );
+        f(self as *mut Self as _, delta)
+    }
+    pub unsafe fn reset(&mut self) {
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
+            (crate::images::base("engine.dll") + 0x2000) as usize,
+        );
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<World> for World {
+    fn as_ref(&self) -> &World {
+        self
+    }
+}
+impl std::convert::AsMut<World> for World {
+    fn as_mut(&mut self) -> &mut World {
+        self
+    }
+}
+pub unsafe fn get_frame_count() -> &'static mut u64 {
+    unsafe { &mut *((crate::images::base("game.exe") + 0x300000) as *mut u64) }
+}
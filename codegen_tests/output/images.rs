@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+#![cfg_attr(any(), rustfmt::skip)]
+//! The images (executables and libraries) that addresses are relative to.
+//! Call [`set_base_resolver`] before using any item whose address is relative to an image.
+use std::sync::OnceLock;
+/// The name of every image, sorted
+pub const NAMES: [&str; 2] = ["engine.dll", "game.exe"];
+static RESOLVER: OnceLock<fn(&str) -> usize> = OnceLock::new();
+static BASES: [OnceLock<usize>; 2] = [const { OnceLock::new() }; 2];
+/// Sets the function that returns the address an image was loaded at, given its name.
+/// Each image's base is only resolved once. Returns false if a resolver was already set.
+pub fn set_base_resolver(resolver: fn(&str) -> usize) -> bool {
+    RESOLVER.set(resolver).is_ok()
+}
+#[doc(hidden)]
+pub fn base(name: &str) -> usize {
+    let index = NAMES
+        .binary_search(&name)
+        .unwrap_or_else(|_| panic!("there is no image named `{name}`"));
+    *BASES[index]
+        .get_or_init(|| {
+            let resolver = RESOLVER
+                .get()
+                .expect("no base resolver has been set; call `set_base_resolver` first");
+            resolver(name)
+        })
+}
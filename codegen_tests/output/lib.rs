@@ -1,5 +1,27 @@
+pub mod base_vftables;
+pub mod bitfields;
+pub mod cfg;
+pub mod chains;
+pub mod constant_expressions;
+pub mod constants;
+pub mod cpp_layouts;
 pub mod diamond_inheritance;
 pub mod doc_comments;
+pub mod function_pointers;
+pub mod generic_types;
+pub mod generics;
+pub mod image_relative_addresses;
+pub mod images;
 pub mod multiple_levels;
+pub mod nested_items;
+pub mod pointer_chains;
+pub mod reexports;
+pub mod signature_scanning;
+pub mod signatures;
 pub mod singleton;
-pub mod two_base_classes;
\ No newline at end of file
+pub mod two_base_classes;
+pub mod type_aliases;
+pub mod unions;
+pub mod versioned_addresses;
+pub mod versions;
+pub mod virtual_inheritance;
\ No newline at end of file
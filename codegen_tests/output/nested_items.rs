@@ -0,0 +1,91 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[derive(Copy, Clone)]
+#[repr(C, align(4))]
+pub struct Entity {
+    pub id: u32,
+    /// Where the entity is in the world
+    pub pos: crate::nested_items::EntityPos,
+    pub state: crate::nested_items::EntityState,
+}
+fn _Entity_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x14], Entity>([0u8; 0x14]);
+    }
+    unreachable!()
+}
+impl Entity {}
+impl std::convert::AsRef<Entity> for Entity {
+    fn as_ref(&self) -> &Entity {
+        self
+    }
+}
+impl std::convert::AsMut<Entity> for Entity {
+    fn as_mut(&mut self) -> &mut Entity {
+        self
+    }
+}
+#[derive(Copy, Clone)]
+#[repr(C, align(4))]
+pub struct EntityPos {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+fn _EntityPos_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0xC], EntityPos>([0u8; 0xC]);
+    }
+    unreachable!()
+}
+impl EntityPos {}
+impl std::convert::AsRef<EntityPos> for EntityPos {
+    fn as_ref(&self) -> &EntityPos {
+        self
+    }
+}
+impl std::convert::AsMut<EntityPos> for EntityPos {
+    fn as_mut(&mut self) -> &mut EntityPos {
+        self
+    }
+}
+#[derive(Copy, Clone)]
+#[repr(C, align(4))]
+pub union EntityState {
+    pub health: f32,
+    pub respawn: crate::nested_items::EntityStateRespawn,
+}
+fn _EntityState_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], EntityState>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+#[derive(Copy, Clone)]
+#[repr(C, align(2))]
+pub struct EntityStateRespawn {
+    pub ticks: u16,
+    pub lives: u16,
+}
+fn _EntityStateRespawn_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], EntityStateRespawn>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl EntityStateRespawn {}
+impl std::convert::AsRef<EntityStateRespawn> for EntityStateRespawn {
+    fn as_ref(&self) -> &EntityStateRespawn {
+        self
+    }
+}
+impl std::convert::AsMut<EntityStateRespawn> for EntityStateRespawn {
+    fn as_mut(&mut self) -> &mut EntityStateRespawn {
+        self
+    }
+}
@@ -0,0 +1,98 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(u32)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
+pub enum Difficulty {
+    Easy = 0,
+    Hard = 1,
+}
+fn _Difficulty_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], Difficulty>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl Difficulty {
+    pub unsafe fn get() -> Option<Self> {
+        unsafe {
+            crate::chains::walk(0x140210000, &[0x8])
+                .map(|address| *(address as *const Self))
+        }
+    }
+}
+#[repr(C, align(4))]
+/// Stored at the address itself, rather than behind a pointer as with `singleton(0x140310000)`
+pub struct Settings {
+    pub volume: f32,
+}
+fn _Settings_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], Settings>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl Settings {
+    pub unsafe fn get() -> Option<&'static mut Self> {
+        unsafe {
+            crate::chains::walk(0x140310000, &[])
+                .map(|address| &mut *(address as *mut Self))
+        }
+    }
+}
+impl Settings {}
+impl std::convert::AsRef<Settings> for Settings {
+    fn as_ref(&self) -> &Settings {
+        self
+    }
+}
+impl std::convert::AsMut<Settings> for Settings {
+    fn as_mut(&mut self) -> &mut Settings {
+        self
+    }
+}
+#[repr(C, align(4))]
+pub struct World {
+    pub tick: u32,
+}
+fn _World_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], World>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl World {
+    pub unsafe fn get() -> Option<&'static mut Self> {
+        unsafe {
+            crate::chains::walk(0x140200000, &[0x10, 0x48])
+                .map(|address| &mut *(address as *mut Self))
+        }
+    }
+}
+impl World {}
+impl std::convert::AsRef<World> for World {
+    fn as_ref(&self) -> &World {
+        self
+    }
+}
+impl std::convert::AsMut<World> for World {
+    fn as_mut(&mut self) -> &mut World {
+        self
+    }
+}
+pub unsafe fn get_entity_count() -> Option<&'static mut u32> {
+    unsafe {
+        crate::chains::walk(0x140300100, &[0x20, -0x8])
+            .map(|address| &mut *(address as *mut u32))
+    }
+}
+pub unsafe fn get_frame_count() -> Option<&'static mut u64> {
+    unsafe {
+        crate::chains::walk(0x140300000, &[0x10, 0x48, 0x8])
+            .map(|address| &mut *(address as *mut u64))
+    }
+}
@@ -0,0 +1,33 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+pub use crate::generic_types::Item;
+pub use crate::generic_types::Inventory as PlayerInventory;
+pub use crate::singleton::*;
+#[repr(C, align(8))]
+pub struct Player {
+    pub inventory: crate::generic_types::Inventory,
+    pub held: *mut crate::generic_types::Item,
+    pub test: *mut crate::singleton::TestType,
+}
+fn _Player_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x48], Player>([0u8; 0x48]);
+    }
+    unreachable!()
+}
+impl Player {}
+impl std::convert::AsRef<Player> for Player {
+    fn as_ref(&self) -> &Player {
+        self
+    }
+}
+impl std::convert::AsMut<Player> for Player {
+    fn as_mut(&mut self) -> &mut Player {
+        self
+    }
+}
@@ -0,0 +1,50 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(4))]
+pub struct World {
+    pub tick: u32,
+}
+fn _World_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], World>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl World {
+    pub unsafe fn get() -> Option<&'static mut Self> {
+        unsafe {
+            let ptr: *mut Self = *(crate::signatures::address(
+                "signature_scanning::World",
+            ) as *mut *mut Self);
+            ptr.as_mut()
+        }
+    }
+}
+impl World {
+    pub unsafe fn update(&mut self, delta: f32) {
+        let f: unsafe extern "C" fn(this: *mut Self, delta: f32) = ::std::mem::transmute(
+            crate::signatures::address("signature_scanning::World::update") as usize,
+        );
+        f(self as *mut Self as _, delta)
+    }
+}
+impl std::convert::AsRef<World> for World {
+    fn as_ref(&self) -> &World {
+        self
+    }
+}
+impl std::convert::AsMut<World> for World {
+    fn as_mut(&mut self) -> &mut World {
+        self
+    }
+}
+pub unsafe fn get_frame_count() -> &'static mut u64 {
+    unsafe {
+        &mut *(crate::signatures::address("signature_scanning::frame_count") as *mut u64)
+    }
+}
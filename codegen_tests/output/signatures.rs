@@ -0,0 +1,138 @@
+#![allow(dead_code)]
+#![cfg_attr(any(), rustfmt::skip)]
+//! Locates items by scanning memory for their signatures.
+//! Call [`scan`] before using any of them.
+use std::sync::OnceLock;
+/// A byte pattern that locates an item, and how to get the item's address from where it matches
+pub struct Signature {
+    /// The path of the item that the signature locates
+    pub name: &'static str,
+    /// The bytes to match, where `None` matches any byte
+    pub pattern: &'static [Option<u8>],
+    /// The offset from the start of the match to the item, or to its displacement
+    pub offset: usize,
+    /// Whether the item is located by a 32-bit displacement at the offset, relative to the
+    /// end of the displacement
+    pub rip_relative: bool,
+}
+impl Signature {
+    /// The address of the item in `memory`, which starts at the address `base`.
+    /// Only the first match of the pattern is used.
+    pub fn resolve(&self, memory: &[u8], base: usize) -> Option<usize> {
+        let start = memory
+            .windows(self.pattern.len())
+            .position(|window| {
+                window
+                    .iter()
+                    .zip(self.pattern)
+                    .all(|(byte, expected)| {
+                        expected.is_none() || *expected == Some(*byte)
+                    })
+            })?;
+        let offset = start.checked_add(self.offset)?;
+        if !self.rip_relative {
+            return base.checked_add(offset);
+        }
+        let displacement = memory.get(offset..offset.checked_add(4)?)?;
+        let displacement = i32::from_le_bytes(displacement.try_into().ok()?);
+        base.checked_add(offset + 4)?.checked_add_signed(displacement as isize)
+    }
+}
+/// Every signature, and the addresses they resolve to once memory has been scanned
+pub struct Scanner {
+    /// Sorted by name, so that they can be looked up
+    signatures: &'static [Signature],
+    addresses: OnceLock<Vec<Option<usize>>>,
+}
+impl Scanner {
+    pub const fn new(signatures: &'static [Signature]) -> Self {
+        Scanner {
+            signatures,
+            addresses: OnceLock::new(),
+        }
+    }
+    /// Resolves every signature in `memory`, which starts at the address `base`, and caches
+    /// their addresses. Only the first scan has an effect. Returns the names of the items whose
+    /// signatures were not found.
+    pub fn scan(&self, memory: &[u8], base: usize) -> Vec<&'static str> {
+        let addresses = self
+            .addresses
+            .get_or_init(|| {
+                self.signatures.iter().map(|s| s.resolve(memory, base)).collect()
+            });
+        self.signatures
+            .iter()
+            .zip(addresses)
+            .filter(|(_, address)| address.is_none())
+            .map(|(s, _)| s.name)
+            .collect()
+    }
+    /// The cached address of the item `name`.
+    ///
+    /// Panics if memory hasn't been scanned yet, or if the item's signature was not found.
+    pub fn address(&self, name: &str) -> usize {
+        let addresses = self
+            .addresses
+            .get()
+            .expect("signatures have not been scanned for; call `scan` first");
+        let index = self
+            .signatures
+            .binary_search_by_key(&name, |s| s.name)
+            .unwrap_or_else(|_| panic!("there is no signature for `{name}`"));
+        addresses[index]
+            .unwrap_or_else(|| panic!("the signature for `{name}` was not found"))
+    }
+}
+static SCANNER: Scanner = Scanner::new(
+    &[
+        Signature {
+            name: "signature_scanning::World",
+            pattern: &[
+                Some(0x48),
+                Some(0x8B),
+                Some(0x0D),
+                None,
+                None,
+                None,
+                None,
+                Some(0x48),
+                Some(0x85),
+                Some(0xC9),
+            ],
+            offset: 3,
+            rip_relative: true,
+        },
+        Signature {
+            name: "signature_scanning::World::update",
+            pattern: &[
+                Some(0x40),
+                Some(0x53),
+                Some(0x48),
+                Some(0x83),
+                Some(0xEC),
+                None,
+                Some(0x48),
+                Some(0x8B),
+                Some(0xD9),
+            ],
+            offset: 0,
+            rip_relative: false,
+        },
+        Signature {
+            name: "signature_scanning::frame_count",
+            pattern: &[Some(0x8B), Some(0x05), None, None, None, None, Some(0xC3)],
+            offset: 2,
+            rip_relative: true,
+        },
+    ],
+);
+/// Scans `memory`, which starts at the address `base`, for every signature, and caches
+/// the addresses of the items they locate. Only the first scan has an effect.
+/// Returns the names of the items whose signatures were not found.
+pub fn scan(memory: &[u8], base: usize) -> Vec<&'static str> {
+    SCANNER.scan(memory, base)
+}
+#[doc(hidden)]
+pub fn address(name: &str) -> usize {
+    SCANNER.address(name)
+}
@@ -18,7 +18,7 @@ fn _TestType_size_check() {
 impl TestType {
     pub unsafe fn get() -> Option<&'static mut Self> {
         unsafe {
-            let ptr: *mut Self = *(322401073usize as *mut *mut Self);
+            let ptr: *mut Self = *(0x13377331 as *mut *mut Self);
             ptr.as_mut()
         }
     }
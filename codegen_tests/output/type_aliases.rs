@@ -0,0 +1,74 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(4))]
+pub struct Entity {
+    pub handle: crate::type_aliases::EntityHandle,
+    _field_4: [u8; 12],
+    pub transform: crate::type_aliases::Matrix4,
+}
+fn _Entity_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x50], Entity>([0u8; 0x50]);
+    }
+    unreachable!()
+}
+impl Entity {}
+impl std::convert::AsRef<Entity> for Entity {
+    fn as_ref(&self) -> &Entity {
+        self
+    }
+}
+impl std::convert::AsMut<Entity> for Entity {
+    fn as_mut(&mut self) -> &mut Entity {
+        self
+    }
+}
+pub type EntityHandle = u32;
+#[repr(C, align(8))]
+pub struct EntityList {
+    pub first: crate::type_aliases::EntityPointer,
+    pub count: u64,
+}
+fn _EntityList_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], EntityList>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl EntityList {}
+impl std::convert::AsRef<EntityList> for EntityList {
+    fn as_ref(&self) -> &EntityList {
+        self
+    }
+}
+impl std::convert::AsMut<EntityList> for EntityList {
+    fn as_mut(&mut self) -> &mut EntityList {
+        self
+    }
+}
+type EntityPointer = *mut crate::type_aliases::Entity;
+#[derive(Copy, Clone, Default)]
+#[repr(transparent)]
+/// A row-major 4x4 matrix
+pub struct Matrix4(pub [f32; 16]);
+fn _Matrix4_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x40], Matrix4>([0u8; 0x40]);
+    }
+    unreachable!()
+}
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+/// Too large to be `Default` in Rust
+pub struct NameBuffer(pub [u8; 64]);
+fn _NameBuffer_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x40], NameBuffer>([0u8; 0x40]);
+    }
+    unreachable!()
+}
@@ -0,0 +1,91 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(8))]
+pub struct Event {
+    pub kind: u32,
+    _field_4: [u8; 4],
+    /// The payload for the event's kind
+    pub union_0: crate::unions::EventUnion0,
+}
+fn _Event_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x18], Event>([0u8; 0x18]);
+    }
+    unreachable!()
+}
+impl Event {}
+impl std::convert::AsRef<Event> for Event {
+    fn as_ref(&self) -> &Event {
+        self
+    }
+}
+impl std::convert::AsMut<Event> for Event {
+    fn as_mut(&mut self) -> &mut Event {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub union EventUnion0 {
+    pub position: [f32; 3],
+    pub value: crate::unions::Value,
+    pub target: *mut crate::unions::Event,
+}
+fn _EventUnion0_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], EventUnion0>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+#[repr(C, align(4))]
+pub struct Name {
+    pub chars: [u8; 12],
+}
+fn _Name_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0xC], Name>([0u8; 0xC]);
+    }
+    unreachable!()
+}
+impl Name {}
+impl std::convert::AsRef<Name> for Name {
+    fn as_ref(&self) -> &Name {
+        self
+    }
+}
+impl std::convert::AsMut<Name> for Name {
+    fn as_mut(&mut self) -> &mut Name {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub union NamedValue {
+    pub value: crate::unions::Value,
+    /// Not `Copy`, so it is wrapped in `ManuallyDrop`
+    pub name: ::std::mem::ManuallyDrop<crate::unions::Name>,
+}
+fn _NamedValue_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], NamedValue>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+#[derive(Copy, Clone)]
+#[repr(C, align(8))]
+/// A value that can be read as any of its fields
+pub union Value {
+    pub int: i32,
+    pub float: f32,
+    pub pointer: *mut u8,
+    _field_3: [u8; 16],
+}
+fn _Value_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], Value>([0u8; 0x10]);
+    }
+    unreachable!()
+}
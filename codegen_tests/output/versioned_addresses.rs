@@ -0,0 +1,70 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(u32)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
+pub enum Difficulty {
+    Easy = 0,
+    Hard = 1,
+}
+fn _Difficulty_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], Difficulty>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl Difficulty {
+    pub unsafe fn get() -> Self {
+        unsafe { *(crate::versions::address([0x140230000, 0x140220000]) as *const Self) }
+    }
+}
+#[repr(C, align(4))]
+pub struct World {
+    pub tick: u32,
+}
+fn _World_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x4], World>([0u8; 0x4]);
+    }
+    unreachable!()
+}
+impl World {
+    pub unsafe fn get() -> Option<&'static mut Self> {
+        unsafe {
+            let ptr: *mut Self = *(crate::versions::address([0x140200000, 0x140210000])
+                as *mut *mut Self);
+            ptr.as_mut()
+        }
+    }
+}
+impl World {
+    pub unsafe fn update(&mut self, delta: f32) {
+        let f: unsafe extern "C" fn(this: *mut Self, delta: f32) = ::std::mem::transmute(
+            crate::versions::address([0x140001000, 0x140001100]) as usize,
+        );
+        f(self as *mut Self as _, delta)
+    }
+    pub unsafe fn reset(&mut self) {
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
+            0x140002000 as usize,
+        );
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<World> for World {
+    fn as_ref(&self) -> &World {
+        self
+    }
+}
+impl std::convert::AsMut<World> for World {
+    fn as_mut(&mut self) -> &mut World {
+        self
+    }
+}
+pub unsafe fn get_frame_count() -> &'static mut u64 {
+    unsafe { &mut *(crate::versions::address([0x140300000, 0x140310000]) as *mut u64) }
+}
@@ -0,0 +1,42 @@
+#![allow(dead_code, non_camel_case_types)]
+#![cfg_attr(any(), rustfmt::skip)]
+//! The versions of the executable that addresses are given for.
+//! Call [`set_version`] or [`detect_version`] before using any versioned item.
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[repr(usize)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Version {
+    v1_0,
+    v1_1,
+}
+impl Version {
+    pub const ALL: [Version; 2] = [Version::v1_0, Version::v1_1];
+    pub fn name(self) -> &'static str {
+        match self {
+            Version::v1_0 => "v1_0",
+            Version::v1_1 => "v1_1",
+        }
+    }
+}
+static VERSION: AtomicUsize = AtomicUsize::new(usize::MAX);
+/// Selects the version whose addresses are used.
+pub fn set_version(version: Version) {
+    VERSION.store(version as usize, Ordering::Relaxed);
+}
+/// The selected version, if any.
+pub fn version() -> Option<Version> {
+    Version::ALL.get(VERSION.load(Ordering::Relaxed)).copied()
+}
+/// Selects the first version that `detect` returns true for, if any.
+pub fn detect_version(mut detect: impl FnMut(Version) -> bool) -> Option<Version> {
+    let version = Version::ALL.into_iter().find(|v| detect(*v))?;
+    set_version(version);
+    Some(version)
+}
+#[doc(hidden)]
+pub fn address(addresses: [usize; 2]) -> usize {
+    match version() {
+        Some(version) => addresses[version as usize],
+        None => panic!("no version has been selected; call `set_version` first"),
+    }
+}
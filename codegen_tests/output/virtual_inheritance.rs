@@ -0,0 +1,398 @@
+#![allow(
+    dead_code,
+    non_snake_case,
+    clippy::missing_safety_doc,
+    clippy::unnecessary_cast
+)]
+#![cfg_attr(any(), rustfmt::skip)]
+#[repr(C, align(8))]
+pub struct Base {
+    vftable: *const crate::virtual_inheritance::BaseVftable,
+    pub id: u64,
+}
+fn _Base_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], Base>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl Base {
+    pub fn vftable(&self) -> *const crate::virtual_inheritance::BaseVftable {
+        self.vftable as *const crate::virtual_inheritance::BaseVftable
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<Base> for Base {
+    fn as_ref(&self) -> &Base {
+        self
+    }
+}
+impl std::convert::AsMut<Base> for Base {
+    fn as_mut(&mut self) -> &mut Base {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct BaseA {
+    vftable: *const crate::virtual_inheritance::BaseAVftable,
+    vbtable: *const i32,
+    pub a: u32,
+    _field_14: [u8; 4],
+    base: crate::virtual_inheritance::Base,
+}
+fn _BaseA_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x28], BaseA>([0u8; 0x28]);
+    }
+    unreachable!()
+}
+impl BaseA {
+    pub fn vftable(&self) -> *const crate::virtual_inheritance::BaseAVftable {
+        self.vftable as *const crate::virtual_inheritance::BaseAVftable
+    }
+    #[doc(hidden)]
+    pub unsafe fn vbptr(this: *const Self) -> *const *const i32 {
+        std::ptr::addr_of!((* this).vbtable)
+    }
+    #[doc(hidden)]
+    pub unsafe fn vbptr_mut(this: *mut Self) -> *mut *const i32 {
+        std::ptr::addr_of_mut!((* this).vbtable)
+    }
+    unsafe fn virtual_base(&self, index: usize) -> *const u8 {
+        let vbptr = Self::vbptr(self);
+        let offset = (*vbptr).add(index).read();
+        vbptr.cast::<u8>().offset(offset as isize)
+    }
+    unsafe fn virtual_base_mut(&mut self, index: usize) -> *mut u8 {
+        let vbptr = Self::vbptr_mut(self);
+        let offset = (*vbptr).add(index).read();
+        vbptr.cast::<u8>().offset(offset as isize)
+    }
+    pub unsafe fn base(&self) -> &crate::virtual_inheritance::Base {
+        &*self.virtual_base(1).cast::<crate::virtual_inheritance::Base>()
+    }
+    pub unsafe fn base_mut(&mut self) -> &mut crate::virtual_inheritance::Base {
+        &mut *self.virtual_base_mut(1).cast::<crate::virtual_inheritance::Base>()
+    }
+    pub unsafe fn associated(&mut self) {
+        let f: unsafe extern "C" fn(this: *mut Self) = ::std::mem::transmute(
+            0x123 as usize,
+        );
+        f(self as *mut Self as _)
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<BaseA> for BaseA {
+    fn as_ref(&self) -> &BaseA {
+        self
+    }
+}
+impl std::convert::AsMut<BaseA> for BaseA {
+    fn as_mut(&mut self) -> &mut BaseA {
+        self
+    }
+}
+#[repr(C, align(8))]
+/// The non-virtual part of `BaseA`, as embedded in the types that inherit from it
+pub struct BaseANonVirtual {
+    vftable: *const crate::virtual_inheritance::BaseAVftable,
+    vbtable: *const i32,
+    pub a: u32,
+    _field_14: [u8; 4],
+}
+fn _BaseANonVirtual_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x18], BaseANonVirtual>([0u8; 0x18]);
+    }
+    unreachable!()
+}
+impl BaseANonVirtual {}
+impl std::convert::AsRef<BaseANonVirtual> for BaseANonVirtual {
+    fn as_ref(&self) -> &BaseANonVirtual {
+        self
+    }
+}
+impl std::convert::AsMut<BaseANonVirtual> for BaseANonVirtual {
+    fn as_mut(&mut self) -> &mut BaseANonVirtual {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct BaseAVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::virtual_inheritance::BaseA),
+}
+fn _BaseAVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], BaseAVftable>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl BaseAVftable {}
+impl std::convert::AsRef<BaseAVftable> for BaseAVftable {
+    fn as_ref(&self) -> &BaseAVftable {
+        self
+    }
+}
+impl std::convert::AsMut<BaseAVftable> for BaseAVftable {
+    fn as_mut(&mut self) -> &mut BaseAVftable {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct BaseB {
+    vbtable: *const i32,
+    pub b: u64,
+    base: crate::virtual_inheritance::Base,
+}
+fn _BaseB_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x20], BaseB>([0u8; 0x20]);
+    }
+    unreachable!()
+}
+impl BaseB {
+    #[doc(hidden)]
+    pub unsafe fn vbptr(this: *const Self) -> *const *const i32 {
+        std::ptr::addr_of!((* this).vbtable)
+    }
+    #[doc(hidden)]
+    pub unsafe fn vbptr_mut(this: *mut Self) -> *mut *const i32 {
+        std::ptr::addr_of_mut!((* this).vbtable)
+    }
+    unsafe fn virtual_base(&self, index: usize) -> *const u8 {
+        let vbptr = Self::vbptr(self);
+        let offset = (*vbptr).add(index).read();
+        vbptr.cast::<u8>().offset(offset as isize)
+    }
+    unsafe fn virtual_base_mut(&mut self, index: usize) -> *mut u8 {
+        let vbptr = Self::vbptr_mut(self);
+        let offset = (*vbptr).add(index).read();
+        vbptr.cast::<u8>().offset(offset as isize)
+    }
+    pub unsafe fn base(&self) -> &crate::virtual_inheritance::Base {
+        &*self.virtual_base(1).cast::<crate::virtual_inheritance::Base>()
+    }
+    pub unsafe fn base_mut(&mut self) -> &mut crate::virtual_inheritance::Base {
+        &mut *self.virtual_base_mut(1).cast::<crate::virtual_inheritance::Base>()
+    }
+}
+impl std::convert::AsRef<BaseB> for BaseB {
+    fn as_ref(&self) -> &BaseB {
+        self
+    }
+}
+impl std::convert::AsMut<BaseB> for BaseB {
+    fn as_mut(&mut self) -> &mut BaseB {
+        self
+    }
+}
+#[repr(C, align(8))]
+/// The non-virtual part of `BaseB`, as embedded in the types that inherit from it
+pub struct BaseBNonVirtual {
+    vbtable: *const i32,
+    pub b: u64,
+}
+fn _BaseBNonVirtual_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x10], BaseBNonVirtual>([0u8; 0x10]);
+    }
+    unreachable!()
+}
+impl BaseBNonVirtual {}
+impl std::convert::AsRef<BaseBNonVirtual> for BaseBNonVirtual {
+    fn as_ref(&self) -> &BaseBNonVirtual {
+        self
+    }
+}
+impl std::convert::AsMut<BaseBNonVirtual> for BaseBNonVirtual {
+    fn as_mut(&mut self) -> &mut BaseBNonVirtual {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct BaseVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::virtual_inheritance::Base),
+}
+fn _BaseVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], BaseVftable>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl BaseVftable {}
+impl std::convert::AsRef<BaseVftable> for BaseVftable {
+    fn as_ref(&self) -> &BaseVftable {
+        self
+    }
+}
+impl std::convert::AsMut<BaseVftable> for BaseVftable {
+    fn as_mut(&mut self) -> &mut BaseVftable {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct Derived {
+    pub base_a: crate::virtual_inheritance::BaseANonVirtual,
+    pub base_b: crate::virtual_inheritance::BaseBNonVirtual,
+    pub derived: u32,
+    _field_2c: [u8; 4],
+    base: crate::virtual_inheritance::Base,
+}
+fn _Derived_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x40], Derived>([0u8; 0x40]);
+    }
+    unreachable!()
+}
+impl Derived {
+    pub fn vftable(&self) -> *const crate::virtual_inheritance::DerivedVftable {
+        unsafe {
+            (*std::ptr::addr_of!((* (self as * const Self)).base_a)
+                .cast::<crate::virtual_inheritance::BaseA>())
+                .vftable() as *const crate::virtual_inheritance::DerivedVftable
+        }
+    }
+    #[doc(hidden)]
+    pub unsafe fn vbptr(this: *const Self) -> *const *const i32 {
+        <crate::virtual_inheritance::BaseA>::vbptr(
+            std::ptr::addr_of!((* this).base_a).cast(),
+        )
+    }
+    #[doc(hidden)]
+    pub unsafe fn vbptr_mut(this: *mut Self) -> *mut *const i32 {
+        <crate::virtual_inheritance::BaseA>::vbptr_mut(
+            std::ptr::addr_of_mut!((* this).base_a).cast(),
+        )
+    }
+    unsafe fn virtual_base(&self, index: usize) -> *const u8 {
+        let vbptr = Self::vbptr(self);
+        let offset = (*vbptr).add(index).read();
+        vbptr.cast::<u8>().offset(offset as isize)
+    }
+    unsafe fn virtual_base_mut(&mut self, index: usize) -> *mut u8 {
+        let vbptr = Self::vbptr_mut(self);
+        let offset = (*vbptr).add(index).read();
+        vbptr.cast::<u8>().offset(offset as isize)
+    }
+    pub unsafe fn base(&self) -> &crate::virtual_inheritance::Base {
+        &*self.virtual_base(1).cast::<crate::virtual_inheritance::Base>()
+    }
+    pub unsafe fn base_mut(&mut self) -> &mut crate::virtual_inheritance::Base {
+        &mut *self.virtual_base_mut(1).cast::<crate::virtual_inheritance::Base>()
+    }
+    pub unsafe fn associated(&mut self) {
+        (*std::ptr::addr_of_mut!((* (self as * mut Self)).base_a)
+            .cast::<crate::virtual_inheritance::BaseA>())
+            .associated()
+    }
+    pub unsafe fn destructor(&mut self) {
+        let f = std::ptr::addr_of!((* self.vftable()).destructor).read();
+        f(self as *mut Self as _)
+    }
+}
+impl std::convert::AsRef<crate::virtual_inheritance::BaseANonVirtual> for Derived {
+    fn as_ref(&self) -> &crate::virtual_inheritance::BaseANonVirtual {
+        &self.base_a
+    }
+}
+impl std::convert::AsMut<crate::virtual_inheritance::BaseANonVirtual> for Derived {
+    fn as_mut(&mut self) -> &mut crate::virtual_inheritance::BaseANonVirtual {
+        &mut self.base_a
+    }
+}
+impl std::convert::AsRef<crate::virtual_inheritance::BaseBNonVirtual> for Derived {
+    fn as_ref(&self) -> &crate::virtual_inheritance::BaseBNonVirtual {
+        &self.base_b
+    }
+}
+impl std::convert::AsMut<crate::virtual_inheritance::BaseBNonVirtual> for Derived {
+    fn as_mut(&mut self) -> &mut crate::virtual_inheritance::BaseBNonVirtual {
+        &mut self.base_b
+    }
+}
+impl std::convert::AsRef<Derived> for Derived {
+    fn as_ref(&self) -> &Derived {
+        self
+    }
+}
+impl std::convert::AsMut<Derived> for Derived {
+    fn as_mut(&mut self) -> &mut Derived {
+        self
+    }
+}
+#[repr(C, align(8))]
+/// The non-virtual part of `Derived`, as embedded in the types that inherit from it
+pub struct DerivedNonVirtual {
+    pub base_a: crate::virtual_inheritance::BaseANonVirtual,
+    pub base_b: crate::virtual_inheritance::BaseBNonVirtual,
+    pub derived: u32,
+    _field_2c: [u8; 4],
+}
+fn _DerivedNonVirtual_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x30], DerivedNonVirtual>([0u8; 0x30]);
+    }
+    unreachable!()
+}
+impl DerivedNonVirtual {}
+impl std::convert::AsRef<crate::virtual_inheritance::BaseANonVirtual>
+for DerivedNonVirtual {
+    fn as_ref(&self) -> &crate::virtual_inheritance::BaseANonVirtual {
+        &self.base_a
+    }
+}
+impl std::convert::AsMut<crate::virtual_inheritance::BaseANonVirtual>
+for DerivedNonVirtual {
+    fn as_mut(&mut self) -> &mut crate::virtual_inheritance::BaseANonVirtual {
+        &mut self.base_a
+    }
+}
+impl std::convert::AsRef<crate::virtual_inheritance::BaseBNonVirtual>
+for DerivedNonVirtual {
+    fn as_ref(&self) -> &crate::virtual_inheritance::BaseBNonVirtual {
+        &self.base_b
+    }
+}
+impl std::convert::AsMut<crate::virtual_inheritance::BaseBNonVirtual>
+for DerivedNonVirtual {
+    fn as_mut(&mut self) -> &mut crate::virtual_inheritance::BaseBNonVirtual {
+        &mut self.base_b
+    }
+}
+impl std::convert::AsRef<DerivedNonVirtual> for DerivedNonVirtual {
+    fn as_ref(&self) -> &DerivedNonVirtual {
+        self
+    }
+}
+impl std::convert::AsMut<DerivedNonVirtual> for DerivedNonVirtual {
+    fn as_mut(&mut self) -> &mut DerivedNonVirtual {
+        self
+    }
+}
+#[repr(C, align(8))]
+pub struct DerivedVftable {
+    pub destructor: unsafe extern "C" fn(this: *mut crate::virtual_inheritance::Derived),
+}
+fn _DerivedVftable_size_check() {
+    unsafe {
+        ::std::mem::transmute::<[u8; 0x8], DerivedVftable>([0u8; 0x8]);
+    }
+    unreachable!()
+}
+impl DerivedVftable {}
+impl std::convert::AsRef<DerivedVftable> for DerivedVftable {
+    fn as_ref(&self) -> &DerivedVftable {
+        self
+    }
+}
+impl std::convert::AsMut<DerivedVftable> for DerivedVftable {
+    fn as_mut(&mut self) -> &mut DerivedVftable {
+        self
+    }
+}
@@ -0,0 +1,69 @@
+//! Runtime tests for the accessors of virtual bases, which should also pass under Miri
+//! (`cargo +nightly miri test`)
+
+use codegen_tests::virtual_inheritance::{BaseA, BaseANonVirtual, BaseBNonVirtual, Derived};
+use std::mem::MaybeUninit;
+
+/// Creates a zeroed `T` whose vbptr, at `vbptr_offset`, points to `vbtable`
+unsafe fn with_vbtable<T>(vbptr_offset: usize, vbtable: &[i32]) -> T {
+    let mut value = MaybeUninit::<T>::zeroed();
+    value
+        .as_mut_ptr()
+        .cast::<u8>()
+        .add(vbptr_offset)
+        .cast::<*const i32>()
+        .write(vbtable.as_ptr());
+    value.assume_init()
+}
+
+/// Reads the `u64` at `offset` bytes into `value`
+fn read_u64_at<T>(value: &T, offset: usize) -> u64 {
+    unsafe {
+        (value as *const T)
+            .cast::<u8>()
+            .add(offset)
+            .cast::<u64>()
+            .read()
+    }
+}
+
+#[test]
+fn can_write_through_virtual_base_of_type() {
+    // `BaseA`'s vbptr is at 0x8, and its virtual `Base` is at 0x18.
+    let vbtable = [0, 0x10];
+    let mut base_a = unsafe { with_vbtable::<BaseA>(0x8, &vbtable) };
+
+    unsafe { base_a.base_mut() }.id = 0x1234;
+
+    assert_eq!(unsafe { base_a.base() }.id, 0x1234);
+    assert_eq!(read_u64_at(&base_a, 0x20), 0x1234);
+}
+
+#[test]
+fn can_write_through_virtual_base_of_derived_type() {
+    // `Derived` shares `base_a`'s vbptr at 0x8, and its virtual `Base` is at 0x30, past the end
+    // of `base_a`.
+    let vbtable = [0, 0x28];
+    let mut derived = unsafe { with_vbtable::<Derived>(0x8, &vbtable) };
+
+    unsafe { derived.base_mut() }.id = 0x1234;
+
+    assert_eq!(unsafe { derived.base() }.id, 0x1234);
+    assert_eq!(read_u64_at(&derived, 0x38), 0x1234);
+}
+
+#[test]
+fn converts_to_the_non_virtual_part_of_bases_with_virtual_bases() {
+    // Bases with virtual bases are stored as their non-virtual part, which is what types that
+    // inherit from them convert to with `AsRef`, rather than the complete base. That part only
+    // describes the layout; the base's functions are called through the inheriting type.
+    let vbtable = [0, 0x28];
+    let derived = unsafe { with_vbtable::<Derived>(0x8, &vbtable) };
+
+    let base_a: &BaseANonVirtual = derived.as_ref();
+    let base_b: &BaseBNonVirtual = derived.as_ref();
+
+    assert!(std::ptr::eq(base_a, &derived.base_a));
+    assert!(std::ptr::eq(base_b, &derived.base_b));
+    assert_eq!(derived.vftable(), std::ptr::null());
+}
@@ -3,7 +3,14 @@ use std::path::Path;
 fn main() -> anyhow::Result<()> {
     let root = Path::new("codegen_tests");
     let output_dir = root.join("output");
-    pyxis::build(&root.join("input"), &output_dir, 8)?;
+    pyxis::build(
+        &root.join("input"),
+        &output_dir,
+        pyxis::Target::from_preset("x64-msvc").unwrap(),
+        pyxis::Config::new()
+            .with_string("version", "1.1")
+            .with_versions(["v1_0", "v1_1"]),
+    )?;
 
     let mut module_decls = std::fs::read_dir(&output_dir)?
         .filter_map(|entry| Some(entry.ok()?.path()))
@@ -16,12 +23,21 @@ fn main() -> anyhow::Result<()> {
     std::fs::write(output_dir.join("lib.rs"), module_decls.join("\n"))?;
 
     let status = std::process::Command::new("cargo")
-        .arg("clippy")
+        .args(["clippy", "--all-targets"])
         .current_dir(&output_dir)
         .status()?;
     if !status.success() {
         anyhow::bail!("cargo clippy failed");
     }
 
+    // The tests exercise the generated code at runtime; they should also be run under Miri.
+    let status = std::process::Command::new("cargo")
+        .arg("test")
+        .current_dir(&output_dir)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("cargo test failed");
+    }
+
     Ok(())
 }
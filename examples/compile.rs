@@ -9,14 +9,48 @@ struct Args {
     #[clap(default_value = "out")]
     /// The directory to write the generated Rust files to
     out_dir: PathBuf,
-    #[clap(long, default_value = "4")]
-    /// The size of a pointer in bytes
-    pointer_size: usize,
+    #[clap(long, default_value = "x86-msvc")]
+    /// The platform to lay types out for: one of `x86-msvc`, `x64-msvc`, `i686-linux` or
+    /// `x86_64-linux`
+    platform: String,
+    #[clap(long)]
+    /// The target to use the addresses of, for declarations with an address for each target.
+    /// Defaults to the architecture of the platform (`x86` or `x64`).
+    target: Option<String>,
+    #[clap(long, value_delimiter = ',')]
+    /// The versions of the executable that addresses can be given for, as `v1_0,v1_1`. The
+    /// version is selected at runtime with the generated `versions` module.
+    versions: Vec<String>,
+    #[clap(long)]
+    /// A key to set for `#[cfg(...)]`, as `name`, `name=123` or `name=value`
+    cfg: Vec<String>,
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    let Some(platform) = pyxis::Target::from_preset(&args.platform) else {
+        anyhow::bail!(
+            "unknown platform `{}`; expected one of {:?}",
+            args.platform,
+            pyxis::Target::PRESETS
+        );
+    };
+    let mut config = pyxis::Config::new().with_versions(&args.versions);
+    if let Some(target) = &args.target {
+        config = config.with_target(target);
+    }
+    let config = args
+        .cfg
+        .iter()
+        .fold(config, |config, cfg| match cfg.split_once('=') {
+            None => config.with_flag(cfg),
+            Some((name, value)) => match value.parse::<i128>() {
+                Ok(value) => config.with_integer(name, value),
+                Err(_) => config.with_string(name, value),
+            },
+        });
+
     std::fs::create_dir_all(&args.out_dir)?;
-    pyxis::build(&args.in_dir, &args.out_dir, args.pointer_size)
+    Ok(pyxis::build(&args.in_dir, &args.out_dir, platform, config)?)
 }
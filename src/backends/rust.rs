@@ -1,26 +1,38 @@
-use std::{collections::HashMap, fmt::Write as _, path::Path, str::FromStr};
+use std::{collections::HashMap, fmt::Write as _, path::Path, str::FromStr, sync::Arc};
 
 use crate::{
+    error::{Diagnostic, ErrorKind, Location},
     grammar::ItemPath,
     semantic::{
         types::{
-            Argument, EnumDefinition, ExternValue, Function, FunctionBody, ItemCategory,
-            ItemDefinition, ItemDefinitionInner, ItemStateResolved, Region, Type, TypeDefinition,
-            Visibility,
+            Address, AliasDefinition, Argument, Bitfield, Constant, EnumDefinition, ExternValue,
+            Function, FunctionBody, GenericInstance, ItemCategory, ItemDefinition,
+            ItemDefinitionInner, ItemState, ItemStateResolved, Region, Signature, Type,
+            TypeDefinition, TypeVftable, UnionDefinition, Visibility,
         },
-        Module, ResolvedSemanticState, TypeRegistry,
+        Import, Module, ResolvedSemanticState, TypeRegistry,
     },
+    span::SourceFile,
 };
 
 use anyhow::Context;
 use quote::{quote, ToTokens};
 
+// The scanner and pointer chains are copied into the generated `signatures` and `chains`
+// modules; they are only compiled here to test them.
+#[cfg(test)]
+mod chain;
+#[cfg(test)]
+mod scanner;
+#[cfg(test)]
+mod tests;
+
 pub fn write_module(
     out_dir: &Path,
     key: &ItemPath,
     semantic_state: &ResolvedSemanticState,
     module: &Module,
-) -> anyhow::Result<()> {
+) -> crate::error::Result<()> {
     const FORMAT_OUTPUT: bool = true;
 
     if key.is_empty() {
@@ -36,6 +48,49 @@ pub fn write_module(
     let directory_path = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
     std::fs::create_dir_all(directory_path)?;
 
+    let raw_output = build_module(semantic_state, module).map_err(|e| {
+        Diagnostic::error(
+            ErrorKind::Codegen,
+            format!("failed to generate Rust code for module `{key}`: {e:#}"),
+        )
+    })?;
+
+    let mut error = None;
+    let output = if FORMAT_OUTPUT {
+        // You may think that this is inefficient. It probably is.
+        // It's still probably faster than running `rustfmt`.
+        match syn::parse_file(&raw_output) {
+            Ok(parsed_file) => prettyplease::unparse(&parsed_file),
+            Err(err) => {
+                let source = Arc::new(SourceFile::new(&path, raw_output));
+                error = Some(
+                    Diagnostic::error(
+                        ErrorKind::Codegen,
+                        format!(
+                            "could not parse generated Rust code to pretty-print; the code has been emitted as-is: {err}"
+                        ),
+                    )
+                    .with_location(Location::new(Some(&source), err.span().into()))
+                    .with_note(
+                        "this may be due to a bug in Pyxis or an issue with one of your backend definitions",
+                    ),
+                );
+                source.contents.clone()
+            }
+        }
+    } else {
+        raw_output
+    };
+
+    std::fs::write(&path, output)?;
+
+    match error {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+fn build_module(semantic_state: &ResolvedSemanticState, module: &Module) -> anyhow::Result<String> {
     let mut raw_output = String::new();
 
     writeln!(
@@ -64,6 +119,22 @@ pub fn write_module(
 
     writeln!(raw_output, "{prologues}")?;
 
+    for import in module.uses() {
+        if import.visibility == Visibility::Public {
+            writeln!(
+                raw_output,
+                "{}",
+                build_reexport(semantic_state.type_registry(), import)
+            )?;
+        }
+    }
+
+    let mut constants = module.constants().to_vec();
+    constants.sort_by_key(|c| c.name.clone());
+    for constant in &constants {
+        writeln!(raw_output, "{}", build_constant(constant)?)?;
+    }
+
     let mut definitions = module
         .definitions(semantic_state.type_registry())
         .collect::<Vec<_>>();
@@ -79,78 +150,159 @@ pub fn write_module(
     let mut extern_values = module.extern_values.clone();
     extern_values.sort_by_key(|ev| ev.name.clone());
     for ev in &extern_values {
-        writeln!(raw_output, "{}", build_extern_value(ev)?)?;
+        writeln!(raw_output, "{}", build_extern_value(&module.path, ev)?)?;
     }
 
     writeln!(raw_output, "{epilogues}")?;
 
-    let mut error = None;
-    let output = if FORMAT_OUTPUT {
-        // You may think that this is inefficient. It probably is.
-        // It's still probably faster than running `rustfmt`.
-        match syn::parse_file(&raw_output) {
-            Ok(parsed_file) => prettyplease::unparse(&parsed_file),
-            Err(err) => {
-                let lc = err.span().start();
-                error = Some(format!(
-                    concat!(
-                        "Could not parse generated Rust code to pretty-print. The code has been emitted as-is.\n",
-                        "This may be due to a bug in Pyxis or an issue with one of your backend definitions.\n",
-                        "\n",
-                        "Error: {}\n",
-                        "  --> {}:{}:{}\n",
-                        "   | {}\n",
-                        "   | {}"
-                    ),
-                    err,
-                    path.display(),
-                    lc.line,
-                    lc.column,
-                    raw_output.lines().nth(lc.line - 1).unwrap(),
-                    format!("{}^", " ".repeat(lc.column))
-                ));
-                raw_output
-            }
-        }
-    } else {
-        raw_output
-    };
-
-    std::fs::write(&path, output).context("failed to write file")?;
+    Ok(raw_output)
+}
 
-    if let Some(error) = error {
-        anyhow::bail!("{error}");
+/// Builds the Rust re-export for a `pub use`.
+fn build_reexport(type_registry: &TypeRegistry, import: &Import) -> proc_macro2::TokenStream {
+    // Predefined types are Rust primitives, which are already visible everywhere.
+    let is_predefined = type_registry
+        .canonical_path(&import.path)
+        .and_then(|p| type_registry.get(&p))
+        .is_some_and(|t| t.is_predefined());
+    if is_predefined {
+        return quote! {};
     }
 
-    Ok(())
+    let segments = import.path.iter().map(|s| str_to_ident(s.as_str()));
+    let path = quote! { crate #(:: #segments)* };
+    match import.name.as_deref() {
+        Some(name) if import.path.last().map(|s| s.as_str()) == Some(name) => {
+            quote! { pub use #path; }
+        }
+        Some(name) => {
+            let alias = str_to_ident(name);
+            quote! { pub use #path as #alias; }
+        }
+        None => quote! { pub use #path::*; },
+    }
 }
 
 fn build_item(
     type_registry: &TypeRegistry,
     definition: &ItemDefinition,
 ) -> anyhow::Result<proc_macro2::TokenStream> {
+    let visibility = definition.visibility;
+    let path = &definition.path;
+    if let ItemState::Generic(grammar_definition) = &definition.state {
+        let generics = match &grammar_definition.inner {
+            crate::grammar::ItemDefinitionInner::Type(td) => &td.generics,
+            crate::grammar::ItemDefinitionInner::Enum(_)
+            | crate::grammar::ItemDefinitionInner::Union(_)
+            | crate::grammar::ItemDefinitionInner::Alias(_) => return Ok(quote! {}),
+        };
+        let generics = generics.iter().map(|g| g.0.clone()).collect::<Vec<_>>();
+        return build_generic_type(type_registry, path, &generics, visibility);
+    }
+
     let ItemStateResolved {
         size,
         inner,
         alignment,
     } = &definition.resolved().context("type was not resolved")?;
-    let visibility = definition.visibility;
-    let path = &definition.path;
 
     use ItemDefinitionInner as IDI;
     match definition.category() {
         ItemCategory::Defined => match inner {
-            IDI::Type(td) => build_type(type_registry, path, *size, *alignment, visibility, td),
+            IDI::Type(TypeDefinition {
+                generic_instance:
+                    Some(
+                        instance @ GenericInstance {
+                            generic_regions: Some(_),
+                            ..
+                        },
+                    ),
+                ..
+            }) => build_generic_instance(path, *size, instance),
+            IDI::Type(td) => {
+                build_type(type_registry, path, &[], *size, *alignment, visibility, td)
+            }
             IDI::Enum(ed) => build_enum(path, *size, visibility, ed),
+            IDI::Union(ud) => build_union(type_registry, path, *size, *alignment, visibility, ud),
+            IDI::Alias(ad) => build_alias(path, *size, visibility, ad),
         },
         ItemCategory::Predefined => Ok(quote! {}),
         ItemCategory::Extern => Ok(quote! {}),
     }
 }
 
+/// Builds the generic type shared by all instantiations of the generic type definition at
+/// `path`. Monomorphised definitions have no generic type; their instantiations are built instead.
+fn build_generic_type(
+    type_registry: &TypeRegistry,
+    path: &ItemPath,
+    generics: &[String],
+    visibility: Visibility,
+) -> anyhow::Result<proc_macro2::TokenStream> {
+    // All instantiations share the same layout, so we can use any of them.
+    let Some((resolved, type_definition, generic_regions)) = type_registry
+        .instances(path)
+        .into_iter()
+        .filter_map(|item| {
+            let resolved = item.resolved()?;
+            let type_definition = resolved.inner.as_type()?;
+            let generic_regions = type_definition
+                .generic_instance
+                .as_ref()?
+                .generic_regions
+                .as_ref()?;
+            Some((resolved, type_definition, generic_regions))
+        })
+        .next()
+    else {
+        return Ok(quote! {});
+    };
+
+    let type_definition = TypeDefinition {
+        regions: generic_regions.clone(),
+        generic_instance: None,
+        ..type_definition.clone()
+    };
+    build_type(
+        type_registry,
+        path,
+        generics,
+        resolved.size,
+        resolved.alignment,
+        visibility,
+        &type_definition,
+    )
+}
+
+/// Builds the size check for an instantiation of a generic type, as the generic type itself
+/// can't be checked.
+fn build_generic_instance(
+    path: &ItemPath,
+    size: usize,
+    instance: &GenericInstance,
+) -> anyhow::Result<proc_macro2::TokenStream> {
+    let name = path.last().context("failed to get last of item path")?;
+    let type_ = sa_type_to_syn_type(&Type::Generic(
+        instance.template.clone(),
+        instance.arguments.clone(),
+    ))?;
+
+    let size_check_ident = quote::format_ident!("_{}_size_check", name.as_str());
+    let size = hex_literal(size);
+    Ok(quote! {
+        fn #size_check_ident() {
+            unsafe {
+                ::std::mem::transmute::<[u8; #size], #type_>([0u8; #size]);
+            }
+            unreachable!()
+        }
+    })
+}
+
 fn build_type(
     type_registry: &TypeRegistry,
     path: &ItemPath,
+    generics: &[String],
     size: usize,
     alignment: usize,
     visibility: Visibility,
@@ -164,10 +316,15 @@ fn build_type(
         doc,
         associated_functions,
         vftable,
+        base_vftables,
+        vbtable,
         copyable,
         cloneable,
         defaultable,
         packed,
+        data_size: _,
+        generic_instance,
+        non_virtual_part_of: _,
     } = type_definition;
 
     let visibility = visibility_to_tokens(visibility);
@@ -181,6 +338,7 @@ fn build_type(
                 doc,
                 type_ref,
                 is_base: _,
+                bitfields: _,
             } = r;
             let field_name = field.as_deref().context("field name not present")?;
             let field_ident = str_to_ident(field_name);
@@ -194,9 +352,27 @@ fn build_type(
         })
         .collect::<anyhow::Result<Vec<_>>>()?;
 
+    let bitfield_impl = regions
+        .iter()
+        .flat_map(|r| r.bitfields.iter().map(move |b| (r, b)))
+        .map(|(region, bitfield)| {
+            let storage = region.name.as_deref().context("field name not present")?;
+            let storage_bits = region
+                .size(type_registry)
+                .context("failed to get size of bitfield storage")?
+                * 8;
+            build_bitfield_accessors(storage, storage_bits, bitfield)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
     let name_ident = str_to_ident(name.as_str());
+    let generics = (!generics.is_empty()).then(|| {
+        let generics = generics.iter().map(|g| str_to_ident(g));
+        quote! { <#(#generics),*> }
+    });
+    // Generic types are checked through each of their instantiations instead.
     let size_check_ident = quote::format_ident!("_{}_size_check", name.as_str());
-    let size_check_impl = (size > 0).then(|| {
+    let size_check_impl = (size > 0 && generics.is_none()).then(|| {
         let size = hex_literal(size);
         quote! {
             fn #size_check_ident() {
@@ -207,34 +383,124 @@ fn build_type(
             }
         }
     });
+    // Monomorphised instantiations are named after their arguments (e.g. `Array_u32`).
+    let allow_non_camel_case = generic_instance
+        .is_some()
+        .then(|| quote! { #[allow(non_camel_case_types)] });
 
-    let singleton_impl = singleton.map(|address| {
+    let singleton_impl = singleton.as_ref().map(|address| {
+        let is_chain = matches!(address, Address::Chain { .. });
+        let address = address_to_tokens(path, address);
+        let body = if is_chain {
+            quote! { #address.map(|address| &mut *(address as *mut Self)) }
+        } else {
+            quote! {
+                let ptr: *mut Self = *(#address as *mut *mut Self);
+                ptr.as_mut()
+            }
+        };
         quote! {
             impl #name_ident {
                 #visibility unsafe fn get() -> Option<&'static mut Self> {
                     unsafe {
-                        let ptr: *mut Self = *(#address as *mut *mut Self);
-                        ptr.as_mut()
+                        #body
                     }
                 }
             }
         }
     });
 
+    let non_virtual_bases = non_virtual_bases(type_registry, regions)?;
     let vftable_fn_impl = vftable
+        .as_ref()
+        .map(|v| build_vftable_accessor(quote! { vftable }, *packed, &non_virtual_bases, v))
+        .transpose()?;
+    // The vftables declared for bases replace the vftable of the base's subobject
+    let base_vftable_fn_impl = base_vftables
+        .iter()
+        .map(|v| {
+            let field = v
+                .base_field
+                .as_deref()
+                .context("base vftable field not present")?;
+            build_vftable_accessor(
+                quote::format_ident!("{field}_vftable").into_token_stream(),
+                *packed,
+                &non_virtual_bases,
+                v,
+            )
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // Virtual bases are found through the offsets in the vbtable, which are relative to the
+    // vbptr; the first entry is the offset of the vbptr itself, and is skipped. The vbptr is
+    // found through raw pointers, so that the virtual bases past the end of a base's storage
+    // are reached with the access of the complete type, and only written to through `&mut self`.
+    let vbtable_fn_impl = vbtable
         .as_ref()
         .map(|v| {
-            let accessor = if let Some(field) = &v.base_field {
+            let (vbptr, vbptr_mut) = if let Some(field) = &v.base_field {
+                let type_ = non_virtual_bases
+                    .get(field)
+                    .with_context(|| format!("vbptr base field `{field}` not present"))?;
                 let field = str_to_ident(field);
-                quote! { #field . vftable() }
+                (
+                    quote! { <#type_>::vbptr(std::ptr::addr_of!((*this).#field).cast()) },
+                    quote! { <#type_>::vbptr_mut(std::ptr::addr_of_mut!((*this).#field).cast()) },
+                )
             } else {
-                quote! { vftable }
+                (
+                    quote! { std::ptr::addr_of!((*this).vbtable) },
+                    quote! { std::ptr::addr_of_mut!((*this).vbtable) },
+                )
             };
-            let vftable_type = sa_type_to_syn_type(&v.type_)?;
+            let accessors = v
+                .bases
+                .iter()
+                .enumerate()
+                .map(|(index, base)| {
+                    let name = base
+                        .name
+                        .as_deref()
+                        .context("virtual base name not present")?;
+                    let getter = str_to_ident(name);
+                    let getter_mut = quote::format_ident!("{name}_mut");
+                    let visibility = visibility_to_tokens(base.visibility);
+                    let doc = doc_to_tokens(false, base.doc.as_deref());
+                    let type_ = sa_type_to_syn_type(&base.type_ref)?;
+                    let index = proc_macro2::Literal::usize_unsuffixed(index + 1);
+                    anyhow::Ok(quote! {
+                        #doc
+                        #visibility unsafe fn #getter(&self) -> & #type_ {
+                            &*self.virtual_base(#index).cast::<#type_>()
+                        }
+                        #doc
+                        #visibility unsafe fn #getter_mut(&mut self) -> &mut #type_ {
+                            &mut *self.virtual_base_mut(#index).cast::<#type_>()
+                        }
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
             anyhow::Ok(quote! {
-                pub fn vftable(&self) -> #vftable_type {
-                    self. #accessor as #vftable_type
+                #[doc(hidden)]
+                pub unsafe fn vbptr(this: *const Self) -> *const *const i32 {
+                    #vbptr
+                }
+                #[doc(hidden)]
+                pub unsafe fn vbptr_mut(this: *mut Self) -> *mut *const i32 {
+                    #vbptr_mut
+                }
+                unsafe fn virtual_base(&self, index: usize) -> *const u8 {
+                    let vbptr = Self::vbptr(self);
+                    let offset = (*vbptr).add(index).read();
+                    vbptr.cast::<u8>().offset(offset as isize)
                 }
+                unsafe fn virtual_base_mut(&mut self, index: usize) -> *mut u8 {
+                    let vbptr = Self::vbptr_mut(self);
+                    let offset = (*vbptr).add(index).read();
+                    vbptr.cast::<u8>().offset(offset as isize)
+                }
+                #(#accessors)*
             })
         })
         .transpose()?;
@@ -244,7 +510,7 @@ fn build_type(
     let associated_functions_impl = associated_functions
         .iter()
         .filter(|f| !f.is_internal())
-        .map(build_function)
+        .map(|f| build_function(path, *packed, &non_virtual_bases, f))
         .collect::<anyhow::Result<Vec<_>>>()?;
 
     let vftable_function_impl = vftable
@@ -253,7 +519,7 @@ fn build_type(
             v.functions
                 .iter()
                 .filter(|f| !f.is_internal())
-                .map(build_function)
+                .map(|f| build_function(path, *packed, &non_virtual_bases, f))
                 .collect::<anyhow::Result<Vec<_>>>()
         })
         .transpose()?
@@ -348,12 +614,12 @@ fn build_type(
                     }
                 } else {
                     quote! {
-                        impl std::convert::AsRef<#type_> for #name_ident {
+                        impl #generics std::convert::AsRef<#type_> for #name_ident #generics {
                             fn as_ref(&self) -> & #type_ {
                                 &self #(. #field_path)*
                             }
                         }
-                        impl std::convert::AsMut<#type_> for #name_ident {
+                        impl #generics std::convert::AsMut<#type_> for #name_ident #generics {
                             fn as_mut(&mut self) -> &mut #type_ {
                                 &mut self #(. #field_path)*
                             }
@@ -363,13 +629,13 @@ fn build_type(
             })
             // Inject conversions from T to T to make it easier to work with traits that rely on AsRef/AsMut
             .chain(std::iter::once(quote! {
-                impl std::convert::AsRef<#name_ident> for #name_ident {
-                    fn as_ref(&self) -> & #name_ident {
+                impl #generics std::convert::AsRef<#name_ident #generics> for #name_ident #generics {
+                    fn as_ref(&self) -> & #name_ident #generics {
                         self
                     }
                 }
-                impl std::convert::AsMut<#name_ident> for #name_ident {
-                    fn as_mut(&mut self) -> &mut #name_ident {
+                impl #generics std::convert::AsMut<#name_ident #generics> for #name_ident #generics {
+                    fn as_mut(&mut self) -> &mut #name_ident #generics {
                         self
                     }
                 }
@@ -380,14 +646,18 @@ fn build_type(
     Ok(quote! {
         #derives
         #[repr(C #packed #alignment)]
+        #allow_non_camel_case
         #doc
-        #visibility struct #name_ident {
+        #visibility struct #name_ident #generics {
             #(#fields),*
         }
         #size_check_impl
         #singleton_impl
-        impl #name_ident {
+        impl #generics #name_ident #generics {
             #vftable_fn_impl
+            #(#base_vftable_fn_impl)*
+            #vbtable_fn_impl
+            #(#bitfield_impl)*
             #(#associated_functions_impl)*
             #(#vftable_function_impl)*
         }
@@ -395,6 +665,76 @@ fn build_type(
     })
 }
 
+/// Builds the getter and setter for a bitfield within the integer field `storage`
+fn build_bitfield_accessors(
+    storage: &str,
+    storage_bits: usize,
+    bitfield: &Bitfield,
+) -> anyhow::Result<proc_macro2::TokenStream> {
+    let Bitfield {
+        visibility,
+        name,
+        doc,
+        type_ref,
+        offset,
+        width,
+    } = bitfield;
+
+    let storage = str_to_ident(storage);
+    let visibility = visibility_to_tokens(*visibility);
+    let doc = doc_to_tokens(false, doc.as_deref());
+    let getter = str_to_ident(name);
+    let setter = quote::format_ident!("set_{}", name);
+    let syn_type = sa_type_to_syn_type(type_ref)?;
+    let storage_type = quote::format_ident!("u{}", storage_bits);
+    let signed_storage_type = quote::format_ident!("i{}", storage_bits);
+
+    let storage_mask = u128::MAX >> (128 - storage_bits);
+    let mask = u128::MAX >> (128 - width);
+    let clear_mask = !(mask << offset) & storage_mask;
+    let hex = |value: u128| proc_macro2::Literal::from_str(&format!("0x{value:X}")).unwrap();
+    let (mask, clear_mask) = (hex(mask), hex(clear_mask));
+    // Shifts by zero are left out, as they would trip `clippy::identity_op`
+    let shift = |value: proc_macro2::TokenStream, op: proc_macro2::TokenStream, by: usize| {
+        if by == 0 {
+            value
+        } else {
+            let by = proc_macro2::Literal::usize_unsuffixed(by);
+            quote! { (#value #op #by) }
+        }
+    };
+    let stored = shift(quote! { self.#storage }, quote! { >> }, *offset);
+    let value = if bitfield.is_bool() {
+        quote! { #stored & #mask != 0 }
+    } else if bitfield.is_signed() {
+        // Shift the bitfield to the top of the storage, then back down to sign-extend it
+        let top = shift(
+            quote! { self.#storage },
+            quote! { << },
+            storage_bits - offset - width,
+        );
+        let right = proc_macro2::Literal::usize_unsuffixed(storage_bits - width);
+        quote! { ((#top as #signed_storage_type) >> #right) as #syn_type }
+    } else {
+        quote! { (#stored & #mask) as #syn_type }
+    };
+    let new_value = shift(
+        quote! { ((value as #storage_type) & #mask) },
+        quote! { << },
+        *offset,
+    );
+
+    Ok(quote! {
+        #doc
+        #visibility fn #getter(&self) -> #syn_type {
+            #value
+        }
+        #visibility fn #setter(&mut self, value: #syn_type) {
+            self.#storage = (self.#storage & #clear_mask) | #new_value;
+        }
+    })
+}
+
 fn build_enum(
     path: &ItemPath,
     size: usize,
@@ -422,8 +762,9 @@ fn build_enum(
 
     let syn_fields = fields.iter().enumerate().map(|(idx, (name, value))| {
         let name_ident = str_to_ident(name);
+        let value = proc_macro2::Literal::i128_unsuffixed(*value);
         let field = quote! {
-            #name_ident = #value as _
+            #name_ident = #value
         };
 
         if default_index.is_some_and(|i| i == idx) {
@@ -449,8 +790,20 @@ fn build_enum(
         }
     });
 
-    let singleton_impl = singleton.map(|address| {
-        let address = hex_literal(address);
+    let singleton_impl = singleton.as_ref().map(|address| {
+        let is_chain = matches!(address, Address::Chain { .. });
+        let address = address_to_tokens(path, address);
+        if is_chain {
+            return quote! {
+                impl #name_ident {
+                    #visibility unsafe fn get() -> Option<Self> {
+                        unsafe {
+                            #address.map(|address| *(address as *const Self))
+                        }
+                    }
+                }
+            };
+        }
         quote! {
             impl #name_ident {
                 #visibility unsafe fn get() -> Self {
@@ -485,7 +838,239 @@ fn build_enum(
     })
 }
 
-fn build_function(function: &Function) -> Result<proc_macro2::TokenStream, anyhow::Error> {
+fn build_union(
+    type_registry: &TypeRegistry,
+    path: &ItemPath,
+    size: usize,
+    alignment: usize,
+    visibility: Visibility,
+    union_definition: &UnionDefinition,
+) -> anyhow::Result<proc_macro2::TokenStream> {
+    let name = path.last().context("failed to get last of item path")?;
+
+    let UnionDefinition {
+        fields,
+        doc,
+        copyable,
+        cloneable,
+    } = union_definition;
+
+    let visibility = visibility_to_tokens(visibility);
+    let doc = doc_to_tokens(false, doc.as_deref());
+    let fields = fields
+        .iter()
+        .map(|r| {
+            let field_name = r.name.as_deref().context("field name not present")?;
+            let field_ident = str_to_ident(field_name);
+            let visibility = visibility_to_tokens(r.visibility);
+            let doc = doc_to_tokens(false, r.doc.as_deref());
+            let mut syn_type = sa_type_to_syn_type(&r.type_ref)?.into_token_stream();
+            // Rust only allows union fields that are `Copy` or that won't be dropped.
+            if !r.type_ref.is_copyable(type_registry) {
+                syn_type = quote! { ::std::mem::ManuallyDrop<#syn_type> };
+            }
+            Ok(quote! {
+                #doc
+                #visibility #field_ident: #syn_type
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let name_ident = str_to_ident(name.as_str());
+    let size_check_ident = quote::format_ident!("_{}_size_check", name.as_str());
+    let size_check_impl = (size > 0).then(|| {
+        let size = hex_literal(size);
+        quote! {
+            fn #size_check_ident() {
+                unsafe {
+                    ::std::mem::transmute::<[u8; #size], #name_ident>([0u8; #size]);
+                }
+                unreachable!()
+            }
+        }
+    });
+
+    let mut extra_derives = vec![];
+    if *copyable {
+        extra_derives.push(quote! { Copy });
+    }
+    if *cloneable {
+        extra_derives.push(quote! { Clone });
+    }
+    let derives = if extra_derives.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[derive(#(#extra_derives),*)] }
+    };
+
+    let alignment: syn::Index = alignment.into();
+    Ok(quote! {
+        #derives
+        #[repr(C, align(#alignment))]
+        #doc
+        #visibility union #name_ident {
+            #(#fields),*
+        }
+        #size_check_impl
+    })
+}
+
+fn build_alias(
+    path: &ItemPath,
+    size: usize,
+    visibility: Visibility,
+    alias_definition: &AliasDefinition,
+) -> anyhow::Result<proc_macro2::TokenStream> {
+    let name = path.last().context("failed to get last of item path")?;
+
+    let AliasDefinition {
+        type_,
+        doc,
+        newtype,
+        copyable,
+        cloneable,
+        defaultable,
+    } = alias_definition;
+
+    let syn_type = sa_type_to_syn_type(type_)?;
+    let name_ident = str_to_ident(name.as_str());
+    let visibility = visibility_to_tokens(visibility);
+    let doc = doc_to_tokens(false, doc.as_deref());
+
+    if !*newtype {
+        return Ok(quote! {
+            #doc
+            #visibility type #name_ident = #syn_type;
+        });
+    }
+
+    let size_check_ident = quote::format_ident!("_{}_size_check", name.as_str());
+    let size_check_impl = (size > 0).then(|| {
+        let size = hex_literal(size);
+        quote! {
+            fn #size_check_ident() {
+                unsafe {
+                    ::std::mem::transmute::<[u8; #size], #name_ident>([0u8; #size]);
+                }
+                unreachable!()
+            }
+        }
+    });
+
+    let mut extra_derives = vec![];
+    if *copyable {
+        extra_derives.push(quote! { Copy });
+    }
+    if *cloneable {
+        extra_derives.push(quote! { Clone });
+    }
+    if *defaultable {
+        extra_derives.push(quote! { Default });
+    }
+    let derives = if extra_derives.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[derive(#(#extra_derives),*)] }
+    };
+
+    Ok(quote! {
+        #derives
+        #[repr(transparent)]
+        #doc
+        #visibility struct #name_ident(pub #syn_type);
+        #size_check_impl
+    })
+}
+
+/// The complete types of the bases of a type that are stored as their non-virtual part, by field
+type NonVirtualBases = HashMap<String, syn::Type>;
+
+fn non_virtual_bases(
+    type_registry: &TypeRegistry,
+    regions: &[Region],
+) -> anyhow::Result<NonVirtualBases> {
+    regions
+        .iter()
+        .filter(|r| r.is_base)
+        .filter_map(|r| {
+            let Type::Raw(path) = &r.type_ref else {
+                return None;
+            };
+            let complete_type = type_registry
+                .get(path)?
+                .resolved()?
+                .inner
+                .as_type()?
+                .non_virtual_part_of
+                .clone()?;
+            Some((r.name.clone()?, Type::Raw(complete_type)))
+        })
+        .map(|(field, complete_type)| Ok((field, sa_type_to_syn_type(&complete_type)?)))
+        .collect()
+}
+
+/// The base stored in `field`, as a place to call its functions on, and whether reaching it is
+/// unsafe.
+///
+/// A base stored as its non-virtual part is used as its complete type, as that part only
+/// describes its layout. Its virtual bases lie past the end of the field, so it is reached
+/// through a pointer to all of `self`. The fields of packed types are also reached through
+/// pointers, as references can't be taken to them.
+fn base_place(
+    field: &str,
+    packed: bool,
+    non_virtual_bases: &NonVirtualBases,
+    mutable: bool,
+) -> (proc_macro2::TokenStream, bool) {
+    let complete_type = non_virtual_bases.get(field);
+    let field = str_to_ident(field);
+    match (complete_type, mutable) {
+        (Some(complete_type), false) => (
+            quote! {
+                (*std::ptr::addr_of!((*(self as *const Self)).#field).cast::<#complete_type>())
+            },
+            true,
+        ),
+        (Some(complete_type), true) => (
+            quote! {
+                (*std::ptr::addr_of_mut!((*(self as *mut Self)).#field).cast::<#complete_type>())
+            },
+            true,
+        ),
+        (None, false) if packed => (quote! { (*std::ptr::addr_of!(self.#field)) }, true),
+        (None, true) if packed => (quote! { (*std::ptr::addr_of_mut!(self.#field)) }, true),
+        (None, _) => (quote! { self.#field }, false),
+    }
+}
+
+/// Builds the accessor for a vftable, which is either stored in the type or shared with a base
+fn build_vftable_accessor(
+    name: proc_macro2::TokenStream,
+    packed: bool,
+    non_virtual_bases: &NonVirtualBases,
+    vftable: &TypeVftable,
+) -> anyhow::Result<proc_macro2::TokenStream> {
+    let vftable_type = sa_type_to_syn_type(&vftable.type_)?;
+    let body = match &vftable.base_field {
+        Some(field) => match base_place(field, packed, non_virtual_bases, false) {
+            (base, true) => quote! { unsafe { #base.vftable() as #vftable_type } },
+            (base, false) => quote! { #base.vftable() as #vftable_type },
+        },
+        None => quote! { self.vftable as #vftable_type },
+    };
+    Ok(quote! {
+        pub fn #name(&self) -> #vftable_type {
+            #body
+        }
+    })
+}
+
+fn build_function(
+    type_path: &ItemPath,
+    packed: bool,
+    non_virtual_bases: &NonVirtualBases,
+    function: &Function,
+) -> Result<proc_macro2::TokenStream, anyhow::Error> {
     let name = str_to_ident(&function.name);
     let doc = doc_to_tokens(false, function.doc.as_deref());
 
@@ -526,15 +1111,24 @@ fn build_function(function: &Function) -> Result<proc_macro2::TokenStream, anyho
         .collect::<anyhow::Result<Vec<_>>>()?;
 
     let is_field_function = function.body.is_field();
+    // Functions called through the vftable of a base are passed a pointer to that base
+    let base_field = match &function.body {
+        FunctionBody::BaseVftable { field, .. } => Some(str_to_ident(field)),
+        _ => None,
+    };
     let call_arguments = function
         .arguments
         .iter()
         // Only pass `self` to the function if it's not a field function
         .filter(|a| !is_field_function || !a.is_self())
-        .map(|a| match a {
-            Argument::ConstSelf => quote! { self as *const Self as _ },
-            Argument::MutSelf => quote! { self as *mut Self as _ },
-            Argument::Field(name, _) => {
+        .map(|a| match (a, &base_field) {
+            (Argument::ConstSelf, Some(field)) => quote! { std::ptr::addr_of!(self.#field) as _ },
+            (Argument::MutSelf, Some(field)) => {
+                quote! { std::ptr::addr_of_mut!(self.#field) as _ }
+            }
+            (Argument::ConstSelf, None) => quote! { self as *const Self as _ },
+            (Argument::MutSelf, None) => quote! { self as *mut Self as _ },
+            (Argument::Field(name, _), _) => {
                 let name = str_to_ident(name);
                 quote! { #name }
             }
@@ -551,25 +1145,33 @@ fn build_function(function: &Function) -> Result<proc_macro2::TokenStream, anyho
         .transpose()?;
 
     let calling_convention = function.calling_convention.as_str();
+    let function_path = type_path.join(function.name.as_str().into());
+    let call_address = |address: proc_macro2::TokenStream| {
+        quote! {
+            let f:
+                unsafe extern #calling_convention
+                fn(#(#lambda_arguments),*) #return_type
+            = ::std::mem::transmute(#address as usize);
+            f(#(#call_arguments),*)
+        }
+    };
     let function_body = match &function.body {
         FunctionBody::Address { address } => {
-            let address = hex_literal(*address);
-            quote! {
-                let f:
-                    unsafe extern #calling_convention
-                    fn(#(#lambda_arguments),*) #return_type
-                = ::std::mem::transmute(#address as usize);
-                f(#(#call_arguments),*)
-            }
+            call_address(address_to_tokens(&function_path, address))
         }
+        FunctionBody::Signature { .. } => call_address(signature_address_to_tokens(&function_path)),
         FunctionBody::Field {
             field,
             function_name,
         } => {
-            let field_ident = str_to_ident(field);
             let function_to_call_name = str_to_ident(function_name);
+            let mutable = function
+                .arguments
+                .iter()
+                .any(|a| matches!(a, Argument::MutSelf));
+            let (field, _) = base_place(field, packed, non_virtual_bases, mutable);
             quote! {
-                self.#field_ident.#function_to_call_name(#(#call_arguments),*)
+                #field.#function_to_call_name(#(#call_arguments),*)
             }
         }
         FunctionBody::Vftable { function_name } => {
@@ -579,6 +1181,17 @@ fn build_function(function: &Function) -> Result<proc_macro2::TokenStream, anyho
                 f(#(#call_arguments),*)
             }
         }
+        FunctionBody::BaseVftable {
+            field,
+            function_name,
+        } => {
+            let vftable = quote::format_ident!("{field}_vftable");
+            let function_to_call_name = str_to_ident(function_name);
+            quote! {
+                let f = std::ptr::addr_of!((*self.#vftable()).#function_to_call_name).read();
+                f(#(#call_arguments),*)
+            }
+        }
     };
 
     let visibility = visibility_to_tokens(function.visibility);
@@ -590,12 +1203,35 @@ fn build_function(function: &Function) -> Result<proc_macro2::TokenStream, anyho
     })
 }
 
-fn build_extern_value(ev: &ExternValue) -> anyhow::Result<proc_macro2::TokenStream> {
+fn build_constant(constant: &Constant) -> anyhow::Result<proc_macro2::TokenStream> {
+    let visibility = visibility_to_tokens(constant.visibility);
+    let doc = doc_to_tokens(false, constant.doc());
+    let name = str_to_ident(&constant.name);
+    let type_ = sa_type_to_syn_type(&constant.type_)?;
+    let value = proc_macro2::Literal::i128_unsuffixed(constant.value);
+
+    Ok(quote! {
+        #doc
+        #visibility const #name: #type_ = #value;
+    })
+}
+
+fn build_extern_value(
+    module_path: &ItemPath,
+    ev: &ExternValue,
+) -> anyhow::Result<proc_macro2::TokenStream> {
     let visibility = visibility_to_tokens(ev.visibility);
     let function_ident = quote::format_ident!("get_{}", ev.name);
     let type_ = sa_type_to_syn_type(&ev.type_)?;
-    let address = hex_literal(ev.address);
+    let address = address_to_tokens(&module_path.join(ev.name.as_str().into()), &ev.address);
 
+    if let Address::Chain { .. } = ev.address {
+        return Ok(quote! {
+            #visibility unsafe fn #function_ident() -> Option<&'static mut #type_> {
+                unsafe { #address.map(|address| &mut *(address as *mut #type_)) }
+            }
+        });
+    }
     Ok(quote! {
         #visibility unsafe fn #function_ident() -> &'static mut #type_ {
             unsafe { &mut *(#address as *mut #type_) }
@@ -603,6 +1239,239 @@ fn build_extern_value(ev: &ExternValue) -> anyhow::Result<proc_macro2::TokenStre
     })
 }
 
+/// Writes the `versions` module, which selects the addresses of versioned items at runtime.
+/// Every versioned address is a table with an entry for each of `versions`, in order.
+pub fn write_versions(out_dir: &Path, versions: &[String]) -> crate::error::Result<()> {
+    let count = proc_macro2::Literal::usize_unsuffixed(versions.len());
+    let idents = versions.iter().map(|v| str_to_ident(v)).collect::<Vec<_>>();
+    let output = quote! {
+        #![allow(dead_code, non_camel_case_types)]
+        #![cfg_attr(any(), rustfmt::skip)]
+        //! The versions of the executable that addresses are given for.
+        //! Call [`set_version`] or [`detect_version`] before using any versioned item.
+
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[repr(usize)]
+        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub enum Version {
+            #(#idents),*
+        }
+        impl Version {
+            pub const ALL: [Version; #count] = [#(Version::#idents),*];
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    #(Version::#idents => #versions),*
+                }
+            }
+        }
+
+        static VERSION: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+        /// Selects the version whose addresses are used.
+        pub fn set_version(version: Version) {
+            VERSION.store(version as usize, Ordering::Relaxed);
+        }
+
+        /// The selected version, if any.
+        pub fn version() -> Option<Version> {
+            Version::ALL.get(VERSION.load(Ordering::Relaxed)).copied()
+        }
+
+        /// Selects the first version that `detect` returns true for, if any.
+        pub fn detect_version(mut detect: impl FnMut(Version) -> bool) -> Option<Version> {
+            let version = Version::ALL.into_iter().find(|v| detect(*v))?;
+            set_version(version);
+            Some(version)
+        }
+
+        #[doc(hidden)]
+        pub fn address(addresses: [usize; #count]) -> usize {
+            match version() {
+                Some(version) => addresses[version as usize],
+                None => panic!("no version has been selected; call `set_version` first"),
+            }
+        }
+    };
+
+    let path = out_dir.join("versions.rs");
+    std::fs::create_dir_all(out_dir)?;
+    let output = prettyplease::unparse(&syn::parse2(output).map_err(|e| {
+        Diagnostic::error(
+            ErrorKind::Codegen,
+            format!("failed to generate the `versions` module: {e}"),
+        )
+    })?);
+    std::fs::write(path, output)?;
+    Ok(())
+}
+
+/// Writes the `images` module, which looks up where each image in `images` was loaded with a
+/// resolver set at runtime. `images` must be sorted.
+pub fn write_images(out_dir: &Path, images: &[&str]) -> crate::error::Result<()> {
+    let count = proc_macro2::Literal::usize_unsuffixed(images.len());
+    let output = quote! {
+        #![allow(dead_code)]
+        #![cfg_attr(any(), rustfmt::skip)]
+        //! The images (executables and libraries) that addresses are relative to.
+        //! Call [`set_base_resolver`] before using any item whose address is relative to an image.
+
+        use std::sync::OnceLock;
+
+        /// The name of every image, sorted
+        pub const NAMES: [&str; #count] = [#(#images),*];
+
+        static RESOLVER: OnceLock<fn(&str) -> usize> = OnceLock::new();
+        static BASES: [OnceLock<usize>; #count] = [const { OnceLock::new() }; #count];
+
+        /// Sets the function that returns the address an image was loaded at, given its name.
+        /// Each image's base is only resolved once. Returns false if a resolver was already set.
+        pub fn set_base_resolver(resolver: fn(&str) -> usize) -> bool {
+            RESOLVER.set(resolver).is_ok()
+        }
+
+        #[doc(hidden)]
+        pub fn base(name: &str) -> usize {
+            let index = NAMES
+                .binary_search(&name)
+                .unwrap_or_else(|_| panic!("there is no image named `{name}`"));
+            *BASES[index].get_or_init(|| {
+                let resolver = RESOLVER
+                    .get()
+                    .expect("no base resolver has been set; call `set_base_resolver` first");
+                resolver(name)
+            })
+        }
+    };
+
+    let path = out_dir.join("images.rs");
+    std::fs::create_dir_all(out_dir)?;
+    let output = prettyplease::unparse(&syn::parse2(output).map_err(|e| {
+        Diagnostic::error(
+            ErrorKind::Codegen,
+            format!("failed to generate the `images` module: {e}"),
+        )
+    })?);
+    std::fs::write(path, output)?;
+    Ok(())
+}
+
+/// Writes the `chains` module, which follows the pointer chains that find singletons and
+/// extern values.
+pub fn write_chains(out_dir: &Path) -> crate::error::Result<()> {
+    let raw_output = format!(
+        "#![allow(dead_code)]\n#![cfg_attr(any(), rustfmt::skip)]\n{}\n{}",
+        "//! Follows chains of pointers to the items that are found through them.",
+        include_str!("rust/chain.rs")
+    );
+    let output = syn::parse_file(&raw_output)
+        .map(|file| prettyplease::unparse(&file))
+        .map_err(|e| {
+            Diagnostic::error(
+                ErrorKind::Codegen,
+                format!("failed to generate the `chains` module: {e}"),
+            )
+        })?;
+    std::fs::create_dir_all(out_dir)?;
+    std::fs::write(out_dir.join("chains.rs"), output)?;
+    Ok(())
+}
+
+/// Writes the `signatures` module, which scans memory for every signature in `signatures` and
+/// caches the addresses of the items they locate.
+pub fn write_signatures(
+    out_dir: &Path,
+    signatures: &[(ItemPath, &Signature)],
+) -> crate::error::Result<()> {
+    // The scanner looks signatures up by name with a binary search.
+    let mut signatures = signatures
+        .iter()
+        .map(|(path, signature)| (path.to_string(), *signature))
+        .collect::<Vec<_>>();
+    signatures.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let signatures = signatures.iter().map(|(name, signature)| {
+        let pattern = signature.pattern.iter().map(|byte| match byte {
+            Some(byte) => {
+                let byte = proc_macro2::Literal::from_str(&format!("0x{byte:02X}")).unwrap();
+                quote! { Some(#byte) }
+            }
+            None => quote! { None },
+        });
+        let offset = proc_macro2::Literal::usize_unsuffixed(signature.offset);
+        let rip_relative = signature.rip_relative;
+        quote! {
+            Signature {
+                name: #name,
+                pattern: &[#(#pattern),*],
+                offset: #offset,
+                rip_relative: #rip_relative,
+            }
+        }
+    });
+    let table = quote! {
+        static SCANNER: Scanner = Scanner::new(&[#(#signatures),*]);
+
+        /// Scans `memory`, which starts at the address `base`, for every signature, and caches
+        /// the addresses of the items they locate. Only the first scan has an effect.
+        /// Returns the names of the items whose signatures were not found.
+        pub fn scan(memory: &[u8], base: usize) -> Vec<&'static str> {
+            SCANNER.scan(memory, base)
+        }
+
+        #[doc(hidden)]
+        pub fn address(name: &str) -> usize {
+            SCANNER.address(name)
+        }
+    };
+
+    let raw_output = format!(
+        "#![allow(dead_code)]\n#![cfg_attr(any(), rustfmt::skip)]\n{}\n{}\n{table}",
+        "//! Locates items by scanning memory for their signatures.\n//! Call [`scan`] before using any of them.",
+        include_str!("rust/scanner.rs")
+    );
+    let output = syn::parse_file(&raw_output)
+        .map(|file| prettyplease::unparse(&file))
+        .map_err(|e| {
+            Diagnostic::error(
+                ErrorKind::Codegen,
+                format!("failed to generate the `signatures` module: {e}"),
+            )
+        })?;
+    std::fs::create_dir_all(out_dir)?;
+    std::fs::write(out_dir.join("signatures.rs"), output)?;
+    Ok(())
+}
+
+/// The address of the item at `path` as an expression, which looks versioned addresses up in
+/// the version table, signatures up in the scanner and image-relative addresses up in the
+/// image table. Chains evaluate to an `Option<usize>`, as they can end early.
+fn address_to_tokens(path: &ItemPath, address: &Address) -> proc_macro2::TokenStream {
+    match address {
+        Address::Fixed(address) => hex_literal(*address).into_token_stream(),
+        Address::Versioned(addresses) => {
+            let addresses = addresses.iter().map(|(_, address)| hex_literal(*address));
+            quote! { crate::versions::address([#(#addresses),*]) }
+        }
+        Address::Signature(_) => signature_address_to_tokens(path),
+        Address::Relative { image, rva } => {
+            let rva = address_to_tokens(path, rva);
+            quote! { (crate::images::base(#image) + #rva) }
+        }
+        Address::Chain { start, offsets } => {
+            let start = address_to_tokens(path, start);
+            let offsets = offsets.iter().map(|offset| signed_hex_literal(*offset));
+            quote! { crate::chains::walk(#start, &[#(#offsets),*]) }
+        }
+    }
+}
+
+fn signature_address_to_tokens(path: &ItemPath) -> proc_macro2::TokenStream {
+    let name = path.to_string();
+    quote! { crate::signatures::address(#name) }
+}
+
 fn str_to_ident(s: &str) -> syn::Ident {
     quote::format_ident!("{}", s)
 }
@@ -616,13 +1485,19 @@ fn fully_qualified_type_ref_impl(out: &mut String, type_ref: &Type) -> Result<()
             if path.len() == 1 && path.last() == Some(&"void".into()) {
                 write!(out, "::std::ffi::c_void")
             } else {
-                // todo: re-evaluate this hack
-                if path.len() > 1 {
-                    write!(out, "crate::")?;
-                }
-                write!(out, "{}", path)
+                write_item_path(out, path)
+            }
+        }
+        Type::Generic(path, arguments) => {
+            write_item_path(out, path)?;
+            write!(out, "<")?;
+            for argument in arguments {
+                fully_qualified_type_ref_impl(out, argument)?;
+                write!(out, ", ")?;
             }
+            write!(out, ">")
         }
+        Type::Parameter(name) => write!(out, "{name}"),
         Type::ConstPointer(tr) => {
             write!(out, "*const ")?;
             fully_qualified_type_ref_impl(out, tr.as_ref())
@@ -636,7 +1511,10 @@ fn fully_qualified_type_ref_impl(out: &mut String, type_ref: &Type) -> Result<()
             fully_qualified_type_ref_impl(out, tr.as_ref())?;
             write!(out, "; {}]", size)
         }
-        Type::Function(calling_convention, args, return_type) => {
+        Type::Function(calling_convention, args, return_type, nullable) => {
+            if *nullable {
+                write!(out, "Option<")?;
+            }
             write!(out, r#"unsafe extern "{calling_convention}" fn ("#)?;
             for (field, type_ref) in args.iter() {
                 write!(out, "{field}: ")?;
@@ -648,11 +1526,24 @@ fn fully_qualified_type_ref_impl(out: &mut String, type_ref: &Type) -> Result<()
                 write!(out, " -> ")?;
                 fully_qualified_type_ref_impl(out, type_ref)?;
             }
+            if *nullable {
+                write!(out, ">")?;
+            }
             Ok(())
         }
     }
 }
 
+fn write_item_path(out: &mut String, path: &ItemPath) -> Result<(), std::fmt::Error> {
+    use std::fmt::Write;
+
+    // todo: re-evaluate this hack
+    if path.len() > 1 {
+        write!(out, "crate::")?;
+    }
+    write!(out, "{}", path)
+}
+
 fn fully_qualified_type_ref(type_ref: &Type) -> Result<String, std::fmt::Error> {
     let mut out = String::new();
     fully_qualified_type_ref_impl(&mut out, type_ref)?;
@@ -690,3 +1581,12 @@ fn hex_literal(value: impl Into<usize>) -> proc_macro2::Literal {
     // https://stackoverflow.com/a/78902864
     proc_macro2::Literal::from_str(&format!("0x{:X}", value.into())).unwrap()
 }
+
+fn signed_hex_literal(value: isize) -> proc_macro2::TokenStream {
+    let literal = hex_literal(value.unsigned_abs());
+    if value < 0 {
+        quote! { -#literal }
+    } else {
+        literal.into_token_stream()
+    }
+}
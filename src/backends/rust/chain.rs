@@ -0,0 +1,21 @@
+// Only `walk` is needed at runtime: the chains themselves are written out as arguments to it
+// wherever a chained item is looked up.
+
+/// Follows a chain of pointers from `start`: the pointer at the current address is read, and
+/// the next offset is added to it. Returns the final address, or `None` if any of the pointers
+/// read along the way are null. Without any offsets, `start` is returned without reading it.
+///
+/// # Safety
+///
+/// Every address read along the chain must be valid to read a pointer from.
+pub unsafe fn walk(start: usize, offsets: &[isize]) -> Option<usize> {
+    let mut address = start;
+    for offset in offsets {
+        let pointer = unsafe { (address as *const usize).read_unaligned() };
+        if pointer == 0 {
+            return None;
+        }
+        address = pointer.wrapping_add_signed(*offset);
+    }
+    Some(address)
+}
@@ -0,0 +1,163 @@
+use super::{
+    chain::walk,
+    scanner::{Scanner, Signature},
+};
+
+use pretty_assertions::assert_eq;
+
+const BASE: usize = 0x1_4000_0000;
+
+/// `mov rax, [rip + 0x10]` followed by `test rax, rax`, after some padding
+const MEMORY: &[u8] = &[
+    0xCC, 0xCC, 0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x85, 0xC0, 0xCC, 0xCC,
+];
+
+fn signature(pattern: &'static [Option<u8>], offset: usize, rip_relative: bool) -> Signature {
+    Signature {
+        name: "test::item",
+        pattern,
+        offset,
+        rip_relative,
+    }
+}
+
+#[test]
+fn can_resolve_signatures_with_wildcards() {
+    let pattern = &[
+        Some(0x48),
+        Some(0x8B),
+        Some(0x05),
+        None,
+        None,
+        None,
+        None,
+        Some(0x48),
+    ];
+    assert_eq!(
+        signature(pattern, 0, false).resolve(MEMORY, BASE),
+        Some(BASE + 2)
+    );
+    assert_eq!(
+        signature(pattern, 3, false).resolve(MEMORY, BASE),
+        Some(BASE + 5)
+    );
+}
+
+#[test]
+fn can_resolve_rip_relative_signatures() {
+    // The displacement is relative to the end of the instruction, at offset 9.
+    let pattern = &[Some(0x48), Some(0x8B), Some(0x05)];
+    assert_eq!(
+        signature(pattern, 3, true).resolve(MEMORY, BASE),
+        Some(BASE + 9 + 0x10)
+    );
+
+    let memory = [0x48, 0x8B, 0x05, 0xF0, 0xFF, 0xFF, 0xFF];
+    assert_eq!(
+        signature(pattern, 3, true).resolve(&memory, BASE),
+        Some(BASE + 7 - 0x10)
+    );
+}
+
+#[test]
+fn will_not_resolve_missing_signatures() {
+    let pattern = &[Some(0x48), Some(0x8B), Some(0x0D)];
+    assert_eq!(signature(pattern, 0, false).resolve(MEMORY, BASE), None);
+
+    // The displacement would run past the end of memory.
+    let pattern = &[Some(0x85), Some(0xC0)];
+    assert_eq!(signature(pattern, 2, true).resolve(MEMORY, BASE), None);
+}
+
+#[test]
+fn can_cache_scanned_addresses() {
+    static SCANNER: Scanner = Scanner::new(&[
+        Signature {
+            name: "test::a",
+            pattern: &[Some(0x48), Some(0x85)],
+            offset: 0,
+            rip_relative: false,
+        },
+        Signature {
+            name: "test::b",
+            pattern: &[Some(0x48), Some(0x8B), Some(0x05)],
+            offset: 3,
+            rip_relative: true,
+        },
+        Signature {
+            name: "test::c",
+            pattern: &[Some(0x90)],
+            offset: 0,
+            rip_relative: false,
+        },
+    ]);
+
+    assert_eq!(SCANNER.scan(MEMORY, BASE), ["test::c"]);
+    assert_eq!(SCANNER.address("test::a"), BASE + 9);
+    assert_eq!(SCANNER.address("test::b"), BASE + 9 + 0x10);
+
+    // Later scans reuse the cached addresses.
+    assert_eq!(SCANNER.scan(&[], 0), ["test::c"]);
+    assert_eq!(SCANNER.address("test::a"), BASE + 9);
+}
+
+#[test]
+#[should_panic(expected = "signatures have not been scanned for; call `scan` first")]
+fn will_panic_on_address_before_scan() {
+    static SCANNER: Scanner = Scanner::new(&[]);
+    SCANNER.address("test::a");
+}
+
+#[test]
+#[should_panic(expected = "the signature for `test::a` was not found")]
+fn will_panic_on_address_of_missing_signature() {
+    static SCANNER: Scanner = Scanner::new(&[Signature {
+        name: "test::a",
+        pattern: &[Some(0x90)],
+        offset: 0,
+        rip_relative: false,
+    }]);
+    SCANNER.scan(MEMORY, BASE);
+    SCANNER.address("test::a");
+}
+
+#[test]
+fn can_walk_pointer_chains() {
+    // `[[root] + size_of::<usize>()] + 4`, which is the second element of `target`
+    let target = [1u32, 2, 3, 4];
+    let node = [0, target.as_ptr() as usize];
+    let root = node.as_ptr() as usize;
+
+    let start = &root as *const usize as usize;
+    let address = unsafe { walk(start, &[size_of::<usize>() as isize, 4]) };
+    assert_eq!(address, Some(&target[1] as *const u32 as usize));
+    assert_eq!(unsafe { *(address.unwrap() as *const u32) }, 2);
+
+    // A chain with one offset reads a single pointer, like a singleton.
+    let address = unsafe { walk(start, &[0]) };
+    assert_eq!(address, Some(node.as_ptr() as usize));
+
+    // A chain without offsets doesn't read anything, unlike a singleton.
+    let address = unsafe { walk(start, &[]) };
+    assert_eq!(address, Some(start));
+
+    // `[last] - 8`, which is also the second element of `target`
+    let last = &target[3] as *const u32 as usize;
+    let address = unsafe { walk(&last as *const usize as usize, &[-8]) };
+    assert_eq!(address, Some(&target[1] as *const u32 as usize));
+}
+
+#[test]
+fn will_not_walk_through_null_pointers() {
+    let node = [0usize, 0];
+    let root = node.as_ptr() as usize;
+    let start = &root as *const usize as usize;
+    assert_eq!(
+        unsafe { walk(start, &[size_of::<usize>() as isize, 4]) },
+        None
+    );
+
+    let root = 0usize;
+    let start = &root as *const usize as usize;
+    assert_eq!(unsafe { walk(start, &[0x10]) }, None);
+}
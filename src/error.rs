@@ -0,0 +1,274 @@
+use std::{fmt, sync::Arc};
+
+use crate::span::{SourceFile, Span};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The kind of a [`Diagnostic`]. Library users should match on this instead of on messages.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A source file could not be parsed
+    Parse,
+    /// A file could not be read or written
+    Io,
+    /// A type could not be found, or type resolution did not terminate
+    UnresolvedType,
+    /// A required attribute was not present
+    MissingAttribute,
+    /// An attribute was present but had an invalid value, or was used in the wrong place
+    InvalidAttribute,
+    /// A field or type violates alignment requirements
+    Alignment,
+    /// A type's calculated size does not match its declared size
+    SizeMismatch,
+    /// A field overlaps with the field before it
+    Overlap,
+    /// A vftable is malformed or is inconsistent with its base class
+    Vftable,
+    /// An item or function was defined more than once
+    DuplicateDefinition,
+    /// A `defaultable` type or enum is missing what it needs to be defaulted
+    NotDefaultable,
+    /// An enum variant has an unsupported value
+    InvalidEnumValue,
+    /// A function has no way to be called
+    MissingImplementation,
+    /// Code generation failed
+    Codegen,
+    /// An invariant within Pyxis was violated
+    Internal,
+    /// A generic type is used incorrectly, or cannot be represented as a generic type
+    InvalidGeneric,
+    /// A constant expression could not be evaluated
+    InvalidExpression,
+    /// A name could refer to items from more than one glob import
+    AmbiguousImport,
+    /// The configuration disagrees with the target being built for
+    InvalidConfig,
+}
+impl ErrorKind {
+    /// A stable code for this kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Parse => "E0001",
+            ErrorKind::Io => "E0002",
+            ErrorKind::UnresolvedType => "E0003",
+            ErrorKind::MissingAttribute => "E0004",
+            ErrorKind::InvalidAttribute => "E0005",
+            ErrorKind::Alignment => "E0006",
+            ErrorKind::SizeMismatch => "E0007",
+            ErrorKind::Overlap => "E0008",
+            ErrorKind::Vftable => "E0009",
+            ErrorKind::DuplicateDefinition => "E0010",
+            ErrorKind::NotDefaultable => "E0011",
+            ErrorKind::InvalidEnumValue => "E0012",
+            ErrorKind::MissingImplementation => "E0013",
+            ErrorKind::Codegen => "E0014",
+            ErrorKind::Internal => "E0015",
+            ErrorKind::InvalidGeneric => "E0016",
+            ErrorKind::InvalidExpression => "E0017",
+            ErrorKind::AmbiguousImport => "E0018",
+            ErrorKind::InvalidConfig => "E0019",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A location within a source file that a [`Diagnostic`] points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub source: Arc<SourceFile>,
+    pub span: Span,
+}
+impl Location {
+    /// Returns `None` if there is no source, or if the span does not point anywhere.
+    pub fn new(source: Option<&Arc<SourceFile>>, span: Span) -> Option<Location> {
+        if span.is_none() {
+            return None;
+        }
+        Some(Location {
+            source: source?.clone(),
+            span,
+        })
+    }
+    /// The file, line and column of the start of the location (e.g. `types/game.pyxis:3:9`)
+    pub fn position(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.source.path.display(),
+            self.span.start.line,
+            self.span.start.column + 1
+        )
+    }
+}
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.source.render(self.span) {
+            Some(rendered) => write!(f, "{rendered}"),
+            None => write!(f, "  --> {}", self.position()),
+        }
+    }
+}
+
+/// A single problem found while processing Pyxis sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: ErrorKind,
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<Location>,
+    pub notes: Vec<String>,
+}
+impl Diagnostic {
+    pub fn error(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Diagnostic {
+            kind,
+            severity: Severity::Error,
+            message: message.into(),
+            location: None,
+            notes: vec![],
+        }
+    }
+    pub fn warning(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Self::error(kind, message)
+        }
+    }
+    pub fn with_location(mut self, location: impl Into<Option<Location>>) -> Self {
+        self.location = location.into();
+        self
+    }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity,
+            self.kind.code(),
+            self.message
+        )?;
+        if let Some(location) = &self.location {
+            write!(f, "\n{location}")?;
+        }
+        for note in &self.notes {
+            write!(f, "\n   = note: {note}")?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for Diagnostic {}
+
+/// One or more [`Diagnostic`]s produced while processing Pyxis sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    diagnostics: Vec<Diagnostic>,
+}
+impl Error {
+    pub fn new(diagnostics: impl Into<Vec<Diagnostic>>) -> Self {
+        Error {
+            diagnostics: diagnostics.into(),
+        }
+    }
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+    /// Adds `note` to every diagnostic in this error.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        let note = note.into();
+        for diagnostic in &mut self.diagnostics {
+            diagnostic.notes.push(note.clone());
+        }
+        self
+    }
+}
+impl From<Diagnostic> for Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Error::new([diagnostic])
+    }
+}
+impl From<Vec<Diagnostic>> for Error {
+    fn from(diagnostics: Vec<Diagnostic>) -> Self {
+        Error::new(diagnostics)
+    }
+}
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Diagnostic::error(ErrorKind::Io, error.to_string()).into()
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, diagnostic) in self.diagnostics.iter().enumerate() {
+            if index > 0 {
+                write!(f, "\n\n")?;
+            }
+            write!(f, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for Error {}
+
+/// Collects diagnostics from independent operations so that they can be reported together.
+#[derive(Debug, Default)]
+pub(crate) struct Diagnostics(Vec<Diagnostic>);
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.0.push(diagnostic);
+    }
+    pub fn extend(&mut self, error: Error) {
+        self.0.extend(error.diagnostics);
+    }
+    /// Records the error of `result` if there is one, returning its value otherwise.
+    pub fn record<T>(&mut self, result: std::result::Result<T, impl Into<Error>>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.extend(error.into());
+                None
+            }
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Returns `Ok(value)` if no diagnostics were recorded, and all of the diagnostics otherwise.
+    pub fn finish<T>(self, value: T) -> Result<T> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error::new(self.0))
+        }
+    }
+}
+impl From<Diagnostics> for Error {
+    fn from(diagnostics: Diagnostics) -> Self {
+        Error::new(diagnostics.0)
+    }
+}
@@ -1,5 +1,9 @@
 use std::{fmt, path::Path};
 
+pub use crate::span::Span;
+
+use crate::error::{Diagnostic, ErrorKind};
+
 pub mod test_aliases {
     pub type M = super::Module;
     pub type ID = super::ItemDefinition;
@@ -7,31 +11,47 @@ pub mod test_aliases {
     pub type TD = super::TypeDefinition;
     pub type ES = super::EnumStatement;
     pub type ED = super::EnumDefinition;
+    pub type UD = super::UnionDefinition;
+    pub type AD = super::AliasDefinition;
     pub type T = super::Type;
     pub type A = super::Attribute;
     pub type As = super::Attributes;
     pub type Ar = super::Argument;
     pub type TF = super::TypeField;
+    pub type FT = super::FunctionType;
     pub type E = super::Expr;
+    pub type BO = super::BinaryOp;
+    pub type UO = super::UnaryOp;
     pub type F = super::Function;
     pub type FB = super::FunctionBlock;
     pub type IP = super::ItemPath;
     pub type B = super::Backend;
     pub type V = super::Visibility;
     pub type EV = super::ExternValue;
+    pub type C = super::Constant;
+    pub type U = super::Use;
+    pub type CP = super::CfgPredicate;
+    pub type SG = super::Signature;
+    pub type IM = super::Image;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Ident(pub String);
+pub struct Ident(pub String, pub Span);
 impl From<&str> for Ident {
     fn from(item: &str) -> Self {
-        Ident(item.to_string())
+        Ident(item.to_string(), Span::none())
     }
 }
 impl Ident {
+    pub fn new(name: impl Into<String>, span: Span) -> Self {
+        Ident(name.into(), span)
+    }
     pub fn as_str(&self) -> &str {
         self.0.as_str()
     }
+    pub fn span(&self) -> Span {
+        self.1
+    }
 }
 impl AsRef<str> for Ident {
     fn as_ref(&self) -> &str {
@@ -48,8 +68,16 @@ impl fmt::Display for Ident {
 pub enum Type {
     ConstPointer(Box<Type>),
     MutPointer(Box<Type>),
-    Array(Box<Type>, usize),
+    /// An array of a type, with a length given by a constant expression
+    Array(Box<Type>, Expr),
     Ident(Ident),
+    /// A path to a type through one or more modules (e.g. `world::Entity`). The path may start
+    /// with `crate`, `self` or `super`.
+    Path(Vec<Ident>),
+    /// A generic type with its arguments; the type is named by a path of one or more segments.
+    Generic(Vec<Ident>, Vec<Type>),
+    /// A function pointer (e.g. `extern "thiscall" fn(a: i32) -> i32`)
+    Function(FunctionType),
     Unknown(usize),
 }
 impl Type {
@@ -73,18 +101,159 @@ impl Type {
     }
 
     pub fn array(self, size: usize) -> Type {
+        Type::Array(Box::new(self), Expr::IntLiteral(size as i128))
+    }
+
+    pub fn array_with_expr(self, size: Expr) -> Type {
         Type::Array(Box::new(self), size)
     }
 
+    pub fn path(path: &str) -> Type {
+        Type::Path(path.split("::").map(Ident::from).collect())
+    }
+
+    pub fn generic(path: &str, arguments: impl Into<Vec<Type>>) -> Type {
+        Type::Generic(
+            path.split("::").map(Ident::from).collect(),
+            arguments.into(),
+        )
+    }
+
     pub fn unknown(size: usize) -> Type {
         Type::Unknown(size)
     }
+
+    /// The span of the identifier this type is built around, if any
+    pub fn span(&self) -> Span {
+        match self {
+            Type::ConstPointer(t) | Type::MutPointer(t) | Type::Array(t, _) => t.span(),
+            Type::Ident(ident) => ident.span(),
+            Type::Path(path) | Type::Generic(path, _) => {
+                path.first().map(|i| i.span()).unwrap_or_default()
+            }
+            Type::Function(function) => function
+                .types()
+                .map(Type::span)
+                .fold(Span::none(), Span::or),
+            Type::Unknown(_) => Span::none(),
+        }
+    }
+}
+impl From<FunctionType> for Type {
+    fn from(item: FunctionType) -> Self {
+        Type::Function(item)
+    }
 }
 impl From<&str> for Type {
     fn from(item: &str) -> Self {
         Type::Ident(item.into())
     }
 }
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::ConstPointer(t) => write!(f, "*const {t}"),
+            Type::MutPointer(t) => write!(f, "*mut {t}"),
+            Type::Array(t, size) => write!(f, "[{t}; {size}]"),
+            Type::Ident(ident) => write!(f, "{ident}"),
+            Type::Path(path) => write_path(f, path),
+            Type::Generic(path, arguments) => {
+                write_path(f, path)?;
+                write!(f, "<")?;
+                for (index, argument) in arguments.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{argument}")?;
+                }
+                write!(f, ">")
+            }
+            Type::Function(function) => write!(f, "{function}"),
+            Type::Unknown(size) => write!(f, "unknown<{size}>"),
+        }
+    }
+}
+
+/// The type of a function pointer. Function pointers can't be null unless they are written
+/// as `Option<fn(...)>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    /// The calling convention given with `extern "..."`, if any
+    pub calling_convention: Option<String>,
+    /// The arguments of the function, which may be unnamed (e.g. `fn(*mut Entity, f32)`)
+    pub arguments: Vec<(Option<Ident>, Type)>,
+    pub return_type: Option<Box<Type>>,
+    pub nullable: bool,
+}
+impl FunctionType {
+    pub fn new<'a>(
+        arguments: impl IntoIterator<Item = (Option<&'a str>, Type)>,
+        return_type: impl Into<Option<Type>>,
+    ) -> Self {
+        Self {
+            calling_convention: None,
+            arguments: arguments
+                .into_iter()
+                .map(|(name, type_)| (name.map(Ident::from), type_))
+                .collect(),
+            return_type: return_type.into().map(Box::new),
+            nullable: false,
+        }
+    }
+    pub fn with_calling_convention(mut self, calling_convention: &str) -> Self {
+        self.calling_convention = Some(calling_convention.to_string());
+        self
+    }
+    pub fn with_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
+    /// The types of the arguments, followed by the return type
+    pub fn types(&self) -> impl Iterator<Item = &Type> {
+        self.arguments
+            .iter()
+            .map(|(_, t)| t)
+            .chain(self.return_type.as_deref())
+    }
+}
+impl fmt::Display for FunctionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nullable {
+            write!(f, "Option<")?;
+        }
+        if let Some(calling_convention) = &self.calling_convention {
+            write!(f, "extern {calling_convention:?} ")?;
+        }
+        write!(f, "fn(")?;
+        for (index, (name, type_)) in self.arguments.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            if let Some(name) = name {
+                write!(f, "{name}: ")?;
+            }
+            write!(f, "{type_}")?;
+        }
+        write!(f, ")")?;
+        if let Some(return_type) = &self.return_type {
+            write!(f, " -> {return_type}")?;
+        }
+        if self.nullable {
+            write!(f, ">")?;
+        }
+        Ok(())
+    }
+}
+
+fn write_path(f: &mut fmt::Formatter<'_>, path: &[Ident]) -> fmt::Result {
+    for (index, segment) in path.iter().enumerate() {
+        if index > 0 {
+            write!(f, "::")?;
+        }
+        write!(f, "{segment}")?;
+    }
+    Ok(())
+}
 
 #[derive(PartialEq, Hash, Eq, Clone, Debug, PartialOrd, Ord)]
 pub struct ItemPathSegment(String);
@@ -92,6 +261,11 @@ impl ItemPathSegment {
     pub fn as_str(&self) -> &str {
         self.0.as_str()
     }
+
+    /// Whether this segment is `crate`, `self` or `super`, which make a path relative
+    pub fn is_relative(&self) -> bool {
+        matches!(self.as_str(), "crate" | "self" | "super")
+    }
 }
 impl From<&str> for ItemPathSegment {
     fn from(value: &str) -> Self {
@@ -109,7 +283,7 @@ impl fmt::Display for ItemPathSegment {
     }
 }
 
-#[derive(PartialEq, Hash, Eq, Clone, Debug, PartialOrd, Ord)]
+#[derive(PartialEq, Hash, Eq, Clone, Debug, PartialOrd, Ord, Default)]
 pub struct ItemPath(Vec<ItemPathSegment>);
 impl ItemPath {
     pub fn empty() -> ItemPath {
@@ -181,14 +355,127 @@ impl From<&str> for ItemPath {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    /// `-x`
+    Negate,
+    /// `!x`
+    Not,
+}
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOp::Negate => write!(f, "-"),
+            UnaryOp::Not => write!(f, "!"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
+}
+impl BinaryOp {
+    /// How tightly this operator binds; operators with a higher precedence are evaluated first.
+    /// This matches Rust's precedence for these operators.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 5,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Shl | BinaryOp::Shr => 3,
+            BinaryOp::BitAnd => 2,
+            BinaryOp::BitXor => 1,
+            BinaryOp::BitOr => 0,
+        }
+    }
+}
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+        };
+        write!(f, "{op}")
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
-    IntLiteral(isize),
+    /// An integer literal, which may be negative (e.g. `-1`) or use the full width of a
+    /// 64-bit address (e.g. `0xFFFF_F800_0000_0000`)
+    IntLiteral(i128),
     StringLiteral(String),
+    /// A constant, named by an identifier
     Ident(Ident),
+    /// A constant, named by a path through one or more modules (e.g. `game::MAX_PLAYERS`)
+    Path(Vec<Ident>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    /// `sizeof(T)`: the size of a type in bytes
+    SizeOf(Box<Type>),
+    /// `alignof(T)`: the alignment of a type in bytes
+    AlignOf(Box<Type>),
+    /// `offsetof(T, field)`: the offset of a field from the start of a type in bytes
+    OffsetOf(Box<Type>, Ident),
 }
 impl Expr {
-    pub fn int_literal(&self) -> Option<isize> {
+    pub fn unary(op: UnaryOp, expr: Expr) -> Expr {
+        Expr::Unary(op, Box::new(expr))
+    }
+    pub fn binary(lhs: Expr, op: BinaryOp, rhs: Expr) -> Expr {
+        Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+    }
+    pub fn size_of(type_: impl Into<Type>) -> Expr {
+        Expr::SizeOf(Box::new(type_.into()))
+    }
+    pub fn align_of(type_: impl Into<Type>) -> Expr {
+        Expr::AlignOf(Box::new(type_.into()))
+    }
+    pub fn offset_of(type_: impl Into<Type>, field: &str) -> Expr {
+        Expr::OffsetOf(Box::new(type_.into()), field.into())
+    }
+    /// The types this expression refers to (e.g. the `T` of `sizeof(T)`)
+    pub fn types(&self) -> Vec<&Type> {
+        match self {
+            Expr::IntLiteral(_) | Expr::StringLiteral(_) | Expr::Ident(_) | Expr::Path(_) => {
+                vec![]
+            }
+            Expr::Unary(_, expr) => expr.types(),
+            Expr::Binary(lhs, _, rhs) => lhs.types().into_iter().chain(rhs.types()).collect(),
+            Expr::SizeOf(type_) | Expr::AlignOf(type_) | Expr::OffsetOf(type_, _) => vec![type_],
+        }
+    }
+    /// The paths of the constants this expression refers to
+    pub fn constants(&self) -> Vec<&[Ident]> {
+        match self {
+            Expr::IntLiteral(_) | Expr::StringLiteral(_) => vec![],
+            Expr::Ident(ident) => vec![std::slice::from_ref(ident)],
+            Expr::Path(path) => vec![path],
+            Expr::Unary(_, expr) => expr.constants(),
+            Expr::Binary(lhs, _, rhs) => {
+                lhs.constants().into_iter().chain(rhs.constants()).collect()
+            }
+            Expr::SizeOf(_) | Expr::AlignOf(_) | Expr::OffsetOf(_, _) => vec![],
+        }
+    }
+    pub fn int_literal(&self) -> Option<i128> {
         match self {
             Expr::IntLiteral(value) => Some(*value),
             _ => None,
@@ -201,14 +488,165 @@ impl Expr {
         }
     }
 }
+impl From<i128> for Expr {
+    fn from(value: i128) -> Self {
+        Expr::IntLiteral(value)
+    }
+}
+impl From<&str> for Expr {
+    fn from(value: &str) -> Self {
+        Expr::StringLiteral(value.into())
+    }
+}
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Operands that are themselves binary operations are parenthesised, so that the
+        // output does not depend on precedence.
+        let operand = |f: &mut fmt::Formatter<'_>, expr: &Expr| match expr {
+            Expr::Binary(..) => write!(f, "({expr})"),
+            _ => write!(f, "{expr}"),
+        };
+        match self {
+            Expr::IntLiteral(value) => write!(f, "{value}"),
+            Expr::StringLiteral(value) => write!(f, "{value:?}"),
+            Expr::Ident(ident) => write!(f, "{ident}"),
+            Expr::Path(path) => write_path(f, path),
+            Expr::Unary(op, expr) => {
+                write!(f, "{op}")?;
+                operand(f, expr)
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                operand(f, lhs)?;
+                write!(f, " {op} ")?;
+                operand(f, rhs)
+            }
+            Expr::SizeOf(type_) => write!(f, "sizeof({type_})"),
+            Expr::AlignOf(type_) => write!(f, "alignof({type_})"),
+            Expr::OffsetOf(type_, field) => write!(f, "offsetof({type_}, {field})"),
+        }
+    }
+}
+
+/// A condition on the configuration being built for, as used by `#[cfg(...)]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CfgPredicate {
+    /// Whether a key is set (e.g. `debug`)
+    Ident(Ident),
+    /// Whether a key is set to a value (e.g. `pointer_size = 8` or `version = "1.2"`)
+    Assign(Ident, Expr),
+    /// Whether every predicate holds (e.g. `all(a, b)`)
+    All(Vec<CfgPredicate>),
+    /// Whether any predicate holds (e.g. `any(a, b)`)
+    Any(Vec<CfgPredicate>),
+    /// Whether the predicate does not hold (e.g. `not(a)`)
+    Not(Box<CfgPredicate>),
+}
+impl CfgPredicate {
+    pub fn ident(name: &str) -> Self {
+        CfgPredicate::Ident(name.into())
+    }
+    pub fn assign(name: &str, value: impl Into<Expr>) -> Self {
+        CfgPredicate::Assign(name.into(), value.into())
+    }
+    pub fn all(predicates: impl IntoIterator<Item = CfgPredicate>) -> Self {
+        CfgPredicate::All(predicates.into_iter().collect())
+    }
+    pub fn any(predicates: impl IntoIterator<Item = CfgPredicate>) -> Self {
+        CfgPredicate::Any(predicates.into_iter().collect())
+    }
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(predicate: CfgPredicate) -> Self {
+        CfgPredicate::Not(Box::new(predicate))
+    }
+}
+
+/// A byte pattern that locates an item in memory, as used by `#[signature(...)]`
+/// (e.g. `signature("48 8B 05 ?? ?? ?? ??", offset = 3, rip_relative)`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Signature {
+    /// The bytes to match, where `None` matches any byte
+    pub pattern: Vec<Option<u8>>,
+    /// The offset from the start of the match to the item, or to its displacement
+    pub offset: Option<Expr>,
+    /// Whether the item is located by a 32-bit displacement at the offset, relative to the
+    /// end of the displacement
+    pub rip_relative: bool,
+}
+impl Signature {
+    pub fn new(pattern: impl IntoIterator<Item = Option<u8>>) -> Self {
+        Signature {
+            pattern: pattern.into_iter().collect(),
+            offset: None,
+            rip_relative: false,
+        }
+    }
+    pub fn with_offset(mut self, offset: impl Into<Expr>) -> Self {
+        self.offset = Some(offset.into());
+        self
+    }
+    pub fn with_rip_relative(mut self, rip_relative: bool) -> Self {
+        self.rip_relative = rip_relative;
+        self
+    }
+}
+
+/// An executable or library that addresses can be relative to, as declared by a module's
+/// `#![image(...)]` (e.g. `image("game.exe", preferred_base = 0x140000000)`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    pub name: String,
+    /// The address the image is built to be loaded at, which the module's absolute addresses
+    /// assume
+    pub preferred_base: Option<Expr>,
+}
+impl Image {
+    pub fn new(name: impl Into<String>) -> Self {
+        Image {
+            name: name.into(),
+            preferred_base: None,
+        }
+    }
+    pub fn with_preferred_base(mut self, preferred_base: impl Into<Expr>) -> Self {
+        self.preferred_base = Some(preferred_base.into());
+        self
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Attribute {
     Ident(Ident),
     Function(Ident, Vec<Expr>),
     Assign(Ident, Expr),
+    /// `#[cfg(predicate)]`: the annotated element only exists if the predicate holds
+    Cfg(Ident, CfgPredicate),
+    /// A function attribute whose arguments are all named
+    /// (e.g. `address(x86 = 0x401000, x64 = 0x140001000)`)
+    Keyed(Ident, Vec<(Ident, Expr)>),
+    /// `#[signature(...)]`: the annotated element is located by scanning memory for a pattern
+    Signature(Ident, Signature),
+    /// `#![image(...)]`: the module's addresses are relative to where an image is loaded
+    Image(Ident, Image),
+    /// An address attribute that follows a chain of pointers from a starting address, adding
+    /// an offset after each one (e.g. `singleton(chain(0x140001000, 0x48, 0x8))`)
+    Chain(Ident, Vec<Expr>),
 }
 impl Attribute {
+    pub fn name(&self) -> &Ident {
+        match self {
+            Attribute::Ident(ident) => ident,
+            Attribute::Function(ident, _) => ident,
+            Attribute::Assign(ident, _) => ident,
+            Attribute::Cfg(ident, _) => ident,
+            Attribute::Keyed(ident, _) => ident,
+            Attribute::Signature(ident, _) => ident,
+            Attribute::Image(ident, _) => ident,
+            Attribute::Chain(ident, _) => ident,
+        }
+    }
+    pub fn span(&self) -> Span {
+        self.name().span()
+    }
+
     // Ident attributes
     pub fn copyable() -> Self {
         Attribute::Ident("copyable".into())
@@ -229,6 +667,12 @@ impl Attribute {
     pub fn packed() -> Self {
         Attribute::Ident("packed".into())
     }
+    pub fn monomorphise() -> Self {
+        Attribute::Ident("monomorphise".into())
+    }
+    pub fn newtype() -> Self {
+        Attribute::Ident("newtype".into())
+    }
 
     // Function attributes
     pub fn function(&self) -> Option<(&Ident, &Vec<Expr>)> {
@@ -237,23 +681,62 @@ impl Attribute {
             _ => None,
         }
     }
-    pub fn integer_fn(name: &str, value: isize) -> Self {
-        Attribute::Function(name.into(), vec![Expr::IntLiteral(value)])
+    pub fn integer_fn(name: &str, value: i128) -> Self {
+        Self::expr_fn(name, Expr::IntLiteral(value))
+    }
+    pub fn expr_fn(name: &str, expr: Expr) -> Self {
+        Attribute::Function(name.into(), vec![expr])
     }
     pub fn address(address: usize) -> Self {
-        Self::integer_fn("address", address as isize)
+        Self::integer_fn("address", address as i128)
     }
     pub fn size(size: usize) -> Self {
-        Self::integer_fn("size", size as isize)
+        Self::integer_fn("size", size as i128)
     }
     pub fn align(align: usize) -> Self {
-        Self::integer_fn("align", align as isize)
+        Self::integer_fn("align", align as i128)
+    }
+    pub fn bits(bits: usize) -> Self {
+        Self::integer_fn("bits", bits as i128)
     }
     pub fn singleton(address: usize) -> Self {
-        Self::integer_fn("singleton", address as isize)
+        Self::integer_fn("singleton", address as i128)
     }
     pub fn index(index: usize) -> Self {
-        Self::integer_fn("index", index as isize)
+        Self::integer_fn("index", index as i128)
+    }
+    pub fn address_per_target(addresses: impl IntoIterator<Item = (&'static str, usize)>) -> Self {
+        Self::keyed("address", addresses)
+    }
+    pub fn address_per_version(addresses: impl IntoIterator<Item = (&'static str, usize)>) -> Self {
+        Self::keyed("address", addresses)
+    }
+    pub fn address_relative(rva: usize, image: Option<&str>) -> Self {
+        let mut entries = vec![("rva".into(), Expr::IntLiteral(rva as i128))];
+        if let Some(image) = image {
+            entries.push(("image".into(), Expr::StringLiteral(image.into())));
+        }
+        Attribute::Keyed("address".into(), entries)
+    }
+    pub fn singleton_per_version(
+        addresses: impl IntoIterator<Item = (&'static str, usize)>,
+    ) -> Self {
+        Self::keyed("singleton", addresses)
+    }
+    fn keyed(name: &str, entries: impl IntoIterator<Item = (&'static str, usize)>) -> Self {
+        Attribute::Keyed(
+            name.into(),
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), Expr::IntLiteral(value as i128)))
+                .collect(),
+        )
+    }
+    pub fn virtual_base() -> Self {
+        Attribute::Function("base".into(), vec![Expr::Ident("virtual".into())])
+    }
+    pub fn layout(abi: &str) -> Self {
+        Attribute::Function("layout".into(), vec![Expr::Ident(abi.into())])
     }
     pub fn calling_convention(name: &str) -> Self {
         Attribute::Function(
@@ -272,6 +755,38 @@ impl Attribute {
     pub fn doc(doc: &str) -> Self {
         Attribute::Assign("doc".into(), Expr::StringLiteral(doc.into()))
     }
+
+    // Cfg attributes
+    pub fn cfg(predicate: CfgPredicate) -> Self {
+        Attribute::Cfg("cfg".into(), predicate)
+    }
+
+    // Signature attributes
+    pub fn signature(signature: Signature) -> Self {
+        Attribute::Signature("signature".into(), signature)
+    }
+
+    // Chain attributes
+    pub fn address_chain(start: usize, offsets: impl IntoIterator<Item = isize>) -> Self {
+        Self::chain("address", start, offsets)
+    }
+    pub fn singleton_chain(start: usize, offsets: impl IntoIterator<Item = isize>) -> Self {
+        Self::chain("singleton", start, offsets)
+    }
+    fn chain(name: &str, start: usize, offsets: impl IntoIterator<Item = isize>) -> Self {
+        Attribute::Chain(
+            name.into(),
+            std::iter::once(start as i128)
+                .chain(offsets.into_iter().map(|offset| offset as i128))
+                .map(Expr::IntLiteral)
+                .collect(),
+        )
+    }
+
+    // Image attributes
+    pub fn image(image: Image) -> Self {
+        Attribute::Image("image".into(), image)
+    }
 }
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Attributes(pub Vec<Attribute>);
@@ -307,7 +822,10 @@ impl FromIterator<Attribute> for Attributes {
     }
 }
 impl Attributes {
-    pub fn doc(&self, path: &ItemPath) -> anyhow::Result<Option<String>> {
+    pub fn iter(&self) -> std::slice::Iter<'_, Attribute> {
+        self.0.iter()
+    }
+    pub fn doc(&self, path: &ItemPath) -> Result<Option<String>, Diagnostic> {
         let mut doc = None;
         for attr in &self.0 {
             let Some((key, value)) = attr.assign() else {
@@ -318,7 +836,10 @@ impl Attributes {
             }
 
             let Some(value) = value.string_literal() else {
-                anyhow::bail!("doc attribute for `{path}` must be a string literal");
+                return Err(Diagnostic::error(
+                    ErrorKind::InvalidAttribute,
+                    format!("doc attribute for `{path}` must be a string literal"),
+                ));
             };
 
             let doc = doc.get_or_insert_with(String::new);
@@ -329,6 +850,21 @@ impl Attributes {
         }
         Ok(doc)
     }
+    /// The image declared by a module's `#![image(...)]`, if any
+    pub fn image(&self, path: &ItemPath) -> Result<Option<Image>, Diagnostic> {
+        let mut images = self.0.iter().filter_map(|attr| match attr {
+            Attribute::Image(_, image) => Some(image),
+            _ => None,
+        });
+        let image = images.next().cloned();
+        if images.next().is_some() {
+            return Err(Diagnostic::error(
+                ErrorKind::InvalidAttribute,
+                format!("module `{path}` has more than one image"),
+            ));
+        }
+        Ok(image)
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -378,6 +914,9 @@ impl Function {
         self.return_type = Some(return_type.into());
         self
     }
+    pub fn span(&self) -> Span {
+        self.name.span()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -401,6 +940,13 @@ impl From<(Ident, Expr)> for ExprField {
 pub enum TypeField {
     Field(Visibility, Ident, Type),
     Vftable(Vec<Function>),
+    /// The vftable of the subobject for a base, overriding the base's (e.g.
+    /// `vftable(base_b) { ... }`)
+    BaseVftable(Ident, Vec<Function>),
+    /// An inline group of fields that share the same memory (e.g. `pub union { a: u32, b: f32 }`)
+    Union(Visibility, Vec<TypeStatement>),
+    /// A field whose type is defined inline (e.g. `pub pos: type { x: f32, y: f32 }`)
+    Nested(Visibility, Ident, ItemDefinitionInner),
 }
 impl TypeField {
     pub fn field(
@@ -415,6 +961,28 @@ impl TypeField {
         TypeField::Vftable(functions.into_iter().collect())
     }
 
+    pub fn base_vftable(
+        base: impl Into<Ident>,
+        functions: impl IntoIterator<Item = Function>,
+    ) -> TypeField {
+        TypeField::BaseVftable(base.into(), functions.into_iter().collect())
+    }
+
+    pub fn union(
+        visibility: Visibility,
+        statements: impl IntoIterator<Item = TypeStatement>,
+    ) -> TypeField {
+        TypeField::Union(visibility, statements.into_iter().collect())
+    }
+
+    pub fn nested(
+        visibility: Visibility,
+        name: impl Into<Ident>,
+        inner: impl Into<ItemDefinitionInner>,
+    ) -> TypeField {
+        TypeField::Nested(visibility, name.into(), inner.into())
+    }
+
     pub fn is_vftable(&self) -> bool {
         matches!(self, TypeField::Vftable(_))
     }
@@ -424,38 +992,82 @@ impl TypeField {
 pub struct TypeStatement {
     pub field: TypeField,
     pub attributes: Attributes,
+    pub span: Span,
 }
 impl TypeStatement {
     pub fn field((visibility, name): (Visibility, &str), type_: Type) -> TypeStatement {
         TypeStatement {
             field: TypeField::Field(visibility, name.into(), type_),
             attributes: Default::default(),
+            span: Span::none(),
         }
     }
     pub fn vftable(functions: impl IntoIterator<Item = Function>) -> TypeStatement {
         TypeStatement {
             field: TypeField::vftable(functions),
             attributes: Default::default(),
+            span: Span::none(),
+        }
+    }
+    pub fn base_vftable(
+        base: &str,
+        functions: impl IntoIterator<Item = Function>,
+    ) -> TypeStatement {
+        TypeStatement {
+            field: TypeField::base_vftable(base, functions),
+            attributes: Default::default(),
+            span: Span::none(),
+        }
+    }
+    pub fn union(
+        visibility: Visibility,
+        statements: impl IntoIterator<Item = TypeStatement>,
+    ) -> TypeStatement {
+        TypeStatement {
+            field: TypeField::union(visibility, statements),
+            attributes: Default::default(),
+            span: Span::none(),
+        }
+    }
+    pub fn nested(
+        (visibility, name): (Visibility, &str),
+        inner: impl Into<ItemDefinitionInner>,
+    ) -> TypeStatement {
+        TypeStatement {
+            field: TypeField::nested(visibility, name, inner),
+            attributes: Default::default(),
+            span: Span::none(),
         }
     }
     pub fn with_attributes(mut self, attributes: impl Into<Attributes>) -> Self {
         self.attributes = attributes.into();
         self
     }
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TypeDefinition {
+    /// The names of the generic parameters of this type (e.g. `T` in `type Array<T>`)
+    pub generics: Vec<Ident>,
     pub statements: Vec<TypeStatement>,
     pub attributes: Attributes,
 }
 impl TypeDefinition {
     pub fn new(statements: impl Into<Vec<TypeStatement>>) -> Self {
         Self {
+            generics: vec![],
             statements: statements.into(),
             attributes: Default::default(),
         }
     }
+    pub fn with_generics<'a>(mut self, generics: impl IntoIterator<Item = &'a str>) -> Self {
+        self.generics = generics.into_iter().map(Ident::from).collect();
+        self
+    }
     pub fn with_attributes(mut self, attributes: impl Into<Attributes>) -> Self {
         self.attributes = attributes.into();
         self
@@ -467,13 +1079,16 @@ pub struct EnumStatement {
     pub name: Ident,
     pub expr: Option<Expr>,
     pub attributes: Attributes,
+    pub span: Span,
 }
 impl EnumStatement {
     pub fn new(name: Ident, expr: Option<Expr>) -> EnumStatement {
+        let span = name.span();
         EnumStatement {
             name,
             expr,
             attributes: Default::default(),
+            span,
         }
     }
     pub fn field(name: &str) -> EnumStatement {
@@ -486,6 +1101,10 @@ impl EnumStatement {
         self.attributes = attributes.into();
         self
     }
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -512,10 +1131,50 @@ impl EnumDefinition {
     }
 }
 
+/// A type whose fields all start at its beginning, and so share the same memory
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnionDefinition {
+    pub statements: Vec<TypeStatement>,
+    pub attributes: Attributes,
+}
+impl UnionDefinition {
+    pub fn new(statements: impl Into<Vec<TypeStatement>>) -> Self {
+        Self {
+            statements: statements.into(),
+            attributes: Default::default(),
+        }
+    }
+    pub fn with_attributes(mut self, attributes: impl Into<Attributes>) -> Self {
+        self.attributes = attributes.into();
+        self
+    }
+}
+
+/// Another name for a type (e.g. `type Handle = u32;`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AliasDefinition {
+    pub type_: Type,
+    pub attributes: Attributes,
+}
+impl AliasDefinition {
+    pub fn new(type_: impl Into<Type>) -> Self {
+        Self {
+            type_: type_.into(),
+            attributes: Default::default(),
+        }
+    }
+    pub fn with_attributes(mut self, attributes: impl Into<Attributes>) -> Self {
+        self.attributes = attributes.into();
+        self
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ItemDefinitionInner {
     Type(TypeDefinition),
     Enum(EnumDefinition),
+    Union(UnionDefinition),
+    Alias(AliasDefinition),
 }
 impl From<TypeDefinition> for ItemDefinitionInner {
     fn from(item: TypeDefinition) -> Self {
@@ -527,6 +1186,16 @@ impl From<EnumDefinition> for ItemDefinitionInner {
         ItemDefinitionInner::Enum(item)
     }
 }
+impl From<UnionDefinition> for ItemDefinitionInner {
+    fn from(item: UnionDefinition) -> Self {
+        ItemDefinitionInner::Union(item)
+    }
+}
+impl From<AliasDefinition> for ItemDefinitionInner {
+    fn from(item: AliasDefinition) -> Self {
+        ItemDefinitionInner::Alias(item)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ItemDefinition {
@@ -614,11 +1283,103 @@ impl ExternValue {
     }
 }
 
+/// A named constant (e.g. `pub const MAX_PLAYERS: u32 = 64;`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Constant {
+    pub visibility: Visibility,
+    pub name: Ident,
+    pub type_: Type,
+    pub value: Expr,
+    pub attributes: Attributes,
+}
+impl Constant {
+    pub fn new(
+        (visibility, name): (Visibility, &str),
+        type_: impl Into<Type>,
+        value: impl Into<Expr>,
+    ) -> Self {
+        Self {
+            visibility,
+            name: name.into(),
+            type_: type_.into(),
+            value: value.into(),
+            attributes: Default::default(),
+        }
+    }
+    pub fn with_attributes(mut self, attributes: impl Into<Attributes>) -> Self {
+        self.attributes = attributes.into();
+        self
+    }
+}
+
+/// An item or module imported by a `use`. Grouped imports (e.g. `use a::{B, C};`) are
+/// flattened into one `Use` per imported item.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Use {
+    pub visibility: Visibility,
+    pub path: ItemPath,
+    /// The name the item is imported as, if it was renamed (e.g. `use a::B as C;`)
+    pub alias: Option<Ident>,
+    /// Whether everything within `path` is imported (e.g. `use a::*;`)
+    pub is_glob: bool,
+    pub span: Span,
+}
+impl Use {
+    pub fn new(path: impl Into<ItemPath>) -> Self {
+        Self {
+            visibility: Visibility::Private,
+            path: path.into(),
+            alias: None,
+            is_glob: false,
+            span: Span::none(),
+        }
+    }
+    pub fn glob(path: impl Into<ItemPath>) -> Self {
+        Self {
+            is_glob: true,
+            ..Self::new(path)
+        }
+    }
+    pub fn with_alias(mut self, alias: impl Into<Ident>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+impl From<ItemPath> for Use {
+    fn from(path: ItemPath) -> Self {
+        Use::new(path)
+    }
+}
+impl fmt::Display for Use {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.visibility == Visibility::Public {
+            write!(f, "pub ")?;
+        }
+        write!(f, "use {}", self.path)?;
+        if self.is_glob {
+            write!(f, "::*")?;
+        }
+        if let Some(alias) = &self.alias {
+            write!(f, " as {alias}")?;
+        }
+        write!(f, ";")
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Module {
-    pub uses: Vec<ItemPath>,
+    pub uses: Vec<Use>,
     pub extern_types: Vec<(Ident, Attributes)>,
     pub extern_values: Vec<ExternValue>,
+    pub constants: Vec<Constant>,
     pub definitions: Vec<ItemDefinition>,
     pub impls: Vec<FunctionBlock>,
     pub backends: Vec<Backend>,
@@ -629,8 +1390,8 @@ impl Module {
         Self::default()
     }
 
-    pub fn with_uses(mut self, uses: impl Into<Vec<ItemPath>>) -> Self {
-        self.uses = uses.into();
+    pub fn with_uses(mut self, uses: impl IntoIterator<Item = impl Into<Use>>) -> Self {
+        self.uses = uses.into_iter().map(Into::into).collect();
         self
     }
     pub fn with_extern_types(mut self, extern_types: impl Into<Vec<(Ident, Attributes)>>) -> Self {
@@ -641,6 +1402,10 @@ impl Module {
         self.extern_values = extern_values.into();
         self
     }
+    pub fn with_constants(mut self, constants: impl Into<Vec<Constant>>) -> Self {
+        self.constants = constants.into();
+        self
+    }
     pub fn with_definitions(mut self, definitions: impl Into<Vec<ItemDefinition>>) -> Self {
         self.definitions = definitions.into();
         self
@@ -1,33 +1,119 @@
 use std::path::Path;
 
 pub mod backends;
+pub mod error;
 pub mod grammar;
 pub mod parser;
 pub mod semantic;
+pub mod span;
 
 pub(crate) mod util;
 
-pub fn build(in_dir: &Path, out_dir: &Path, pointer_size: usize) -> anyhow::Result<()> {
-    let mut semantic_state = semantic::SemanticState::new(pointer_size);
+pub use error::{Diagnostic, Error, ErrorKind, Location, Result, Severity};
+pub use semantic::{Config, Target};
 
-    for path in glob::glob(&format!("{}/**/*.pyxis", in_dir.display()))?.filter_map(Result::ok) {
-        semantic_state.add_file(Path::new(&in_dir), &path)?;
+/// Builds every `.pyxis` file in `in_dir` into Rust modules in `out_dir`.
+///
+/// Items, fields and functions with `#[cfg(...)]` predicates are only included if their
+/// predicates hold for `config`. If `config` declares versions, the `versions` module that
+/// selects between them at runtime is also written to `out_dir`, as is the `signatures` module
+/// that scans for the items located by `#[signature(...)]`, the `images` module that
+/// resolves where images were loaded for image-relative addresses and the `chains` module that
+/// follows pointer chains.
+///
+/// Errors are collected from every file before returning, so a single run reports
+/// as many problems as possible.
+pub fn build(in_dir: &Path, out_dir: &Path, target: Target, config: Config) -> Result<()> {
+    let versions = config.versions().to_vec();
+    let mut semantic_state = semantic::SemanticState::new(target).with_config(config);
+    let mut diagnostics = error::Diagnostics::new();
+
+    let pattern = format!("{}/**/*.pyxis", in_dir.display());
+    let mut paths = glob::glob(&pattern)
+        .map_err(|e| {
+            Diagnostic::error(
+                ErrorKind::Io,
+                format!("invalid input directory `{}`: {e}", in_dir.display()),
+            )
+        })?
+        .filter_map(std::result::Result::ok)
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        diagnostics.record(semantic_state.add_file(in_dir, &path));
+    }
+    if !diagnostics.is_empty() {
+        // Resolution would only report follow-on errors from the files that failed to load.
+        return Err(diagnostics.into());
     }
 
     let resolved_semantic_state = semantic_state.build()?;
-    for (key, module) in resolved_semantic_state.modules() {
-        backends::rust::write_module(Path::new(&out_dir), key, &resolved_semantic_state, module)?;
+    let mut modules = resolved_semantic_state.modules().iter().collect::<Vec<_>>();
+    modules.sort_by_key(|(key, _)| *key);
+    let signatures = resolved_semantic_state.signatures();
+    let images = resolved_semantic_state.images();
+    let is_reserved = |name: &str| modules.iter().any(|(key, _)| key.to_string() == name);
+    if !versions.is_empty() {
+        if is_reserved("versions") {
+            return Err(Diagnostic::error(
+                ErrorKind::Codegen,
+                "the module `versions` is reserved for the version table when versions are declared",
+            )
+            .into());
+        }
+        diagnostics.record(backends::rust::write_versions(out_dir, &versions));
+    }
+    if !signatures.is_empty() {
+        if is_reserved("signatures") {
+            return Err(Diagnostic::error(
+                ErrorKind::Codegen,
+                "the module `signatures` is reserved for the signature scanner when signatures are used",
+            )
+            .into());
+        }
+        diagnostics.record(backends::rust::write_signatures(out_dir, &signatures));
+    }
+    if !images.is_empty() {
+        if is_reserved("images") {
+            return Err(Diagnostic::error(
+                ErrorKind::Codegen,
+                "the module `images` is reserved for the image table when addresses are relative to images",
+            )
+            .into());
+        }
+        diagnostics.record(backends::rust::write_images(out_dir, &images));
+    }
+    if resolved_semantic_state.has_chains() {
+        if is_reserved("chains") {
+            return Err(Diagnostic::error(
+                ErrorKind::Codegen,
+                "the module `chains` is reserved for walking pointer chains when they are used",
+            )
+            .into());
+        }
+        diagnostics.record(backends::rust::write_chains(out_dir));
+    }
+    for (key, module) in modules {
+        diagnostics.record(backends::rust::write_module(
+            out_dir,
+            key,
+            &resolved_semantic_state,
+            module,
+        ));
     }
 
-    Ok(())
+    diagnostics.finish(())
 }
 
-pub fn build_script(out_dir: Option<&Path>) -> anyhow::Result<()> {
+pub fn build_script(out_dir: Option<&Path>, config: Config) -> anyhow::Result<()> {
     println!("cargo:rerun-if-changed=types");
 
     let cargo_out_dir = std::env::var("OUT_DIR")?;
     let out_dir = out_dir.unwrap_or(Path::new(&cargo_out_dir));
-    let pointer_size = std::env::var("CARGO_CFG_TARGET_POINTER_WIDTH")?.parse::<usize>()? / 8;
+    let arch = std::env::var("CARGO_CFG_TARGET_ARCH")?;
+    let os = std::env::var("CARGO_CFG_TARGET_OS")?;
+    let target = Target::from_rust_target(&arch, &os)
+        .ok_or_else(|| anyhow::anyhow!("there is no pyxis target for `{arch}` on `{os}`"))?;
 
-    build(Path::new("types"), out_dir, pointer_size)
+    Ok(build(Path::new("types"), out_dir, target, config)?)
 }
@@ -1,6 +1,8 @@
 use syn::{
-    braced, bracketed, parenthesized,
-    parse::{Parse, ParseStream, Result},
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{discouraged::Speculative, Parse, ParseStream, Result},
     punctuated::Punctuated,
     Token,
 };
@@ -18,42 +20,85 @@ mod kw {
     syn::custom_keyword!(prologue);
     syn::custom_keyword!(epilogue);
     syn::custom_keyword!(vftable);
+    syn::custom_keyword!(sizeof);
+    syn::custom_keyword!(alignof);
+    syn::custom_keyword!(offsetof);
+    syn::custom_keyword!(chain);
 }
 
 impl Parse for Ident {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.peek(Token![_]) {
-            input.parse::<Token![_]>()?;
-            Ok(Ident("_".to_string()))
+            let underscore = input.parse::<Token![_]>()?;
+            Ok(Ident("_".to_string(), underscore.span.into()))
         } else if input.peek(syn::Ident) {
-            Ok(Ident(input.parse::<syn::Ident>()?.to_string()))
+            let ident = input.parse::<syn::Ident>()?;
+            Ok(Ident(ident.to_string(), ident.span().into()))
         } else {
             Err(input.error("expected identifier"))
         }
     }
 }
 
-fn parse_type_ident(input: ParseStream) -> Result<String> {
-    // dodgy hack to "support" generics for now
-    let ident: syn::Ident = input.parse()?;
-    let mut name = ident.to_string();
+/// Parses the name of an extern type, including any generic parameters (e.g. `TArray<T>`).
+///
+/// Extern types are opaque, so their layout can't depend on their arguments;
+/// only the name is kept.
+fn parse_extern_type_name(input: ParseStream) -> Result<Ident> {
+    let ident: Ident = input.parse()?;
+    parse_generic_parameters(input)?;
+    Ok(ident)
+}
+
+/// Parses the generic parameters of a type definition (e.g. `<T, U>`), if present.
+fn parse_generic_parameters(input: ParseStream) -> Result<Vec<Ident>> {
+    let mut parameters = vec![];
+    if input.peek(Token![<]) {
+        input.parse::<Token![<]>()?;
+        loop {
+            parameters.push(input.parse::<Ident>()?);
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+        input.parse::<Token![>]>()?;
+    }
+    Ok(parameters)
+}
 
+/// Parses the arguments of a generic type, starting from the opening `<` (e.g. `<u32, *mut Foo>`).
+fn parse_generic_arguments(input: ParseStream) -> Result<Vec<Type>> {
+    input.parse::<Token![<]>()?;
+    let mut arguments = vec![];
     loop {
-        if input.peek(Token![<]) {
-            input.parse::<Token![<]>()?;
-            name += "<";
-        } else if input.peek(syn::Ident) {
-            let ident: syn::Ident = input.parse()?;
-            name += &ident.to_string();
-        } else if input.peek(Token![>]) {
+        arguments.push(input.parse()?);
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+        if input.peek(Token![>]) {
             input.parse::<Token![>]>()?;
-            name += ">";
-        } else {
-            break Ok(name);
+            break Ok(arguments);
         }
     }
 }
 
+/// Whether the next token is `crate`, `self` or `super`, which can start a path
+fn peek_relative_path_segment(input: ParseStream) -> bool {
+    input.peek(Token![crate]) || input.peek(Token![self]) || input.peek(Token![super])
+}
+
+/// Parses a segment of a path, including `crate`, `self` and `super`.
+fn parse_path_segment(input: ParseStream) -> Result<Ident> {
+    if peek_relative_path_segment(input) {
+        let ident = input.call(syn::Ident::parse_any)?;
+        Ok(Ident(ident.to_string(), ident.span().into()))
+    } else {
+        input.parse()
+    }
+}
+
 impl Parse for ItemPath {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut item_path = ItemPath::empty();
@@ -63,12 +108,10 @@ impl Parse for ItemPath {
             // all types
             // that is to say, `use lol<lol>::lol` should not parse, but
             // `use lol::lol<lol>` should
-            if input.peek(syn::Ident) {
-                item_path.push(parse_type_ident(input)?.into());
+            if input.peek(syn::Ident) || peek_relative_path_segment(input) {
+                item_path.push(parse_path_segment(input)?.0.into());
             } else if input.peek(Token![::]) {
                 input.parse::<Token![::]>()?;
-            } else if input.peek(Token![super]) {
-                return Err(input.error("super not supported"));
             } else {
                 break;
             }
@@ -87,8 +130,30 @@ impl Parse for Type {
             input.parse::<Token![>]>()?;
 
             Ok(Type::Unknown(size))
-        } else if lookahead.peek(syn::Ident) {
-            Ok(Type::Ident(parse_type_ident(input)?.as_str().into()))
+        } else if lookahead.peek(syn::Ident) || peek_relative_path_segment(input) {
+            let is_relative = peek_relative_path_segment(input);
+            let mut path = vec![parse_path_segment(input)?];
+            while input.peek(Token![::]) {
+                input.parse::<Token![::]>()?;
+                path.push(parse_path_segment(input)?);
+            }
+
+            if input.peek(Token![<]) {
+                let arguments = parse_generic_arguments(input)?;
+                // `Option<fn(...)>` is a function pointer that may be null.
+                match (path.as_slice(), arguments.as_slice()) {
+                    ([option], [Type::Function(function)])
+                        if option.as_str() == "Option" && !function.nullable =>
+                    {
+                        Ok(Type::Function(function.clone().with_nullable(true)))
+                    }
+                    _ => Ok(Type::Generic(path, arguments)),
+                }
+            } else if path.len() == 1 && !is_relative {
+                Ok(Type::Ident(path.remove(0)))
+            } else {
+                Ok(Type::Path(path))
+            }
         } else if lookahead.peek(Token![*]) {
             input.parse::<Token![*]>()?;
 
@@ -102,35 +167,184 @@ impl Parse for Type {
             } else {
                 Err(lookahead.error())
             }
+        } else if lookahead.peek(Token![extern]) || lookahead.peek(Token![fn]) {
+            Ok(Type::Function(input.parse()?))
         } else if lookahead.peek(syn::token::Bracket) {
             let content;
             bracketed!(content in input);
 
             let type_: Type = content.parse()?;
             content.parse::<Token![;]>()?;
-            let size: syn::LitInt = content.parse()?;
-            let size: usize = size.base10_parse()?;
-            Ok(Type::Array(Box::new(type_), size))
+            Ok(Type::Array(Box::new(type_), content.parse()?))
         } else {
             Err(lookahead.error())
         }
     }
 }
 
+impl Parse for FunctionType {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let calling_convention = if input.peek(Token![extern]) {
+            input.parse::<Token![extern]>()?;
+            Some(input.parse::<syn::LitStr>()?.value())
+        } else {
+            None
+        };
+        input.parse::<Token![fn]>()?;
+
+        let content;
+        parenthesized!(content in input);
+        let mut arguments = vec![];
+        while !content.is_empty() {
+            let name = if content.peek(syn::Ident)
+                && content.peek2(Token![:])
+                && !content.peek2(Token![::])
+            {
+                let name: Ident = content.parse()?;
+                content.parse::<Token![:]>()?;
+                Some(name)
+            } else {
+                None
+            };
+            arguments.push((name, content.parse()?));
+            if content.is_empty() {
+                break;
+            }
+            content.parse::<Token![,]>()?;
+        }
+
+        let return_type = if input.peek(Token![->]) {
+            input.parse::<Token![->]>()?;
+            Some(Box::new(input.parse()?))
+        } else {
+            None
+        };
+
+        Ok(FunctionType {
+            calling_convention,
+            arguments,
+            return_type,
+            nullable: false,
+        })
+    }
+}
+
 impl Parse for Expr {
     fn parse(input: ParseStream) -> Result<Self> {
-        let lookahead = input.lookahead1();
-        if lookahead.peek(syn::Ident) {
-            Ok(Expr::Ident(input.parse()?))
-        } else if lookahead.peek(syn::LitInt) {
-            let lit: syn::LitInt = input.parse()?;
-            Ok(Expr::IntLiteral(lit.base10_parse()?))
-        } else if lookahead.peek(syn::LitStr) {
-            let lit: syn::LitStr = input.parse()?;
-            Ok(Expr::StringLiteral(lit.value()))
+        parse_binary_expr(input, 0)
+    }
+}
+
+/// Parses an expression made up of binary operations that bind at least as tightly as
+/// `min_precedence`. Operators of the same precedence are left-associative.
+fn parse_binary_expr(input: ParseStream, min_precedence: u8) -> Result<Expr> {
+    let mut lhs = parse_unary_expr(input)?;
+    while let Some(op) = peek_binary_op(input) {
+        if op.precedence() < min_precedence {
+            break;
+        }
+        match op {
+            BinaryOp::Add => drop(input.parse::<Token![+]>()?),
+            BinaryOp::Sub => drop(input.parse::<Token![-]>()?),
+            BinaryOp::Mul => drop(input.parse::<Token![*]>()?),
+            BinaryOp::Div => drop(input.parse::<Token![/]>()?),
+            BinaryOp::Rem => drop(input.parse::<Token![%]>()?),
+            BinaryOp::Shl => drop(input.parse::<Token![<<]>()?),
+            BinaryOp::Shr => drop(input.parse::<Token![>>]>()?),
+            BinaryOp::BitAnd => drop(input.parse::<Token![&]>()?),
+            BinaryOp::BitOr => drop(input.parse::<Token![|]>()?),
+            BinaryOp::BitXor => drop(input.parse::<Token![^]>()?),
+        }
+        let rhs = parse_binary_expr(input, op.precedence() + 1)?;
+        lhs = Expr::binary(lhs, op, rhs);
+    }
+    Ok(lhs)
+}
+
+fn peek_binary_op(input: ParseStream) -> Option<BinaryOp> {
+    // Two-character operators must be checked before the operators they start with.
+    if input.peek(Token![<<]) {
+        Some(BinaryOp::Shl)
+    } else if input.peek(Token![>>]) {
+        Some(BinaryOp::Shr)
+    } else if input.peek(Token![+]) {
+        Some(BinaryOp::Add)
+    } else if input.peek(Token![-]) && !input.peek(Token![->]) {
+        Some(BinaryOp::Sub)
+    } else if input.peek(Token![*]) {
+        Some(BinaryOp::Mul)
+    } else if input.peek(Token![/]) {
+        Some(BinaryOp::Div)
+    } else if input.peek(Token![%]) {
+        Some(BinaryOp::Rem)
+    } else if input.peek(Token![&]) {
+        Some(BinaryOp::BitAnd)
+    } else if input.peek(Token![|]) {
+        Some(BinaryOp::BitOr)
+    } else if input.peek(Token![^]) {
+        Some(BinaryOp::BitXor)
+    } else {
+        None
+    }
+}
+
+fn parse_unary_expr(input: ParseStream) -> Result<Expr> {
+    // Negative integer literals are parsed as literals, not as negations.
+    if input.peek(Token![-]) && !input.peek2(syn::LitInt) {
+        input.parse::<Token![-]>()?;
+        Ok(Expr::unary(UnaryOp::Negate, parse_unary_expr(input)?))
+    } else if input.peek(Token![!]) {
+        input.parse::<Token![!]>()?;
+        Ok(Expr::unary(UnaryOp::Not, parse_unary_expr(input)?))
+    } else {
+        parse_primary_expr(input)
+    }
+}
+
+fn parse_primary_expr(input: ParseStream) -> Result<Expr> {
+    let lookahead = input.lookahead1();
+    if lookahead.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        content.parse()
+    } else if lookahead.peek(kw::sizeof) {
+        input.parse::<kw::sizeof>()?;
+        let content;
+        parenthesized!(content in input);
+        Ok(Expr::SizeOf(content.parse()?))
+    } else if lookahead.peek(kw::alignof) {
+        input.parse::<kw::alignof>()?;
+        let content;
+        parenthesized!(content in input);
+        Ok(Expr::AlignOf(content.parse()?))
+    } else if lookahead.peek(kw::offsetof) {
+        input.parse::<kw::offsetof>()?;
+        let content;
+        parenthesized!(content in input);
+        let type_ = content.parse()?;
+        content.parse::<Token![,]>()?;
+        Ok(Expr::OffsetOf(type_, content.parse()?))
+    } else if lookahead.peek(syn::Ident) || peek_relative_path_segment(input) {
+        let is_relative = peek_relative_path_segment(input);
+        let mut path = vec![parse_path_segment(input)?];
+        while input.peek(Token![::]) {
+            input.parse::<Token![::]>()?;
+            path.push(parse_path_segment(input)?);
+        }
+
+        if path.len() == 1 && !is_relative {
+            Ok(Expr::Ident(path.remove(0)))
         } else {
-            Err(lookahead.error())
+            Ok(Expr::Path(path))
         }
+    } else if lookahead.peek(syn::LitInt) {
+        let lit: syn::LitInt = input.parse()?;
+        Ok(Expr::IntLiteral(lit.base10_parse()?))
+    } else if lookahead.peek(syn::LitStr) {
+        let lit: syn::LitStr = input.parse()?;
+        Ok(Expr::StringLiteral(lit.value()))
+    } else {
+        Err(lookahead.error())
     }
 }
 
@@ -140,17 +354,131 @@ impl Attribute {
     fn parse_many(input: ParseStream, expect_module_attributes: bool) -> Result<Attributes> {
         enum AttributePart {
             Ident(Ident),
-            Function { name: Ident, arguments: Vec<Expr> },
-            Assign { name: Ident, value: Expr },
+            Function {
+                name: Ident,
+                arguments: Vec<Expr>,
+            },
+            Assign {
+                name: Ident,
+                value: Expr,
+            },
+            Cfg {
+                name: Ident,
+                predicate: CfgPredicate,
+            },
+            Keyed {
+                name: Ident,
+                arguments: Vec<(Ident, Expr)>,
+            },
+            Signature {
+                name: Ident,
+                signature: Signature,
+            },
+            Image {
+                name: Ident,
+                image: Image,
+            },
+            Chain {
+                name: Ident,
+                arguments: Vec<Expr>,
+            },
         }
         impl Parse for AttributePart {
             fn parse(input: ParseStream) -> Result<Self> {
-                let name = input.parse()?;
+                let name: Ident = input.parse()?;
+
+                if name.as_str() == "cfg" && input.peek(syn::token::Paren) {
+                    let content2;
+                    parenthesized!(content2 in input);
+
+                    let predicate = content2.parse()?;
+                    content2.parse::<Option<Token![,]>>()?;
+                    if !content2.is_empty() {
+                        return Err(content2.error("`cfg` takes exactly one predicate"));
+                    }
+
+                    Ok(AttributePart::Cfg { name, predicate })
+                } else if name.as_str() == "signature" && input.peek(syn::token::Paren) {
+                    let content2;
+                    parenthesized!(content2 in input);
+
+                    Ok(AttributePart::Signature {
+                        name,
+                        signature: content2.parse()?,
+                    })
+                } else if name.as_str() == "image" && input.peek(syn::token::Paren) {
+                    let content2;
+                    parenthesized!(content2 in input);
+
+                    Ok(AttributePart::Image {
+                        name,
+                        image: content2.parse()?,
+                    })
+                } else if name.as_str() == "base" && input.peek(syn::token::Paren) {
+                    // `virtual` is a keyword, so it can't be parsed as an expression.
+                    let content2;
+                    parenthesized!(content2 in input);
 
-                if input.peek(syn::token::Paren) {
+                    let keyword = content2.parse::<Token![virtual]>()?;
+                    content2.parse::<Option<Token![,]>>()?;
+                    if !content2.is_empty() {
+                        return Err(content2.error("`base` only takes `virtual`"));
+                    }
+
+                    Ok(AttributePart::Function {
+                        name,
+                        arguments: vec![Expr::Ident(Ident(
+                            "virtual".to_string(),
+                            keyword.span.into(),
+                        ))],
+                    })
+                } else if input.peek(syn::token::Paren) {
                     let content2;
                     parenthesized!(content2 in input);
 
+                    if content2.peek(kw::chain) && content2.peek2(syn::token::Paren) {
+                        let span = content2.span();
+                        content2.parse::<kw::chain>()?;
+                        let content3;
+                        parenthesized!(content3 in content2);
+                        if content3.peek(syn::Ident) && content3.peek2(Token![=]) {
+                            return Err(content3.error(
+                                "`chain` only takes one starting address and its offsets; it cannot have an entry for each version or target",
+                            ));
+                        }
+                        let arguments: Punctuated<_, Token![,]> =
+                            content3.parse_terminated(Expr::parse, Token![,])?;
+                        content2.parse::<Option<Token![,]>>()?;
+                        if !content2.is_empty() {
+                            return Err(content2.error("`chain` must be the only argument"));
+                        }
+                        if arguments.is_empty() {
+                            return Err(syn::Error::new(
+                                span,
+                                "`chain` takes a starting address, followed by its offsets",
+                            ));
+                        }
+
+                        return Ok(AttributePart::Chain {
+                            name,
+                            arguments: Vec::from_iter(arguments),
+                        });
+                    }
+
+                    if content2.peek(syn::Ident) && content2.peek2(Token![=]) {
+                        let arguments: Punctuated<_, Token![,]> = content2.parse_terminated(
+                            |input| {
+                                let key = input.parse()?;
+                                input.parse::<Token![=]>()?;
+                                Ok((key, input.parse()?))
+                            },
+                            Token![,],
+                        )?;
+                        let arguments = Vec::from_iter(arguments);
+
+                        return Ok(AttributePart::Keyed { name, arguments });
+                    }
+
                     let arguments: Punctuated<_, Token![,]> =
                         content2.parse_terminated(Expr::parse, Token![,])?;
                     let arguments = Vec::from_iter(arguments);
@@ -198,6 +526,13 @@ impl Attribute {
                         Attribute::Function(name, arguments)
                     }
                     AttributePart::Assign { name, value } => Attribute::Assign(name, value),
+                    AttributePart::Cfg { name, predicate } => Attribute::Cfg(name, predicate),
+                    AttributePart::Keyed { name, arguments } => Attribute::Keyed(name, arguments),
+                    AttributePart::Signature { name, signature } => {
+                        Attribute::Signature(name, signature)
+                    }
+                    AttributePart::Image { name, image } => Attribute::Image(name, image),
+                    AttributePart::Chain { name, arguments } => Attribute::Chain(name, arguments),
                 });
             }
 
@@ -208,6 +543,124 @@ impl Attribute {
     }
 }
 
+impl Parse for Signature {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let pattern_lit: syn::LitStr = input.parse()?;
+        let mut pattern = vec![];
+        for byte in pattern_lit.value().split_whitespace() {
+            if byte == "?" || byte == "??" {
+                pattern.push(None);
+                continue;
+            }
+            let is_hex = byte.len() == 2 && byte.chars().all(|c| c.is_ascii_hexdigit());
+            match u8::from_str_radix(byte, 16) {
+                Ok(byte) if is_hex => pattern.push(Some(byte)),
+                _ => {
+                    return Err(syn::Error::new(
+                        pattern_lit.span(),
+                        format!(
+                            "invalid byte `{byte}` in signature; expected two hex digits or `??`"
+                        ),
+                    ))
+                }
+            }
+        }
+        if pattern.is_empty() {
+            return Err(syn::Error::new(
+                pattern_lit.span(),
+                "signature must have at least one byte",
+            ));
+        }
+
+        let mut signature = Signature::new(pattern);
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let span = input.span();
+            let option: Ident = input.parse()?;
+            match option.as_str() {
+                "offset" => {
+                    input.parse::<Token![=]>()?;
+                    signature.offset = Some(input.parse()?);
+                }
+                "rip_relative" => signature.rip_relative = true,
+                _ => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!(
+                        "unknown signature option `{option}`; expected `offset` or `rip_relative`"
+                    ),
+                    ))
+                }
+            }
+        }
+        Ok(signature)
+    }
+}
+
+impl Parse for Image {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name: syn::LitStr = input.parse()?;
+        let mut image = Image::new(name.value());
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let span = input.span();
+            let option: Ident = input.parse()?;
+            match option.as_str() {
+                "preferred_base" => {
+                    input.parse::<Token![=]>()?;
+                    image.preferred_base = Some(input.parse()?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("unknown image option `{option}`; expected `preferred_base`"),
+                    ))
+                }
+            }
+        }
+        Ok(image)
+    }
+}
+
+impl Parse for CfgPredicate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let name: Ident = input.parse()?;
+
+        if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let predicates: Punctuated<_, Token![,]> =
+                content.parse_terminated(CfgPredicate::parse, Token![,])?;
+            let mut predicates = Vec::from_iter(predicates);
+
+            match name.as_str() {
+                "all" => Ok(CfgPredicate::All(predicates)),
+                "any" => Ok(CfgPredicate::Any(predicates)),
+                "not" if predicates.len() == 1 => {
+                    Ok(CfgPredicate::Not(Box::new(predicates.remove(0))))
+                }
+                "not" => Err(syn::Error::new(span, "`not` takes exactly one predicate")),
+                _ => Err(syn::Error::new(
+                    span,
+                    format!("unknown cfg predicate `{name}`; expected `all`, `any` or `not`"),
+                )),
+            }
+        } else if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Ok(CfgPredicate::Assign(name, input.parse()?))
+        } else {
+            Ok(CfgPredicate::Ident(name))
+        }
+    }
+}
+
 impl Parse for Visibility {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.peek(Token![pub]) {
@@ -294,6 +747,19 @@ impl Parse for TypeField {
         if input.peek(kw::vftable) {
             input.parse::<kw::vftable>()?;
 
+            // `vftable(base) { ... }` overrides the vftable of a base's subobject.
+            let base = if input.peek(syn::token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                let base: Ident = content.parse()?;
+                if !content.is_empty() {
+                    return Err(content.error("`vftable` only takes the name of a base"));
+                }
+                Some(base)
+            } else {
+                None
+            };
+
             let content;
             braced!(content in input);
 
@@ -301,11 +767,28 @@ impl Parse for TypeField {
                 content.parse_terminated(Function::parse, Token![;])?;
             let functions = Vec::from_iter(functions);
 
-            Ok(TypeField::Vftable(functions))
+            Ok(match base {
+                Some(base) => TypeField::BaseVftable(base, functions),
+                None => TypeField::Vftable(functions),
+            })
         } else {
             let visibility: Visibility = input.parse()?;
+            if input.peek(Token![union]) && input.peek2(syn::token::Brace) {
+                input.parse::<Token![union]>()?;
+                return Ok(TypeField::Union(visibility, parse_union_fields(input)?));
+            }
+
             let name: Ident = input.parse()?;
             input.parse::<Token![:]>()?;
+            if input.peek(Token![type]) && input.peek2(syn::token::Brace) {
+                input.parse::<Token![type]>()?;
+                let definition = TypeDefinition::new(parse_type_statements(input)?);
+                return Ok(TypeField::nested(visibility, name, definition));
+            } else if input.peek(Token![union]) && input.peek2(syn::token::Brace) {
+                input.parse::<Token![union]>()?;
+                let definition = UnionDefinition::new(parse_union_fields(input)?);
+                return Ok(TypeField::nested(visibility, name, definition));
+            }
             Ok(TypeField::Field(visibility, name, input.parse()?))
         }
     }
@@ -314,36 +797,101 @@ impl Parse for TypeField {
 impl Parse for TypeStatement {
     fn parse(input: ParseStream) -> Result<Self> {
         let attributes = Attribute::parse_many(input, false)?;
+        let span = input.span().into();
         Ok(TypeStatement {
             field: input.parse()?,
             attributes,
+            span,
         })
     }
 }
 
-fn parse_type_definition(input: ParseStream, attributes: Attributes) -> Result<TypeDefinition> {
+fn parse_type_definition(
+    input: ParseStream,
+    generics: Vec<Ident>,
+    attributes: Attributes,
+) -> Result<TypeDefinition> {
     let statements = if input.peek(Token![;]) {
         input.parse::<Token![;]>()?;
         vec![]
     } else {
-        let content;
-        braced!(content in input);
-
-        let statements: Punctuated<TypeStatement, Token![,]> =
-            content.parse_terminated(TypeStatement::parse, Token![,])?;
-        Vec::from_iter(statements)
+        parse_type_statements(input)?
     };
 
     Ok(TypeDefinition {
+        generics,
         statements,
         attributes,
     })
 }
 
+fn parse_alias_definition(
+    input: ParseStream,
+    generics: &[Ident],
+    attributes: Attributes,
+) -> Result<AliasDefinition> {
+    let eq = input.parse::<Token![=]>()?;
+    if !generics.is_empty() {
+        return Err(syn::Error::new(eq.span, "type aliases cannot be generic"));
+    }
+    let type_ = input.parse()?;
+    input.parse::<Token![;]>()?;
+
+    Ok(AliasDefinition { type_, attributes })
+}
+
+fn parse_union_definition(input: ParseStream, attributes: Attributes) -> Result<UnionDefinition> {
+    Ok(UnionDefinition {
+        statements: parse_union_fields(input)?,
+        attributes,
+    })
+}
+
+/// Parses the fields of a union, which can't be empty or include a vftable or an inline union
+fn parse_union_fields(input: ParseStream) -> Result<Vec<TypeStatement>> {
+    let content;
+    let braces = braced!(content in input);
+
+    let mut statements = vec![];
+    while !content.is_empty() {
+        let span = content.span();
+        let statement: TypeStatement = content.parse()?;
+        if !matches!(
+            statement.field,
+            TypeField::Field(..) | TypeField::Nested(..)
+        ) {
+            return Err(syn::Error::new(span, "unions can only contain fields"));
+        }
+        statements.push(statement);
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<Token![,]>()?;
+    }
+    if statements.is_empty() {
+        return Err(syn::Error::new(
+            braces.span.join(),
+            "unions must have at least one field",
+        ));
+    }
+    Ok(statements)
+}
+
+/// Parses the braced, comma-separated fields of a type or union
+fn parse_type_statements(input: ParseStream) -> Result<Vec<TypeStatement>> {
+    let content;
+    braced!(content in input);
+
+    let statements: Punctuated<TypeStatement, Token![,]> =
+        content.parse_terminated(TypeStatement::parse, Token![,])?;
+    Ok(Vec::from_iter(statements))
+}
+
 impl Parse for EnumStatement {
     fn parse(input: ParseStream) -> Result<Self> {
         let attributes = Attribute::parse_many(input, false)?;
 
+        let span = input.span().into();
         let name: Ident = input.parse()?;
         let expr = if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
@@ -352,7 +900,9 @@ impl Parse for EnumStatement {
             None
         };
 
-        Ok(EnumStatement::new(name, expr).with_attributes(attributes))
+        Ok(EnumStatement::new(name, expr)
+            .with_attributes(attributes)
+            .with_span(span))
     }
 }
 
@@ -386,10 +936,13 @@ fn parse_item_definition(
     let (name, inner) = if lookahead.peek(Token![type]) {
         input.parse::<Token![type]>()?;
         let name = input.parse()?;
-        (
-            name,
-            parse_type_definition(input, attributes).map(ItemDefinitionInner::from),
-        )
+        let generics = parse_generic_parameters(input)?;
+        let inner = if input.peek(Token![=]) {
+            parse_alias_definition(input, &generics, attributes).map(ItemDefinitionInner::from)
+        } else {
+            parse_type_definition(input, generics, attributes).map(ItemDefinitionInner::from)
+        };
+        (name, inner)
     } else if lookahead.peek(Token![enum]) {
         input.parse::<Token![enum]>()?;
         let name = input.parse()?;
@@ -397,6 +950,13 @@ fn parse_item_definition(
             name,
             parse_enum_definition(input, attributes).map(ItemDefinitionInner::from),
         )
+    } else if lookahead.peek(Token![union]) {
+        input.parse::<Token![union]>()?;
+        let name = input.parse()?;
+        (
+            name,
+            parse_union_definition(input, attributes).map(ItemDefinitionInner::from),
+        )
     } else {
         return Err(lookahead.error());
     };
@@ -466,95 +1026,246 @@ fn parse_backend(input: ParseStream) -> Result<Backend> {
 }
 
 impl Parse for Module {
+    /// Parses a module, failing with every syntax error in the module if there were any.
+    /// Use [`parse_str_with_recovery`] to also get the items that did parse.
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut uses = vec![];
-        let mut extern_types = vec![];
-        let mut extern_values = vec![];
-        let mut definitions = vec![];
-        let mut impls = vec![];
-        let mut backends = vec![];
+        let (module, errors) = parse_module_with_recovery(input)?;
+        match errors.into_iter().reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        }) {
+            Some(error) => Err(error),
+            None => Ok(module),
+        }
+    }
+}
 
-        // Parse all module attributes before parsing any other statements
-        let module_attributes = Attribute::parse_many(input, true)?;
+/// Parses a module, recovering from errors by skipping to the next top-level item.
+/// Returns the items that parsed successfully alongside every error encountered.
+fn parse_module_with_recovery(input: ParseStream) -> Result<(Module, Vec<syn::Error>)> {
+    let mut module = Module::default();
+    let mut errors = vec![];
+
+    // Parse all module attributes before parsing any other statements
+    let fork = input.fork();
+    match Attribute::parse_many(&fork, true) {
+        Ok(attributes) => {
+            input.advance_to(&fork);
+            module.attributes = attributes;
+        }
+        Err(error) => {
+            errors.push(error);
+            skip_failed_item(input, &fork)?;
+        }
+    }
 
-        // Exhaust all of our declarations
-        while !input.is_empty() {
-            // Attribute-less statements
-            if input.peek(Token![use]) {
-                input.parse::<Token![use]>()?;
-                let item_path = input.parse()?;
-                input.parse::<Token![;]>()?;
-                uses.push(item_path);
-                continue;
-            } else if input.peek(kw::backend) {
-                backends.push(parse_backend(input)?);
-                continue;
+    // Exhaust all of our declarations
+    while !input.is_empty() {
+        // Each item is parsed on a fork so that the leftover tokens of a failed item
+        // aren't reported as unexpected once the whole module has been parsed.
+        let fork = input.fork();
+        match parse_module_item(&fork, &mut module) {
+            Ok(()) => input.advance_to(&fork),
+            Err(error) => {
+                errors.push(error);
+                let in_attributes = Attribute::parse_many(&input.fork(), false).is_err();
+                skip_failed_item(input, &fork)?;
+
+                // Recovery stops at the rest of an item whose attributes failed to parse. Drop
+                // it as well, so that it isn't reported again for missing those attributes.
+                if in_attributes {
+                    let fork = input.fork();
+                    if parse_module_item(&fork, &mut Module::default()).is_ok() {
+                        input.advance_to(&fork);
+                    }
+                }
             }
+        }
+    }
 
-            // Attributed statements
-            let attributes = Attribute::parse_many(input, false)?;
-            if input.peek(Token![extern]) && input.peek2(Token![type]) {
-                input.parse::<Token![extern]>()?;
-                input.parse::<Token![type]>()?;
-                let ident: Ident = parse_type_ident(input)?.as_str().into();
-                input.parse::<Token![;]>()?;
-
-                extern_types.push((ident, attributes));
-                continue;
-            } else if input.peek(Token![impl]) {
-                input.parse::<Token![impl]>()?;
-                let name: Ident = input.parse()?;
+    Ok((module, errors))
+}
 
-                let content;
-                braced!(content in input);
-                let functions: Punctuated<Function, Token![;]> =
-                    content.parse_terminated(Function::parse, Token![;])?;
-                let functions = Vec::from_iter(functions);
-
-                impls.push(FunctionBlock {
-                    name,
-                    functions,
-                    attributes,
-                });
-                continue;
+/// Skips past the tokens that a failed parse on `fork` consumed, and then on to the start
+/// of something that looks like a top-level item. Always skips at least one token, so that
+/// an item that fails on its first token can't stall recovery.
+fn skip_failed_item(input: ParseStream, fork: ParseStream) -> Result<()> {
+    let failed_at = fork.cursor();
+    input.step(|cursor| {
+        let mut rest = cursor.token_tree().map_or(*cursor, |(_, next)| next);
+        while rest < failed_at {
+            let Some((_, next)) = rest.token_tree() else {
+                break;
+            };
+            rest = next;
+        }
+        while let Some((token_tree, next)) = rest.token_tree() {
+            let is_item_start = match &token_tree {
+                proc_macro2::TokenTree::Ident(ident) => [
+                    "pub", "type", "enum", "union", "impl", "extern", "use", "backend", "const",
+                ]
+                .iter()
+                .any(|keyword| ident == keyword),
+                proc_macro2::TokenTree::Punct(punct) => punct.as_char() == '#',
+                _ => false,
+            };
+            if is_item_start {
+                break;
             }
+            rest = next;
+        }
+        Ok(((), rest))
+    })
+}
 
-            // Attributed statements with visibility
-            let visibility: Visibility = input.parse()?;
-            if input.peek(Token![extern]) {
-                input.parse::<Token![extern]>()?;
-                let name: Ident = input.parse()?;
-                input.parse::<Token![:]>()?;
-                let type_: Type = input.parse()?;
-                input.parse::<Token![;]>()?;
-
-                extern_values.push(ExternValue {
-                    visibility,
-                    name,
-                    type_,
-                    attributes,
-                });
-                continue;
-            } else if input.peek(Token![type]) || input.peek(Token![enum]) {
-                definitions.push(parse_item_definition(input, visibility, attributes)?);
-                continue;
+fn parse_module_item(input: ParseStream, module: &mut Module) -> Result<()> {
+    // Attribute-less statements
+    if input.peek(Token![use]) {
+        return parse_use(input, Visibility::Private, module);
+    } else if input.peek(kw::backend) {
+        module.backends.push(parse_backend(input)?);
+        return Ok(());
+    }
+
+    // Attributed statements
+    let attributes = Attribute::parse_many(input, false)?;
+    if input.peek(Token![extern]) && input.peek2(Token![type]) {
+        input.parse::<Token![extern]>()?;
+        input.parse::<Token![type]>()?;
+        let ident: Ident = parse_extern_type_name(input)?;
+        input.parse::<Token![;]>()?;
+
+        module.extern_types.push((ident, attributes));
+        return Ok(());
+    } else if input.peek(Token![impl]) {
+        input.parse::<Token![impl]>()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+        let functions: Punctuated<Function, Token![;]> =
+            content.parse_terminated(Function::parse, Token![;])?;
+        let functions = Vec::from_iter(functions);
+
+        module.impls.push(FunctionBlock {
+            name,
+            functions,
+            attributes,
+        });
+        return Ok(());
+    }
+
+    // Attributed statements with visibility
+    let visibility: Visibility = input.parse()?;
+    if input.peek(Token![use]) {
+        return parse_use(input, visibility, module);
+    } else if input.peek(Token![extern]) {
+        input.parse::<Token![extern]>()?;
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let type_: Type = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        module.extern_values.push(ExternValue {
+            visibility,
+            name,
+            type_,
+            attributes,
+        });
+        return Ok(());
+    } else if input.peek(Token![const]) {
+        input.parse::<Token![const]>()?;
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let type_: Type = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value: Expr = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        module.constants.push(Constant {
+            visibility,
+            name,
+            type_,
+            value,
+            attributes,
+        });
+        return Ok(());
+    } else if input.peek(Token![type]) || input.peek(Token![enum]) || input.peek(Token![union]) {
+        module
+            .definitions
+            .push(parse_item_definition(input, visibility, attributes)?);
+        return Ok(());
+    }
+
+    Err(input.error("unexpected keyword"))
+}
+
+fn parse_use(input: ParseStream, visibility: Visibility, module: &mut Module) -> Result<()> {
+    input.parse::<Token![use]>()?;
+    parse_use_tree(input, visibility, &ItemPath::empty(), &mut module.uses)?;
+    input.parse::<Token![;]>()?;
+    Ok(())
+}
+
+/// Parses the tree of a `use` (e.g. `game::{world::Entity, math::*}`), pushing one `Use` for
+/// each item it imports. Each path is prefixed with `prefix`.
+fn parse_use_tree(
+    input: ParseStream,
+    visibility: Visibility,
+    prefix: &ItemPath,
+    uses: &mut Vec<Use>,
+) -> Result<()> {
+    let span = input.span().into();
+    let mut path = prefix.clone();
+    loop {
+        if input.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            uses.push(Use::glob(path).with_visibility(visibility).with_span(span));
+            return Ok(());
+        }
+
+        if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            while !content.is_empty() {
+                parse_use_tree(&content, visibility, &path, uses)?;
+                if content.is_empty() {
+                    break;
+                }
+                content.parse::<Token![,]>()?;
             }
+            return Ok(());
+        }
 
-            return Err(input.error("unexpected keyword"));
+        // `self` within a group refers to the module the group is in (e.g. `use a::{self, B};`)
+        let segment = parse_path_segment(input)?;
+        if segment.as_str() != "self" || path.is_empty() {
+            path.push(segment.0.into());
         }
 
-        Ok(Module {
-            uses,
-            extern_types,
-            extern_values,
-            definitions,
-            impls,
-            backends,
-            attributes: module_attributes,
-        })
+        if input.peek(Token![::]) {
+            input.parse::<Token![::]>()?;
+            continue;
+        }
+
+        let mut use_ = Use::new(path).with_visibility(visibility).with_span(span);
+        if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            use_ = use_.with_alias(input.parse::<Ident>()?);
+        }
+        uses.push(use_);
+        return Ok(());
     }
 }
 
 pub fn parse_str(input: &str) -> Result<Module> {
     syn::parse_str(input)
 }
+
+/// Parses `input`, returning the partial module made up of every item that parsed
+/// successfully, as well as every syntax error in `input`.
+///
+/// Fails outright only if `input` could not be tokenized (e.g. an unclosed delimiter).
+pub fn parse_str_with_recovery(input: &str) -> Result<(Module, Vec<syn::Error>)> {
+    syn::parse::Parser::parse_str(parse_module_with_recovery, input)
+}
@@ -1,4 +1,7 @@
-use crate::{grammar::test_aliases::*, parser::parse_str};
+use crate::{
+    grammar::{test_aliases::*, ItemDefinitionInner, TypeField},
+    parser::{parse_str, parse_str_with_recovery},
+};
 
 use pretty_assertions::assert_eq;
 
@@ -45,6 +48,35 @@ fn can_parse_vftable() {
     assert_eq!(parse_str(text).unwrap(), ast);
 }
 
+#[test]
+fn can_parse_base_vftable() {
+    let text = r#"
+        type Derived {
+            #[base]
+            pub base_b: BaseB,
+            vftable(base_b) {
+                pub fn destructor(&mut self);
+            }
+        }
+        "#;
+
+    let ast = M::new().with_definitions([ID::new(
+        (V::Private, "Derived"),
+        TD::new([
+            TS::field((V::Public, "base_b"), T::ident("BaseB")).with_attributes([A::base()]),
+            TS::base_vftable("base_b", [F::new((V::Public, "destructor"), [Ar::MutSelf])]),
+        ]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+    assert_eq!(
+        parse_str(r#"type A { vftable(base_a, base_b) {} }"#)
+            .unwrap_err()
+            .to_string(),
+        "failed to parse type A: `vftable` only takes the name of a base"
+    );
+}
+
 #[test]
 fn can_parse_vehicle_types() {
     let text = r#"
@@ -149,7 +181,10 @@ fn can_parse_spawn_manager() {
                     (V::Public, "engine_spawn_vehicle"),
                     [
                         Ar::MutSelf,
-                        Ar::named("vehicle", T::ident("SharedPtr<Vehicle>").mut_pointer()),
+                        Ar::named(
+                            "vehicle",
+                            T::generic("SharedPtr", [T::ident("Vehicle")]).mut_pointer(),
+                        ),
                         Ar::named("context", T::ident("i32")),
                         Ar::named("unk1", T::ident("StdString").mut_pointer()),
                         Ar::named("model_id", T::ident("u32").const_pointer()),
@@ -158,7 +193,7 @@ fn can_parse_spawn_manager() {
                     ],
                 )
                 .with_attributes([A::address(0x84C_4C0)])
-                .with_return_type(T::ident("SharedPtr<Vehicle>").mut_pointer()),
+                .with_return_type(T::generic("SharedPtr", [T::ident("Vehicle")]).mut_pointer()),
                 F::new(
                     (V::Public, "request_vehicle_model"),
                     [
@@ -197,32 +232,89 @@ fn can_parse_address_field() {
 #[test]
 fn can_parse_use() {
     let text = r#"
-        use hello::TestType<Hey>;
+        use hello::TestType;
         type Test {
             test: TestType<Hey>,
         }
         "#;
 
     let ast = M::new()
-        .with_uses([IP::from("hello::TestType<Hey>")])
+        .with_uses([IP::from("hello::TestType")])
         .with_definitions([ID::new(
             (V::Private, "Test"),
-            TD::new([TS::field((V::Private, "test"), T::ident("TestType<Hey>"))]),
+            TD::new([TS::field(
+                (V::Private, "test"),
+                T::generic("TestType", [T::ident("Hey")]),
+            )]),
         )]);
 
     assert_eq!(parse_str(text).unwrap(), ast);
 }
 
 #[test]
-fn will_die_on_super_for_now() {
+fn can_parse_grouped_glob_and_aliased_uses() {
     let text = r#"
-        use super::TestType<Hey>;
+        use game::{world::Entity, render::{self, Mesh as RenderMesh}};
+        use game::math::*;
+        pub use game::Entity as GameEntity;
+        pub use super::{*, ui::Widget,};
         "#;
 
-    assert_eq!(
-        parse_str(text).err().unwrap().to_string(),
-        "super not supported"
-    );
+    let ast = M::new().with_uses([
+        U::new(IP::from("game::world::Entity")),
+        U::new(IP::from("game::render")),
+        U::new(IP::from("game::render::Mesh")).with_alias("RenderMesh"),
+        U::glob(IP::from("game::math")),
+        U::new(IP::from("game::Entity"))
+            .with_alias("GameEntity")
+            .with_visibility(V::Public),
+        U::glob(IP::from("super")).with_visibility(V::Public),
+        U::new(IP::from("super::ui::Widget")).with_visibility(V::Public),
+    ]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_relative_paths() {
+    let text = r#"
+        use super::TestType;
+        use crate::world::Entity;
+        type Test {
+            parent: *mut super::Parent,
+            entity: *mut crate::world::Entity,
+            this: *const self::Test,
+            array: super::super::Array<u32>,
+            items: world::Items,
+        }
+        "#;
+
+    let ast = M::new()
+        .with_uses([
+            IP::from("super::TestType"),
+            IP::from("crate::world::Entity"),
+        ])
+        .with_definitions([ID::new(
+            (V::Private, "Test"),
+            TD::new([
+                TS::field(
+                    (V::Private, "parent"),
+                    T::path("super::Parent").mut_pointer(),
+                ),
+                TS::field(
+                    (V::Private, "entity"),
+                    T::path("crate::world::Entity").mut_pointer(),
+                ),
+                TS::field((V::Private, "this"), T::path("self::Test").const_pointer()),
+                TS::field(
+                    (V::Private, "array"),
+                    T::generic("super::super::Array", [T::ident("u32")]),
+                ),
+                TS::field((V::Private, "items"), T::path("world::Items")),
+            ]),
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
 }
 
 #[test]
@@ -236,15 +328,82 @@ fn can_parse_extern() {
         "#;
 
     let ast = M::new()
-        .with_extern_types([("TestType<Hey>".into(), As::from_iter([A::size(12)]))])
+        .with_extern_types([("TestType".into(), As::from_iter([A::size(12)]))])
         .with_definitions([ID::new(
             (V::Private, "Test"),
-            TD::new([TS::field((V::Private, "test"), T::ident("TestType<Hey>"))]),
+            TD::new([TS::field(
+                (V::Private, "test"),
+                T::generic("TestType", [T::ident("Hey")]),
+            )]),
         )]);
 
     assert_eq!(parse_str(text).unwrap(), ast);
 }
 
+#[test]
+fn can_parse_generic_types() {
+    let text = r#"
+        #[size(12), align(4)]
+        extern type Map<K, V>;
+        type Test {
+            pointers: Vec<*mut Test>,
+            map: Map<u32, Vec<[u8; 4]>>,
+            pointer_to_vec: *const Vec<u32,>,
+        }
+        "#;
+
+    let ast = M::new()
+        .with_extern_types([("Map".into(), As::from_iter([A::size(12), A::align(4)]))])
+        .with_definitions([ID::new(
+            (V::Private, "Test"),
+            TD::new([
+                TS::field(
+                    (V::Private, "pointers"),
+                    T::generic("Vec", [T::ident("Test").mut_pointer()]),
+                ),
+                TS::field(
+                    (V::Private, "map"),
+                    T::generic(
+                        "Map",
+                        [
+                            T::ident("u32"),
+                            T::generic("Vec", [T::ident("u8").array(4)]),
+                        ],
+                    ),
+                ),
+                TS::field(
+                    (V::Private, "pointer_to_vec"),
+                    T::generic("Vec", [T::ident("u32")]).const_pointer(),
+                ),
+            ]),
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_generic_type_definitions() {
+    let text = r#"
+        #[monomorphise]
+        pub type Pair<A, B> {
+            pub first: A,
+            pub second: *mut B,
+        }
+        "#;
+
+    let ast = M::new().with_definitions([ID::new(
+        (V::Public, "Pair"),
+        TD::new([
+            TS::field((V::Public, "first"), T::ident("A")),
+            TS::field((V::Public, "second"), T::ident("B").mut_pointer()),
+        ])
+        .with_generics(["A", "B"])
+        .with_attributes([A::monomorphise()]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
 #[test]
 fn can_parse_an_empty_type() {
     let text = r#"
@@ -286,6 +445,197 @@ fn can_parse_extern_value() {
     assert_eq!(parse_str(text).unwrap(), ast);
 }
 
+#[test]
+fn can_parse_full_width_and_negative_integer_literals() {
+    let text = r#"
+        #[address(0xFFFF_F800_0000_0000)]
+        pub extern kernel_base: *mut u8;
+        pub enum Status: i32 {
+            Invalid = -1,
+            Minimum = -0x8000_0000,
+            Maximum = 0x7FFF_FFFF,
+        }
+        "#;
+
+    let ast = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "kernel_base",
+            T::ident("u8").mut_pointer(),
+            [A::integer_fn("address", 0xFFFF_F800_0000_0000)],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "Status"),
+            ED::new(
+                T::ident("i32"),
+                [
+                    ES::field_with_expr("Invalid", E::IntLiteral(-1)),
+                    ES::field_with_expr("Minimum", E::IntLiteral(-0x8000_0000)),
+                    ES::field_with_expr("Maximum", E::IntLiteral(0x7FFF_FFFF)),
+                ],
+                [],
+            ),
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_constant_expressions() {
+    let text = r#"
+        #[size(sizeof(Base) + 0x20), align(alignof(Array<u32>))]
+        pub type TestType {
+            #[address(0x40 + 8 * 2)]
+            pub field_1: [Slot; (4 - 1) << 1],
+            #[address(offsetof(Base, field) | 1 ^ 2 & 3)]
+            pub field_2: [u8; -(-5) % 3],
+        }
+        "#;
+
+    let int = E::IntLiteral;
+    let ast = M::new().with_definitions([ID::new(
+        (V::Public, "TestType"),
+        TD::new([
+            TS::field(
+                (V::Public, "field_1"),
+                T::ident("Slot").array_with_expr(E::binary(
+                    E::binary(int(4), BO::Sub, int(1)),
+                    BO::Shl,
+                    int(1),
+                )),
+            )
+            .with_attributes([A::expr_fn(
+                "address",
+                E::binary(int(0x40), BO::Add, E::binary(int(8), BO::Mul, int(2))),
+            )]),
+            TS::field(
+                (V::Public, "field_2"),
+                T::ident("u8").array_with_expr(E::binary(
+                    E::unary(UO::Negate, int(-5)),
+                    BO::Rem,
+                    int(3),
+                )),
+            )
+            .with_attributes([A::expr_fn(
+                "address",
+                E::binary(
+                    E::offset_of(T::ident("Base"), "field"),
+                    BO::BitOr,
+                    E::binary(int(1), BO::BitXor, E::binary(int(2), BO::BitAnd, int(3))),
+                ),
+            )]),
+        ])
+        .with_attributes([
+            A::expr_fn(
+                "size",
+                E::binary(E::size_of(T::ident("Base")), BO::Add, int(0x20)),
+            ),
+            A::expr_fn("align", E::align_of(T::generic("Array", [T::ident("u32")]))),
+        ]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_constants() {
+    let text = r#"
+        /// The maximum number of players
+        pub const MAX_PLAYERS: u32 = 64;
+        const HALF_PLAYERS: u8 = MAX_PLAYERS / 2 + super::OFFSET;
+
+        pub type Lobby {
+            pub players: [u32; MAX_PLAYERS],
+        }
+        "#;
+
+    let ast = M::new()
+        .with_constants([
+            C::new((V::Public, "MAX_PLAYERS"), T::ident("u32"), 64)
+                .with_attributes([A::doc(" The maximum number of players")]),
+            C::new(
+                (V::Private, "HALF_PLAYERS"),
+                T::ident("u8"),
+                E::binary(
+                    E::binary(E::Ident("MAX_PLAYERS".into()), BO::Div, E::IntLiteral(2)),
+                    BO::Add,
+                    E::Path(vec!["super".into(), "OFFSET".into()]),
+                ),
+            ),
+        ])
+        .with_definitions([ID::new(
+            (V::Public, "Lobby"),
+            TD::new([TS::field(
+                (V::Public, "players"),
+                T::ident("u32").array_with_expr(E::Ident("MAX_PLAYERS".into())),
+            )]),
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_function_pointer_types() {
+    let text = r#"
+        pub type Entity {
+            pub on_damage: fn(*mut Entity, f32),
+            pub on_death: Option<extern "thiscall" fn(this: *mut Entity, killer: *mut Entity) -> bool>,
+        }
+        impl Entity {
+            #[address(0x1000)]
+            pub fn set_callback(&mut self, callback: extern "C" fn(i32) -> i32);
+        }
+        "#;
+
+    let ast = M::new()
+        .with_definitions([ID::new(
+            (V::Public, "Entity"),
+            TD::new([
+                TS::field(
+                    (V::Public, "on_damage"),
+                    T::Function(FT::new(
+                        [
+                            (None, T::ident("Entity").mut_pointer()),
+                            (None, T::ident("f32")),
+                        ],
+                        None,
+                    )),
+                ),
+                TS::field(
+                    (V::Public, "on_death"),
+                    T::Function(
+                        FT::new(
+                            [
+                                (Some("this"), T::ident("Entity").mut_pointer()),
+                                (Some("killer"), T::ident("Entity").mut_pointer()),
+                            ],
+                            T::ident("bool"),
+                        )
+                        .with_calling_convention("thiscall")
+                        .with_nullable(true),
+                    ),
+                ),
+            ]),
+        )])
+        .with_impls([FB::new(
+            "Entity",
+            [F::new(
+                (V::Public, "set_callback"),
+                [
+                    Ar::MutSelf,
+                    Ar::named(
+                        "callback",
+                        FT::new([(None, T::ident("i32"))], T::ident("i32"))
+                            .with_calling_convention("C"),
+                    ),
+                ],
+            )
+            .with_attributes([A::address(0x1000)])],
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
 #[test]
 fn can_parse_enum() {
     let text = r#"
@@ -318,6 +668,466 @@ fn can_parse_enum() {
     assert_eq!(parse_str(text).unwrap(), ast);
 }
 
+#[test]
+fn can_parse_unions() {
+    let text = r#"
+        #[size(8)]
+        pub union Value {
+            pub int: i32,
+            pub float: f32,
+            pub pointer: *mut u8,
+        }
+        pub type Variant {
+            pub tag: u32,
+            #[address(8)]
+            pub union {
+                pub int: i64,
+                pub value: Value,
+            },
+            union: u32,
+        }
+        "#;
+
+    let ast = M::new().with_definitions([
+        ID::new(
+            (V::Public, "Value"),
+            UD::new([
+                TS::field((V::Public, "int"), T::ident("i32")),
+                TS::field((V::Public, "float"), T::ident("f32")),
+                TS::field((V::Public, "pointer"), T::ident("u8").mut_pointer()),
+            ])
+            .with_attributes([A::size(8)]),
+        ),
+        ID::new(
+            (V::Public, "Variant"),
+            TD::new([
+                TS::field((V::Public, "tag"), T::ident("u32")),
+                TS::union(
+                    V::Public,
+                    [
+                        TS::field((V::Public, "int"), T::ident("i64")),
+                        TS::field((V::Public, "value"), T::ident("Value")),
+                    ],
+                )
+                .with_attributes([A::address(8)]),
+                // `union` is still a valid field name
+                TS::field((V::Private, "union"), T::ident("u32")),
+            ]),
+        ),
+    ]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_nested_items() {
+    let text = r#"
+        pub type Entity {
+            /// Where the entity is
+            pub pos: type { pub x: f32, pub y: f32, pub z: f32 },
+            pub data: union {
+                pub id: u32,
+                pub pair: type { pub low: u16, pub high: u16 },
+            },
+        }
+        "#;
+
+    let ast = M::new().with_definitions([ID::new(
+        (V::Public, "Entity"),
+        TD::new([
+            TS::nested(
+                (V::Public, "pos"),
+                TD::new([
+                    TS::field((V::Public, "x"), T::ident("f32")),
+                    TS::field((V::Public, "y"), T::ident("f32")),
+                    TS::field((V::Public, "z"), T::ident("f32")),
+                ]),
+            )
+            .with_attributes([A::doc(" Where the entity is")]),
+            TS::nested(
+                (V::Public, "data"),
+                UD::new([
+                    TS::field((V::Public, "id"), T::ident("u32")),
+                    TS::nested(
+                        (V::Public, "pair"),
+                        TD::new([
+                            TS::field((V::Public, "low"), T::ident("u16")),
+                            TS::field((V::Public, "high"), T::ident("u16")),
+                        ]),
+                    ),
+                ]),
+            ),
+        ]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn will_fail_on_invalid_unions() {
+    for (text, message) in [
+        (
+            "pub union Empty {}",
+            "failed to parse type Empty: unions must have at least one field",
+        ),
+        (
+            "pub union Virtual { vftable {}, pub a: u32 }",
+            "failed to parse type Virtual: unions can only contain fields",
+        ),
+        (
+            "pub type Nested { pub union { pub a: u32, union { b: u32 } } }",
+            "failed to parse type Nested: unions can only contain fields",
+        ),
+    ] {
+        assert_eq!(parse_str(text).unwrap_err().to_string(), message);
+    }
+}
+
+#[test]
+fn can_parse_type_aliases() {
+    let text = r#"
+        pub type EntityHandle = u32;
+        /// A 4x4 matrix
+        #[newtype]
+        pub type Matrix = [f32; 16];
+        type Callback = Option<fn(*mut Entity)>;
+        "#;
+
+    let ast = M::new().with_definitions([
+        ID::new((V::Public, "EntityHandle"), AD::new(T::ident("u32"))),
+        ID::new(
+            (V::Public, "Matrix"),
+            AD::new(T::ident("f32").array(16))
+                .with_attributes([A::doc(" A 4x4 matrix"), A::newtype()]),
+        ),
+        ID::new(
+            (V::Private, "Callback"),
+            AD::new(FT::new([(None, T::ident("Entity").mut_pointer())], None).with_nullable(true)),
+        ),
+    ]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+    assert_eq!(
+        parse_str("pub type Pair<T> = [T; 2];")
+            .unwrap_err()
+            .to_string(),
+        "failed to parse type Pair: type aliases cannot be generic"
+    );
+}
+
+#[test]
+fn can_parse_cfg_attributes() {
+    let text = r#"
+        #[cfg(any(pointer_size = 4, not(version = "1.2")))]
+        pub type Player {
+            #[cfg(pointer_size = 8)]
+            _: unknown<4>,
+            #[cfg(all(debug, version = "1.2")), address(0x10)]
+            pub name: u32,
+            vftable {
+                #[cfg(debug)]
+                pub fn dump(&self);
+            }
+        }
+        "#;
+
+    let ast = M::new().with_definitions([ID::new(
+        (V::Public, "Player"),
+        TD::new([
+            TS::field((V::Private, "_"), T::unknown(4))
+                .with_attributes([A::cfg(CP::assign("pointer_size", 8))]),
+            TS::field((V::Public, "name"), T::ident("u32")).with_attributes([
+                A::cfg(CP::all([CP::ident("debug"), CP::assign("version", "1.2")])),
+                A::address(0x10),
+            ]),
+            TS::vftable([F::new((V::Public, "dump"), [Ar::ConstSelf])
+                .with_attributes([A::cfg(CP::ident("debug"))])]),
+        ])
+        .with_attributes([A::cfg(CP::any([
+            CP::assign("pointer_size", 4),
+            CP::not(CP::assign("version", "1.2")),
+        ]))]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn can_parse_per_target_addresses() {
+    let text = r#"
+        #[address(x86 = 0x1337, x64 = 0x1_4000_1337)]
+        pub extern player_count: u32;
+        impl Player {
+            #[address(x86 = 0x401000, x64 = 0x1_4000_1000,)]
+            pub fn respawn(&mut self);
+        }
+        "#;
+
+    let ast = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_per_target([
+                ("x86", 0x1337),
+                ("x64", 0x1_4000_1337),
+            ])],
+        )])
+        .with_impls([FB::new(
+            "Player",
+            [
+                F::new((V::Public, "respawn"), [Ar::MutSelf]).with_attributes([
+                    A::address_per_target([("x86", 0x40_1000), ("x64", 0x1_4000_1000)]),
+                ]),
+            ],
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn will_fail_on_invalid_cfg_attributes() {
+    for (text, message) in [
+        (
+            "#[cfg(a, b)] pub type A { a: u32 }",
+            "`cfg` takes exactly one predicate",
+        ),
+        (
+            "#[cfg(not(a, b))] pub type A { a: u32 }",
+            "`not` takes exactly one predicate",
+        ),
+        (
+            "#[cfg(either(a, b))] pub type A { a: u32 }",
+            "unknown cfg predicate `either`; expected `all`, `any` or `not`",
+        ),
+    ] {
+        assert_eq!(parse_str(text).unwrap_err().to_string(), message);
+    }
+}
+
+#[test]
+fn can_parse_signature_attributes() {
+    let text = r#"
+        #[signature("48 8B 05 ?? ?? ?? ??", offset = 3, rip_relative)]
+        pub extern player_count: u32;
+
+        #[signature("40 53 48 83 EC ? 48 8B D9")]
+        pub type World {
+            pub tick: u32,
+        }
+        "#;
+
+    let ast = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::signature(
+                SG::new([Some(0x48), Some(0x8B), Some(0x05), None, None, None, None])
+                    .with_offset(3)
+                    .with_rip_relative(true),
+            )],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "World"),
+            TD::new([TS::field((V::Public, "tick"), T::ident("u32"))]).with_attributes([
+                A::signature(SG::new([
+                    Some(0x40),
+                    Some(0x53),
+                    Some(0x48),
+                    Some(0x83),
+                    Some(0xEC),
+                    None,
+                    Some(0x48),
+                    Some(0x8B),
+                    Some(0xD9),
+                ])),
+            ]),
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn will_fail_on_invalid_signature_attributes() {
+    for (text, message) in [
+        (
+            r#"#[signature("48 8G")] pub extern a: u32;"#,
+            "invalid byte `8G` in signature; expected two hex digits or `??`",
+        ),
+        (
+            r#"#[signature("488B")] pub extern a: u32;"#,
+            "invalid byte `488B` in signature; expected two hex digits or `??`",
+        ),
+        (
+            r#"#[signature("")] pub extern a: u32;"#,
+            "signature must have at least one byte",
+        ),
+        (
+            r#"#[signature("48", relative)] pub extern a: u32;"#,
+            "unknown signature option `relative`; expected `offset` or `rip_relative`",
+        ),
+    ] {
+        assert_eq!(parse_str(text).unwrap_err().to_string(), message);
+    }
+}
+
+#[test]
+fn can_parse_image_attributes() {
+    let text = r#"
+        #![image("game.exe", preferred_base = 0x140000000)]
+
+        #[address(rva = 0x1234, image = "engine.dll")]
+        pub extern player_count: u32;
+        "#;
+
+    let ast = M::new()
+        .with_attributes([A::image(
+            IM::new("game.exe").with_preferred_base(0x140000000),
+        )])
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_relative(0x1234, Some("engine.dll"))],
+        )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn will_fail_on_invalid_image_attributes() {
+    for (text, message) in [
+        (r#"#![image(game)]"#, "expected string literal"),
+        (
+            r#"#![image("game.exe", base = 0x140000000)]"#,
+            "unknown image option `base`; expected `preferred_base`",
+        ),
+    ] {
+        assert_eq!(parse_str(text).unwrap_err().to_string(), message);
+    }
+}
+
+#[test]
+fn can_parse_chain_attributes() {
+    let text = r#"
+        #[address(chain(0x140001000, 0x48, 0x8))]
+        pub extern player_count: u32;
+
+        #[singleton(chain(0x140002000, -0x10))]
+        pub type World {
+            pub tick: u32,
+        }
+
+        #[singleton(chain(0x140003000))]
+        pub type Settings {
+            pub volume: f32,
+        }
+        "#;
+
+    let ast = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_chain(0x140001000, [0x48, 0x8])],
+        )])
+        .with_definitions([
+            ID::new(
+                (V::Public, "World"),
+                TD::new([TS::field((V::Public, "tick"), T::ident("u32"))])
+                    .with_attributes([A::singleton_chain(0x140002000, [-0x10])]),
+            ),
+            ID::new(
+                (V::Public, "Settings"),
+                TD::new([TS::field((V::Public, "volume"), T::ident("f32"))])
+                    .with_attributes([A::singleton_chain(0x140003000, [])]),
+            ),
+        ]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn will_fail_on_invalid_chain_attributes() {
+    for (text, message) in [
+        (
+            r#"#[address(chain())] pub extern a: u32;"#,
+            "`chain` takes a starting address, followed by its offsets",
+        ),
+        (
+            r#"#[address(chain(v1_0 = 0x140001000, v1_1 = 0x140002000))] pub extern a: u32;"#,
+            "`chain` only takes one starting address and its offsets; it cannot have an entry for each version or target",
+        ),
+        (
+            r#"#[address(chain(0x140001000, 0x8), 0x10)] pub extern a: u32;"#,
+            "`chain` must be the only argument",
+        ),
+    ] {
+        assert_eq!(parse_str(text).unwrap_err().to_string(), message);
+    }
+}
+
+#[test]
+fn can_parse_virtual_base_attributes() {
+    let text = r#"
+        pub type BaseA {
+            #[base(virtual)]
+            pub base: Base,
+            pub a: u32,
+        }
+        "#;
+
+    let ast = M::new().with_definitions([ID::new(
+        (V::Public, "BaseA"),
+        TD::new([
+            TS::field((V::Public, "base"), T::ident("Base")).with_attributes([A::virtual_base()]),
+            TS::field((V::Public, "a"), T::ident("u32")),
+        ]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
+#[test]
+fn will_fail_on_invalid_virtual_base_attributes() {
+    for (text, message) in [
+        (
+            r#"pub type A { #[base(shared)] pub base: Base }"#,
+            "failed to parse type A: expected `virtual`",
+        ),
+        (
+            r#"pub type A { #[base(virtual, virtual)] pub base: Base }"#,
+            "failed to parse type A: `base` only takes `virtual`",
+        ),
+    ] {
+        assert_eq!(parse_str(text).unwrap_err().to_string(), message);
+    }
+}
+
+#[test]
+fn can_parse_layout_attribute() {
+    let text = r#"
+        #[layout(itanium)]
+        pub type Derived {
+            #[base]
+            pub base: Base,
+            pub derived: u32,
+        }
+        "#;
+
+    let ast = M::new().with_definitions([ID::new(
+        (V::Public, "Derived"),
+        TD::new([
+            TS::field((V::Public, "base"), T::ident("Base")).with_attributes([A::base()]),
+            TS::field((V::Public, "derived"), T::ident("u32")),
+        ])
+        .with_attributes([A::layout("itanium")]),
+    )]);
+
+    assert_eq!(parse_str(text).unwrap(), ast);
+}
+
 #[test]
 fn can_parse_array_field() {
     let text = r#"
@@ -463,3 +1273,150 @@ fn can_parse_doc_comments() {
 
     assert_eq!(parse_str(text).unwrap(), ast);
 }
+
+#[test]
+fn can_parse_spans() {
+    let text = r#"
+type TestType {
+    #[address(0x4)]
+    pub field_1: *const u32,
+}
+"#;
+
+    let module = parse_str(text).unwrap();
+    let definition = &module.definitions[0];
+    assert_eq!(
+        (
+            definition.name.span().start.line,
+            definition.name.span().start.column
+        ),
+        (2, 5)
+    );
+
+    let ItemDefinitionInner::Type(td) = &definition.inner else {
+        panic!("expected type definition");
+    };
+    let statement = &td.statements[0];
+    assert_eq!(
+        (statement.span.start.line, statement.span.start.column),
+        (4, 4)
+    );
+    assert_eq!(
+        (
+            statement.attributes.0[0].span().start.line,
+            statement.attributes.0[0].span().start.column
+        ),
+        (3, 6)
+    );
+
+    let TypeField::Field(_, name, type_) = &statement.field else {
+        panic!("expected field");
+    };
+    assert_eq!((name.span().start.column, name.span().end.column), (8, 15));
+    assert_eq!(
+        (type_.span().start.line, type_.span().start.column),
+        (4, 24)
+    );
+}
+
+#[test]
+fn can_recover_from_errors_in_multiple_items() {
+    let text = r#"
+        pub type TestType1 {
+            field_1: i32,
+        }
+        pub type TestType2 {
+            field_1 i32,
+        }
+        pub type TestType3 {
+            field_1: i32,
+        }
+        pub extern value: ;
+        use TestType1;
+        "#;
+
+    let ast = M::new()
+        .with_uses([IP::from("TestType1")])
+        .with_definitions([
+            ID::new(
+                (V::Public, "TestType1"),
+                TD::new([TS::field((V::Private, "field_1"), T::ident("i32"))]),
+            ),
+            ID::new(
+                (V::Public, "TestType3"),
+                TD::new([TS::field((V::Private, "field_1"), T::ident("i32"))]),
+            ),
+        ]);
+
+    let (module, errors) = parse_str_with_recovery(text).unwrap();
+    assert_eq!(module, ast);
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| (e.span().start().line, e.to_string()))
+            .collect::<Vec<_>>(),
+        [
+            (
+                6,
+                "failed to parse type TestType2: expected `:`".to_string()
+            ),
+            (
+                11,
+                "expected one of: `unknown`, identifier, `*`, `extern`, `fn`, square brackets"
+                    .to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn can_recover_from_garbage_between_items() {
+    let text = r#"
+        this is not an item;
+        pub type TestType {
+            field_1: i32,
+        }
+        "#;
+
+    let (module, errors) = parse_str_with_recovery(text).unwrap();
+    assert_eq!(
+        module,
+        M::new().with_definitions([ID::new(
+            (V::Public, "TestType"),
+            TD::new([TS::field((V::Private, "field_1"), T::ident("i32"))]),
+        )])
+    );
+    assert_eq!(errors.len(), 1);
+    assert!(parse_str(text).is_err());
+}
+
+#[test]
+fn can_recover_from_errors_in_item_attributes() {
+    // The item that the attributes belong to is dropped along with them.
+    let text = r#"
+        #[address(0x10 +)]
+        pub extern value_1: u32;
+        #[signature("48 ZZ")]
+        #[size(4)]
+        pub extern value_2: u32;
+        pub type TestType {
+            field_1: i32,
+        }
+        "#;
+
+    let (module, errors) = parse_str_with_recovery(text).unwrap();
+    assert_eq!(
+        module,
+        M::new().with_definitions([ID::new(
+            (V::Public, "TestType"),
+            TD::new([TS::field((V::Private, "field_1"), T::ident("i32"))]),
+        )])
+    );
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| e.span().start().line)
+            .collect::<Vec<_>>(),
+        [2, 4]
+    );
+}
@@ -0,0 +1,115 @@
+use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Result},
+    grammar::{self, ItemPath},
+    semantic::{
+        expression,
+        types::{ItemStateResolved, Type},
+        SemanticState,
+    },
+    span::Span,
+};
+
+/// Another name for a type, with the same layout as it
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct AliasDefinition {
+    pub type_: Type,
+    pub doc: Option<String>,
+    /// Whether the alias is generated as a wrapper struct, instead of another name for its type
+    pub newtype: bool,
+    pub copyable: bool,
+    pub cloneable: bool,
+    pub defaultable: bool,
+}
+impl AliasDefinition {
+    pub fn new(type_: Type) -> Self {
+        AliasDefinition {
+            type_,
+            doc: None,
+            newtype: false,
+            copyable: false,
+            cloneable: false,
+            defaultable: false,
+        }
+    }
+    pub fn with_doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+    pub fn with_newtype(mut self, newtype: bool) -> Self {
+        self.newtype = newtype;
+        self
+    }
+    pub fn with_copyable(mut self, copyable: bool) -> Self {
+        self.copyable = copyable;
+        self
+    }
+    pub fn with_cloneable(mut self, cloneable: bool) -> Self {
+        self.cloneable = cloneable;
+        self
+    }
+    pub fn with_defaultable(mut self, defaultable: bool) -> Self {
+        self.defaultable = defaultable;
+        self
+    }
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+}
+
+/// Builds the alias at `resolvee_path`. It can be copied, cloned and defaulted if its type can
+/// be, whether or not it is a newtype.
+pub fn build(
+    semantic: &SemanticState,
+    resolvee_path: &ItemPath,
+    span: Span,
+    definition: &grammar::AliasDefinition,
+) -> Result<Option<ItemStateResolved>> {
+    let module = semantic.get_module_for_path(resolvee_path).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to get module for path `{resolvee_path}`"),
+        )
+    })?;
+    let scope = module.scope();
+    let mut diagnostics = Diagnostics::new();
+
+    let doc = diagnostics
+        .record(definition.attributes.doc(resolvee_path))
+        .flatten();
+    let newtype = definition
+        .attributes
+        .iter()
+        .any(|a| matches!(a, grammar::Attribute::Ident(ident) if ident.as_str() == "newtype"));
+
+    let type_registry = &semantic.type_registry;
+    let Some(type_) = type_registry.resolve_grammar_type(&scope, &definition.type_) else {
+        // Report any errors we've already found; they won't go away by waiting.
+        let checked = expression::check_type(
+            type_registry,
+            &scope,
+            &Default::default(),
+            &definition.type_,
+        )
+        .map_err(|d| d.with_location(module.location(definition.type_.span().or(span))));
+        diagnostics.record(checked);
+        return diagnostics.finish(None);
+    };
+    let (Some(size), Some(alignment)) = (type_.size(type_registry), type_.alignment(type_registry))
+    else {
+        return diagnostics.finish(None);
+    };
+
+    diagnostics.finish(Some(ItemStateResolved {
+        size,
+        alignment,
+        inner: AliasDefinition {
+            doc,
+            newtype,
+            copyable: type_.is_copyable(type_registry),
+            cloneable: type_.is_cloneable(type_registry),
+            defaultable: type_.is_defaultable(type_registry),
+            type_,
+        }
+        .into(),
+    }))
+}
@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Location},
+    grammar::{self, CfgPredicate, ItemPath},
+    semantic::target::{Architecture, Target},
+    span::Span,
+};
+
+/// The configuration that `#[cfg(...)]` predicates are evaluated against.
+///
+/// `pointer_size`, `abi` (`"msvc"` or `"sysv"`) and `endian` (`"little"` or `"big"`) are always
+/// set from the [`Target`] that is being built for, and `target` to the address target (see
+/// [`Config::with_target`]). None of them can be overridden.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    target: Option<String>,
+    versions: Vec<String>,
+    flags: HashSet<String>,
+    values: HashMap<String, ConfigValue>,
+}
+impl Config {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Selects the target whose entries are used from per-target attributes
+    /// (e.g. `address(x86 = 0x401000, x64 = 0x140001000)`). It can also be tested with
+    /// `#[cfg(target = "name")]`.
+    ///
+    /// By default, this is the name of the architecture being built for (`x86` or `x64`).
+    /// Selecting the name of a different architecture is an error, as its addresses would not
+    /// be valid for the layout of the target.
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+    /// Declares the versions of the executable that addresses can be given for
+    /// (e.g. `address(v1_0 = 0x401000, v1_1 = 0x402000)`). Unlike targets, the version is
+    /// selected at runtime, so the generated `versions` module holds a table of every version's
+    /// address, and each versioned item must give an address for every version.
+    pub fn with_versions(mut self, versions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.versions = versions.into_iter().map(Into::into).collect();
+        self
+    }
+    /// Sets a key without a value, for use with `#[cfg(name)]`
+    pub fn with_flag(mut self, name: impl Into<String>) -> Self {
+        self.flags.insert(name.into());
+        self
+    }
+    /// Sets a key to an integer, for use with `#[cfg(name = 123)]`
+    pub fn with_integer(mut self, name: impl Into<String>, value: i128) -> Self {
+        self.values.insert(name.into(), ConfigValue::Integer(value));
+        self
+    }
+    /// Sets a key to a string, for use with `#[cfg(name = "value")]`
+    pub fn with_string(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values
+            .insert(name.into(), ConfigValue::String(value.into()));
+        self
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+    /// The target whose entries are used from per-target attributes when building for `target`
+    pub fn address_target<'a>(&'a self, target: &Target) -> &'a str {
+        self.target
+            .as_deref()
+            .unwrap_or_else(|| target.architecture().as_str())
+    }
+    pub fn versions(&self) -> &[String] {
+        &self.versions
+    }
+
+    /// Checks that the selected address target does not name an architecture other than that
+    /// of `target`
+    pub(crate) fn check_target(&self, target: &Target) -> Result<(), Diagnostic> {
+        let Some(selected) = self.target.as_deref() else {
+            return Ok(());
+        };
+        let architecture = target.architecture();
+        match Architecture::ALL.iter().find(|a| a.as_str() == selected) {
+            Some(other) if *other != architecture => Err(Diagnostic::error(
+                ErrorKind::InvalidConfig,
+                format!(
+                    "the selected target `{selected}` is for the {} architecture, but `{}` is {}",
+                    other.as_str(),
+                    target.name(),
+                    architecture.as_str()
+                ),
+            )
+            .with_note(format!(
+                "select the target `{}` instead, or build for an {} target",
+                architecture.as_str(),
+                other.as_str()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `predicate` holds for this configuration when building for `target`.
+    /// Keys that aren't set never match, as with Rust's `cfg`.
+    pub(crate) fn evaluate(
+        &self,
+        target: &Target,
+        predicate: &CfgPredicate,
+    ) -> Result<bool, Diagnostic> {
+        match predicate {
+            CfgPredicate::Ident(name) => Ok(["pointer_size", "abi", "endian"]
+                .contains(&name.as_str())
+                || name.as_str() == "target"
+                || self.flags.contains(name.as_str())
+                || self.values.contains_key(name.as_str())),
+            CfgPredicate::Assign(name, expr) => {
+                let expected = match expr {
+                    grammar::Expr::IntLiteral(value) => ConfigValue::Integer(*value),
+                    grammar::Expr::StringLiteral(value) => ConfigValue::String(value.clone()),
+                    _ => {
+                        return Err(Diagnostic::error(
+                            ErrorKind::InvalidAttribute,
+                            format!(
+                                "the cfg value for `{name}` must be an integer or string literal, not `{expr}`"
+                            ),
+                        ))
+                    }
+                };
+                let pointer_size = ConfigValue::Integer(target.pointer_size() as i128);
+                let abi = ConfigValue::String(target.abi().as_str().to_string());
+                let endian = ConfigValue::String(target.endianness().as_str().to_string());
+                let address_target = ConfigValue::String(self.address_target(target).to_string());
+                let actual = match name.as_str() {
+                    "pointer_size" => Some(&pointer_size),
+                    "abi" => Some(&abi),
+                    "endian" => Some(&endian),
+                    "target" => Some(&address_target),
+                    name => self.values.get(name),
+                };
+                match (actual, &expected) {
+                    (None, _) => Ok(false),
+                    (Some(ConfigValue::Integer(_)), ConfigValue::String(_))
+                    | (Some(ConfigValue::String(_)), ConfigValue::Integer(_)) => {
+                        Err(Diagnostic::error(
+                            ErrorKind::InvalidAttribute,
+                            format!(
+                                "the cfg key `{name}` is set to {}, but is compared with `{expr}`",
+                                actual.map(ConfigValue::description).unwrap_or_default()
+                            ),
+                        ))
+                    }
+                    (Some(actual), expected) => Ok(actual == expected),
+                }
+            }
+            CfgPredicate::All(predicates) => {
+                for predicate in predicates {
+                    if !self.evaluate(target, predicate)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            CfgPredicate::Any(predicates) => {
+                for predicate in predicates {
+                    if self.evaluate(target, predicate)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            CfgPredicate::Not(predicate) => Ok(!self.evaluate(target, predicate)?),
+        }
+    }
+
+    /// Removes every element of `module` whose `#[cfg(...)]` predicates don't hold, and
+    /// replaces every per-target `address` or `singleton` with the address for the selected
+    /// target. The `cfg` attributes of the remaining elements are removed, so that later
+    /// stages never see them.
+    pub(crate) fn apply(
+        &self,
+        diagnostics: &mut Diagnostics,
+        module: &grammar::Module,
+        path: &ItemPath,
+        target: &Target,
+        location: impl Fn(Span) -> Option<Location>,
+    ) -> grammar::Module {
+        let mut applier = ConfigApplier {
+            config: self,
+            target,
+            diagnostics,
+            location: &location,
+        };
+        let mut module = module.clone();
+
+        module
+            .extern_types
+            .retain_mut(|(_, attributes)| applier.is_enabled(attributes));
+        module.extern_values.retain_mut(|ev| {
+            let item = format!("extern value `{}`", path.join(ev.name.as_str().into()));
+            applier.is_enabled(&mut ev.attributes)
+                && applier.select_address(&mut ev.attributes, &item, true)
+        });
+        module
+            .constants
+            .retain_mut(|c| applier.is_enabled(&mut c.attributes));
+        module.definitions.retain_mut(|d| {
+            let item_path = path.join(d.name.as_str().into());
+            let item = match &d.inner {
+                grammar::ItemDefinitionInner::Enum(_) => format!("enum `{item_path}`"),
+                _ => format!("type `{item_path}`"),
+            };
+            let attributes = item_attributes(&mut d.inner);
+            let enabled =
+                applier.is_enabled(attributes) && applier.select_address(attributes, &item, true);
+            if enabled {
+                applier.apply_to_item(&item_path, &mut d.inner);
+            }
+            enabled
+        });
+        module.impls.retain_mut(|i| {
+            let enabled = applier.is_enabled(&mut i.attributes);
+            if enabled {
+                let type_path = path.join(i.name.as_str().into());
+                applier.apply_to_functions(&type_path, &mut i.functions);
+            }
+            enabled
+        });
+
+        module
+    }
+}
+
+/// A value that a key can be set to in a [`Config`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConfigValue {
+    Integer(i128),
+    String(String),
+}
+impl ConfigValue {
+    fn description(&self) -> String {
+        match self {
+            ConfigValue::Integer(value) => format!("the integer `{value}`"),
+            ConfigValue::String(value) => format!("the string `{value:?}`"),
+        }
+    }
+}
+
+struct ConfigApplier<'a, L: Fn(Span) -> Option<Location>> {
+    config: &'a Config,
+    target: &'a Target,
+    diagnostics: &'a mut Diagnostics,
+    location: &'a L,
+}
+impl<L: Fn(Span) -> Option<Location>> ConfigApplier<'_, L> {
+    /// Whether every `cfg` attribute in `attributes` holds, removing them as it goes.
+    /// Elements with invalid predicates are kept, so that they don't cause follow-on errors.
+    fn is_enabled(&mut self, attributes: &mut grammar::Attributes) -> bool {
+        let mut enabled = true;
+        attributes.0.retain(|attribute| {
+            let grammar::Attribute::Cfg(ident, predicate) = attribute else {
+                return true;
+            };
+            match self.config.evaluate(self.target, predicate) {
+                Ok(holds) => enabled &= holds,
+                Err(diagnostic) => self
+                    .diagnostics
+                    .push(diagnostic.with_location((self.location)(ident.span()))),
+            }
+            false
+        });
+        enabled
+    }
+
+    /// Replaces a per-target `address` or `singleton` (e.g.
+    /// `address(x86 = 0x401000, x64 = 0x140001000)`) with the address for the selected target.
+    /// Per-version addresses are kept for the backend, ordered by the declared versions, if
+    /// `allow_versions` is set. Returns false if there is no usable address, so that the element
+    /// can be removed instead of reporting follow-on errors.
+    fn select_address(
+        &mut self,
+        attributes: &mut grammar::Attributes,
+        item: &str,
+        allow_versions: bool,
+    ) -> bool {
+        for attribute in &mut attributes.0 {
+            let grammar::Attribute::Keyed(ident, entries) = attribute else {
+                continue;
+            };
+            if !["address", "singleton"].contains(&ident.as_str()) {
+                continue;
+            }
+            // Image-relative addresses (e.g. `address(rva = 0x1000)`) are evaluated later.
+            if entries
+                .iter()
+                .any(|(key, _)| ["rva", "image"].contains(&key.as_str()))
+            {
+                continue;
+            }
+
+            let error = |message: String| {
+                Diagnostic::error(ErrorKind::MissingAttribute, message)
+                    .with_location((self.location)(ident.span()))
+            };
+            let versions = self.config.versions();
+            if entries
+                .iter()
+                .any(|(key, _)| versions.iter().any(|v| v == key.as_str()))
+            {
+                if !allow_versions {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::InvalidAttribute,
+                            format!("{item} has an address for each version, but it can't change at runtime"),
+                        )
+                        .with_location((self.location)(ident.span()))
+                        .with_note("use `#[cfg(...)]` to choose between layouts when building"),
+                    );
+                    return false;
+                }
+
+                let declared = versions
+                    .iter()
+                    .map(|v| format!("`{v}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut valid = true;
+                for (key, _) in entries.iter() {
+                    if !versions.iter().any(|v| v == key.as_str()) {
+                        self.diagnostics.push(
+                            Diagnostic::error(
+                                ErrorKind::InvalidAttribute,
+                                format!(
+                                    "{item} has an address for `{key}`, which is not a declared version"
+                                ),
+                            )
+                            .with_location((self.location)(key.span()))
+                            .with_note(format!("the declared versions are {declared}")),
+                        );
+                        valid = false;
+                    }
+                }
+                for version in versions {
+                    if !entries.iter().any(|(key, _)| key.as_str() == version) {
+                        self.diagnostics.push(
+                            error(format!("{item} has no address for the version `{version}`"))
+                                .with_note("the version is selected at runtime, so every version needs an address"),
+                        );
+                        valid = false;
+                    }
+                }
+                if !valid {
+                    return false;
+                }
+                entries.sort_by_key(|(key, _)| versions.iter().position(|v| v == key.as_str()));
+                continue;
+            }
+
+            let target = self.config.address_target(self.target);
+            let Some((_, address)) = entries.iter().find(|(key, _)| key.as_str() == target) else {
+                let targets = entries
+                    .iter()
+                    .map(|(key, _)| format!("`{key}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.diagnostics.push(
+                    error(format!("{item} has no address for the target `{target}`"))
+                        .with_note(format!("addresses are only given for {targets}")),
+                );
+                return false;
+            };
+            *attribute = grammar::Attribute::Function(ident.clone(), vec![address.clone()]);
+        }
+        true
+    }
+
+    fn apply_to_item(&mut self, path: &ItemPath, inner: &mut grammar::ItemDefinitionInner) {
+        match inner {
+            grammar::ItemDefinitionInner::Type(td) => {
+                self.apply_to_statements(path, &mut td.statements)
+            }
+            grammar::ItemDefinitionInner::Union(ud) => {
+                self.apply_to_statements(path, &mut ud.statements)
+            }
+            grammar::ItemDefinitionInner::Enum(ed) => ed
+                .statements
+                .retain_mut(|s| self.is_enabled(&mut s.attributes)),
+            grammar::ItemDefinitionInner::Alias(_) => {}
+        }
+    }
+
+    fn apply_to_statements(
+        &mut self,
+        path: &ItemPath,
+        statements: &mut Vec<grammar::TypeStatement>,
+    ) {
+        statements.retain_mut(|statement| {
+            if !self.is_enabled(&mut statement.attributes) {
+                return false;
+            }
+            match &mut statement.field {
+                grammar::TypeField::Field(_, ident, _) => {
+                    let item = format!("field `{ident}` of type `{path}`");
+                    return self.select_address(&mut statement.attributes, &item, false);
+                }
+                grammar::TypeField::Vftable(functions)
+                | grammar::TypeField::BaseVftable(_, functions) => {
+                    self.apply_to_functions(path, functions)
+                }
+                grammar::TypeField::Union(_, statements) => {
+                    self.apply_to_statements(path, statements)
+                }
+                grammar::TypeField::Nested(_, _, inner) => self.apply_to_item(path, inner),
+            }
+            true
+        });
+    }
+
+    fn apply_to_functions(&mut self, path: &ItemPath, functions: &mut Vec<grammar::Function>) {
+        functions.retain_mut(|function| {
+            let item = format!("function `{path}::{}`", function.name);
+            self.is_enabled(&mut function.attributes)
+                && self.select_address(&mut function.attributes, &item, true)
+        });
+    }
+}
+
+fn item_attributes(inner: &mut grammar::ItemDefinitionInner) -> &mut grammar::Attributes {
+    match inner {
+        grammar::ItemDefinitionInner::Type(td) => &mut td.attributes,
+        grammar::ItemDefinitionInner::Enum(ed) => &mut ed.attributes,
+        grammar::ItemDefinitionInner::Union(ud) => &mut ud.attributes,
+        grammar::ItemDefinitionInner::Alias(ad) => &mut ad.attributes,
+    }
+}
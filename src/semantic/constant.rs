@@ -0,0 +1,106 @@
+use crate::{
+    error::{Diagnostic, ErrorKind, Result},
+    grammar::{self, ItemPath},
+    semantic::{
+        expression,
+        types::{Type, Visibility},
+        SemanticState,
+    },
+};
+
+/// A named constant defined in a module (e.g. `pub const MAX_PLAYERS: u32 = 64;`)
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Constant {
+    pub visibility: Visibility,
+    pub name: String,
+    pub doc: Option<String>,
+    pub type_: Type,
+    pub value: i128,
+}
+impl Constant {
+    pub fn new(
+        (visibility, name): (Visibility, impl Into<String>),
+        type_: Type,
+        value: i128,
+    ) -> Self {
+        Self {
+            visibility,
+            name: name.into(),
+            doc: None,
+            type_,
+            value,
+        }
+    }
+    pub fn with_doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+}
+
+/// Evaluates the constant at `resolvee_path`. Returns `None` if its value depends on a type
+/// or constant that hasn't been resolved yet.
+pub fn build(
+    semantic: &SemanticState,
+    resolvee_path: &ItemPath,
+    definition: &grammar::Constant,
+) -> Result<Option<Constant>> {
+    let module = semantic.get_module_for_path(resolvee_path).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to get module for path `{resolvee_path}`"),
+        )
+    })?;
+    let location = module.location(definition.name.span());
+    let scope = module.scope();
+
+    let doc = definition
+        .attributes
+        .doc(resolvee_path)
+        .map_err(|d| d.with_location(location.clone()))?;
+
+    let type_ = semantic
+        .type_registry
+        .resolve_grammar_type(&scope, &definition.type_);
+    let Some(type_) = type_.filter(|t| expression::fits_in_integer_type(t, 0).is_some()) else {
+        return Err(Diagnostic::error(
+            ErrorKind::InvalidExpression,
+            format!(
+                "constant `{resolvee_path}` must have an integer type, but has type `{}`",
+                definition.type_
+            ),
+        )
+        .with_location(location)
+        .into());
+    };
+
+    let value =
+        expression::evaluate(&semantic.type_registry, &scope, &definition.value).map_err(|d| {
+            d.with_location(location.clone())
+                .with_note(format!("while evaluating constant `{resolvee_path}`"))
+        })?;
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    if expression::fits_in_integer_type(&type_, value) != Some(true) {
+        return Err(Diagnostic::error(
+            ErrorKind::InvalidExpression,
+            format!(
+                "constant `{resolvee_path}` has the value {value}, which does not fit in its type `{}`",
+                definition.type_
+            ),
+        )
+        .with_location(location)
+        .into());
+    }
+
+    Ok(Some(Constant {
+        visibility: definition.visibility.into(),
+        name: definition.name.0.clone(),
+        doc,
+        type_,
+        value,
+    }))
+}
@@ -1,19 +1,20 @@
-use anyhow::Context;
-
 use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Result},
     grammar::{self, ItemPath},
     semantic::{
-        types::{ItemStateResolved, Type},
+        expression,
+        types::{Address, ItemStateResolved, Type},
         SemanticState,
     },
+    span::Span,
 };
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 pub struct EnumDefinition {
     pub type_: Type,
     pub doc: Option<String>,
-    pub fields: Vec<(String, isize)>,
-    pub singleton: Option<usize>,
+    pub fields: Vec<(String, i128)>,
+    pub singleton: Option<Address>,
     pub copyable: bool,
     pub cloneable: bool,
     pub defaultable: bool,
@@ -36,15 +37,15 @@ impl EnumDefinition {
         self.doc = Some(doc.into());
         self
     }
-    pub fn with_fields<'a>(mut self, fields: impl IntoIterator<Item = (&'a str, isize)>) -> Self {
+    pub fn with_fields<'a>(mut self, fields: impl IntoIterator<Item = (&'a str, i128)>) -> Self {
         self.fields = fields
             .into_iter()
             .map(|(n, v)| (n.to_string(), v))
             .collect();
         self
     }
-    pub fn with_singleton(mut self, singleton: usize) -> Self {
-        self.singleton = Some(singleton);
+    pub fn with_singleton(mut self, singleton: impl Into<Address>) -> Self {
+        self.singleton = Some(singleton.into());
         self
     }
     pub fn with_copyable(mut self, copyable: bool) -> Self {
@@ -71,15 +72,20 @@ impl EnumDefinition {
 pub fn build(
     semantic: &SemanticState,
     resolvee_path: &ItemPath,
+    span: Span,
     definition: &grammar::EnumDefinition,
-) -> anyhow::Result<Option<ItemStateResolved>> {
-    let module = semantic
-        .get_module_for_path(resolvee_path)
-        .with_context(|| format!("failed to get module for path `{resolvee_path}`"))?;
+) -> Result<Option<ItemStateResolved>> {
+    let module = semantic.get_module_for_path(resolvee_path).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to get module for path `{resolvee_path}`"),
+        )
+    })?;
 
+    let scope = module.scope();
     let Some(ty) = semantic
         .type_registry
-        .resolve_grammar_type(&module.scope(), &definition.type_)
+        .resolve_grammar_type(&scope, &definition.type_)
     else {
         return Ok(None);
     };
@@ -89,7 +95,8 @@ pub fn build(
         return Ok(None);
     };
 
-    let mut fields: Vec<(String, isize)> = vec![];
+    let mut diagnostics = Diagnostics::new();
+    let mut fields: Vec<(String, i128)> = vec![];
     let mut last_field = 0;
     let mut default_index = None;
     for statement in &definition.statements {
@@ -97,21 +104,55 @@ pub fn build(
             name,
             expr,
             attributes,
+            span: statement_span,
         } = statement;
         let value = match expr {
-            Some(grammar::Expr::IntLiteral(value)) => *value,
-            Some(_) => anyhow::bail!(
-                "unsupported enum value for case `{name}` of enum `{resolvee_path}`: {expr:?}"
-            ),
+            Some(expr) => {
+                match expression::evaluate(&semantic.type_registry, &scope, expr) {
+                    Ok(Some(value)) => value,
+                    // This depends on a type that hasn't been resolved yet, so try again later.
+                    Ok(None) => return diagnostics.finish(None),
+                    Err(diagnostic) => {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                ErrorKind::InvalidEnumValue,
+                                format!(
+                                    "unsupported enum value for case `{name}` of enum `{resolvee_path}`: {}",
+                                    diagnostic.message
+                                ),
+                            )
+                            .with_location(module.location(*statement_span)),
+                        );
+                        last_field
+                    }
+                }
+            }
             None => last_field,
         };
+        if expression::fits_in_integer_type(&ty, value) == Some(false) {
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::InvalidEnumValue,
+                    format!(
+                        "value {value} for case `{name}` of enum `{resolvee_path}` does not fit in its type `{ty}`"
+                    ),
+                )
+                .with_location(module.location(*statement_span)),
+            );
+        }
         fields.push((name.0.clone(), value));
 
         for attribute in attributes {
             match attribute {
                 grammar::Attribute::Ident(ident) if ident.as_str() == "default" => {
                     if default_index.is_some() {
-                        anyhow::bail!("enum {resolvee_path} has multiple default variants");
+                        diagnostics.push(
+                            Diagnostic::error(
+                                ErrorKind::NotDefaultable,
+                                format!("enum {resolvee_path} has multiple default variants"),
+                            )
+                            .with_location(module.location(attribute.span())),
+                        );
                     }
                     default_index = Some(fields.len() - 1);
                 }
@@ -119,15 +160,39 @@ pub fn build(
             }
         }
 
-        last_field = value + 1;
+        last_field = value.saturating_add(1);
     }
 
     let mut singleton = None;
     let mut copyable = false;
     let mut cloneable = false;
     let mut defaultable = false;
-    let doc = definition.attributes.doc(resolvee_path)?;
+    let doc = diagnostics
+        .record(
+            definition
+                .attributes
+                .doc(resolvee_path)
+                .map_err(|d| d.with_location(module.location(span))),
+        )
+        .flatten();
+    let item = format!("enum `{resolvee_path}`");
     for attribute in &definition.attributes {
+        if let Some((ident, value)) = expression::evaluate_address_attribute(
+            &semantic.type_registry,
+            &scope,
+            attribute,
+            "singleton",
+            &item,
+        ) {
+            let value = value.map_err(|d| d.with_location(module.location(ident.span())));
+            match diagnostics.record(value) {
+                Some(Some(value)) => singleton = Some(value),
+                Some(None) => return diagnostics.finish(None),
+                None => {}
+            }
+            continue;
+        }
+
         match attribute {
             grammar::Attribute::Ident(ident) => match ident.as_str() {
                 "copyable" => {
@@ -138,34 +203,50 @@ pub fn build(
                 "defaultable" => defaultable = true,
                 _ => {}
             },
-            grammar::Attribute::Function(ident, exprs) => {
-                if let ("singleton", [grammar::Expr::IntLiteral(value)]) =
-                    (ident.as_str(), exprs.as_slice())
-                {
-                    singleton = Some(*value as usize);
-                }
-            }
             grammar::Attribute::Assign(_ident, _expr) => {}
+            grammar::Attribute::Function(_, _)
+            | grammar::Attribute::Cfg(_, _)
+            | grammar::Attribute::Keyed(_, _)
+            | grammar::Attribute::Signature(_, _)
+            | grammar::Attribute::Image(_, _)
+            | grammar::Attribute::Chain(_, _) => {}
         }
     }
 
     if defaultable && default_index.is_none() {
-        anyhow::bail!(
-            "enum `{resolvee_path}` is marked as defaultable but has no default variant set"
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::NotDefaultable,
+                format!(
+                    "enum `{resolvee_path}` is marked as defaultable but has no default variant set"
+                ),
+            )
+            .with_location(module.location(span)),
         );
     }
 
     if !defaultable && default_index.is_some() {
-        anyhow::bail!(
-            "enum `{resolvee_path}` has a default variant set but is not marked as defaultable"
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::NotDefaultable,
+                format!(
+                    "enum `{resolvee_path}` has a default variant set but is not marked as defaultable"
+                ),
+            )
+            .with_location(module.location(span)),
         );
     }
 
-    Ok(Some(ItemStateResolved {
+    let alignment = ty.alignment(&semantic.type_registry).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to get alignment for base type of enum `{resolvee_path}`"),
+        )
+    })?;
+
+    diagnostics.finish(Some(ItemStateResolved {
         size,
-        alignment: ty.alignment(&semantic.type_registry).with_context(|| {
-            format!("failed to get alignment for base type of enum `{resolvee_path}`")
-        })?,
+        alignment,
         inner: EnumDefinition {
             type_: ty,
             doc,
@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{Diagnostic, ErrorKind},
+    grammar::{self, BinaryOp, UnaryOp},
+    semantic::{
+        module::Scope,
+        type_registry::TypeRegistry,
+        types::{Address, CallingConvention, Signature, Type},
+    },
+};
+
+/// Evaluates a constant expression to an integer, from within `scope`.
+///
+/// Returns `Ok(None)` if the expression depends on a type that has not been resolved yet,
+/// in which case it should be evaluated again in a later resolution pass.
+pub(crate) fn evaluate(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    expr: &grammar::Expr,
+) -> Result<Option<i128>, Diagnostic> {
+    evaluate_with_parameters(type_registry, scope, &HashMap::new(), expr)
+}
+
+/// Evaluates a constant expression to an integer, substituting any generic parameters named
+/// in `parameters` in the types it refers to.
+pub(crate) fn evaluate_with_parameters(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    parameters: &HashMap<String, Type>,
+    expr: &grammar::Expr,
+) -> Result<Option<i128>, Diagnostic> {
+    let evaluate = |expr| evaluate_with_parameters(type_registry, scope, parameters, expr);
+    let resolve = |type_: &grammar::Type| {
+        type_registry.resolve_grammar_type_with_parameters(scope, parameters, type_)
+    };
+    let error = |message: String| Diagnostic::error(ErrorKind::InvalidExpression, message);
+    let overflow = || error(format!("evaluating `{expr}` overflowed"));
+
+    match expr {
+        grammar::Expr::IntLiteral(value) => Ok(Some(*value)),
+        grammar::Expr::StringLiteral(value) => Err(error(format!(
+            "expected an integer expression, but found the string {value:?}"
+        ))),
+        grammar::Expr::Ident(ident) => {
+            evaluate_constant(type_registry, scope, std::slice::from_ref(ident), expr)
+        }
+        grammar::Expr::Path(path) => evaluate_constant(type_registry, scope, path, expr),
+        grammar::Expr::Unary(op, operand) => {
+            let Some(value) = evaluate(operand)? else {
+                return Ok(None);
+            };
+            match op {
+                UnaryOp::Negate => value.checked_neg().map(Some).ok_or_else(overflow),
+                UnaryOp::Not => Ok(Some(!value)),
+            }
+        }
+        grammar::Expr::Binary(lhs, op, rhs) => {
+            let (Some(lhs), Some(rhs)) = (evaluate(lhs)?, evaluate(rhs)?) else {
+                return Ok(None);
+            };
+            let shift = |shift: fn(i128, u32) -> Option<i128>| {
+                u32::try_from(rhs).ok().and_then(|rhs| shift(lhs, rhs))
+            };
+            let value = match op {
+                BinaryOp::Div | BinaryOp::Rem if rhs == 0 => {
+                    return Err(error(format!("evaluating `{expr}` divided by zero")));
+                }
+                BinaryOp::Add => lhs.checked_add(rhs),
+                BinaryOp::Sub => lhs.checked_sub(rhs),
+                BinaryOp::Mul => lhs.checked_mul(rhs),
+                BinaryOp::Div => lhs.checked_div(rhs),
+                BinaryOp::Rem => lhs.checked_rem(rhs),
+                BinaryOp::Shl => shift(i128::checked_shl),
+                BinaryOp::Shr => shift(i128::checked_shr),
+                BinaryOp::BitAnd => Some(lhs & rhs),
+                BinaryOp::BitOr => Some(lhs | rhs),
+                BinaryOp::BitXor => Some(lhs ^ rhs),
+            };
+            value.map(Some).ok_or_else(overflow)
+        }
+        grammar::Expr::SizeOf(type_) => Ok(resolve(type_)
+            .and_then(|t| t.size(type_registry))
+            .map(|s| s as i128)),
+        grammar::Expr::AlignOf(type_) => Ok(resolve(type_)
+            .and_then(|t| t.alignment(type_registry))
+            .map(|a| a as i128)),
+        grammar::Expr::OffsetOf(type_, field) => {
+            let Some(type_) = resolve(type_) else {
+                return Ok(None);
+            };
+            let item = match &type_ {
+                Type::Raw(path) => type_registry.get(path),
+                Type::Generic(path, arguments) => type_registry.get_generic(path, arguments),
+                _ => {
+                    return Err(error(format!(
+                        "`{expr}` requires a type with fields, but `{type_}` is {}",
+                        type_.human_friendly_type()
+                    )))
+                }
+            };
+            let Some(resolved) = item.and_then(|i| i.resolved()) else {
+                return Ok(None);
+            };
+            let Some(type_definition) = resolved.inner.as_type() else {
+                return Err(error(format!(
+                    "`{expr}` requires a type with fields, but `{type_}` is {}",
+                    resolved.inner.human_friendly_type()
+                )));
+            };
+
+            let mut offset = 0;
+            for region in &type_definition.regions {
+                if region.name.as_deref() == Some(field.as_str()) {
+                    return Ok(Some(offset as i128));
+                }
+                let Some(size) = region.size(type_registry) else {
+                    return Ok(None);
+                };
+                offset += size;
+            }
+            Err(error(format!("type `{type_}` has no field `{field}`")))
+        }
+    }
+}
+
+/// Returns the value of the constant at `path`, or `None` if it has not been evaluated yet.
+fn evaluate_constant(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    path: &[grammar::Ident],
+    expr: &grammar::Expr,
+) -> Result<Option<i128>, Diagnostic> {
+    let path = type_registry.resolve_constant(scope, path).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::InvalidExpression,
+            format!("failed to find constant `{expr}`"),
+        )
+    })?;
+    Ok(type_registry.constant(&path))
+}
+
+/// Evaluates the length of an array, which must not be negative.
+pub(crate) fn evaluate_array_length(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    parameters: &HashMap<String, Type>,
+    expr: &grammar::Expr,
+) -> Result<Option<usize>, Diagnostic> {
+    let Some(length) = evaluate_with_parameters(type_registry, scope, parameters, expr)? else {
+        return Ok(None);
+    };
+    usize::try_from(length).map(Some).map_err(|_| {
+        Diagnostic::error(
+            ErrorKind::InvalidExpression,
+            format!("array length `{expr}` evaluates to {length}, which is negative"),
+        )
+    })
+}
+
+/// Returns the first error within `type_` that waiting for other types can't fix, such as an
+/// invalid array length or an unknown calling convention. Types that fail to resolve because
+/// of these would otherwise wait forever.
+pub(crate) fn check_type(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    parameters: &HashMap<String, Type>,
+    type_: &grammar::Type,
+) -> Result<(), Diagnostic> {
+    let check = |type_| check_type(type_registry, scope, parameters, type_);
+    match type_ {
+        grammar::Type::ConstPointer(t) | grammar::Type::MutPointer(t) => check(t),
+        grammar::Type::Array(t, length) => {
+            evaluate_array_length(type_registry, scope, parameters, length)?;
+            check(t)
+        }
+        grammar::Type::Generic(_, arguments) => arguments.iter().try_for_each(check),
+        grammar::Type::Function(function) => {
+            if let Some(calling_convention) = &function.calling_convention {
+                match calling_convention.parse::<CallingConvention>() {
+                    Err(_) => {
+                        return Err(Diagnostic::error(
+                            ErrorKind::UnresolvedType,
+                            format!(
+                                "unknown calling convention {calling_convention:?} in `{type_}`"
+                            ),
+                        ))
+                    }
+                    Ok(cc) if !type_registry.target().supports(cc) => {
+                        return Err(Diagnostic::error(
+                            ErrorKind::UnresolvedType,
+                            format!(
+                                "calling convention {calling_convention:?} in `{type_}` is not supported by the target `{}`",
+                                type_registry.target()
+                            ),
+                        ))
+                    }
+                    Ok(_) => {}
+                }
+            }
+            function.types().try_for_each(check)
+        }
+        grammar::Type::Ident(_) | grammar::Type::Path(_) | grammar::Type::Unknown(_) => Ok(()),
+    }
+}
+
+/// Evaluates the argument of an integer attribute (e.g. the `0x10` of `#[size(0x10)]`).
+/// `item` describes what the attribute is attached to, for use in error messages.
+pub(crate) fn evaluate_attribute(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    expr: &grammar::Expr,
+    item: &str,
+) -> Result<Option<usize>, Diagnostic> {
+    let Some(value) = evaluate_attribute_value(type_registry, scope, ident, expr, item)? else {
+        return Ok(None);
+    };
+    usize::try_from(value).map(Some).map_err(|_| {
+        Diagnostic::error(
+            ErrorKind::InvalidAttribute,
+            format!("failed to convert `{ident}` attribute into usize for {item}"),
+        )
+    })
+}
+
+/// Evaluates an offset in a chain of pointers (e.g. the `-0x10` of
+/// `#[singleton(chain(0x140001000, -0x10))]`), which must fit within the target's pointer
+/// width as a signed integer.
+pub(crate) fn evaluate_offset(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    expr: &grammar::Expr,
+    item: &str,
+) -> Result<Option<isize>, Diagnostic> {
+    let Some(value) = evaluate_attribute_value(type_registry, scope, ident, expr, item)? else {
+        return Ok(None);
+    };
+    let pointer_bits = type_registry.pointer_size() * 8;
+    let offset = (value >> (pointer_bits - 1) == 0 || value >> (pointer_bits - 1) == -1)
+        .then(|| isize::try_from(value).ok())
+        .flatten();
+    offset.map(Some).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::InvalidAttribute,
+            format!(
+                "`{ident}` attribute for {item} has the offset {value:#x}, which is not a valid {pointer_bits}-bit offset"
+            ),
+        )
+    })
+}
+
+/// Evaluates the argument of an address attribute (e.g. `#[address(0x140001000)]`), which
+/// must fit within the target's pointer width.
+pub(crate) fn evaluate_address(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    expr: &grammar::Expr,
+    item: &str,
+) -> Result<Option<usize>, Diagnostic> {
+    let Some(value) = evaluate_attribute_value(type_registry, scope, ident, expr, item)? else {
+        return Ok(None);
+    };
+    let pointer_bits = type_registry.pointer_size() * 8;
+    let address = (value >= 0 && value >> pointer_bits == 0)
+        .then(|| usize::try_from(value).ok())
+        .flatten();
+    address.map(Some).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::InvalidAttribute,
+            format!(
+                "`{ident}` attribute for {item} evaluates to {value:#x}, which is not a valid {pointer_bits}-bit address"
+            ),
+        )
+    })
+}
+
+/// Evaluates `attribute` if it is an address attribute named `name`, which gives either one
+/// address (e.g. `#[address(0x401000)]`), an address for each version
+/// (e.g. `#[address(v1_0 = 0x401000, v1_1 = 0x402000)]`) or an offset into an image
+/// (e.g. `#[address(rva = 0x1000, image = "engine.dll")]`), or follows a chain of pointers
+/// (e.g. `#[singleton(chain(0x401000, 0x48, -0x8))]`), or if it is a `signature` attribute that
+/// locates the item instead.
+///
+/// Per-target addresses have already been replaced by the time this is called, so any
+/// remaining keyed address is per-version or image-relative. If the module declares an image
+/// with a preferred base, absolute addresses are made relative to it.
+pub(crate) fn evaluate_address_attribute<'a>(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    attribute: &'a grammar::Attribute,
+    name: &str,
+    item: &str,
+) -> Option<(&'a grammar::Ident, Result<Option<Address>, Diagnostic>)> {
+    match attribute {
+        grammar::Attribute::Function(ident, exprs) if ident.as_str() == name => {
+            let [expr] = exprs.as_slice() else {
+                return None;
+            };
+            let address = evaluate_absolute_address(type_registry, scope, ident, expr, item);
+            Some((ident, address))
+        }
+        grammar::Attribute::Chain(ident, exprs) if ident.as_str() == name => {
+            let (start, offsets) = exprs.split_first()?;
+            let start = evaluate_absolute_address(type_registry, scope, ident, start, item);
+            let offsets = offsets
+                .iter()
+                .map(|offset| evaluate_offset(type_registry, scope, ident, offset, item))
+                .collect::<Result<Option<Vec<_>>, _>>();
+            let address = start.and_then(|start| {
+                Ok(start
+                    .zip(offsets?)
+                    .map(|(start, offsets)| Address::chain(start, offsets)))
+            });
+            Some((ident, address))
+        }
+        grammar::Attribute::Keyed(ident, entries)
+            if ident.as_str() == name
+                && entries
+                    .iter()
+                    .any(|(key, _)| ["rva", "image"].contains(&key.as_str())) =>
+        {
+            let address = evaluate_relative_address(type_registry, scope, ident, entries, item);
+            Some((ident, address))
+        }
+        grammar::Attribute::Keyed(ident, entries) if ident.as_str() == name => {
+            let addresses = entries
+                .iter()
+                .map(|(version, expr)| {
+                    evaluate_address(type_registry, scope, ident, expr, item)
+                        .map(|a| a.map(|a| (version.to_string(), a)))
+                })
+                .collect::<Result<Option<Vec<_>>, _>>();
+            let address = addresses.and_then(|a| {
+                a.map(|a| rebase(type_registry, scope, ident, Address::Versioned(a), item))
+                    .transpose()
+                    .map(Option::flatten)
+            });
+            Some((ident, address))
+        }
+        grammar::Attribute::Signature(..) => {
+            let (ident, signature) =
+                evaluate_signature_attribute(type_registry, scope, attribute, item)?;
+            Some((ident, signature.map(|s| s.map(Address::Signature))))
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates a single address, which is made relative to the module's image if it has a
+/// preferred base
+fn evaluate_absolute_address(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    expr: &grammar::Expr,
+    item: &str,
+) -> Result<Option<Address>, Diagnostic> {
+    let Some(address) = evaluate_address(type_registry, scope, ident, expr, item)? else {
+        return Ok(None);
+    };
+    rebase(type_registry, scope, ident, Address::Fixed(address), item)
+}
+
+/// Makes an absolute `address` relative to the module's image, if the image has a preferred
+/// base. Otherwise, the address is returned as-is.
+fn rebase(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    address: Address,
+    item: &str,
+) -> Result<Option<Address>, Diagnostic> {
+    let Some(image) = &scope.image else {
+        return Ok(Some(address));
+    };
+    let Some(preferred_base) = &image.preferred_base else {
+        return Ok(Some(address));
+    };
+    let Some(preferred_base) =
+        evaluate_address(type_registry, scope, &"image".into(), preferred_base, item)?
+    else {
+        return Ok(None);
+    };
+
+    let subtract = |address: usize| {
+        address.checked_sub(preferred_base).ok_or_else(|| {
+            Diagnostic::error(
+                ErrorKind::InvalidAttribute,
+                format!(
+                    "`{ident}` attribute for {item} is {address:#x}, which is below the preferred base {preferred_base:#x} of the image `{}`",
+                    image.name
+                ),
+            )
+        })
+    };
+    let rva = match address {
+        Address::Fixed(address) => Address::Fixed(subtract(address)?),
+        Address::Versioned(addresses) => Address::Versioned(
+            addresses
+                .into_iter()
+                .map(|(version, address)| Ok((version, subtract(address)?)))
+                .collect::<Result<_, Diagnostic>>()?,
+        ),
+        address => return Ok(Some(address)),
+    };
+    Ok(Some(Address::relative(&image.name, rva)))
+}
+
+/// Evaluates an address that is an offset into an image
+/// (e.g. `#[address(rva = 0x1000, image = "engine.dll")]`). If no image is given, the
+/// module's image is used.
+fn evaluate_relative_address(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    entries: &[(grammar::Ident, grammar::Expr)],
+    item: &str,
+) -> Result<Option<Address>, Diagnostic> {
+    let mut rva = None;
+    let mut image = scope.image.as_ref().map(|i| i.name.as_str());
+    for (key, expr) in entries {
+        match key.as_str() {
+            "rva" => rva = Some(expr),
+            "image" => {
+                image = Some(expr.string_literal().ok_or_else(|| {
+                    Diagnostic::error(
+                        ErrorKind::InvalidAttribute,
+                        format!("`image` in `{ident}` attribute for {item} must be a string literal"),
+                    )
+                })?)
+            }
+            _ => {
+                return Err(Diagnostic::error(
+                    ErrorKind::InvalidAttribute,
+                    format!("unknown key `{key}` in `{ident}` attribute for {item}; expected `rva` or `image`"),
+                ))
+            }
+        }
+    }
+
+    let Some(rva) = rva else {
+        return Err(Diagnostic::error(
+            ErrorKind::MissingAttribute,
+            format!("`{ident}` attribute for {item} has an image, but no `rva`"),
+        ));
+    };
+    let Some(image) = image else {
+        return Err(Diagnostic::error(
+            ErrorKind::MissingAttribute,
+            format!("{item} has an address relative to an image, but no image was given"),
+        )
+        .with_note(r#"give one with `image = "..."`, or declare the module's image with `#![image("...")]`"#));
+    };
+    let rva = evaluate_address(type_registry, scope, ident, rva, item)?;
+    Ok(rva.map(|rva| Address::relative(image, rva)))
+}
+
+/// Evaluates `attribute` if it is a `signature` attribute
+/// (e.g. `#[signature("48 8B 05 ?? ?? ?? ??", offset = 3, rip_relative)]`).
+fn evaluate_signature_attribute<'a>(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    attribute: &'a grammar::Attribute,
+    item: &str,
+) -> Option<(&'a grammar::Ident, Result<Option<Signature>, Diagnostic>)> {
+    let grammar::Attribute::Signature(ident, signature) = attribute else {
+        return None;
+    };
+    let offset = match &signature.offset {
+        Some(offset) => evaluate_attribute(type_registry, scope, ident, offset, item),
+        None => Ok(Some(0)),
+    };
+    let signature = offset.map(|offset| {
+        offset.map(|offset| Signature {
+            pattern: signature.pattern.clone(),
+            offset,
+            rip_relative: signature.rip_relative,
+        })
+    });
+    Some((ident, signature))
+}
+
+fn evaluate_attribute_value(
+    type_registry: &TypeRegistry,
+    scope: &Scope,
+    ident: &grammar::Ident,
+    expr: &grammar::Expr,
+    item: &str,
+) -> Result<Option<i128>, Diagnostic> {
+    evaluate(type_registry, scope, expr)
+        .map_err(|d| d.with_note(format!("while evaluating `{ident}` attribute for {item}")))
+}
+
+/// Returns whether `value` can be represented by `type_`, or `None` if `type_` is not an
+/// integer type.
+pub(crate) fn fits_in_integer_type(type_: &Type, value: i128) -> Option<bool> {
+    let Type::Raw(path) = type_ else {
+        return None;
+    };
+    if path.len() != 1 {
+        return None;
+    }
+    Some(match path.last()?.as_str() {
+        "u8" => u8::try_from(value).is_ok(),
+        "u16" => u16::try_from(value).is_ok(),
+        "u32" => u32::try_from(value).is_ok(),
+        "u64" => u64::try_from(value).is_ok(),
+        "u128" => u128::try_from(value).is_ok(),
+        "i8" => i8::try_from(value).is_ok(),
+        "i16" => i16::try_from(value).is_ok(),
+        "i32" => i32::try_from(value).is_ok(),
+        "i64" => i64::try_from(value).is_ok(),
+        "i128" => true,
+        _ => return None,
+    })
+}
+
+/// The error for an attribute that can't wait for the types it depends on to be resolved.
+pub(crate) fn unresolved_attribute(ident: &grammar::Ident, item: &str) -> Diagnostic {
+    Diagnostic::error(
+        ErrorKind::UnresolvedType,
+        format!("`{ident}` attribute for {item} depends on a type that has not been resolved"),
+    )
+}
@@ -1,12 +1,13 @@
 use std::{fmt, str::FromStr};
 
-use anyhow::Context;
-
 use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Result},
     grammar::{self, ItemPath},
     semantic::{
+        expression,
+        module::Module,
         type_registry::TypeRegistry,
-        types::{Type, Visibility},
+        types::{Address, Signature, Type, Visibility},
     },
 };
 
@@ -64,7 +65,7 @@ impl fmt::Display for CallingConvention {
 }
 impl FromStr for CallingConvention {
     type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
             "C" => Ok(CallingConvention::C),
             "cdecl" => Ok(CallingConvention::Cdecl),
@@ -81,7 +82,11 @@ impl FromStr for CallingConvention {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum FunctionBody {
     Address {
-        address: usize,
+        address: Address,
+    },
+    /// The function is located by scanning memory for a pattern at runtime
+    Signature {
+        signature: Signature,
     },
     Field {
         field: String,
@@ -96,11 +101,22 @@ pub enum FunctionBody {
         /// for inheritance reasons
         function_name: String,
     },
+    /// The function is called through the vftable of the base `field`, with `this` pointing
+    /// to that base
+    BaseVftable {
+        field: String,
+        function_name: String,
+    },
 }
 
 impl FunctionBody {
-    pub fn address(address: usize) -> Self {
-        FunctionBody::Address { address }
+    pub fn address(address: impl Into<Address>) -> Self {
+        FunctionBody::Address {
+            address: address.into(),
+        }
+    }
+    pub fn signature(signature: Signature) -> Self {
+        FunctionBody::Signature { signature }
     }
     pub fn field(field: impl Into<String>, function_name: impl Into<String>) -> Self {
         FunctionBody::Field {
@@ -113,6 +129,12 @@ impl FunctionBody {
             function_name: function_name.into(),
         }
     }
+    pub fn base_vftable(field: impl Into<String>, function_name: impl Into<String>) -> Self {
+        FunctionBody::BaseVftable {
+            field: field.into(),
+            function_name: function_name.into(),
+        }
+    }
     pub fn is_field(&self) -> bool {
         matches!(self, FunctionBody::Field { .. })
     }
@@ -151,12 +173,17 @@ impl fmt::Display for Function {
         }
         write!(f, " = ")?;
         match &self.body {
-            FunctionBody::Address { address } => write!(f, "0x{address:X})")?,
+            FunctionBody::Address { address } => write!(f, "{address})")?,
+            FunctionBody::Signature { signature } => write!(f, "{signature}")?,
             FunctionBody::Field {
                 field,
                 function_name,
             } => write!(f, "self.{field}.{function_name}")?,
             FunctionBody::Vftable { function_name } => write!(f, "self.vftable.{function_name}")?,
+            FunctionBody::BaseVftable {
+                field,
+                function_name,
+            } => write!(f, "self.{field}.vftable.{function_name}")?,
         }
         Ok(())
     }
@@ -205,117 +232,177 @@ impl Function {
 
 pub fn build(
     type_registry: &TypeRegistry,
-    scope: &[ItemPath],
+    module: &Module,
     is_vfunc: bool,
     function: &grammar::Function,
-) -> Result<Function, anyhow::Error> {
+) -> Result<Function> {
+    let scope = module.scope();
+    let mut diagnostics = Diagnostics::new();
     let mut body = is_vfunc.then(|| FunctionBody::Vftable {
         function_name: function.name.0.clone(),
     });
-    // TODO: This ItemPath is not correct, but this should be fixed another time - preferabl
-    // once error handling is improved
-    let doc = function
-        .attributes
-        .doc(&ItemPath::from_iter([function.name.0.clone().into()]))?;
+    // TODO: This ItemPath is not correct, but this should be fixed another time
+    let doc = diagnostics
+        .record(
+            function
+                .attributes
+                .doc(&ItemPath::from_iter([function.name.0.clone().into()]))
+                .map_err(|d| d.with_location(module.location(function.span()))),
+        )
+        .flatten();
     let mut calling_convention = None;
+    let mut has_address = false;
+    let item = format!("function `{}`", function.name);
     for attribute in &function.attributes {
-        let Some((ident, exprs)) = attribute.function() else {
-            continue;
-        };
-        match (ident.as_str(), &exprs[..]) {
-            ("address", [grammar::Expr::IntLiteral(addr)]) => {
-                if is_vfunc {
-                    anyhow::bail!(
-                        "address attribute is not supported for virtual function `{}`",
-                        function.name
-                    );
-                }
-
-                body = Some(FunctionBody::Address {
-                    address: (*addr).try_into().with_context(|| {
+        if let Some((ident, address)) = expression::evaluate_address_attribute(
+            type_registry,
+            &scope,
+            attribute,
+            "address",
+            &item,
+        ) {
+            has_address = true;
+            if is_vfunc {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::InvalidAttribute,
                         format!(
-                            "failed to convert `address` attribute into usize for function `{}`",
+                            "{ident} attribute is not supported for virtual function `{}`",
                             function.name
-                        )
-                    })?,
-                });
+                        ),
+                    )
+                    .with_location(module.location(ident.span())),
+                );
+                continue;
             }
-            ("index", _) => {
-                // ignore index attribute, this is handled by vftable construction
-                if !is_vfunc {
-                    anyhow::bail!(
-                        "index attribute is only supported for virtual functions, not `{}`",
-                        function.name
-                    );
+
+            match address {
+                Ok(Some(Address::Signature(signature))) => {
+                    body = Some(FunctionBody::Signature { signature })
                 }
-            }
-            ("calling_convention", [grammar::Expr::StringLiteral(cc)]) => {
-                calling_convention = Some(cc.parse().map_err(|_| {
-                    anyhow::anyhow!(
-                        "invalid calling convention for function `{}`: {cc}",
-                        function.name
+                Ok(Some(Address::Chain { .. })) => diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::InvalidAttribute,
+                        format!(
+                            "{ident} attribute for function `{}` cannot follow a chain of pointers",
+                            function.name
+                        ),
                     )
-                })?);
+                    .with_location(module.location(ident.span()))
+                    .with_note("only singletons and extern values can be found by a chain"),
+                ),
+                Ok(Some(address)) => body = Some(FunctionBody::Address { address }),
+                Ok(None) => diagnostics.push(
+                    expression::unresolved_attribute(ident, &item)
+                        .with_location(module.location(ident.span())),
+                ),
+                Err(diagnostic) => {
+                    diagnostics.push(diagnostic.with_location(module.location(ident.span())))
+                }
+            }
+            continue;
+        }
+        let Some((ident, exprs)) = attribute.function() else {
+            continue;
+        };
+        let invalid_attribute = |message: String| {
+            Diagnostic::error(ErrorKind::InvalidAttribute, message)
+                .with_location(module.location(ident.span()))
+        };
+        match (ident.as_str(), &exprs[..]) {
+            // ignore index attribute for vfuncs, this is handled by vftable construction
+            ("index", _) if !is_vfunc => {
+                diagnostics.push(invalid_attribute(format!(
+                    "index attribute is only supported for virtual functions, not `{}`",
+                    function.name
+                )));
             }
+            ("calling_convention", [grammar::Expr::StringLiteral(cc)]) => match cc.parse() {
+                Ok(cc) if !type_registry.target().supports(cc) => {
+                    diagnostics.push(invalid_attribute(format!(
+                        "calling convention for function `{}` is not supported by the target `{}`: {cc}",
+                        function.name,
+                        type_registry.target()
+                    )))
+                }
+                Ok(cc) => calling_convention = Some(cc),
+                Err(_) => diagnostics.push(invalid_attribute(format!(
+                    "invalid calling convention for function `{}`: {cc}",
+                    function.name
+                ))),
+            },
             _ => {}
         }
     }
 
-    if !is_vfunc && body.is_none() {
-        anyhow::bail!(
-            "function `{}` has no implementation available; did you forget to assign an `address` attribute?",
-            function.name,
-        );
-    }
-
     let Some(body) = body else {
-        panic!(
-            "function `{}` had no body assigned: {:?}",
-            function.name, function
+        if is_vfunc {
+            panic!(
+                "function `{}` had no body assigned: {:?}",
+                function.name, function
+            );
+        }
+        // An invalid address has already been reported.
+        if has_address {
+            return Err(diagnostics.into());
+        }
+
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::MissingImplementation,
+                format!(
+                    "function `{}` has no implementation available; did you forget to assign an `address` or `signature` attribute?",
+                    function.name,
+                ),
+            )
+            .with_location(module.location(function.span())),
         );
+        return Err(diagnostics.into());
     };
 
     let arguments = function
         .arguments
         .iter()
-        .map(|a| match a {
-            grammar::Argument::ConstSelf => Ok(Argument::ConstSelf),
-            grammar::Argument::MutSelf => Ok(Argument::MutSelf),
-            grammar::Argument::Named(name, type_) => Ok(Argument::Field(
-                name.0.clone(),
-                type_registry
-                    .resolve_grammar_type(scope, type_)
-                    .ok_or_else(|| {
-                        anyhow::anyhow!(
-                            "failed to resolve type of field `{:?}` ({:?})",
-                            name,
-                            type_
+        .filter_map(|a| match a {
+            grammar::Argument::ConstSelf => Some(Argument::ConstSelf),
+            grammar::Argument::MutSelf => Some(Argument::MutSelf),
+            grammar::Argument::Named(name, type_) => {
+                let Some(type_ref) = type_registry.resolve_grammar_type(&scope, type_) else {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::UnresolvedType,
+                            format!("failed to resolve type of field `{name}` ({type_})"),
                         )
-                    })?,
-            )),
+                        .with_location(module.location(type_.span().or(name.span()))),
+                    );
+                    return None;
+                };
+                Some(Argument::Field(name.0.clone(), type_ref))
+            }
         })
-        .collect::<anyhow::Result<Vec<_>>>()?;
+        .collect::<Vec<_>>();
 
     let return_type = function
         .return_type
         .as_ref()
-        .and_then(|t| type_registry.resolve_grammar_type(scope, t));
+        .and_then(|t| type_registry.resolve_grammar_type(&scope, t));
 
     let calling_convention = calling_convention.unwrap_or_else(|| {
-        // Assume that if the function has a self argument, it's a thiscall function, otherwise it's "system"
+        // Assume that if the function has a self argument, it uses the target's calling convention
+        // for member functions (thiscall on 32-bit MSVC), otherwise it's "system"
         // for interoperating with system libraries: <https://doc.rust-lang.org/nomicon/ffi.html#foreign-calling-conventions>
         // Bit sus honestly, maybe we should enforce a calling convention for all non-self functions?
         let has_self = arguments
             .iter()
             .any(|a| matches!(a, Argument::ConstSelf | Argument::MutSelf));
         if has_self {
-            CallingConvention::Thiscall
+            type_registry.target().member_calling_convention()
         } else {
             CallingConvention::System
         }
     });
 
-    Ok(Function {
+    diagnostics.finish(Function {
         visibility: function.visibility.into(),
         name: function.name.0.clone(),
         doc,
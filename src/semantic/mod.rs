@@ -1,15 +1,23 @@
 pub mod types;
 
+mod alias_definition;
+mod config;
+mod constant;
 mod enum_definition;
+mod expression;
 mod function;
 mod module;
 mod semantic_state;
+mod target;
 mod type_definition;
 mod type_registry;
+mod union_definition;
 
 #[cfg(test)]
 mod tests;
 
-pub use module::Module;
+pub use config::Config;
+pub use module::{Import, Module};
 pub use semantic_state::{ResolvedSemanticState, SemanticState};
+pub use target::{AbiFamily, Architecture, Endianness, Target};
 pub use type_registry::TypeRegistry;
@@ -1,22 +1,93 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Location, Result},
     grammar::{self, ItemPath},
     semantic::{
-        type_registry,
-        types::{Backend, ExternValue, ItemDefinition, Type},
+        expression, type_registry,
+        types::{Backend, Constant, ExternValue, ItemDefinition, Type, Visibility},
     },
+    span::{SourceFile, Span},
 };
 
+/// An item or module brought into a module's scope by a `use`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Import {
+    /// Public imports are re-exported from the module
+    pub visibility: Visibility,
+    /// The absolute path of the imported item or module
+    pub path: ItemPath,
+    /// The name the item is imported as, or `None` for a glob import of everything within `path`
+    pub name: Option<String>,
+}
+
+/// The items visible from within a module, against which names and paths are resolved
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    /// The module itself
+    pub(crate) module: ItemPath,
+    /// Everything the module `use`s, in order
+    pub(crate) imports: Vec<Import>,
+    /// The image that the module's addresses are relative to, if it declared one
+    pub(crate) image: Option<grammar::Image>,
+}
+
+/// Resolves a path that may start with `crate`, `self` or `super` to an absolute path, from
+/// within `module`. Paths that do not start with one of these are already absolute.
+///
+/// Returns `None` if the path goes above the root, or if one of these appears later in the path.
+pub(crate) fn resolve_relative_path<'a>(
+    module: &ItemPath,
+    path: impl IntoIterator<Item = &'a str>,
+) -> Option<ItemPath> {
+    let mut segments = path.into_iter().peekable();
+    let mut resolved = match segments.peek().copied() {
+        Some("crate") => {
+            segments.next();
+            ItemPath::empty()
+        }
+        Some("self") => {
+            segments.next();
+            module.clone()
+        }
+        Some("super") => {
+            let mut resolved = module.clone();
+            while segments.next_if_eq(&"super").is_some() {
+                resolved = resolved.parent()?;
+            }
+            resolved
+        }
+        _ => ItemPath::empty(),
+    };
+
+    for segment in segments {
+        let segment = grammar::ItemPathSegment::from(segment);
+        if segment.is_relative() {
+            return None;
+        }
+        resolved.push(segment);
+    }
+    Some(resolved)
+}
+
 #[derive(Debug, Clone)]
 pub struct Module {
     pub(crate) path: ItemPath,
     pub(crate) ast: grammar::Module,
+    pub(crate) uses: Vec<Import>,
     pub(crate) definition_paths: HashSet<ItemPath>,
+    /// The items that were defined inline in another item's field, and were given names
+    pub(crate) nested_definition_paths: HashSet<ItemPath>,
     pub(crate) extern_values: Vec<ExternValue>,
+    pub(crate) constants: Vec<Constant>,
     pub(crate) impls: HashMap<ItemPath, grammar::FunctionBlock>,
     pub(crate) backends: HashMap<String, Vec<Backend>>,
     pub(crate) doc: Option<String>,
+    pub(crate) image: Option<grammar::Image>,
+    pub(crate) source: Option<Arc<SourceFile>>,
 }
 
 impl Default for Module {
@@ -24,23 +95,33 @@ impl Default for Module {
         Self {
             path: ItemPath::empty(),
             ast: Default::default(),
+            uses: Default::default(),
             definition_paths: Default::default(),
+            nested_definition_paths: Default::default(),
             extern_values: Default::default(),
+            constants: Default::default(),
             impls: Default::default(),
             backends: Default::default(),
             doc: Default::default(),
+            image: Default::default(),
+            source: Default::default(),
         }
     }
 }
 
 impl Module {
+    /// Creates the module at `path`, recording every invalid `use` and module attribute in
+    /// `diagnostics`. The module is still created without them, so that the rest of it can be
+    /// checked.
     pub(crate) fn new(
+        diagnostics: &mut Diagnostics,
         path: ItemPath,
         ast: grammar::Module,
         extern_values: Vec<ExternValue>,
         impls: &[grammar::FunctionBlock],
         backends: &[grammar::Backend],
-    ) -> anyhow::Result<Self> {
+        source: Option<Arc<SourceFile>>,
+    ) -> Self {
         let impls = impls
             .iter()
             .map(|f| (path.join(f.name.as_str().into()), f.clone()))
@@ -57,26 +138,78 @@ impl Module {
                 });
         }
 
-        let doc = ast.attributes.doc(&path)?;
-        Ok(Self {
+        let uses = ast
+            .uses
+            .iter()
+            .filter_map(|use_| {
+                let import_path = resolve_relative_path(&path, use_.path.iter().map(|s| s.as_str()));
+                let name = match (&use_.alias, use_.is_glob, &import_path) {
+                    (_, true, _) => Some(None),
+                    (Some(alias), false, _) => Some(Some(alias.0.clone())),
+                    (None, false, Some(import_path)) => {
+                        import_path.last().map(|s| Some(s.as_str().to_string()))
+                    }
+                    (None, false, None) => None,
+                };
+                match (import_path, name) {
+                    (Some(import_path), Some(name)) => Some(Import {
+                        visibility: use_.visibility.into(),
+                        path: import_path,
+                        name,
+                    }),
+                    _ => {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                ErrorKind::UnresolvedType,
+                                format!("failed to resolve `{use_}` in module `{path}`"),
+                            )
+                            .with_location(Location::new(source.as_ref(), use_.span))
+                            .with_note("`super` cannot go above the root module, and `crate`, `self` and `super` can only start a path"),
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let doc = ast.attributes.doc(&path).unwrap_or_else(|error| {
+            diagnostics.push(error);
+            None
+        });
+        let image = ast.attributes.image(&path).unwrap_or_else(|error| {
+            diagnostics.push(error);
+            None
+        });
+        Self {
             path,
             ast,
+            uses,
             definition_paths: HashSet::new(),
+            nested_definition_paths: HashSet::new(),
             extern_values,
+            constants: vec![],
             impls,
             backends: backends_map,
             doc,
-        })
+            image,
+            source,
+        }
     }
 
-    pub fn uses(&self) -> &[ItemPath] {
-        &self.ast.uses
+    /// Everything this module `use`s, with absolute paths
+    pub fn uses(&self) -> &[Import] {
+        &self.uses
     }
 
     pub fn definition_paths(&self) -> &HashSet<ItemPath> {
         &self.definition_paths
     }
 
+    /// The constants defined in this module, in the order they were evaluated
+    pub fn constants(&self) -> &[Constant] {
+        &self.constants
+    }
+
     pub fn definitions<'a>(
         &'a self,
         type_registry: &'a type_registry::TypeRegistry,
@@ -86,30 +219,85 @@ impl Module {
             .filter_map(|p| type_registry.get(p))
     }
 
-    pub fn scope(&self) -> Vec<ItemPath> {
-        std::iter::once(self.path.clone())
-            .chain(self.uses().iter().cloned())
-            .collect()
+    pub fn scope(&self) -> Scope {
+        Scope {
+            module: self.path.clone(),
+            imports: self.uses.clone(),
+            image: self.image.clone(),
+        }
     }
 
+    /// Resolves the addresses and types of the extern values, skipping those in `failed`, whose
+    /// errors have already been reported.
     pub(crate) fn resolve_extern_values(
         &mut self,
         type_registry: &mut type_registry::TypeRegistry,
-    ) -> anyhow::Result<()> {
+        failed: &HashSet<ItemPath>,
+    ) -> Result<()> {
         let scope = self.scope();
+        let mut diagnostics = Diagnostics::new();
 
         for ev in &mut self.extern_values {
+            if failed.contains(&self.path.join(ev.name.as_str().into())) {
+                continue;
+            }
+            let item = format!("extern value `{}`", ev.name);
+            let address = self
+                .ast
+                .extern_values
+                .iter()
+                .filter(|e| e.name.as_str() == ev.name)
+                .flat_map(|e| &e.attributes)
+                .find_map(|a| {
+                    expression::evaluate_address_attribute(
+                        type_registry,
+                        &scope,
+                        a,
+                        "address",
+                        &item,
+                    )
+                });
+            if let Some((ident, address)) = address {
+                match address {
+                    Ok(Some(address)) => ev.address = address,
+                    Ok(None) => diagnostics.push(
+                        expression::unresolved_attribute(ident, &item)
+                            .with_location(Location::new(self.source.as_ref(), ident.span())),
+                    ),
+                    Err(diagnostic) => diagnostics.push(
+                        diagnostic.with_location(Location::new(self.source.as_ref(), ident.span())),
+                    ),
+                }
+            }
+
             if let Type::Unresolved(type_ref) = &ev.type_ {
-                ev.type_ = type_registry
-                    .resolve_grammar_type(&scope, type_ref)
-                    .ok_or_else(|| anyhow::anyhow!("failed to resolve type for {}", ev.name))?;
+                match type_registry.resolve_grammar_type(&scope, type_ref) {
+                    Some(type_) => ev.type_ = type_,
+                    None => diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::UnresolvedType,
+                            format!("failed to resolve type for {}", ev.name),
+                        )
+                        .with_location(Location::new(self.source.as_ref(), type_ref.span())),
+                    ),
+                }
             }
         }
 
-        Ok(())
+        diagnostics.finish(())
     }
 
     pub fn doc(&self) -> Option<&str> {
         self.doc.as_deref()
     }
+
+    /// The source file this module was parsed from, if any
+    pub fn source(&self) -> Option<&SourceFile> {
+        self.source.as_deref()
+    }
+
+    /// The location of `span` within this module's source, for use in diagnostics
+    pub(crate) fn location(&self, span: Span) -> Option<Location> {
+        Location::new(self.source.as_ref(), span)
+    }
 }
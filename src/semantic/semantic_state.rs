@@ -1,32 +1,52 @@
-use std::{collections::HashMap, path::Path};
-
-use anyhow::Context;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
 
 use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Location, Result},
     grammar::{self, ItemPath},
     parser,
     semantic::{
-        enum_definition,
+        alias_definition,
+        config::Config,
+        constant, enum_definition, expression,
         module::Module,
+        target::Target,
         type_definition,
-        type_registry::TypeRegistry,
+        type_registry::{self, Namespace, TypeRegistry},
         types::{
-            ExternValue, ItemCategory, ItemDefinition, ItemState, ItemStateResolved, Type,
-            TypeDefinition, Visibility,
+            Address, ExternValue, Function, FunctionBody, ItemCategory, ItemDefinition,
+            ItemDefinitionInner, ItemState, ItemStateResolved, Signature, Type, TypeDefinition,
+            Visibility,
         },
+        union_definition,
     },
+    span::{SourceFile, Span},
+    util,
 };
 
 pub struct SemanticState {
     modules: HashMap<ItemPath, Module>,
     pub(crate) type_registry: TypeRegistry,
+    config: Config,
+    /// Where each instantiation of a generic type was first used, so that errors in its layout
+    /// can point back at the use that caused it
+    instantiation_sites: HashMap<ItemPath, Location>,
 }
 
 impl SemanticState {
-    pub fn new(pointer_size: usize) -> Self {
+    pub fn new(target: Target) -> Self {
+        let primitives = target
+            .primitives()
+            .map(|(name, size, alignment)| (ItemPath::from(name), size, alignment))
+            .collect::<Vec<_>>();
         let mut semantic_state = Self {
             modules: HashMap::new(),
-            type_registry: TypeRegistry::new(pointer_size),
+            type_registry: TypeRegistry::new(target),
+            config: Config::default(),
+            instantiation_sites: HashMap::new(),
         };
 
         // Insert the empty root module.
@@ -34,27 +54,8 @@ impl SemanticState {
             .modules
             .insert(ItemPath::empty(), Module::default());
 
-        // Insert all of our predefined types.
-        let predefined_types = [
-            ("void", 0),
-            ("bool", 1),
-            ("u8", 1),
-            ("u16", 2),
-            ("u32", 4),
-            ("u64", 8),
-            ("u128", 16),
-            ("i8", 1),
-            ("i16", 2),
-            ("i32", 4),
-            ("i64", 8),
-            ("i128", 16),
-            ("f32", 4),
-            ("f64", 8),
-        ];
-
-        for (name, size) in predefined_types {
-            let path = ItemPath::from(name);
-            let alignment = size.max(1);
+        // Insert all of our predefined types, laid out as the target lays them out.
+        for (path, size, alignment) in primitives {
             semantic_state
                 .add_item(ItemDefinition {
                     visibility: Visibility::Public,
@@ -76,77 +77,155 @@ impl SemanticState {
         semantic_state
     }
 
-    // todo: define an actual error type
-    pub fn add_file(&mut self, base_path: &Path, path: &Path) -> anyhow::Result<()> {
-        self.add_module(
-            &parser::parse_str(&std::fs::read_to_string(path)?).map_err(|e| {
-                let proc_macro2::LineColumn { line, column } = e.span().start();
-                anyhow::Error::new(e).context(format!(
-                    "failed to parse {}:{}:{}",
-                    path.display(),
-                    line,
-                    column + 1
-                ))
-            })?,
+    /// Sets the configuration that `#[cfg(...)]` predicates are evaluated against. This must be
+    /// set before any modules are added.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn add_file(&mut self, base_path: &Path, path: &Path) -> Result<()> {
+        self.add_source_file(
+            SourceFile::new(path, std::fs::read_to_string(path)?),
             &ItemPath::from_path(path.strip_prefix(base_path).unwrap_or(path)),
         )
     }
 
-    pub fn add_module(&mut self, module: &grammar::Module, path: &ItemPath) -> anyhow::Result<()> {
+    /// Parses `source` and adds it as the module at `module_path`. Errors in this module
+    /// will point into `source`.
+    ///
+    /// If `source` has syntax errors, the items that did parse are still added, and every
+    /// syntax error is reported.
+    pub fn add_source_file(&mut self, source: SourceFile, module_path: &ItemPath) -> Result<()> {
+        let source = Arc::new(source);
+        let parse_error = |e: syn::Error| {
+            Diagnostic::error(ErrorKind::Parse, e.to_string())
+                .with_location(Location::new(Some(&source), e.span().into()))
+        };
+        let (module, errors) =
+            parser::parse_str_with_recovery(&source.contents).map_err(parse_error)?;
+
+        let mut diagnostics = Diagnostics::new();
+        for error in errors.into_iter().flatten() {
+            diagnostics.push(parse_error(error));
+        }
+        diagnostics.record(self.add_module_impl(&module, module_path, Some(source.clone())));
+        diagnostics.finish(())
+    }
+
+    pub fn add_module(&mut self, module: &grammar::Module, path: &ItemPath) -> Result<()> {
+        self.add_module_impl(module, path, None)
+    }
+
+    fn add_module_impl(
+        &mut self,
+        module: &grammar::Module,
+        path: &ItemPath,
+        source: Option<Arc<SourceFile>>,
+    ) -> Result<()> {
+        let location = |span| Location::new(source.as_ref(), span);
+        let mut diagnostics = Diagnostics::new();
+        let module = self.config.apply(
+            &mut diagnostics,
+            module,
+            path,
+            self.type_registry.target(),
+            location,
+        );
+        let (module, nested_paths) =
+            extract_nested_items(&mut diagnostics, &module, path, location);
+        let module = &module;
+
         let extern_values = module
             .extern_values
             .iter()
-            .map(|ev| {
+            .filter_map(|ev| {
                 let name = &ev.name;
-                let mut address = None;
-                for attribute in &ev.attributes {
-                    let Some((ident, exprs)) = attribute.function() else {
-                        continue;
-                    };
-                    if let ("address", [grammar::Expr::IntLiteral(addr)]) =
-                        (ident.as_str(), &exprs[..])
-                    {
-                        address = Some(*addr as usize);
+                let has_address = ev.attributes.iter().any(|a| match a {
+                    grammar::Attribute::Function(ident, exprs) => {
+                        ident.as_str() == "address" && exprs.len() == 1
+                    }
+                    // Per-version addresses; per-target addresses have already been selected.
+                    grammar::Attribute::Keyed(ident, _) | grammar::Attribute::Chain(ident, _) => {
+                        ident.as_str() == "address"
                     }
+                    grammar::Attribute::Signature(_, _) => true,
+                    _ => false,
+                });
+                if !has_address {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::MissingAttribute,
+                            format!(
+                                "failed to find `address` or `signature` attribute for extern value `{name}` in module `{path}`"
+                            ),
+                        )
+                        .with_location(location(name.span())),
+                    );
+                    return None;
                 }
 
-                let address = address.with_context(|| {
-                    format!(
-                        "failed to find `address` attribute for extern value `{}` in module `{}`",
-                        name, path
-                    )
-                })?;
-
-                Ok(ExternValue {
+                // The address is evaluated alongside the type, as it may depend on types.
+                Some(ExternValue {
                     visibility: Visibility::from(ev.visibility),
                     name: name.as_str().to_owned(),
                     type_: Type::Unresolved(ev.type_.clone()),
-                    address,
+                    address: Address::Fixed(0),
                 })
             })
-            .collect::<anyhow::Result<Vec<_>>>()?;
+            .collect::<Vec<_>>();
 
-        self.modules.insert(
+        let mut new_module = Module::new(
+            &mut diagnostics,
             path.clone(),
-            Module::new(
-                path.clone(),
-                module.clone(),
-                extern_values,
-                &module.impls,
-                &module.backends,
-            )?,
+            module.clone(),
+            extern_values,
+            &module.impls,
+            &module.backends,
+            source.clone(),
         );
+        new_module.nested_definition_paths = nested_paths;
+        for import in new_module.uses() {
+            if import.visibility == Visibility::Public {
+                self.type_registry.add_reexport(path, import);
+            }
+        }
+        self.modules.insert(path.clone(), new_module);
+
+        for constant in &module.constants {
+            let constant_path = path.join(constant.name.as_str().into());
+            if self.type_registry.is_constant(&constant_path) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::DuplicateDefinition,
+                        format!("constant `{constant_path}` is already defined"),
+                    )
+                    .with_location(location(constant.name.span())),
+                );
+                continue;
+            }
+            self.type_registry.add_constant(constant_path);
+        }
 
         for definition in &module.definitions {
             let new_path = path.join(definition.name.as_str().into());
-            self.add_item(ItemDefinition {
+            let state = match &definition.inner {
+                grammar::ItemDefinitionInner::Type(td) if !td.generics.is_empty() => {
+                    check_generic_definition(&mut diagnostics, module, &new_path, td, location);
+                    ItemState::Generic(definition.clone())
+                }
+                _ => ItemState::Unresolved(definition.clone()),
+            };
+            let result = self.add_item(ItemDefinition {
                 visibility: definition.visibility.into(),
                 path: new_path,
-                state: ItemState::Unresolved(definition.clone()),
+                state,
                 category: ItemCategory::Defined,
-            })?;
+            });
+            diagnostics.record(result);
         }
 
+        let scope = self.modules[path].scope();
         for (extern_path, attributes) in &module.extern_types {
             let mut size = None;
             let mut alignment = None;
@@ -154,34 +233,50 @@ impl SemanticState {
                 let Some((ident, exprs)) = attribute.function() else {
                     continue;
                 };
-                match (ident.as_str(), &exprs[..]) {
-                    ("size", [grammar::Expr::IntLiteral(size_)]) => {
-                        size = Some(
-                            (*size_)
-                                .try_into()
-                                .with_context(|| format!("failed to convert `size` attribute into usize for extern type `{extern_path}` in module `{path}`"))?,
-                        );
-                    }
-                    ("align", [grammar::Expr::IntLiteral(alignment_)]) => {
-                        alignment = Some(
-                            (*alignment_)
-                                .try_into()
-                                .with_context(|| format!("failed to convert `align` attribute into usize for extern type `{extern_path}` in module `{path}`"))?,
-                        );
+                let (target, expr) = match (ident.as_str(), &exprs[..]) {
+                    ("size", [expr]) => (&mut size, expr),
+                    ("align", [expr]) => (&mut alignment, expr),
+                    _ => continue,
+                };
+                let item = format!("extern type `{extern_path}` in module `{path}`");
+                match expression::evaluate_attribute(&self.type_registry, &scope, ident, expr, &item)
+                {
+                    Ok(Some(value)) => *target = Some(value),
+                    Ok(None) => diagnostics.push(
+                        expression::unresolved_attribute(ident, &item)
+                            .with_location(location(attribute.span()))
+                            .with_note("extern types are added before other types are resolved, so their attributes can only depend on predefined types and extern types that have already been added"),
+                    ),
+                    Err(diagnostic) => {
+                        diagnostics.push(diagnostic.with_location(location(attribute.span())))
                     }
-                    _ => {}
                 }
             }
-            let size = size.with_context(|| {
-                format!("failed to find `size` attribute for extern type `{extern_path}` in module `{path}`")
-            })?;
-            let alignment = alignment.with_context(|| {
-                format!("failed to find `align` attribute for extern type `{extern_path}` in module `{path}`")
-            })?;
+
+            let missing_attribute = |name: &str| {
+                Diagnostic::error(
+                    ErrorKind::MissingAttribute,
+                    format!(
+                        "failed to find `{name}` attribute for extern type `{extern_path}` in module `{path}`"
+                    ),
+                )
+                .with_location(location(extern_path.span()))
+            };
+            let (size, alignment) = match (size, alignment) {
+                (Some(size), Some(alignment)) => (size, alignment),
+                (None, _) => {
+                    diagnostics.push(missing_attribute("size"));
+                    continue;
+                }
+                (_, None) => {
+                    diagnostics.push(missing_attribute("align"));
+                    continue;
+                }
+            };
 
             let extern_path = path.join(extern_path.as_str().into());
 
-            self.add_item(ItemDefinition {
+            let result = self.add_item(ItemDefinition {
                 visibility: Visibility::Public,
                 path: extern_path.clone(),
                 state: ItemState::Resolved(ItemStateResolved {
@@ -190,80 +285,170 @@ impl SemanticState {
                     inner: TypeDefinition::default().into(),
                 }),
                 category: ItemCategory::Extern,
-            })?;
+            });
+            diagnostics.record(result);
         }
 
-        Ok(())
+        diagnostics.finish(())
     }
 
-    pub fn add_item(&mut self, item_definition: ItemDefinition) -> anyhow::Result<()> {
-        let parent_path = &item_definition.path.parent().with_context(|| {
-            format!(
-                "failed to get parent path for type `{}`",
-                item_definition.path
+    pub fn add_item(&mut self, item_definition: ItemDefinition) -> Result<()> {
+        let parent_path = &item_definition.path.parent().ok_or_else(|| {
+            Diagnostic::error(
+                ErrorKind::Internal,
+                format!(
+                    "failed to get parent path for type `{}`",
+                    item_definition.path
+                ),
             )
         })?;
         self.modules
             .get_mut(parent_path)
-            .with_context(|| format!("failed to get module for path `{parent_path}`"))?
+            .ok_or_else(|| {
+                Diagnostic::error(
+                    ErrorKind::Internal,
+                    format!("failed to get module for path `{parent_path}`"),
+                )
+            })?
             .definition_paths
             .insert(item_definition.path.clone());
         self.type_registry.add(item_definition);
         Ok(())
     }
 
-    pub fn build(mut self) -> anyhow::Result<ResolvedSemanticState> {
+    /// Resolves all of the types that have been added. Every independent failure is
+    /// collected and returned together, instead of stopping at the first one.
+    pub fn build(mut self) -> Result<ResolvedSemanticState> {
+        let mut diagnostics = Diagnostics::new();
+        if let Err(error) = self.config.check_target(self.type_registry.target()) {
+            diagnostics.push(error);
+        }
+        let mut failed = self.instantiate_generics(&mut diagnostics);
+        failed.extend(self.check_ambiguous_imports(&mut diagnostics));
+        let unresolved = |type_registry: &TypeRegistry, failed: &HashSet<ItemPath>| {
+            let mut unresolved = type_registry.unresolved();
+            unresolved.retain(|p| !failed.contains(p));
+            unresolved.sort();
+            unresolved
+        };
+
         loop {
-            let to_resolve = self.type_registry.unresolved();
+            let to_resolve = unresolved(&self.type_registry, &failed);
             if to_resolve.is_empty() {
                 break;
             }
 
             for resolvee_path in &to_resolve {
-                let ItemState::Unresolved(definition) = self
+                if self.type_registry.is_constant(resolvee_path) {
+                    if let Err(error) = self.build_constant(resolvee_path) {
+                        diagnostics.extend(error);
+                        failed.insert(resolvee_path.clone());
+                    }
+                    continue;
+                }
+
+                let (definition, instance) = match self
                     .type_registry
                     .get(resolvee_path)
-                    .with_context(|| format!("failed to get type `{resolvee_path}`"))?
-                    .state
-                    .clone()
-                else {
-                    continue;
+                    .map(|t| t.state.clone())
+                {
+                    Some(ItemState::Unresolved(definition)) => (definition, None),
+                    Some(ItemState::Instance(template, arguments)) => {
+                        let Some(ItemState::Generic(definition)) =
+                            self.type_registry.get(&template).map(|t| t.state.clone())
+                        else {
+                            continue;
+                        };
+                        (definition, Some((template, arguments)))
+                    }
+                    _ => continue,
                 };
 
                 let visibility: Visibility = definition.visibility.into();
 
                 let item = match &definition.inner {
-                    grammar::ItemDefinitionInner::Type(ty) => {
-                        type_definition::build(&mut self, resolvee_path, visibility, ty)?
-                    }
+                    grammar::ItemDefinitionInner::Type(ty) => type_definition::build(
+                        &mut self,
+                        resolvee_path,
+                        visibility,
+                        definition.name.span(),
+                        ty,
+                        instance.as_ref().map(|(t, a)| (t, a.as_slice())),
+                    ),
                     grammar::ItemDefinitionInner::Enum(e) => {
-                        enum_definition::build(&self, resolvee_path, e)?
+                        enum_definition::build(&self, resolvee_path, definition.name.span(), e)
+                    }
+                    grammar::ItemDefinitionInner::Union(u) => {
+                        union_definition::build(&self, resolvee_path, definition.name.span(), u)
+                    }
+                    grammar::ItemDefinitionInner::Alias(a) => {
+                        alias_definition::build(&self, resolvee_path, definition.name.span(), a)
                     }
                 };
 
-                let Some(item) = item else { continue };
-                self.type_registry.get_mut(resolvee_path).unwrap().state =
-                    ItemState::Resolved(item);
+                match item {
+                    Ok(Some(item)) => {
+                        self.type_registry.get_mut(resolvee_path).unwrap().state =
+                            ItemState::Resolved(item);
+                    }
+                    Ok(None) => {}
+                    Err(mut error) => {
+                        // Errors in an instantiation point into the generic type, so point out
+                        // where it was instantiated as well.
+                        if let (Some((template, arguments)), Some(site)) =
+                            (&instance, self.instantiation_sites.get(resolvee_path))
+                        {
+                            let instance = Type::Generic(template.clone(), arguments.clone());
+                            error = error.with_note(format!(
+                                "`{resolvee_path}` is the instantiation `{instance}` at {}",
+                                site.position()
+                            ));
+                        }
+                        // Record the failure and stop trying to resolve this type; anything that
+                        // depends on it is left unresolved, and failed along with it.
+                        diagnostics.extend(error);
+                        failed.insert(resolvee_path.clone());
+                    }
+                }
             }
 
-            if to_resolve == self.type_registry.unresolved() {
-                // Oh no! We failed to resolve any new types!
+            if to_resolve == unresolved(&self.type_registry, &failed) {
+                // Oh no! We failed to resolve any new types! Those that can't be resolved
+                // because of a type that failed to build have already been reported through it.
+                self.fail_dependents(&to_resolve, &mut failed);
+                let to_resolve = unresolved(&self.type_registry, &failed);
+                if to_resolve.is_empty() {
+                    break;
+                }
+
                 // Bail from the loop.
-                return Err(anyhow::anyhow!(
-                    "type resolution will not terminate, failed on types: {:?} (resolved types: {:?})",
-                    Vec::from_iter(to_resolve.iter().map(|s| s.to_string())),
-                    Vec::from_iter(self.type_registry.resolved().iter().map(|s| s.to_string())),
-                ));
+                let mut resolved = self.type_registry.resolved();
+                resolved.sort();
+                let diagnostic = Diagnostic::error(
+                    ErrorKind::UnresolvedType,
+                    format!(
+                        "type resolution will not terminate, failed on types: {:?} (resolved types: {:?})",
+                        Vec::from_iter(to_resolve.iter().map(|s| s.to_string())),
+                        Vec::from_iter(resolved.iter().map(|s| s.to_string())),
+                    ),
+                );
+                diagnostics.push(diagnostic);
+                break;
             }
         }
 
+        diagnostics.record(self.check_generic_instances());
+
         // Now that we've finished resolving all of our types, we should be able
         // to resolve our extern values.
-        for module in self.modules.values_mut() {
-            module.resolve_extern_values(&mut self.type_registry)?;
+        let mut module_paths: Vec<_> = self.modules.keys().cloned().collect();
+        module_paths.sort();
+        for module_path in module_paths {
+            let module = self.modules.get_mut(&module_path).unwrap();
+            diagnostics.record(module.resolve_extern_values(&mut self.type_registry, &failed));
         }
 
-        Ok(ResolvedSemanticState {
+        diagnostics.finish(ResolvedSemanticState {
             modules: self.modules,
             type_registry: self.type_registry,
         })
@@ -271,11 +456,807 @@ impl SemanticState {
 }
 
 impl SemanticState {
+    /// Evaluates the constant at `path`, if everything it depends on has been resolved.
+    fn build_constant(&mut self, path: &ItemPath) -> Result<()> {
+        let name = path.last().map(|s| s.as_str()).unwrap_or_default();
+        let Some(definition) = self
+            .get_module_for_path(path)
+            .and_then(|m| m.ast.constants.iter().find(|c| c.name.as_str() == name))
+            .cloned()
+        else {
+            return Err(Diagnostic::error(
+                ErrorKind::Internal,
+                format!("failed to get definition for constant `{path}`"),
+            )
+            .into());
+        };
+
+        if let Some(constant) = constant::build(self, path, &definition)? {
+            self.type_registry.set_constant(path, constant.value);
+            if let Some(module) = path.parent().and_then(|p| self.modules.get_mut(&p)) {
+                module.constants.push(constant);
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers every instantiation of a generic type that is used by a module, as well as
+    /// the instantiations that those instantiations use in turn.
+    ///
+    /// Returns the items that use generic types incorrectly, whose errors have been recorded.
+    fn instantiate_generics(&mut self, diagnostics: &mut Diagnostics) -> HashSet<ItemPath> {
+        let mut failed = HashSet::new();
+        let mut instances = vec![];
+        let mut collisions = HashSet::new();
+
+        let mut module_paths: Vec<_> = self.modules.keys().cloned().collect();
+        module_paths.sort();
+        for module_path in &module_paths {
+            let module = &self.modules[module_path];
+            // Each item's uses are checked separately, so that an item that uses a generic type
+            // incorrectly can be left out of type resolution instead of failing again there.
+            for (path, types, exprs) in item_uses(module_path, &module.ast) {
+                let mut item_diagnostics = Diagnostics::new();
+                // Types can also be used by expressions (e.g. `sizeof(T)`).
+                for type_ in types
+                    .into_iter()
+                    .chain(exprs.iter().flat_map(|e| e.types()))
+                {
+                    find_instances(
+                        &self.type_registry,
+                        module,
+                        &HashMap::new(),
+                        type_,
+                        &mut instances,
+                        &mut item_diagnostics,
+                    );
+                }
+                if !item_diagnostics.is_empty() {
+                    diagnostics.extend(item_diagnostics.into());
+                    failed.insert(path);
+                }
+            }
+        }
+
+        while let Some((template, arguments, site)) = instances.pop() {
+            let path = type_registry::instance_path(&template, &arguments);
+            if let Some(existing) = self.type_registry.get(&path) {
+                // Distinct instantiations can mangle to the same name (e.g. with types whose
+                // names contain underscores), and must not silently share a layout.
+                let instance = Type::Generic(template.clone(), arguments.clone());
+                let message = match &existing.state {
+                    ItemState::Instance(t, a) if t == &template && a == &arguments => continue,
+                    _ if !collisions.insert(instance.clone()) => continue,
+                    ItemState::Instance(t, a) => format!(
+                        "instantiations `{}` and `{instance}` of generic type `{template}` would both be named `{path}`",
+                        Type::Generic(t.clone(), a.clone())
+                    ),
+                    _ => format!(
+                        "instantiation `{instance}` of generic type `{template}` would be named `{path}`, which is already the name of another item"
+                    ),
+                };
+                let location = match self.type_registry.get(&template).map(|t| &t.state) {
+                    Some(ItemState::Generic(definition)) => self
+                        .get_module_for_path(&template)
+                        .and_then(|m| m.location(definition.name.span())),
+                    _ => None,
+                };
+                diagnostics.push(
+                    Diagnostic::error(ErrorKind::InvalidGeneric, message)
+                        .with_location(location)
+                        .with_note(
+                            "rename one of the types so that the instantiations can be told apart",
+                        ),
+                );
+                continue;
+            }
+            let Some(template_definition) = self.type_registry.get(&template) else {
+                continue;
+            };
+            let ItemState::Generic(definition) = template_definition.state.clone() else {
+                continue;
+            };
+            let grammar::ItemDefinitionInner::Type(td) = &definition.inner else {
+                continue;
+            };
+            diagnostics.record(self.add_item(ItemDefinition {
+                visibility: template_definition.visibility,
+                path: path.clone(),
+                state: ItemState::Instance(template.clone(), arguments.clone()),
+                category: ItemCategory::Defined,
+            }));
+            if let Some(site) = &site {
+                self.instantiation_sites.insert(path.clone(), site.clone());
+            }
+
+            // Find the instantiations used by the fields of this instantiation. They are
+            // attributed to the use that caused this one, as that is what the user wrote.
+            let Some(module) = self.get_module_for_path(&template) else {
+                continue;
+            };
+            let first_nested = instances.len();
+            let parameters = td
+                .generics
+                .iter()
+                .map(|g| g.0.clone())
+                .zip(arguments)
+                .collect();
+            let mut item_diagnostics = Diagnostics::new();
+            for statement in &td.statements {
+                if let grammar::TypeField::Field(_, _, type_) = &statement.field {
+                    find_instances(
+                        &self.type_registry,
+                        module,
+                        &parameters,
+                        type_,
+                        &mut instances,
+                        &mut item_diagnostics,
+                    );
+                }
+            }
+            if site.is_some() {
+                for (_, _, nested_site) in &mut instances[first_nested..] {
+                    nested_site.clone_from(&site);
+                }
+            }
+            if !item_diagnostics.is_empty() {
+                diagnostics.extend(item_diagnostics.into());
+                failed.insert(path);
+            }
+        }
+
+        failed
+    }
+
+    /// Adds the items in `unresolved` that refer, directly or through each other, to an item in
+    /// `failed` to `failed`, as they can't be resolved and their error has already been reported.
+    fn fail_dependents(&self, unresolved: &[ItemPath], failed: &mut HashSet<ItemPath>) {
+        fn referenced_items(type_: &Type, items: &mut Vec<ItemPath>) {
+            match type_ {
+                Type::Raw(path) => items.push(path.clone()),
+                Type::Generic(path, arguments) => {
+                    items.push(path.clone());
+                    items.push(type_registry::instance_path(path, arguments));
+                    for argument in arguments {
+                        referenced_items(argument, items);
+                    }
+                }
+                Type::ConstPointer(t) | Type::MutPointer(t) | Type::Array(t, _) => {
+                    referenced_items(t, items)
+                }
+                Type::Function(_, arguments, return_type, _) => {
+                    for type_ in arguments.iter().map(|(_, t)| t).chain(return_type) {
+                        referenced_items(type_, items);
+                    }
+                }
+                Type::Unresolved(_) | Type::Parameter(_) => {}
+            }
+        }
+
+        let mut dependencies: HashMap<ItemPath, Vec<ItemPath>> = HashMap::new();
+        for (module_path, module) in &self.modules {
+            let scope = module.scope();
+            for (path, types, exprs) in item_uses(module_path, &module.ast) {
+                let items = dependencies.entry(path).or_default();
+                for type_ in types
+                    .into_iter()
+                    .chain(exprs.iter().flat_map(|e| e.types()))
+                {
+                    if let Some(type_) = self.type_registry.resolve_grammar_type(&scope, type_) {
+                        referenced_items(&type_, items);
+                    }
+                }
+                for constant in exprs.iter().flat_map(|e| e.constants()) {
+                    items.extend(self.type_registry.resolve_constant(&scope, constant));
+                }
+            }
+        }
+        // Instantiations refer to their arguments, and to what the fields of their generic type
+        // refer to once the arguments are substituted.
+        for path in unresolved {
+            let Some(ItemState::Instance(template, arguments)) =
+                self.type_registry.get(path).map(|t| &t.state)
+            else {
+                continue;
+            };
+            let (Some(ItemState::Generic(definition)), Some(module)) = (
+                self.type_registry.get(template).map(|t| &t.state),
+                self.get_module_for_path(template),
+            ) else {
+                continue;
+            };
+            let grammar::ItemDefinitionInner::Type(td) = &definition.inner else {
+                continue;
+            };
+            let parameters = td
+                .generics
+                .iter()
+                .map(|g| g.0.to_string())
+                .zip(arguments.iter().cloned())
+                .collect();
+            let scope = module.scope();
+            let items = dependencies.entry(path.clone()).or_default();
+            for argument in arguments {
+                referenced_items(argument, items);
+            }
+            for statement in &td.statements {
+                if let grammar::TypeField::Field(_, _, type_) = &statement.field {
+                    if let Some(type_) = self.type_registry.resolve_grammar_type_with_parameters(
+                        &scope,
+                        &parameters,
+                        type_,
+                    ) {
+                        referenced_items(&type_, items);
+                    }
+                }
+            }
+        }
+
+        loop {
+            let dependents: Vec<_> = unresolved
+                .iter()
+                .filter(|path| !failed.contains(*path))
+                .filter(|path| {
+                    dependencies
+                        .get(*path)
+                        .is_some_and(|items| items.iter().any(|item| failed.contains(item)))
+                })
+                .cloned()
+                .collect();
+            if dependents.is_empty() {
+                break;
+            }
+            failed.extend(dependents);
+        }
+    }
+
+    /// Reports the names used by each item that more than one glob import provides a different
+    /// item for, as they can't be resolved.
+    ///
+    /// Returns the items that use them, whose errors have been recorded.
+    fn check_ambiguous_imports(&self, diagnostics: &mut Diagnostics) -> HashSet<ItemPath> {
+        type Name<'a> = (Namespace, &'a [grammar::Ident]);
+        fn type_names<'a>(type_: &'a grammar::Type, names: &mut Vec<Name<'a>>) {
+            match type_ {
+                grammar::Type::ConstPointer(t) | grammar::Type::MutPointer(t) => {
+                    type_names(t, names)
+                }
+                grammar::Type::Array(t, length) => {
+                    type_names(t, names);
+                    expr_names(length, names);
+                }
+                grammar::Type::Ident(ident) => {
+                    names.push((Namespace::Types, std::slice::from_ref(ident)))
+                }
+                grammar::Type::Path(path) => names.push((Namespace::Types, path)),
+                grammar::Type::Generic(path, arguments) => {
+                    names.push((Namespace::Types, path));
+                    for argument in arguments {
+                        type_names(argument, names);
+                    }
+                }
+                grammar::Type::Function(function) => {
+                    for type_ in function.types() {
+                        type_names(type_, names);
+                    }
+                }
+                grammar::Type::Unknown(_) => {}
+            }
+        }
+        fn expr_names<'a>(expr: &'a grammar::Expr, names: &mut Vec<Name<'a>>) {
+            match expr {
+                grammar::Expr::IntLiteral(_) | grammar::Expr::StringLiteral(_) => {}
+                grammar::Expr::Ident(ident) => {
+                    names.push((Namespace::Constants, std::slice::from_ref(ident)))
+                }
+                grammar::Expr::Path(path) => names.push((Namespace::Constants, path)),
+                grammar::Expr::Unary(_, expr) => expr_names(expr, names),
+                grammar::Expr::Binary(lhs, _, rhs) => {
+                    expr_names(lhs, names);
+                    expr_names(rhs, names);
+                }
+                grammar::Expr::SizeOf(type_)
+                | grammar::Expr::AlignOf(type_)
+                | grammar::Expr::OffsetOf(type_, _) => type_names(type_, names),
+            }
+        }
+
+        let mut failed = HashSet::new();
+        let mut reported = HashSet::new();
+
+        let mut module_paths: Vec<_> = self.modules.keys().collect();
+        module_paths.sort();
+        for module_path in module_paths {
+            let module = &self.modules[module_path];
+            let scope = module.scope();
+            for (path, types, exprs) in item_uses(module_path, &module.ast) {
+                let mut names = vec![];
+                for type_ in types {
+                    type_names(type_, &mut names);
+                }
+                for expr in exprs {
+                    expr_names(expr, &mut names);
+                }
+
+                for (namespace, name) in names {
+                    let segments: Vec<_> = name.iter().map(|s| s.as_str()).collect();
+                    let Some(imports) = self
+                        .type_registry
+                        .ambiguous_imports(namespace, &scope, &segments)
+                    else {
+                        continue;
+                    };
+                    failed.insert(path.clone());
+
+                    // Report each name once per module, at its first use.
+                    let span = name[0].span();
+                    let name = segments.join("::");
+                    if !reported.insert((module_path, name.clone())) {
+                        continue;
+                    }
+                    let mut diagnostic = Diagnostic::error(
+                        ErrorKind::AmbiguousImport,
+                        format!("`{name}` is ambiguous in module `{module_path}`, as more than one glob import provides it"),
+                    )
+                    .with_location(module.location(span));
+                    for (import, item) in imports {
+                        diagnostic = diagnostic.with_note(format!(
+                            "`{name}` could refer to `{item}`, imported by `use {}::*`",
+                            import.path
+                        ));
+                    }
+                    diagnostics.push(
+                        diagnostic.with_note(format!("import `{name}` by name to choose one")),
+                    );
+                }
+            }
+        }
+
+        failed
+    }
+
+    /// Ensures that all of the instantiations of each generic type that is not monomorphised
+    /// share a layout, so that they can be represented by one generic type.
+    fn check_generic_instances(&self) -> Result<()> {
+        let mut diagnostics = Diagnostics::new();
+
+        let mut instances: HashMap<&ItemPath, Vec<(&ItemPath, usize, &Vec<_>)>> = HashMap::new();
+        let mut resolved = self.type_registry.resolved();
+        resolved.sort();
+        for path in &resolved {
+            let Some(item) = self.type_registry.get(path) else {
+                continue;
+            };
+            let Some(resolved) = item.resolved() else {
+                continue;
+            };
+            let Some(instance) = resolved
+                .inner
+                .as_type()
+                .and_then(|td| td.generic_instance.as_ref())
+            else {
+                continue;
+            };
+            if let Some(generic_regions) = &instance.generic_regions {
+                instances.entry(&instance.template).or_default().push((
+                    &item.path,
+                    resolved.alignment,
+                    generic_regions,
+                ));
+            }
+        }
+
+        let mut templates: Vec<_> = instances.keys().copied().collect();
+        templates.sort();
+        for template in templates {
+            let instances = &instances[template];
+            let (first_path, first_alignment, first_regions) = instances[0];
+            let Some((path, _, _)) = instances[1..].iter().find(|(_, alignment, regions)| {
+                *alignment != first_alignment || *regions != first_regions
+            }) else {
+                continue;
+            };
+
+            let location = match self.type_registry.get(template).map(|t| &t.state) {
+                Some(ItemState::Generic(definition)) => self
+                    .get_module_for_path(template)
+                    .and_then(|m| m.location(definition.name.span())),
+                _ => None,
+            };
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::InvalidGeneric,
+                    format!(
+                        "instantiations `{first_path}` and `{path}` of generic type `{template}` have different layouts, so they cannot share a generic type"
+                    ),
+                )
+                .with_location(location)
+                .with_note("use the `monomorphise` attribute to generate a separate type for each instantiation"),
+            );
+        }
+
+        diagnostics.finish(())
+    }
+
     pub(super) fn get_module_for_path(&self, path: &ItemPath) -> Option<&Module> {
         self.modules.get(&path.parent()?)
     }
 }
 
+/// Reports the features that generic type definitions do not support.
+fn check_generic_definition(
+    diagnostics: &mut Diagnostics,
+    module: &grammar::Module,
+    path: &ItemPath,
+    definition: &grammar::TypeDefinition,
+    location: impl Fn(Span) -> Option<Location>,
+) {
+    let mut unsupported = |span: Span, feature: &str| {
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::InvalidGeneric,
+                format!("generic type `{path}` cannot {feature}"),
+            )
+            .with_location(location(span)),
+        );
+    };
+
+    for attribute in &definition.attributes {
+        match attribute {
+            grammar::Attribute::Function(ident, _)
+            | grammar::Attribute::Keyed(ident, _)
+            | grammar::Attribute::Chain(ident, _)
+                if ident.as_str() == "singleton" =>
+            {
+                unsupported(attribute.span(), "be a singleton")
+            }
+            grammar::Attribute::Signature(..) => unsupported(attribute.span(), "be a singleton"),
+            _ => {}
+        }
+    }
+    for statement in &definition.statements {
+        match &statement.field {
+            grammar::TypeField::Vftable(_) | grammar::TypeField::BaseVftable(..) => {
+                unsupported(statement.span, "have a vftable")
+            }
+            grammar::TypeField::Union(..) => unsupported(statement.span, "have an inline union"),
+            grammar::TypeField::Nested(..) => unsupported(statement.span, "have a nested item"),
+            grammar::TypeField::Field(..) => {
+                // Both `base` and `base(virtual)`
+                let is_base = statement.attributes.iter().any(|a| {
+                    matches!(
+                        a,
+                        grammar::Attribute::Ident(_) | grammar::Attribute::Function(..)
+                    ) && a.name().as_str() == "base"
+                });
+                if is_base {
+                    unsupported(statement.span, "have a base type");
+                }
+            }
+        }
+    }
+    let name = path.last().map(|s| s.as_str()).unwrap_or_default();
+    for impl_ in module.impls.iter().filter(|i| i.name.as_str() == name) {
+        unsupported(impl_.name.span(), "have associated functions");
+    }
+}
+
+/// Replaces each nested item and inline union in the module's types and unions with a field
+/// whose type is a new item in the module. Nested items are named after their owner and field
+/// (e.g. `pub pos: type { .. }` in `Entity` becomes `pub pos: EntityPos`); inline unions are
+/// named after their index within their owner (e.g. `pub union { .. }` becomes
+/// `pub union_0: EntityUnion0`). The new items inherit the `copyable`, `cloneable` and
+/// `defaultable` attributes they support from their owner, as the owner can't derive them
+/// otherwise. Generic types can't have nested items or inline unions.
+///
+/// Returns the new module and the paths of the new items.
+fn extract_nested_items(
+    diagnostics: &mut Diagnostics,
+    module: &grammar::Module,
+    path: &ItemPath,
+    location: impl Fn(Span) -> Option<Location>,
+) -> (grammar::Module, HashSet<ItemPath>) {
+    let mut module = module.clone();
+    let mut nested_paths = HashSet::new();
+
+    // New items are appended to the definitions, so that their own nested items are extracted.
+    let mut index = 0;
+    while index < module.definitions.len() {
+        let definition = &mut module.definitions[index];
+        index += 1;
+        let (attributes, statements) = match &mut definition.inner {
+            grammar::ItemDefinitionInner::Type(td) if td.generics.is_empty() => {
+                (&td.attributes, &mut td.statements)
+            }
+            grammar::ItemDefinitionInner::Union(ud) => (&ud.attributes, &mut ud.statements),
+            _ => continue,
+        };
+        let inherited_attributes = |names: &[&str]| -> Vec<grammar::Attribute> {
+            attributes
+                .iter()
+                .filter(|a| {
+                    matches!(a, grammar::Attribute::Ident(ident) if names.contains(&ident.as_str()))
+                })
+                .cloned()
+                .collect()
+        };
+
+        let mut nested = vec![];
+        let mut union_index = 0;
+        for statement in statements.iter_mut() {
+            let (visibility, field_name, name, inner) = match &statement.field {
+                grammar::TypeField::Union(visibility, statements) => {
+                    let name = format!("{}Union{union_index}", definition.name);
+                    let field_name =
+                        grammar::Ident::new(format!("union_{union_index}"), statement.span);
+                    union_index += 1;
+                    let inner = grammar::UnionDefinition::new(statements.clone()).into();
+                    (*visibility, field_name, name, inner)
+                }
+                grammar::TypeField::Nested(visibility, field_name, inner) => {
+                    let name = format!(
+                        "{}{}",
+                        definition.name,
+                        util::to_pascal_case(field_name.as_str())
+                    );
+                    (*visibility, field_name.clone(), name, inner.clone())
+                }
+                _ => continue,
+            };
+            let inner = match inner {
+                grammar::ItemDefinitionInner::Type(mut td) => {
+                    td.attributes.0.extend(inherited_attributes(&[
+                        "copyable",
+                        "cloneable",
+                        "defaultable",
+                    ]));
+                    grammar::ItemDefinitionInner::Type(td)
+                }
+                grammar::ItemDefinitionInner::Union(mut ud) => {
+                    ud.attributes
+                        .0
+                        .extend(inherited_attributes(&["copyable", "cloneable"]));
+                    grammar::ItemDefinitionInner::Union(ud)
+                }
+                inner => inner,
+            };
+
+            let name = grammar::Ident::new(name, statement.span);
+            statement.field = grammar::TypeField::Field(
+                visibility,
+                field_name,
+                grammar::Type::Ident(name.clone()),
+            );
+            nested.push(grammar::ItemDefinition {
+                visibility: definition.visibility,
+                name,
+                inner,
+            });
+        }
+
+        for item in nested {
+            if module.definitions.iter().any(|d| d.name == item.name) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::DuplicateDefinition,
+                        format!(
+                            "the nested item `{}` in module `{path}` has the same name as another item",
+                            item.name
+                        ),
+                    )
+                    .with_location(location(item.name.span())),
+                );
+                continue;
+            }
+            nested_paths.insert(path.join(item.name.as_str().into()));
+            module.definitions.push(item);
+        }
+    }
+
+    (module, nested_paths)
+}
+
+/// The types and expressions used by each item in the module `ast` at `module_path`, keyed by
+/// the item's path. Functions in an `impl` are used by the item they're implemented for.
+/// Generic type definitions are skipped, as their types are only used once instantiated.
+fn item_uses<'a>(
+    module_path: &ItemPath,
+    ast: &'a grammar::Module,
+) -> Vec<(ItemPath, Vec<&'a grammar::Type>, Vec<&'a grammar::Expr>)> {
+    fn function_types(f: &grammar::Function) -> Vec<&grammar::Type> {
+        f.arguments
+            .iter()
+            .filter_map(|a| match a {
+                grammar::Argument::Named(_, type_) => Some(type_),
+                _ => None,
+            })
+            .chain(f.return_type.as_ref())
+            .collect()
+    }
+    fn field_types(statements: &[grammar::TypeStatement]) -> Vec<&grammar::Type> {
+        statements
+            .iter()
+            .flat_map(|s| match &s.field {
+                grammar::TypeField::Field(_, _, type_) => vec![type_],
+                grammar::TypeField::Vftable(functions)
+                | grammar::TypeField::BaseVftable(_, functions) => {
+                    functions.iter().flat_map(function_types).collect()
+                }
+                _ => vec![],
+            })
+            .collect()
+    }
+    fn attribute_exprs<'a>(
+        attributes: impl IntoIterator<Item = &'a grammar::Attribute>,
+    ) -> Vec<&'a grammar::Expr> {
+        attributes
+            .into_iter()
+            .filter_map(|a| a.function())
+            .flat_map(|(_, exprs)| exprs)
+            .collect()
+    }
+
+    let item_path = |name: &grammar::Ident| module_path.join(name.as_str().into());
+    let definitions = ast.definitions.iter().map(|d| {
+        let (types, exprs) = match &d.inner {
+            grammar::ItemDefinitionInner::Type(td) if td.generics.is_empty() => (
+                field_types(&td.statements),
+                attribute_exprs(
+                    td.attributes
+                        .iter()
+                        .chain(td.statements.iter().flat_map(|s| s.attributes.iter())),
+                ),
+            ),
+            grammar::ItemDefinitionInner::Type(_) => (vec![], vec![]),
+            grammar::ItemDefinitionInner::Enum(ed) => (
+                vec![],
+                attribute_exprs(
+                    ed.attributes
+                        .iter()
+                        .chain(ed.statements.iter().flat_map(|s| s.attributes.iter())),
+                )
+                .into_iter()
+                .chain(ed.statements.iter().filter_map(|s| s.expr.as_ref()))
+                .collect(),
+            ),
+            grammar::ItemDefinitionInner::Union(ud) => (
+                field_types(&ud.statements),
+                attribute_exprs(
+                    ud.attributes
+                        .iter()
+                        .chain(ud.statements.iter().flat_map(|s| s.attributes.iter())),
+                ),
+            ),
+            grammar::ItemDefinitionInner::Alias(ad) => {
+                (vec![&ad.type_], attribute_exprs(ad.attributes.iter()))
+            }
+        };
+        (item_path(&d.name), types, exprs)
+    });
+    let impls = ast.impls.iter().map(|i| {
+        (
+            item_path(&i.name),
+            i.functions.iter().flat_map(function_types).collect(),
+            vec![],
+        )
+    });
+    let extern_values = ast.extern_values.iter().map(|ev| {
+        (
+            item_path(&ev.name),
+            vec![&ev.type_],
+            attribute_exprs(ev.attributes.iter()),
+        )
+    });
+    let constants = ast
+        .constants
+        .iter()
+        .map(|c| (item_path(&c.name), vec![], vec![&c.value]));
+
+    definitions
+        .chain(impls)
+        .chain(extern_values)
+        .chain(constants)
+        .collect()
+}
+
+/// Finds the instantiations of generic types used by `type_`, with the generic parameters in
+/// `parameters` substituted, and adds them to `instances`.
+fn find_instances(
+    type_registry: &TypeRegistry,
+    module: &Module,
+    parameters: &HashMap<String, Type>,
+    type_: &grammar::Type,
+    instances: &mut Vec<(ItemPath, Vec<Type>, Option<Location>)>,
+    diagnostics: &mut Diagnostics,
+) {
+    let scope = module.scope();
+    let generics_of = |path: &[grammar::Ident]| {
+        let Some(Type::Raw(path)) = type_registry.resolve_path(&scope, path) else {
+            return None;
+        };
+        match type_registry.get(&path).map(|t| &t.state) {
+            Some(ItemState::Generic(grammar::ItemDefinition {
+                inner: grammar::ItemDefinitionInner::Type(td),
+                ..
+            })) => Some((path, td.generics.len())),
+            _ => None,
+        }
+    };
+    let missing_arguments = |path: &[grammar::Ident], diagnostics: &mut Diagnostics| {
+        if let Some((path, _)) = generics_of(path) {
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::InvalidGeneric,
+                    format!("generic type `{path}` must be given arguments"),
+                )
+                .with_location(module.location(type_.span())),
+            );
+        }
+    };
+
+    match type_ {
+        grammar::Type::ConstPointer(t) | grammar::Type::MutPointer(t) => {
+            find_instances(type_registry, module, parameters, t, instances, diagnostics)
+        }
+        grammar::Type::Array(t, length) => {
+            for t in std::iter::once(t.as_ref()).chain(length.types()) {
+                find_instances(type_registry, module, parameters, t, instances, diagnostics);
+            }
+        }
+        grammar::Type::Ident(ident) if parameters.contains_key(ident.as_str()) => {}
+        grammar::Type::Ident(ident) => {
+            missing_arguments(std::slice::from_ref(ident), diagnostics);
+        }
+        grammar::Type::Path(path) => missing_arguments(path, diagnostics),
+        grammar::Type::Generic(path, arguments) => {
+            for argument in arguments {
+                find_instances(
+                    type_registry,
+                    module,
+                    parameters,
+                    argument,
+                    instances,
+                    diagnostics,
+                );
+            }
+
+            let Some((path, parameter_count)) = generics_of(path) else {
+                return;
+            };
+            if parameter_count != arguments.len() {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::InvalidGeneric,
+                        format!(
+                            "generic type `{path}` takes {parameter_count} argument(s), but {} were given",
+                            arguments.len()
+                        ),
+                    )
+                    .with_location(module.location(type_.span())),
+                );
+                return;
+            }
+
+            let Some(arguments) = arguments
+                .iter()
+                .map(|a| type_registry.resolve_grammar_type_with_parameters(&scope, parameters, a))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return;
+            };
+            if !arguments.iter().any(Type::has_parameters) {
+                instances.push((path, arguments, module.location(type_.span())));
+            }
+        }
+        grammar::Type::Function(function) => {
+            for t in function.types() {
+                find_instances(type_registry, module, parameters, t, instances, diagnostics);
+            }
+        }
+        grammar::Type::Unknown(_) => {}
+    }
+}
+
 #[derive(Debug)]
 pub struct ResolvedSemanticState {
     type_registry: TypeRegistry,
@@ -290,4 +1271,84 @@ impl ResolvedSemanticState {
     pub fn modules(&self) -> &HashMap<ItemPath, Module> {
         &self.modules
     }
+
+    /// Every signature that locates an item, with the path of that item, in no particular order
+    pub fn signatures(&self) -> Vec<(ItemPath, &Signature)> {
+        let mut signatures = vec![];
+        for module in self.modules.values() {
+            for definition in module.definitions(&self.type_registry) {
+                let Some((singleton, functions)) = addressed_items(definition) else {
+                    continue;
+                };
+                if let Some(Address::Signature(signature)) = singleton {
+                    signatures.push((definition.path.clone(), signature));
+                }
+                for function in functions {
+                    if let FunctionBody::Signature { signature } = &function.body {
+                        let path = definition.path.join(function.name.as_str().into());
+                        signatures.push((path, signature));
+                    }
+                }
+            }
+            for ev in &module.extern_values {
+                if let Address::Signature(signature) = &ev.address {
+                    signatures.push((module.path.join(ev.name.as_str().into()), signature));
+                }
+            }
+        }
+        signatures
+    }
+
+    /// The name of every image that an address is relative to, sorted
+    pub fn images(&self) -> Vec<&str> {
+        fn image(address: &Address) -> Option<&str> {
+            match address {
+                Address::Relative { image, .. } => Some(image),
+                Address::Chain { start, .. } => image(start),
+                _ => None,
+            }
+        }
+
+        let images = self.addresses().into_iter().filter_map(image);
+        images.collect::<BTreeSet<_>>().into_iter().collect()
+    }
+
+    /// Whether any item is found by following a chain of pointers
+    pub fn has_chains(&self) -> bool {
+        self.addresses()
+            .into_iter()
+            .any(|a| matches!(a, Address::Chain { .. }))
+    }
+
+    /// The address of every singleton, function and extern value that has one, in no
+    /// particular order
+    fn addresses(&self) -> Vec<&Address> {
+        let mut addresses = vec![];
+        for module in self.modules.values() {
+            for definition in module.definitions(&self.type_registry) {
+                let Some((singleton, functions)) = addressed_items(definition) else {
+                    continue;
+                };
+                addresses.extend(singleton);
+                addresses.extend(functions.iter().filter_map(|f| match &f.body {
+                    FunctionBody::Address { address } => Some(address),
+                    _ => None,
+                }));
+            }
+            addresses.extend(module.extern_values.iter().map(|ev| &ev.address));
+        }
+        addresses
+    }
+}
+
+/// The singleton and associated functions of a resolved definition, which are the parts of it
+/// that can have addresses
+fn addressed_items(definition: &ItemDefinition) -> Option<(Option<&Address>, &[Function])> {
+    match &definition.resolved()?.inner {
+        ItemDefinitionInner::Type(td) => {
+            Some((td.singleton.as_ref(), td.associated_functions.as_slice()))
+        }
+        ItemDefinitionInner::Enum(ed) => Some((ed.singleton.as_ref(), [].as_slice())),
+        ItemDefinitionInner::Union(_) | ItemDefinitionInner::Alias(_) => None,
+    }
 }
@@ -0,0 +1,224 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::semantic::function::CallingConvention;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Architecture {
+    X86,
+    X86_64,
+}
+impl Architecture {
+    pub const ALL: [Architecture; 2] = [Architecture::X86, Architecture::X86_64];
+
+    /// The name that per-target addresses (e.g. `address(x86 = 0x401000)`) use for targets with
+    /// this architecture, unless another is selected with [`Config::with_target`](crate::Config::with_target)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Architecture::X86 => "x86",
+            Architecture::X86_64 => "x64",
+        }
+    }
+    pub fn pointer_size(&self) -> usize {
+        match self {
+            Architecture::X86 => 4,
+            Architecture::X86_64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+impl Endianness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        }
+    }
+}
+
+/// The family of ABIs that a target follows, which decides the calling conventions of member
+/// functions and the layout of its C types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AbiFamily {
+    Msvc,
+    SystemV,
+}
+impl AbiFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AbiFamily::Msvc => "msvc",
+            AbiFamily::SystemV => "sysv",
+        }
+    }
+}
+
+/// A description of the platform that types are laid out for.
+///
+/// The predefined types (`u8`, `f64`, etc.) take their size and alignment from the target.
+/// C types whose layout depends on the target (e.g. `c_long` and `wchar_t`) are replaced by
+/// the Rust primitive with the same layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    name: String,
+    architecture: Architecture,
+    endianness: Endianness,
+    abi: AbiFamily,
+    /// The size and alignment of each predefined type
+    primitives: BTreeMap<String, (usize, usize)>,
+    /// C types mapped to the predefined type with the same layout
+    c_types: BTreeMap<String, String>,
+}
+impl Target {
+    /// The names of the targets that can be created with [`Target::from_preset`]
+    pub const PRESETS: [&'static str; 4] = ["x86-msvc", "x64-msvc", "i686-linux", "x86_64-linux"];
+
+    /// Creates a target whose primitives are naturally aligned, and whose C types are those of
+    /// its ABI family and architecture.
+    pub fn new(
+        name: impl Into<String>,
+        architecture: Architecture,
+        endianness: Endianness,
+        abi: AbiFamily,
+    ) -> Self {
+        let primitives = [
+            ("void", 0),
+            ("bool", 1),
+            ("u8", 1),
+            ("u16", 2),
+            ("u32", 4),
+            ("u64", 8),
+            ("u128", 16),
+            ("i8", 1),
+            ("i16", 2),
+            ("i32", 4),
+            ("i64", 8),
+            ("i128", 16),
+            ("f32", 4),
+            ("f64", 8),
+        ]
+        .into_iter()
+        .map(|(name, size)| (name.to_string(), (size, size.max(1))))
+        .collect();
+
+        let long_is_64_bit = abi == AbiFamily::SystemV && architecture == Architecture::X86_64;
+        let c_types = [
+            ("c_long", if long_is_64_bit { "i64" } else { "i32" }),
+            ("c_ulong", if long_is_64_bit { "u64" } else { "u32" }),
+            (
+                "wchar_t",
+                match abi {
+                    AbiFamily::Msvc => "u16",
+                    AbiFamily::SystemV => "i32",
+                },
+            ),
+        ]
+        .into_iter()
+        .map(|(name, primitive)| (name.to_string(), primitive.to_string()))
+        .collect();
+
+        Target {
+            name: name.into(),
+            architecture,
+            endianness,
+            abi,
+            primitives,
+            c_types,
+        }
+    }
+
+    /// Creates one of the targets named in [`Target::PRESETS`]
+    pub fn from_preset(name: &str) -> Option<Self> {
+        use {AbiFamily::*, Architecture::*};
+
+        match name {
+            "x86-msvc" => Some(Target::new(name, X86, Endianness::Little, Msvc)),
+            "x64-msvc" => Some(Target::new(name, X86_64, Endianness::Little, Msvc)),
+            // The i386 System V ABI only aligns 8-byte primitives to 4 bytes.
+            "i686-linux" => Some(
+                Target::new(name, X86, Endianness::Little, SystemV)
+                    .with_primitive("u64", 8, 4)
+                    .with_primitive("i64", 8, 4)
+                    .with_primitive("f64", 8, 4),
+            ),
+            "x86_64-linux" => Some(Target::new(name, X86_64, Endianness::Little, SystemV)),
+            _ => None,
+        }
+    }
+
+    /// The preset that matches a Rust target, as described by Cargo's `CARGO_CFG_TARGET_*`
+    /// variables (e.g. `x86_64` and `windows`)
+    pub fn from_rust_target(arch: &str, os: &str) -> Option<Self> {
+        let preset = match (arch, os) {
+            ("x86", "windows") => "x86-msvc",
+            ("x86_64", "windows") => "x64-msvc",
+            ("x86", _) => "i686-linux",
+            ("x86_64", _) => "x86_64-linux",
+            _ => return None,
+        };
+        Target::from_preset(preset)
+    }
+
+    /// Overrides the size and alignment of a predefined type
+    pub fn with_primitive(
+        mut self,
+        name: impl Into<String>,
+        size: usize,
+        alignment: usize,
+    ) -> Self {
+        self.primitives.insert(name.into(), (size, alignment));
+        self
+    }
+
+    /// Overrides the predefined type that a C type is replaced by
+    pub fn with_c_type(mut self, name: impl Into<String>, primitive: impl Into<String>) -> Self {
+        self.c_types.insert(name.into(), primitive.into());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn architecture(&self) -> Architecture {
+        self.architecture
+    }
+    pub fn pointer_size(&self) -> usize {
+        self.architecture.pointer_size()
+    }
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+    pub fn abi(&self) -> AbiFamily {
+        self.abi
+    }
+    /// The predefined types, with their sizes and alignments
+    pub fn primitives(&self) -> impl Iterator<Item = (&str, usize, usize)> {
+        self.primitives
+            .iter()
+            .map(|(name, (size, alignment))| (name.as_str(), *size, *alignment))
+    }
+    /// The predefined type that the C type `name` is replaced by, if it is one
+    pub fn c_type(&self, name: &str) -> Option<&str> {
+        self.c_types.get(name).map(|s| s.as_str())
+    }
+
+    /// Whether functions can be called with `calling_convention` on this target
+    pub fn supports(&self, calling_convention: CallingConvention) -> bool {
+        calling_convention != CallingConvention::Thiscall || self.architecture == Architecture::X86
+    }
+    /// The calling convention of functions that take `self`, unless another is given
+    pub fn member_calling_convention(&self) -> CallingConvention {
+        match (self.architecture, self.abi) {
+            (Architecture::X86, AbiFamily::Msvc) => CallingConvention::Thiscall,
+            _ => CallingConvention::C,
+        }
+    }
+}
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -0,0 +1,142 @@
+use super::*;
+
+#[test]
+fn can_resolve_type_aliases() {
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            ID::new((V::Public, "EntityHandle"), AD::new(T::ident("u32"))),
+            ID::new(
+                (V::Public, "Matrix"),
+                AD::new(T::ident("f32").array(16))
+                    .with_attributes([A::newtype(), A::doc(" A 4x4 matrix")]),
+            ),
+            ID::new(
+                (V::Private, "EntityPointer"),
+                AD::new(T::ident("Entity").mut_pointer()),
+            ),
+            ID::new(
+                (V::Public, "Entity"),
+                TD::new([
+                    TS::field((V::Public, "handle"), T::ident("EntityHandle")),
+                    TS::field((V::Private, "_"), T::unknown(12)),
+                    TS::field((V::Public, "transform"), T::ident("Matrix")),
+                ])
+                .with_attributes([A::align(4), A::defaultable()]),
+            ),
+        ]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::EntityHandle"),
+                SISR::new(
+                    (4, 4),
+                    SAD::new(ST::raw("u32"))
+                        .with_copyable(true)
+                        .with_cloneable(true)
+                        .with_defaultable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Matrix"),
+                SISR::new(
+                    (64, 4),
+                    SAD::new(ST::raw("f32").array(16))
+                        .with_doc(" A 4x4 matrix")
+                        .with_newtype(true)
+                        .with_copyable(true)
+                        .with_cloneable(true)
+                        .with_defaultable(true),
+                ),
+            ),
+            // Pointers can be copied, but not defaulted
+            SID::defined_resolved(
+                (SV::Private, "test::EntityPointer"),
+                SISR::new(
+                    (pointer_size(), pointer_size()),
+                    SAD::new(ST::raw("test::Entity").mut_pointer())
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Entity"),
+                SISR::new(
+                    (80, 4),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "handle"), ST::raw("test::EntityHandle")),
+                            SR::field((SV::Private, "_field_4"), unknown(12)),
+                            SR::field((SV::Public, "transform"), ST::raw("test::Matrix")),
+                        ])
+                        .with_defaultable(true),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_fail_on_non_defaultable_alias_in_defaultable_type() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Pointer"),
+                AD::new(T::ident("u8").mut_pointer()),
+            ),
+            ID::new(
+                (V::Public, "Buffer"),
+                TD::new([TS::field((V::Public, "data"), T::ident("Pointer"))])
+                    .with_attributes([A::defaultable()]),
+            ),
+        ]),
+        ErrorKind::NotDefaultable,
+        "field `data` of type `test::Buffer` is not a defaultable type",
+    );
+}
+
+#[test]
+fn will_fail_on_unresolved_alias() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new((V::Public, "Handle"), AD::new(T::ident("Missing")))]),
+        ErrorKind::UnresolvedType,
+        r#"type resolution will not terminate, failed on types: ["test::Handle"] (resolved types: [])"#,
+    );
+}
+
+#[test]
+fn arrays_larger_than_32_elements_are_not_defaultable() {
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Small"),
+                AD::new(T::ident("u8").array(32)).with_attributes([A::newtype()]),
+            ),
+            ID::new(
+                (V::Public, "Large"),
+                AD::new(T::ident("u8").array(33)).with_attributes([A::newtype()]),
+            ),
+        ]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Small"),
+                SISR::new(
+                    (32, 1),
+                    SAD::new(ST::raw("u8").array(32))
+                        .with_newtype(true)
+                        .with_copyable(true)
+                        .with_cloneable(true)
+                        .with_defaultable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Large"),
+                SISR::new(
+                    (33, 1),
+                    SAD::new(ST::raw("u8").array(33))
+                        .with_newtype(true)
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+        ],
+    );
+}
@@ -1,4 +1,7 @@
 use super::*;
+use crate::{error::ErrorKind, span::SourceFile};
+
+use pretty_assertions::assert_eq;
 
 #[test]
 fn size_not_multiple_of_alignment_should_be_rejected() {
@@ -11,6 +14,7 @@ fn size_not_multiple_of_alignment_should_be_rejected() {
             ])
             .with_attributes([A::align(4)]),
         )]),
+        ErrorKind::Alignment,
         "the type `test::TestType` has a size of 7, which is not a multiple of its alignment 4",
     );
 }
@@ -24,6 +28,7 @@ fn unaligned_field_should_be_rejected() {
                 TS::field((V::Public, "field_1"), T::ident("i32")).with_attributes([A::address(1)])
             ]),
         )]),
+        ErrorKind::Alignment,
         concat!(
             "field `field_1` of type `test::TestType` is located at 0x1, ",
             "which is not divisible by 4 (the alignment of the type of the field)",
@@ -43,6 +48,7 @@ fn align_incongruent_with_fields_should_be_rejected() {
             ])
             .with_attributes([A::align(4)]),
         )]),
+        ErrorKind::Alignment,
         "alignment 4 is less than minimum required alignment 8 for type `test::TestType`",
     );
 }
@@ -55,6 +61,7 @@ fn both_align_and_packed_should_be_rejected() {
             TD::new([TS::field((V::Public, "field_1"), T::ident("i32"))])
                 .with_attributes([A::align(4), A::packed()]),
         )]),
+        ErrorKind::InvalidAttribute,
         "cannot specify both `packed` and `align` attributes for type `test::TestType`",
     );
 }
@@ -67,6 +74,7 @@ fn type_with_bool_at_end_should_be_rejected_due_to_alignment() {
             TD::new([TS::field((V::Public, "field_1"), T::ident("bool"))
                 .with_attributes([A::address(0xEC4)])]),
         )]),
+        ErrorKind::Alignment,
         &format!(
             concat!(
                 "the type `test::TestType` has a size of 3781, ",
@@ -96,3 +104,29 @@ fn type_with_bool_at_end_should_be_rejected_due_to_alignment() {
         )],
     );
 }
+
+#[test]
+fn unaligned_field_error_should_point_at_source() {
+    let mut semantic_state = SemanticState::new(target());
+    semantic_state
+        .add_source_file(
+            SourceFile::new(
+                "test.pyxis",
+                "pub type TestType {\n    #[address(1)]\n    pub field_1: i32,\n}\n",
+            ),
+            &IP::from("test"),
+        )
+        .unwrap();
+    let err = semantic_state.build().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        concat!(
+            "error[E0006]: field `field_1` of type `test::TestType` is located at 0x1, ",
+            "which is not divisible by 4 (the alignment of the type of the field)\n",
+            "  --> test.pyxis:3:9\n",
+            "   |     pub field_1: i32,\n",
+            "   |         ^^^^^^^"
+        )
+    );
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+#[test]
+fn can_pack_bitfields() {
+    let bitfield = |name: &str, type_: &str, bits: usize| {
+        TS::field((V::Public, name), T::ident(type_)).with_attributes([A::bits(bits)])
+    };
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Flags"),
+            TD::new([
+                bitfield("visible", "u32", 1),
+                bitfield("layer", "u32", 5),
+                TS::field((V::Private, "_"), T::ident("u32")).with_attributes([A::bits(3)]),
+                // Types of the same size share a storage unit...
+                bitfield("bias", "i32", 4),
+                // ...until it runs out of bits
+                bitfield("big", "u32", 20),
+                // Types of a different size start a new storage unit
+                bitfield("small", "u16", 8),
+                bitfield("enabled", "bool", 1),
+                // Unnamed zero-width bitfields end the current storage unit
+                TS::field((V::Private, "_"), T::ident("u8")).with_attributes([A::bits(0)]),
+                bitfield("other", "bool", 1),
+                TS::field((V::Public, "after"), T::ident("u32")),
+                bitfield("placed", "u32", 2).with_attributes([A::address(0x10), A::bits(2)]),
+            ])
+            .with_attributes([A::align(4)]),
+        )]),
+        [SID::defined_resolved(
+            (SV::Public, "test::Flags"),
+            SISR::new(
+                (0x14, 4),
+                STD::new().with_regions([
+                    SR::field((SV::Private, "_field_0"), ST::raw("u32")).with_bitfields([
+                        SBF::new((SV::Public, "visible"), ST::raw("u32"), 0, 1),
+                        SBF::new((SV::Public, "layer"), ST::raw("u32"), 1, 5),
+                        SBF::new((SV::Public, "bias"), ST::raw("i32"), 9, 4),
+                    ]),
+                    SR::field((SV::Private, "_field_4"), ST::raw("u32"))
+                        .with_bitfields([SBF::new((SV::Public, "big"), ST::raw("u32"), 0, 20)]),
+                    SR::field((SV::Private, "_field_8"), ST::raw("u16"))
+                        .with_bitfields([SBF::new((SV::Public, "small"), ST::raw("u16"), 0, 8)]),
+                    SR::field((SV::Private, "_field_a"), ST::raw("u8")).with_bitfields([SBF::new(
+                        (SV::Public, "enabled"),
+                        ST::raw("bool"),
+                        0,
+                        1,
+                    )]),
+                    SR::field((SV::Private, "_field_b"), ST::raw("u8")).with_bitfields([SBF::new(
+                        (SV::Public, "other"),
+                        ST::raw("bool"),
+                        0,
+                        1,
+                    )]),
+                    SR::field((SV::Public, "after"), ST::raw("u32")),
+                    SR::field((SV::Private, "_field_10"), ST::raw("u32"))
+                        .with_bitfields([SBF::new((SV::Public, "placed"), ST::raw("u32"), 0, 2)]),
+                ]),
+            ),
+        )],
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_bitfields() {
+    assert_ast_produces_failures(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Flags"),
+            TD::new([
+                TS::field((V::Public, "ratio"), T::ident("f32")).with_attributes([A::bits(4)]),
+                TS::field((V::Public, "wide"), T::ident("u8")).with_attributes([A::bits(9)]),
+                TS::field((V::Public, "empty"), T::ident("u32")).with_attributes([A::bits(0)]),
+            ]),
+        )]),
+        [
+            (
+                ErrorKind::InvalidAttribute,
+                "bitfield `ratio` of type `test::Flags` must have an integer or bool type, but has type `f32`",
+            ),
+            (
+                ErrorKind::InvalidAttribute,
+                "bitfield `wide` of type `test::Flags` is 9 bits wide, but its type `u8` only has 8 bits",
+            ),
+            (
+                ErrorKind::InvalidAttribute,
+                "bitfield `empty` of type `test::Flags` has a width of zero, which is only allowed for unnamed bitfields",
+            ),
+        ],
+    );
+}
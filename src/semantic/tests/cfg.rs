@@ -0,0 +1,240 @@
+use super::*;
+
+use crate::semantic::Config;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_remove_elements_whose_cfg_does_not_hold() {
+    let module = M::new()
+        .with_definitions([
+            ID::new(
+                (V::Public, "Player"),
+                TD::new([
+                    TS::field((V::Public, "health"), T::ident("u32")),
+                    TS::field((V::Private, "_"), T::unknown(4))
+                        .with_attributes([A::cfg(CP::assign("pointer_size", 8))]),
+                    TS::field((V::Public, "debug_id"), T::ident("u32"))
+                        .with_attributes([A::cfg(CP::ident("debug"))]),
+                    TS::field((V::Public, "name"), T::ident("u8").const_pointer()),
+                ]),
+            ),
+            ID::new(
+                (V::Public, "DebugInfo"),
+                TD::new([TS::field((V::Public, "id"), T::ident("u32"))])
+                    .with_attributes([A::cfg(CP::ident("debug"))]),
+            ),
+        ])
+        .with_impls([FB::new(
+            "Player",
+            [
+                F::new((V::Public, "respawn"), [Ar::MutSelf])
+                    .with_attributes([A::cfg(CP::assign("version", "1.2")), A::address(0x100)]),
+                F::new((V::Public, "respawn"), [Ar::MutSelf]).with_attributes([
+                    A::cfg(CP::not(CP::assign("version", "1.2"))),
+                    A::address(0x200),
+                ]),
+            ],
+        )]);
+
+    let state = build_state_with(
+        &module,
+        &IP::from("test"),
+        target(),
+        Config::new().with_string("version", "1.1"),
+    )
+    .unwrap();
+    let created_module = state.modules().get(&IP::from("test")).unwrap();
+    let created_type_definitions: Vec<_> = created_module
+        .definitions(state.type_registry())
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        created_type_definitions,
+        [SID::defined_resolved(
+            (SV::Public, "test::Player"),
+            SISR::new(
+                (pointer_size() * 2, pointer_size()),
+                STD::new()
+                    .with_regions(filter_out_empty_regions([
+                        SR::field((SV::Public, "health"), ST::raw("u32")),
+                        SR::field((SV::Private, "_field_4"), unknown(pointer_size() - 4)),
+                        SR::field((SV::Public, "name"), ST::raw("u8").const_pointer()),
+                    ]))
+                    .with_associated_functions([SF::new(
+                        (SV::Public, "respawn"),
+                        SFB::address(0x200),
+                    )
+                    .with_calling_convention(member_cc())
+                    .with_arguments([SAr::MutSelf])]),
+            ),
+        )]
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_cfg_values() {
+    let module = M::new().with_definitions([ID::new(
+        (V::Public, "Player"),
+        TD::new([
+            TS::field((V::Public, "health"), T::ident("u32"))
+                .with_attributes([A::cfg(CP::assign("version", 2))]),
+            TS::field((V::Public, "armor"), T::ident("u32"))
+                .with_attributes([A::cfg(CP::assign("pointer_size", E::Ident("SIZE".into())))]),
+        ]),
+    )]);
+
+    let err = build_state_with(
+        &module,
+        &IP::from("test"),
+        target(),
+        Config::new().with_string("version", "1.1"),
+    )
+    .unwrap_err();
+    let diagnostics: Vec<_> = err
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            (
+                ErrorKind::InvalidAttribute,
+                r#"the cfg key `version` is set to the string `"1.1"`, but is compared with `2`"#
+            ),
+            (
+                ErrorKind::InvalidAttribute,
+                "the cfg value for `pointer_size` must be an integer or string literal, not `SIZE`"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn can_select_per_target_addresses() {
+    let module = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_per_target([
+                ("x86", 0x1337),
+                ("x64", 0x1_4000_1337),
+            ])],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "Player"),
+            TD::new([TS::field((V::Public, "health"), T::ident("u32"))
+                .with_attributes([A::address_per_target([("x86", 4), ("x64", 8)])])])
+            .with_attributes([A::align(4)]),
+        )])
+        .with_impls([FB::new(
+            "Player",
+            [
+                F::new((V::Public, "respawn"), [Ar::MutSelf]).with_attributes([
+                    A::address_per_target([("x86", 0x40_1000), ("x64", 0x1_4000_1000)]),
+                ]),
+            ],
+        )]);
+
+    // The addresses are only valid for the pointer size of their target.
+    let (health, respawn, player_count) = match pointer_size() {
+        4 => (4, 0x40_1000, 0x1337),
+        _ => (8, 0x1_4000_1000, 0x1_4000_1337),
+    };
+    let state = build_state_with(&module, &IP::from("test"), target(), Config::new()).unwrap();
+    let created_module = state.modules().get(&IP::from("test")).unwrap();
+    let created_type_definitions: Vec<_> = created_module
+        .definitions(state.type_registry())
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        created_type_definitions,
+        [SID::defined_resolved(
+            (SV::Public, "test::Player"),
+            SISR::new(
+                (health + 4, 4),
+                STD::new()
+                    .with_regions([
+                        SR::field((SV::Private, "_field_0"), unknown(health)),
+                        SR::field((SV::Public, "health"), ST::raw("u32")),
+                    ])
+                    .with_associated_functions([SF::new(
+                        (SV::Public, "respawn"),
+                        SFB::address(respawn),
+                    )
+                    .with_calling_convention(member_cc())
+                    .with_arguments([SAr::MutSelf])]),
+            ),
+        )]
+    );
+    assert_eq!(
+        created_module.extern_values,
+        [SEV {
+            visibility: SV::Public,
+            name: "player_count".into(),
+            type_: ST::raw("u32"),
+            address: SA::Fixed(player_count),
+        }]
+    );
+}
+
+#[test]
+fn will_fail_on_missing_per_target_address() {
+    let module = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_per_target([("x86", 0x1337)])],
+        )])
+        .with_impls([FB::new(
+            "Player",
+            [F::new((V::Public, "respawn"), [Ar::MutSelf])
+                .with_attributes([A::address_per_target([("x86", 0x40_1000)])])],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "Player"),
+            TD::new([TS::field((V::Public, "health"), T::ident("u32"))
+                .with_attributes([A::address_per_target([("x86", 4)])])]),
+        )]);
+
+    // The address target defaults to the architecture of the target being built for.
+    let target = target_for_pointer_size(8);
+    let messages = build_state_with(&module, &IP::from("test"), target, Config::new())
+        .unwrap_err()
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind, d.message.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "extern value `test::player_count` has no address for the target `x64`",
+            "field `health` of type `test::Player` has no address for the target `x64`",
+            "function `test::Player::respawn` has no address for the target `x64`",
+        ]
+        .map(|m| (ErrorKind::MissingAttribute, m.to_string()))
+    );
+}
+
+#[test]
+fn will_fail_on_address_target_for_another_architecture() {
+    let config = Config::new().with_target("x64");
+    let err = build_state_with(
+        &M::new(),
+        &IP::from("test"),
+        target_for_pointer_size(4),
+        config,
+    )
+    .unwrap_err();
+    let diagnostics = err.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, ErrorKind::InvalidConfig);
+    assert_eq!(
+        diagnostics[0].message,
+        "the selected target `x64` is for the x64 architecture, but `x86-msvc` is x86"
+    );
+}
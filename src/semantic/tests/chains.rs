@@ -0,0 +1,152 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_resolve_pointer_chains() {
+    let module = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_chain(0x1000, [0x10, 0x48, 0x8])],
+        )])
+        .with_definitions([
+            ID::new(
+                (V::Public, "World"),
+                TD::new([TS::field((V::Public, "tick"), T::ident("u32"))])
+                    .with_attributes([A::singleton_chain(0x2000, [0x10])]),
+            ),
+            ID::new(
+                (V::Public, "Difficulty"),
+                ED::new(T::ident("u32"), [ES::field("Easy"), ES::field("Hard")], [])
+                    .with_attributes([A::singleton_chain(0x3000, [0x20, 0x4]), A::copyable()]),
+            ),
+        ]);
+
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    assert!(state.has_chains());
+
+    let created_module = assert_ast_produces_type_definitions(
+        module,
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Difficulty"),
+                SISR::new(
+                    (4, 4),
+                    SED::new(ST::raw("u32"))
+                        .with_fields([("Easy", 0), ("Hard", 1)])
+                        .with_singleton(SA::chain(0x3000, [0x20, 0x4]))
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::World"),
+                SISR::new(
+                    (4, 4),
+                    STD::new()
+                        .with_regions([SR::field((SV::Public, "tick"), ST::raw("u32"))])
+                        .with_singleton(SA::chain(0x2000, [0x10])),
+                ),
+            ),
+        ],
+    );
+    assert_eq!(
+        created_module.extern_values,
+        [SEV {
+            visibility: SV::Public,
+            name: "player_count".into(),
+            type_: ST::raw("u32"),
+            address: SA::chain(0x1000, [0x10, 0x48, 0x8]),
+        }]
+    );
+}
+
+#[test]
+fn can_resolve_pointer_chains_from_an_image() {
+    let module = M::new()
+        .with_attributes([A::image(IM::new("game.exe").with_preferred_base(0x40_0000))])
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_chain(0x40_1000, [0x48])],
+        )]);
+
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    assert_eq!(state.images(), ["game.exe"]);
+    assert_eq!(
+        state.modules()[&IP::from("test")].extern_values[0].address,
+        SA::chain(SA::relative("game.exe", 0x1000), [0x48])
+    );
+}
+
+#[test]
+fn will_fail_on_pointer_chain_for_function() {
+    assert_ast_produces_failure(
+        M::new()
+            .with_definitions([ID::new(
+                (V::Public, "World"),
+                TD::new([TS::field((V::Public, "tick"), T::ident("u32"))]),
+            )])
+            .with_impls([FB::new(
+                "World",
+                [F::new((V::Public, "update"), [Ar::MutSelf])
+                    .with_attributes([A::address_chain(0x1000, [0x8])])],
+            )]),
+        ErrorKind::InvalidAttribute,
+        "address attribute for function `update` cannot follow a chain of pointers",
+    );
+}
+
+#[test]
+fn can_resolve_pointer_chains_with_negative_offsets_or_no_offsets() {
+    let module = M::new().with_extern_values([
+        EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_chain(0x1000, [0x10, -0x8])],
+        ),
+        // Without offsets, the chain is only its starting address.
+        EV::new(
+            V::Public,
+            "tick",
+            T::ident("u32"),
+            [A::address_chain(0x2000, [])],
+        ),
+    ]);
+
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    let addresses: Vec<_> = state.modules()[&IP::from("test")]
+        .extern_values
+        .iter()
+        .map(|ev| ev.address.clone())
+        .collect();
+    assert_eq!(
+        addresses,
+        [SA::chain(0x1000, [0x10, -0x8]), SA::chain(0x2000, [])]
+    );
+}
+
+#[test]
+fn will_fail_on_pointer_chain_offset_that_does_not_fit_in_a_pointer() {
+    let offset = 1i128 << (pointer_size() * 8 - 1);
+    assert_ast_produces_failure(
+        M::new().with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::Chain(
+                "address".into(),
+                vec![E::IntLiteral(0x1000), E::IntLiteral(offset)],
+            )],
+        )]),
+        ErrorKind::InvalidAttribute,
+        &format!(
+            "`address` attribute for extern value `player_count` has the offset {offset:#x}, which is not a valid {}-bit offset",
+            pointer_size() * 8
+        ),
+    );
+}
@@ -0,0 +1,166 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_resolve_constants() {
+    // `SLOTS` depends on a constant that is defined after it, and `Inventory` depends on both.
+    let module = assert_ast_produces_type_definitions(
+        M::new()
+            .with_constants([
+                C::new(
+                    (V::Private, "SLOTS"),
+                    T::ident("u32"),
+                    E::binary(E::Ident("MAX_SLOTS".into()), BO::Div, E::IntLiteral(2)),
+                ),
+                C::new((V::Public, "MAX_SLOTS"), T::ident("u32"), 8)
+                    .with_attributes([A::doc("The most slots an inventory can have")]),
+                C::new(
+                    (V::Public, "HEADER_SIZE"),
+                    T::ident("u8"),
+                    E::size_of(T::ident("Header")),
+                ),
+            ])
+            .with_definitions([
+                ID::new(
+                    (V::Public, "Header"),
+                    TD::new([TS::field((V::Public, "id"), T::ident("u32"))]),
+                ),
+                ID::new(
+                    (V::Public, "Inventory"),
+                    TD::new([
+                        TS::field((V::Public, "header"), T::ident("Header")),
+                        TS::field(
+                            (V::Public, "slots"),
+                            T::ident("u32").array_with_expr(E::Ident("SLOTS".into())),
+                        ),
+                    ])
+                    .with_attributes([
+                        A::expr_fn(
+                            "size",
+                            E::binary(
+                                E::Ident("HEADER_SIZE".into()),
+                                BO::Add,
+                                E::binary(E::Ident("SLOTS".into()), BO::Mul, E::IntLiteral(4)),
+                            ),
+                        ),
+                        A::align(4),
+                    ]),
+                ),
+            ]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Header"),
+                SISR::new(
+                    (4, 4),
+                    STD::new().with_regions([SR::field((SV::Public, "id"), ST::raw("u32"))]),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Inventory"),
+                SISR::new(
+                    (20, 4),
+                    STD::new().with_regions([
+                        SR::field((SV::Public, "header"), ST::raw("test::Header")),
+                        SR::field((SV::Public, "slots"), ST::raw("u32").array(4)),
+                    ]),
+                ),
+            ),
+        ],
+    );
+
+    let mut constants = module.constants().to_vec();
+    constants.sort_by_key(|c| c.name.clone());
+    assert_eq!(
+        constants,
+        [
+            SC::new((SV::Public, "HEADER_SIZE"), ST::raw("u8"), 4),
+            SC::new((SV::Public, "MAX_SLOTS"), ST::raw("u32"), 8)
+                .with_doc("The most slots an inventory can have"),
+            SC::new((SV::Private, "SLOTS"), ST::raw("u32"), 4),
+        ]
+    );
+}
+
+#[test]
+fn can_use_constants_from_other_modules() {
+    let config = M::new().with_constants([
+        C::new((V::Public, "MAX_PLAYERS"), T::ident("u32"), 4),
+        C::new((V::Public, "MAX_TEAMS"), T::ident("u32"), 2),
+    ]);
+    let world = M::new()
+        .with_uses([U::new(IP::from("game::config::MAX_PLAYERS"))])
+        .with_definitions([ID::new(
+            (V::Public, "World"),
+            TD::new([TS::field(
+                (V::Public, "ids"),
+                T::ident("u8").array_with_expr(E::binary(
+                    E::Ident("MAX_PLAYERS".into()),
+                    BO::Mul,
+                    E::Path(vec!["super".into(), "config".into(), "MAX_TEAMS".into()]),
+                )),
+            )]),
+        )]);
+
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
+    semantic_state
+        .add_module(&config, &IP::from("game::config"))
+        .unwrap();
+    semantic_state
+        .add_module(&world, &IP::from("game::world"))
+        .unwrap();
+    let semantic_state = semantic_state.build().unwrap();
+
+    let path = IP::from("game::world::World");
+    assert_eq!(
+        semantic_state.type_registry().get(&path).cloned().unwrap(),
+        SID::defined_resolved(
+            (SV::Public, path.clone()),
+            SISR::new(
+                (8, 1),
+                STD::new().with_regions([SR::field((SV::Public, "ids"), ST::raw("u8").array(8))])
+            )
+        )
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_constants() {
+    assert_ast_produces_failures(
+        M::new().with_constants([
+            C::new((V::Public, "NOT_AN_INTEGER"), T::ident("f32"), 1),
+            C::new((V::Public, "TOO_BIG"), T::ident("u8"), 256),
+            C::new(
+                (V::Public, "UNKNOWN"),
+                T::ident("u32"),
+                E::Ident("MISSING".into()),
+            ),
+        ]),
+        [
+            (
+                ErrorKind::InvalidExpression,
+                "constant `test::NOT_AN_INTEGER` must have an integer type, but has type `f32`",
+            ),
+            (
+                ErrorKind::InvalidExpression,
+                "constant `test::TOO_BIG` has the value 256, which does not fit in its type `u8`",
+            ),
+            (
+                ErrorKind::InvalidExpression,
+                "failed to find constant `MISSING`",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_fail_on_duplicate_constants() {
+    assert_ast_produces_failure(
+        M::new().with_constants([
+            C::new((V::Public, "DUPLICATE"), T::ident("u32"), 1),
+            C::new((V::Public, "DUPLICATE"), T::ident("u32"), 2),
+        ]),
+        ErrorKind::DuplicateDefinition,
+        "constant `test::DUPLICATE` is already defined",
+    );
+}
@@ -0,0 +1,269 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+fn zed() -> ID {
+    ID::new(
+        (V::Public, "Zed"),
+        TD::new([
+            TS::field((V::Public, "a"), T::ident("u32")),
+            TS::field((V::Public, "b"), T::ident("u32")),
+        ])
+        .with_attributes([A::align(4)]),
+    )
+}
+
+#[test]
+fn can_evaluate_expressions_that_depend_on_other_types() {
+    // `Alpha` is resolved before `Zed`, so it must wait for `Zed` to be resolved.
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Alpha"),
+                TD::new([
+                    TS::field((V::Public, "zed"), T::ident("Zed")),
+                    TS::field(
+                        (V::Public, "values"),
+                        T::ident("u8").array_with_expr(E::binary(
+                            E::size_of(T::ident("Zed")),
+                            BO::Div,
+                            E::IntLiteral(2),
+                        )),
+                    )
+                    .with_attributes([A::expr_fn(
+                        "address",
+                        E::binary(
+                            E::offset_of(T::ident("Zed"), "b"),
+                            BO::Add,
+                            E::IntLiteral(8),
+                        ),
+                    )]),
+                ])
+                .with_attributes([
+                    A::expr_fn(
+                        "size",
+                        E::binary(E::size_of(T::ident("Zed")), BO::Add, E::IntLiteral(0x8)),
+                    ),
+                    A::expr_fn("align", E::align_of(T::ident("Zed"))),
+                ]),
+            ),
+            zed(),
+        ]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Alpha"),
+                SISR::new(
+                    (16, 4),
+                    STD::new().with_regions([
+                        SR::field((SV::Public, "zed"), ST::raw("test::Zed")),
+                        SR::field((SV::Private, "_field_8"), unknown(4)),
+                        SR::field((SV::Public, "values"), ST::raw("u8").array(4)),
+                    ]),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Zed"),
+                SISR::new(
+                    (8, 4),
+                    STD::new().with_regions([
+                        SR::field((SV::Public, "a"), ST::raw("u32")),
+                        SR::field((SV::Public, "b"), ST::raw("u32")),
+                    ]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn can_evaluate_enum_discriminants() {
+    let shl = |lhs, rhs| E::binary(E::IntLiteral(lhs), BO::Shl, E::IntLiteral(rhs));
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Flags"),
+            ED::new(
+                T::ident("i32"),
+                [
+                    ES::field_with_expr("A", shl(1, 0)),
+                    ES::field_with_expr("B", shl(1, 1)),
+                    ES::field_with_expr("C", E::binary(shl(1, 2), BO::BitOr, E::IntLiteral(1))),
+                    ES::field("D"),
+                    ES::field_with_expr(
+                        "E",
+                        E::unary(
+                            UO::Negate,
+                            E::binary(E::IntLiteral(2), BO::Mul, E::IntLiteral(3)),
+                        ),
+                    ),
+                    ES::field_with_expr(
+                        "F",
+                        E::binary(E::size_of(T::ident("u64")), BO::Sub, E::IntLiteral(1)),
+                    ),
+                ],
+                [A::expr_fn(
+                    "singleton",
+                    E::binary(E::IntLiteral(0x1000), BO::Add, E::IntLiteral(0x234)),
+                )],
+            ),
+        )]),
+        [SID::defined_resolved(
+            (SV::Public, "test::Flags"),
+            SISR::new(
+                (4, 4),
+                SED::new(ST::raw("i32"))
+                    .with_fields([("A", 1), ("B", 2), ("C", 5), ("D", 6), ("E", -6), ("F", 7)])
+                    .with_singleton(0x1234),
+            ),
+        )],
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_expressions() {
+    let type_with_attribute = |name: &str, attribute: &str, expr: E| {
+        ID::new(
+            (V::Public, name),
+            TD::new([TS::field((V::Public, "a"), T::ident("u32"))])
+                .with_attributes([A::expr_fn(attribute, expr)]),
+        )
+    };
+    assert_ast_produces_failures(
+        M::new().with_definitions([
+            type_with_attribute(
+                "DividesByZero",
+                "size",
+                E::binary(
+                    E::IntLiteral(8),
+                    BO::Div,
+                    E::binary(E::IntLiteral(2), BO::Sub, E::IntLiteral(2)),
+                ),
+            ),
+            type_with_attribute("MissingField", "size", E::offset_of(T::ident("Zed"), "c")),
+            ID::new(
+                (V::Public, "NegativeLength"),
+                TD::new([TS::field(
+                    (V::Public, "a"),
+                    T::ident("u8").array_with_expr(E::binary(
+                        E::IntLiteral(2),
+                        BO::Sub,
+                        E::IntLiteral(4),
+                    )),
+                )]),
+            ),
+            type_with_attribute(
+                "Overflows",
+                "align",
+                E::binary(E::IntLiteral(i128::MAX), BO::Add, E::IntLiteral(1)),
+            ),
+            type_with_attribute("UnknownConstant", "size", E::Ident("UNKNOWN".into())),
+            zed(),
+        ]),
+        [
+            (
+                ErrorKind::InvalidExpression,
+                "evaluating `8 / (2 - 2)` divided by zero",
+            ),
+            (
+                ErrorKind::InvalidExpression,
+                "array length `2 - 4` evaluates to -2, which is negative",
+            ),
+            (
+                ErrorKind::InvalidExpression,
+                &format!("evaluating `{} + 1` overflowed", i128::MAX),
+            ),
+            (
+                ErrorKind::InvalidExpression,
+                "failed to find constant `UNKNOWN`",
+            ),
+            (
+                ErrorKind::InvalidExpression,
+                "type `test::Zed` has no field `c`",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn can_use_full_width_addresses() {
+    let module = M::new().with_extern_values([EV::new(
+        V::Public,
+        "kernel_base",
+        T::ident("u8").mut_pointer(),
+        [A::integer_fn("address", 0xFFFF_F800_0000_0000)],
+    )]);
+    let module_path = IP::from("test");
+
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(8));
+    semantic_state.add_module(&module, &module_path).unwrap();
+    let semantic_state = semantic_state.build().unwrap();
+    assert_eq!(
+        semantic_state.modules()[&module_path].extern_values,
+        [SEV {
+            visibility: SV::Public,
+            name: "kernel_base".into(),
+            type_: ST::raw("u8").mut_pointer(),
+            address: SA::Fixed(0xFFFF_F800_0000_0000_u64 as usize),
+        }]
+    );
+
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
+    semantic_state.add_module(&module, &module_path).unwrap();
+    let diagnostics: Vec<_> = semantic_state
+        .build()
+        .unwrap_err()
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind, d.message.clone()))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [(
+            ErrorKind::InvalidAttribute,
+            "`address` attribute for extern value `kernel_base` evaluates to 0xfffff80000000000, which is not a valid 32-bit address".to_string()
+        )]
+    );
+}
+
+#[test]
+fn will_fail_on_out_of_range_enum_values() {
+    assert_ast_produces_failures(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Small"),
+                ED::new(
+                    T::ident("u8"),
+                    [
+                        ES::field_with_expr("Negative", E::IntLiteral(-1)),
+                        ES::field_with_expr("TooLarge", E::IntLiteral(256)),
+                    ],
+                    [],
+                ),
+            ),
+            ID::new(
+                (V::Public, "Signed"),
+                ED::new(
+                    T::ident("i8"),
+                    [
+                        ES::field_with_expr("Maximum", E::IntLiteral(127)),
+                        ES::field("Overflowed"),
+                    ],
+                    [],
+                ),
+            ),
+        ]),
+        [
+            (
+                ErrorKind::InvalidEnumValue,
+                "value 128 for case `Overflowed` of enum `test::Signed` does not fit in its type `i8`",
+            ),
+            (
+                ErrorKind::InvalidEnumValue,
+                "value -1 for case `Negative` of enum `test::Small` does not fit in its type `u8`",
+            ),
+            (
+                ErrorKind::InvalidEnumValue,
+                "value 256 for case `TooLarge` of enum `test::Small` does not fit in its type `u8`",
+            ),
+        ],
+    );
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+#[test]
+fn can_resolve_function_pointer_fields() {
+    let entity = || T::ident("Entity").mut_pointer();
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Entity"),
+                TD::new([
+                    TS::field(
+                        (V::Public, "on_damage"),
+                        T::Function(FT::new([(None, entity()), (None, T::ident("f32"))], None)),
+                    ),
+                    TS::field(
+                        (V::Public, "on_death"),
+                        T::Function(
+                            FT::new(
+                                [(Some("this"), entity()), (Some("killer"), entity())],
+                                T::ident("bool"),
+                            )
+                            .with_calling_convention(member_cc().as_str())
+                            .with_nullable(true),
+                        ),
+                    ),
+                ]),
+            ),
+            // Nullable function pointers default to null.
+            ID::new(
+                (V::Public, "Callbacks"),
+                TD::new([TS::field(
+                    (V::Public, "on_tick"),
+                    T::Function(FT::new([], None).with_nullable(true)),
+                )])
+                .with_attributes([A::defaultable()]),
+            ),
+        ]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Callbacks"),
+                SISR::new(
+                    (pointer_size(), pointer_size()),
+                    STD::new()
+                        .with_regions([SR::field(
+                            (SV::Public, "on_tick"),
+                            ST::function(SCC::System, [], None).nullable(),
+                        )])
+                        .with_defaultable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Entity"),
+                SISR::new(
+                    (2 * pointer_size(), pointer_size()),
+                    STD::new().with_regions([
+                        SR::field(
+                            (SV::Public, "on_damage"),
+                            ST::function(
+                                SCC::System,
+                                [
+                                    ("_", ST::raw("test::Entity").mut_pointer()),
+                                    ("_", ST::raw("f32")),
+                                ],
+                                None,
+                            ),
+                        ),
+                        SR::field(
+                            (SV::Public, "on_death"),
+                            ST::function(
+                                member_cc(),
+                                [
+                                    ("this", ST::raw("test::Entity").mut_pointer()),
+                                    ("killer", ST::raw("test::Entity").mut_pointer()),
+                                ],
+                                ST::raw("bool"),
+                            )
+                            .nullable(),
+                        ),
+                    ]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_reject_defaultable_on_non_nullable_function_pointer() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Callbacks"),
+            TD::new([TS::field(
+                (V::Public, "on_tick"),
+                T::Function(FT::new([], None)),
+            )])
+            .with_attributes([A::defaultable()]),
+        )]),
+        ErrorKind::NotDefaultable,
+        "field `on_tick` of type `test::Callbacks` is not a defaultable type (pointer or function?)",
+    );
+}
+
+#[test]
+fn will_fail_on_unknown_calling_convention() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Callbacks"),
+            TD::new([TS::field(
+                (V::Public, "on_tick"),
+                T::Function(FT::new([], None).with_calling_convention("bogus")),
+            )]),
+        )]),
+        ErrorKind::UnresolvedType,
+        r#"unknown calling convention "bogus" in `extern "bogus" fn()`"#,
+    );
+}
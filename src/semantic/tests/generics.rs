@@ -0,0 +1,534 @@
+use super::*;
+
+use crate::span::SourceFile;
+use pretty_assertions::assert_eq;
+
+fn array_definition() -> ID {
+    ID::new(
+        (V::Public, "Array"),
+        TD::new([
+            TS::field((V::Public, "data"), T::ident("T").mut_pointer()),
+            TS::field((V::Public, "end"), T::ident("T").mut_pointer()),
+        ])
+        .with_generics(["T"]),
+    )
+}
+
+fn array_template() -> SID {
+    SID {
+        visibility: SV::Public,
+        path: "test::Array".into(),
+        state: SIS::Generic(array_definition()),
+        category: SIC::Defined,
+    }
+}
+
+fn array_instance(name: &str, argument: ST) -> SID {
+    let regions = |type_: ST| {
+        [
+            SR::field((SV::Public, "data"), type_.clone().mut_pointer()),
+            SR::field((SV::Public, "end"), type_.mut_pointer()),
+        ]
+    };
+    SID::defined_resolved(
+        (SV::Public, name),
+        SISR::new(
+            (2 * pointer_size(), pointer_size()),
+            STD::new()
+                .with_regions(regions(argument.clone()))
+                .with_generic_instance(
+                    SGI::new("test::Array", [argument])
+                        .with_generic_regions(regions(ST::parameter("T"))),
+                ),
+        ),
+    )
+}
+
+#[test]
+fn can_resolve_generic_type_instantiations() {
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            array_definition(),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([
+                    TS::field(
+                        (V::Public, "numbers"),
+                        T::generic("Array", [T::ident("u32")]),
+                    ),
+                    TS::field(
+                        (V::Public, "children"),
+                        T::generic("Array", [T::ident("TestType").mut_pointer()]),
+                    ),
+                ]),
+            ),
+        ]),
+        [
+            array_template(),
+            array_instance("test::Array_u32", ST::raw("u32")),
+            array_instance(
+                "test::Array_mut_TestType",
+                ST::raw("test::TestType").mut_pointer(),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::TestType"),
+                SISR::new(
+                    (4 * pointer_size(), pointer_size()),
+                    STD::new().with_regions([
+                        SR::field(
+                            (SV::Public, "numbers"),
+                            ST::generic("test::Array", [ST::raw("u32")]),
+                        ),
+                        SR::field(
+                            (SV::Public, "children"),
+                            ST::generic("test::Array", [ST::raw("test::TestType").mut_pointer()]),
+                        ),
+                    ]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn can_resolve_nested_generic_type_instantiations() {
+    let list_definition = ID::new(
+        (V::Public, "List"),
+        TD::new([
+            TS::field((V::Public, "items"), T::generic("Array", [T::ident("T")])),
+            TS::field(
+                (V::Public, "next"),
+                T::generic("List", [T::ident("T")]).mut_pointer(),
+            ),
+        ])
+        .with_generics(["T"]),
+    );
+
+    let list_regions = |type_: ST, array_type: ST| {
+        [
+            SR::field(
+                (SV::Public, "items"),
+                ST::generic("test::Array", [array_type]),
+            ),
+            SR::field(
+                (SV::Public, "next"),
+                ST::generic("test::List", [type_]).mut_pointer(),
+            ),
+        ]
+    };
+
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            array_definition(),
+            list_definition.clone(),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field(
+                    (V::Public, "list"),
+                    T::generic("List", [T::ident("u8")]),
+                )]),
+            ),
+        ]),
+        [
+            array_template(),
+            array_instance("test::Array_u8", ST::raw("u8")),
+            SID {
+                visibility: SV::Public,
+                path: "test::List".into(),
+                state: SIS::Generic(list_definition),
+                category: SIC::Defined,
+            },
+            SID::defined_resolved(
+                (SV::Public, "test::List_u8"),
+                SISR::new(
+                    (3 * pointer_size(), pointer_size()),
+                    STD::new()
+                        .with_regions(list_regions(ST::raw("u8"), ST::raw("u8")))
+                        .with_generic_instance(
+                            SGI::new("test::List", [ST::raw("u8")]).with_generic_regions(
+                                list_regions(ST::parameter("T"), ST::parameter("T")),
+                            ),
+                        ),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::TestType"),
+                SISR::new(
+                    (3 * pointer_size(), pointer_size()),
+                    STD::new().with_regions([SR::field(
+                        (SV::Public, "list"),
+                        ST::generic("test::List", [ST::raw("u8")]),
+                    )]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn can_monomorphise_generic_type() {
+    let pair_definition = ID::new(
+        (V::Public, "Pair"),
+        TD::new([
+            TS::field((V::Public, "first"), T::ident("A")),
+            TS::field((V::Public, "second"), T::ident("B")),
+        ])
+        .with_generics(["A", "B"])
+        .with_attributes([A::monomorphise()]),
+    );
+
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            pair_definition.clone(),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field(
+                    (V::Public, "pair"),
+                    T::generic("Pair", [T::ident("u32"), T::ident("u32")]),
+                )]),
+            ),
+        ]),
+        [
+            SID {
+                visibility: SV::Public,
+                path: "test::Pair".into(),
+                state: SIS::Generic(pair_definition),
+                category: SIC::Defined,
+            },
+            SID::defined_resolved(
+                (SV::Public, "test::Pair_u32_u32"),
+                SISR::new(
+                    (8, 4),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "first"), ST::raw("u32")),
+                            SR::field((SV::Public, "second"), ST::raw("u32")),
+                        ])
+                        .with_generic_instance(SGI::new(
+                            "test::Pair",
+                            [ST::raw("u32"), ST::raw("u32")],
+                        )),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::TestType"),
+                SISR::new(
+                    (8, 4),
+                    STD::new().with_regions([SR::field(
+                        (SV::Public, "pair"),
+                        ST::raw("test::Pair_u32_u32"),
+                    )]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_fail_on_generic_type_with_wrong_argument_count() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            array_definition(),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field(
+                    (V::Public, "field_1"),
+                    T::generic("Array", [T::ident("u32"), T::ident("u32")]),
+                )]),
+            ),
+        ]),
+        ErrorKind::InvalidGeneric,
+        "generic type `test::Array` takes 1 argument(s), but 2 were given",
+    );
+}
+
+#[test]
+fn will_fail_on_generic_type_without_arguments() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            array_definition(),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field((V::Public, "field_1"), T::ident("Array"))]),
+            ),
+        ]),
+        ErrorKind::InvalidGeneric,
+        "generic type `test::Array` must be given arguments",
+    );
+}
+
+#[test]
+fn will_fail_on_generic_instances_with_different_layouts() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Boxed"),
+                TD::new([TS::field((V::Public, "value"), T::ident("T"))]).with_generics(["T"]),
+            ),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([
+                    TS::field((V::Public, "large"), T::generic("Boxed", [T::ident("u64")])),
+                    TS::field((V::Public, "small"), T::generic("Boxed", [T::ident("u8")])),
+                    TS::field((V::Private, "_"), T::unknown(7)),
+                ])
+                .with_attributes([A::align(8)]),
+            ),
+        ]),
+        ErrorKind::InvalidGeneric,
+        "instantiations `test::Boxed_u64` and `test::Boxed_u8` of generic type `test::Boxed` have different layouts, so they cannot share a generic type",
+    );
+}
+
+#[test]
+fn can_pad_each_instantiation_of_generic_type() {
+    let pair_definition = ID::new(
+        (V::Public, "Pair"),
+        TD::new([
+            TS::field((V::Public, "first"), T::ident("A")),
+            TS::field((V::Public, "second"), T::ident("B")),
+        ])
+        .with_generics(["A", "B"])
+        .with_attributes([A::monomorphise()]),
+    );
+
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            pair_definition.clone(),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([
+                    TS::field(
+                        (V::Public, "leading"),
+                        T::generic("Pair", [T::ident("u8"), T::ident("u32")]),
+                    ),
+                    TS::field(
+                        (V::Public, "trailing"),
+                        T::generic("Pair", [T::ident("u32"), T::ident("u8")]),
+                    ),
+                ])
+                .with_attributes([A::align(4)]),
+            ),
+        ]),
+        [
+            SID {
+                visibility: SV::Public,
+                path: "test::Pair".into(),
+                state: SIS::Generic(pair_definition),
+                category: SIC::Defined,
+            },
+            SID::defined_resolved(
+                (SV::Public, "test::Pair_u8_u32"),
+                SISR::new(
+                    (8, 4),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "first"), ST::raw("u8")),
+                            SR::field((SV::Private, "_field_1"), unknown(3)),
+                            SR::field((SV::Public, "second"), ST::raw("u32")),
+                        ])
+                        .with_generic_instance(SGI::new(
+                            "test::Pair",
+                            [ST::raw("u8"), ST::raw("u32")],
+                        )),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Pair_u32_u8"),
+                SISR::new(
+                    (8, 4),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "first"), ST::raw("u32")),
+                            SR::field((SV::Public, "second"), ST::raw("u8")),
+                            SR::field((SV::Private, "_field_5"), unknown(3)),
+                        ])
+                        .with_generic_instance(SGI::new(
+                            "test::Pair",
+                            [ST::raw("u32"), ST::raw("u8")],
+                        )),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::TestType"),
+                SISR::new(
+                    (16, 4),
+                    STD::new().with_regions([
+                        SR::field((SV::Public, "leading"), ST::raw("test::Pair_u8_u32")),
+                        SR::field((SV::Public, "trailing"), ST::raw("test::Pair_u32_u8")),
+                    ]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn errors_in_instantiations_should_point_at_the_instantiation() {
+    let mut semantic_state = SemanticState::new(target());
+    semantic_state
+        .add_source_file(
+            SourceFile::new(
+                "test.pyxis",
+                concat!(
+                    "pub type Pair<A, B> {\n",
+                    "    pub a: A,\n",
+                    "    #[address(2)]\n",
+                    "    pub b: B,\n",
+                    "}\n",
+                    "pub type Holder {\n",
+                    "    pub pair: Pair<u8, u32>,\n",
+                    "}\n",
+                ),
+            ),
+            &IP::from("test"),
+        )
+        .unwrap();
+    let err = semantic_state.build().unwrap_err();
+
+    // `Holder` can't be resolved either, but that follows from the instantiation's error.
+    let [diagnostic] = err.diagnostics() else {
+        panic!("expected one diagnostic, got {:?}", err.diagnostics());
+    };
+    assert_eq!(diagnostic.kind, ErrorKind::Alignment);
+    assert_eq!(
+        diagnostic.location.as_ref().map(|l| l.span.start.line),
+        Some(4)
+    );
+    assert_eq!(
+        diagnostic.notes,
+        ["`test::Pair_u8_u32` is the instantiation `test::Pair<u8, u32>` at test.pyxis:7:15"]
+    );
+}
+
+#[test]
+fn will_fail_on_generic_type_with_associated_functions() {
+    assert_ast_produces_failure(
+        M::new()
+            .with_definitions([array_definition()])
+            .with_impls([FB::new(
+                "Array",
+                [F::new((V::Public, "len"), [Ar::ConstSelf])
+                    .with_attributes([A::address(0x800_000)])
+                    .with_return_type(T::ident("u32"))],
+            )]),
+        ErrorKind::InvalidGeneric,
+        "generic type `test::Array` cannot have associated functions",
+    );
+}
+
+fn boxed_definition() -> ID {
+    ID::new(
+        (V::Public, "Boxed"),
+        TD::new([TS::field((V::Public, "value"), T::ident("T"))])
+            .with_generics(["T"])
+            .with_attributes([A::monomorphise()]),
+    )
+}
+
+#[test]
+fn can_resolve_instantiations_with_types_from_other_modules() {
+    let item = |type_: &str| {
+        M::new().with_definitions([ID::new(
+            (V::Public, "Item"),
+            TD::new([TS::field((V::Public, "value"), T::ident(type_))]),
+        )])
+    };
+    let user = M::new().with_definitions([
+        boxed_definition(),
+        ID::new(
+            (V::Public, "Item"),
+            TD::new([TS::field((V::Public, "value"), T::ident("u16"))]),
+        ),
+        ID::new(
+            (V::Public, "User"),
+            TD::new([
+                TS::field((V::Public, "b"), T::generic("Boxed", [T::path("b::Item")])),
+                TS::field((V::Public, "a"), T::generic("Boxed", [T::path("a::Item")])),
+                TS::field((V::Public, "c"), T::generic("Boxed", [T::ident("Item")])),
+                TS::field((V::Private, "_"), T::unknown(2)),
+            ])
+            .with_attributes([A::align(8)]),
+        ),
+    ]);
+
+    let mut semantic_state = SemanticState::new(target());
+    for (module, path) in [(item("u32"), "a"), (item("u64"), "b"), (user, "c")] {
+        semantic_state.add_module(&module, &IP::from(path)).unwrap();
+    }
+    let state = semantic_state.build().unwrap();
+
+    // Types from other modules are named by their full path, so their instantiations don't
+    // collide with each other or with those of the module's own types.
+    let size = |path: &str| {
+        state
+            .type_registry()
+            .get(&IP::from(path))
+            .and_then(|t| t.resolved())
+            .map(|r| r.size)
+    };
+    assert_eq!(size("c::Boxed_a__Item"), Some(4));
+    assert_eq!(size("c::Boxed_b__Item"), Some(8));
+    assert_eq!(size("c::Boxed_Item"), Some(2));
+    assert_eq!(size("c::User"), Some(16));
+}
+
+#[test]
+fn will_fail_on_instantiations_with_the_same_name() {
+    let type_ = |name: &str| {
+        ID::new(
+            (V::Public, name),
+            TD::new([TS::field((V::Public, "value"), T::ident("u32"))]),
+        )
+    };
+    let pair = ID::new(
+        (V::Public, "Pair"),
+        TD::new([
+            TS::field((V::Public, "first"), T::ident("A")),
+            TS::field((V::Public, "second"), T::ident("B")),
+        ])
+        .with_generics(["A", "B"])
+        .with_attributes([A::monomorphise()]),
+    );
+
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            pair,
+            type_("A"),
+            type_("A_B"),
+            type_("B_C"),
+            type_("C"),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([
+                    TS::field(
+                        (V::Public, "first"),
+                        T::generic("Pair", [T::ident("A_B"), T::ident("C")]),
+                    ),
+                    TS::field(
+                        (V::Public, "second"),
+                        T::generic("Pair", [T::ident("A"), T::ident("B_C")]),
+                    ),
+                ]),
+            ),
+        ]),
+        ErrorKind::InvalidGeneric,
+        "instantiations `test::Pair<test::A, test::B_C>` and `test::Pair<test::A_B, test::C>` of generic type `test::Pair` would both be named `test::Pair_A_B_C`",
+    );
+
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            boxed_definition(),
+            type_("Boxed_u32"),
+            ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field(
+                    (V::Public, "value"),
+                    T::generic("Boxed", [T::ident("u32")]),
+                )]),
+            ),
+        ]),
+        ErrorKind::InvalidGeneric,
+        "instantiation `test::Boxed<u32>` of generic type `test::Boxed` would be named `test::Boxed_u32`, which is already the name of another item",
+    );
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_resolve_image_relative_addresses() {
+    // Absolute addresses are made relative to the module's image, and `rva` addresses use
+    // the module's image unless they name another.
+    let module = M::new()
+        .with_attributes([A::image(IM::new("game.exe").with_preferred_base(0x40_0000))])
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address(0x40_1234)],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "World"),
+            TD::new([TS::field((V::Public, "tick"), T::ident("u32"))]).with_attributes([A::Keyed(
+                "singleton".into(),
+                vec![("rva".into(), E::IntLiteral(0x3000))],
+            )]),
+        )])
+        .with_impls([FB::new(
+            "World",
+            [F::new((V::Public, "update"), [Ar::MutSelf])
+                .with_attributes([A::address_relative(0x2000, Some("engine.dll"))])],
+        )]);
+
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    assert_eq!(state.images(), ["engine.dll", "game.exe"]);
+
+    let created_module = assert_ast_produces_type_definitions(
+        module,
+        [SID::defined_resolved(
+            (SV::Public, "test::World"),
+            SISR::new(
+                (4, 4),
+                STD::new()
+                    .with_regions([SR::field((SV::Public, "tick"), ST::raw("u32"))])
+                    .with_singleton(SA::relative("game.exe", 0x3000))
+                    .with_associated_functions([SF::new(
+                        (SV::Public, "update"),
+                        SFB::address(SA::relative("engine.dll", 0x2000)),
+                    )
+                    .with_calling_convention(member_cc())
+                    .with_arguments([SAr::MutSelf])]),
+            ),
+        )],
+    );
+    assert_eq!(
+        created_module.extern_values,
+        [SEV {
+            visibility: SV::Public,
+            name: "player_count".into(),
+            type_: ST::raw("u32"),
+            address: SA::relative("game.exe", 0x1234),
+        }]
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_image_relative_addresses() {
+    assert_ast_produces_failures(
+        M::new()
+            .with_attributes([A::image(IM::new("game.exe").with_preferred_base(0x40_0000))])
+            .with_extern_values([
+                EV::new(
+                    V::Public,
+                    "player_count",
+                    T::ident("u32"),
+                    [A::address(0x1234)],
+                ),
+                EV::new(
+                    V::Public,
+                    "tick",
+                    T::ident("u32"),
+                    [A::Keyed(
+                        "address".into(),
+                        vec![("image".into(), E::StringLiteral("engine.dll".into()))],
+                    )],
+                ),
+            ]),
+        [
+            (
+                ErrorKind::InvalidAttribute,
+                "`address` attribute for extern value `player_count` is 0x1234, which is below the preferred base 0x400000 of the image `game.exe`",
+            ),
+            (
+                ErrorKind::MissingAttribute,
+                "`address` attribute for extern value `tick` has an image, but no `rva`",
+            ),
+        ],
+    );
+
+    assert_ast_produces_failure(
+        M::new().with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_relative(0x1234, None)],
+        )]),
+        ErrorKind::MissingAttribute,
+        "extern value `player_count` has an address relative to an image, but no image was given",
+    );
+
+    assert_ast_produces_failure(
+        M::new().with_attributes([
+            A::image(IM::new("game.exe")),
+            A::image(IM::new("engine.dll")),
+        ]),
+        ErrorKind::InvalidAttribute,
+        "module `test` has more than one image",
+    );
+}
@@ -0,0 +1,158 @@
+//! Tests for vftables declared for the subobjects of secondary bases with
+//! `vftable(base) { ... }`
+
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+fn destructor() -> F {
+    F::new((V::Public, "destructor"), [Ar::MutSelf])
+}
+
+fn owner(owner_type: &str) -> F {
+    F::new((V::Public, "owner"), [Ar::ConstSelf])
+        .with_return_type(T::ident(owner_type).mut_pointer())
+}
+
+fn base(name: &str, functions: impl IntoIterator<Item = F>) -> ID {
+    ID::new((V::Public, name), TD::new([TS::vftable(functions)]))
+}
+
+fn derived(statements: impl IntoIterator<Item = TS>) -> ID {
+    ID::new(
+        (V::Public, "Derived"),
+        TD::new(
+            [
+                TS::vftable([destructor()]),
+                TS::field((V::Public, "base_a"), T::ident("BaseA")).with_attributes([A::base()]),
+                TS::field((V::Public, "base_b"), T::ident("BaseB")).with_attributes([A::base()]),
+            ]
+            .into_iter()
+            .chain(statements)
+            .collect::<Vec<_>>(),
+        ),
+    )
+}
+
+fn module(statements: impl IntoIterator<Item = TS>) -> M {
+    M::new().with_definitions([
+        base("BaseA", [destructor()]),
+        base("BaseB", [destructor(), owner("BaseB")]),
+        derived(statements),
+    ])
+}
+
+#[test]
+fn can_resolve_base_vftables() {
+    let module = module([TS::base_vftable(
+        "base_b",
+        [
+            destructor(),
+            owner("Derived").with_attributes([A::doc("Returns the owning `Derived`")]),
+        ],
+    )]);
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    let definition = |path: &str| {
+        state
+            .type_registry()
+            .get(&IP::from(path))
+            .and_then(|d| d.resolved())
+            .and_then(|r| r.inner.as_type())
+            .cloned()
+            .unwrap()
+    };
+
+    let base_destructor = |name: &str| {
+        SF::new(
+            (SV::Public, name),
+            SFB::base_vftable("base_b", "destructor"),
+        )
+        .with_calling_convention(member_cc())
+        .with_arguments([SAr::MutSelf])
+    };
+    let base_owner = SF::new((SV::Public, "owner"), SFB::base_vftable("base_b", "owner"))
+        .with_doc("Returns the owning `Derived`")
+        .with_calling_convention(member_cc())
+        .with_arguments([SAr::ConstSelf])
+        .with_return_type(ST::raw("test::Derived").mut_pointer());
+
+    let derived = definition("test::Derived");
+    assert_eq!(
+        derived.base_vftables,
+        [STV::new(
+            [base_destructor("destructor"), base_owner.clone()],
+            Some("base_b".to_string()),
+            ST::raw("test::DerivedBaseBVftable").const_pointer(),
+        )]
+    );
+    // The functions are called through `base_b` instead of being injected from `BaseB`, and
+    // are renamed if they clash with the type's own.
+    assert_eq!(
+        derived.associated_functions,
+        [base_destructor("base_b_destructor"), base_owner]
+    );
+
+    // The functions of the subobject's vftable are passed a pointer to the subobject.
+    let vftable = definition("test::DerivedBaseBVftable");
+    assert_eq!(
+        vftable.regions[1],
+        SR::field(
+            (SV::Public, "owner"),
+            ST::function(
+                member_cc(),
+                vec![("this", ST::raw("test::BaseB").const_pointer())],
+                ST::raw("test::Derived").mut_pointer(),
+            ),
+        )
+        .with_doc("Returns the owning `Derived`")
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_base_vftables() {
+    for (statements, kind, message) in [
+        (
+            vec![TS::base_vftable("base_c", [])],
+            ErrorKind::Vftable,
+            "vftable for base `base_c` of type `test::Derived` does not name a non-virtual base of the type",
+        ),
+        (
+            vec![TS::base_vftable("base_a", [destructor()])],
+            ErrorKind::Vftable,
+            "vftable for base `base_a` of type `test::Derived` is shared with the type, so its functions belong in the type's own vftable",
+        ),
+        (
+            vec![TS::base_vftable("base_b", [destructor()])],
+            ErrorKind::Vftable,
+            "vftable for base `base_b` of type `test::Derived` is missing functions from the base's vftable",
+        ),
+        (
+            vec![TS::base_vftable("base_b", [owner("Derived"), destructor()])],
+            ErrorKind::Vftable,
+            "vftable for base `base_b` of type `test::Derived` has function `owner` at index 0 but the base's vftable has function `destructor`",
+        ),
+        (
+            vec![
+                TS::base_vftable("base_b", [destructor(), owner("Derived")]),
+                TS::base_vftable("base_b", [destructor(), owner("Derived")]),
+            ],
+            ErrorKind::DuplicateDefinition,
+            "vftable for base `base_b` of type `test::Derived` is defined more than once",
+        ),
+    ] {
+        assert_ast_produces_failure(module(statements), kind, message);
+    }
+
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            base("BaseA", [destructor()]),
+            ID::new(
+                (V::Public, "BaseB"),
+                TD::new([TS::field((V::Public, "b"), T::ident("u8").const_pointer())]),
+            ),
+            derived([TS::base_vftable("base_b", [destructor()])]),
+        ]),
+        ErrorKind::Vftable,
+        "vftable for base `base_b` of type `test::Derived` overrides a base without a vftable",
+    );
+}
@@ -96,6 +96,7 @@ fn multiple_inheritance_with_vftables() {
                             (SV::Public, "associated"),
                             SFB::address(0x123),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::MutSelf])]),
                 ),
             ),
@@ -123,6 +124,7 @@ fn multiple_inheritance_with_vftables() {
                             (SV::Public, "associated"),
                             SFB::address(0x123),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::MutSelf])]),
                 ),
             ),
@@ -155,12 +157,14 @@ fn multiple_inheritance_with_vftables() {
                                 (SV::Public, "associated"),
                                 SFB::field("base_a", "associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             // base_b_associated
                             SF::new(
                                 (SV::Public, "base_b_associated"),
                                 SFB::field("base_b", "associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             // base_b_destructor
                             SF::new(
@@ -173,7 +177,7 @@ fn multiple_inheritance_with_vftables() {
                                 SAr::field("arg1", ST::raw("f32")),
                             ])
                             .with_return_type(ST::raw("i32"))
-                            .with_calling_convention(SCC::Thiscall),
+                            .with_calling_convention(member_cc()),
                         ]),
                 ),
             ),
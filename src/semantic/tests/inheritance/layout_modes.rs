@@ -0,0 +1,244 @@
+//! Tests for types laid out with the rules of a C++ ABI, mirroring the MSVC layouts in
+//! `layout/msvc2022`
+
+use super::*;
+
+use crate::semantic::ResolvedSemanticState;
+use pretty_assertions::assert_eq;
+
+fn base_a() -> ID {
+    ID::new(
+        (V::Public, "BaseA_0"),
+        TD::new([TS::field((V::Public, "field1"), T::ident("i32"))])
+            .with_attributes([A::layout("msvc")]),
+    )
+}
+
+fn base_b(layout: &str) -> ID {
+    ID::new(
+        (V::Public, "BaseB_1"),
+        TD::new([
+            TS::vftable([F::new((V::Public, "func2"), [Ar::MutSelf])]),
+            TS::field((V::Public, "field2"), T::ident("i32")),
+        ])
+        .with_attributes([A::layout(layout)]),
+    )
+}
+
+fn derived(name: &str, layout: &str, statements: impl Into<Vec<TS>>) -> ID {
+    ID::new(
+        (V::Public, name),
+        TD::new(statements).with_attributes([A::layout(layout)]),
+    )
+}
+
+fn derived_a0_b1_0(layout: &str) -> ID {
+    derived(
+        "Derived_A0_B1_0",
+        layout,
+        [
+            TS::field((V::Public, "base_a"), T::ident("BaseA_0")).with_attributes([A::base()]),
+            TS::field((V::Public, "base_b"), T::ident("BaseB_1")).with_attributes([A::base()]),
+            TS::field((V::Public, "derived_field"), T::ident("i32")),
+        ],
+    )
+}
+
+/// Returns the size, alignment and definition of the type at `path`
+fn resolve(state: &ResolvedSemanticState, path: &str) -> Option<(usize, usize, STD)> {
+    let resolved = state.type_registry().get(&IP::from(path))?.resolved()?;
+    Some((
+        resolved.size,
+        resolved.alignment,
+        resolved.inner.as_type()?.clone(),
+    ))
+}
+
+fn region_names(definition: &STD) -> Vec<&str> {
+    definition
+        .regions
+        .iter()
+        .map(|r| r.name.as_deref().unwrap())
+        .collect()
+}
+
+#[test]
+fn can_lay_out_polymorphic_bases_first_under_msvc_rules() {
+    let module = M::new().with_definitions([base_a(), base_b("msvc"), derived_a0_b1_0("msvc")]);
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    let ps = pointer_size();
+
+    // `BaseB_1` is padded out to the alignment of its vftable.
+    let (size, alignment, base_b) = resolve(&state, "test::BaseB_1").unwrap();
+    assert_eq!((size, alignment), (2 * ps, ps));
+    if ps == 8 {
+        assert_eq!(region_names(&base_b), ["vftable", "field2", "_field_c"]);
+    }
+    assert_eq!(base_b.data_size, None);
+
+    let (size, alignment, derived) = resolve(&state, "test::Derived_A0_B1_0").unwrap();
+    assert_eq!((size, alignment), (2 * ps + 8, ps));
+    assert_eq!(
+        derived.regions,
+        [
+            SR::field((SV::Public, "base_b"), ST::raw("test::BaseB_1")).marked_as_base(),
+            SR::field((SV::Public, "base_a"), ST::raw("test::BaseA_0")).marked_as_base(),
+            SR::field((SV::Public, "derived_field"), ST::raw("i32")),
+        ]
+    );
+    assert_eq!(
+        derived.vftable.and_then(|v| v.base_field),
+        Some("base_b".to_string())
+    );
+    // The shared vftable's functions aren't injected again.
+    assert!(derived.associated_functions.is_empty());
+}
+
+#[test]
+fn can_reuse_tail_padding_under_itanium_rules() {
+    let module = M::new().with_definitions([
+        base_a(),
+        base_b("itanium"),
+        derived_a0_b1_0("itanium"),
+        // POD types don't have their tail padding reused.
+        ID::new(
+            (V::Public, "Pod"),
+            TD::new([
+                TS::field((V::Public, "a"), T::ident("u64")),
+                TS::field((V::Public, "b"), T::ident("u8")),
+            ])
+            .with_attributes([A::layout("itanium")]),
+        ),
+        derived(
+            "PodDerived",
+            "itanium",
+            [
+                TS::field((V::Public, "pod"), T::ident("Pod")).with_attributes([A::base()]),
+                TS::field((V::Public, "c"), T::ident("u8")),
+            ],
+        ),
+    ]);
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    let ps = pointer_size();
+
+    let (size, alignment, derived) = resolve(&state, "test::Derived_A0_B1_0").unwrap();
+    if ps == 8 {
+        // `BaseA_0` is placed in the tail padding of `BaseB_1`, which is embedded without it.
+        let (base_size, base_alignment, base_b) = resolve(&state, "test::BaseB_1").unwrap();
+        assert_eq!(
+            (base_size, base_alignment, base_b.data_size),
+            (16, 8, Some(12))
+        );
+        let (unpadded_size, unpadded_alignment, unpadded) =
+            resolve(&state, "test::BaseB_1Unpadded").unwrap();
+        assert_eq!(
+            (unpadded_size, unpadded_alignment, unpadded.packed),
+            (12, 1, true)
+        );
+        assert_eq!(region_names(&unpadded), ["vftable", "field2"]);
+
+        assert_eq!(
+            derived.regions,
+            [
+                SR::field((SV::Public, "base_b"), ST::raw("test::BaseB_1Unpadded"))
+                    .marked_as_base(),
+                SR::field((SV::Public, "base_a"), ST::raw("test::BaseA_0")).marked_as_base(),
+                SR::field((SV::Public, "derived_field"), ST::raw("i32")),
+                SR::field((SV::Private, "_field_14"), unknown(4)),
+            ]
+        );
+        // The packed base doesn't lower the alignment of the type.
+        assert_eq!((size, alignment, derived.data_size), (24, 8, Some(20)));
+        assert!(state
+            .type_registry()
+            .get(&IP::from("test::Derived_A0_B1_0Unpadded"))
+            .is_some());
+    } else {
+        // There's no tail padding to reuse with 4-byte pointers.
+        assert!(state
+            .type_registry()
+            .get(&IP::from("test::BaseB_1Unpadded"))
+            .is_none());
+        assert_eq!(
+            region_names(&derived),
+            ["base_b", "base_a", "derived_field"]
+        );
+        assert_eq!((size, alignment, derived.data_size), (16, 4, None));
+    }
+
+    let (size, _, pod) = resolve(&state, "test::Pod").unwrap();
+    assert_eq!((size, pod.data_size), (16, None));
+    let (size, _, pod_derived) = resolve(&state, "test::PodDerived").unwrap();
+    assert_eq!(region_names(&pod_derived), ["pod", "c", "_field_11"]);
+    assert_eq!(size, 24);
+}
+
+#[test]
+fn can_lay_out_empty_bases_first() {
+    let module = M::new().with_definitions([
+        base_a(),
+        ID::new(
+            (V::Public, "Empty"),
+            TD::new([]).with_attributes([A::layout("msvc")]),
+        ),
+        derived(
+            "Derived",
+            "msvc",
+            [
+                TS::field((V::Public, "flag"), T::ident("u8")),
+                TS::field((V::Public, "base_a"), T::ident("BaseA_0")).with_attributes([A::base()]),
+                TS::field((V::Public, "empty"), T::ident("Empty")).with_attributes([A::base()]),
+                TS::field((V::Public, "value"), T::ident("u16")),
+            ],
+        ),
+    ]);
+    let state = build_state(&module, &IP::from("test")).unwrap();
+
+    assert_eq!(
+        resolve(&state, "test::Empty").map(|(size, alignment, _)| (size, alignment)),
+        Some((0, 1))
+    );
+
+    // Bases come before fields, and each field is placed at its alignment.
+    let (size, alignment, derived) = resolve(&state, "test::Derived").unwrap();
+    assert_eq!(
+        region_names(&derived),
+        ["empty", "base_a", "flag", "_field_5", "value"]
+    );
+    assert_eq!((size, alignment), (8, 4));
+}
+
+#[test]
+fn will_fail_on_invalid_layouts() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([derived(
+            "Test",
+            "gcc",
+            [TS::field((V::Public, "a"), T::ident("u32"))],
+        )]),
+        ErrorKind::InvalidAttribute,
+        "`layout` attribute for type `test::Test` must be `msvc` or `itanium`, but was `gcc`",
+    );
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Test"),
+            TD::new([TS::field((V::Public, "a"), T::ident("u32"))])
+                .with_attributes([A::layout("msvc"), A::packed()]),
+        )]),
+        ErrorKind::InvalidAttribute,
+        "cannot specify both `packed` and `layout` attributes for type `test::Test`",
+    );
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            base_a(),
+            derived(
+                "Test",
+                "itanium",
+                [TS::field((V::Public, "base"), T::ident("BaseA_0"))
+                    .with_attributes([A::virtual_base()])],
+            ),
+        ]),
+        ErrorKind::InvalidAttribute,
+        "virtual base `base` of type `test::Test` is not supported by the `itanium` layout",
+    );
+}
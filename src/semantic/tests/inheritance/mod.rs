@@ -1,11 +1,14 @@
-//! Tests for inheritance of types with optional vftables.
+//! Tests for inheritance of types with optional vftables and vbtables.
 
 use super::*;
 
+mod base_vftables;
 mod diamond_inheritance;
+mod layout_modes;
 mod multiple_levels;
 mod one_base_class;
 mod two_base_classes;
+mod virtual_inheritance;
 
 fn vfunc_grammar(name: &str) -> F {
     F::new(
@@ -23,7 +26,7 @@ fn vfunc_region(name: &str, self_type: &str) -> SR {
     SR::field(
         (SV::Public, name),
         ST::function(
-            SCC::Thiscall,
+            member_cc(),
             vec![
                 ("this", ST::raw(self_type).mut_pointer()),
                 ("arg0", ST::raw("u32")),
@@ -36,6 +39,7 @@ fn vfunc_region(name: &str, self_type: &str) -> SR {
 
 fn vfunc_semantic(name: &str) -> SF {
     SF::new((SV::Public, name), SFB::vftable(name))
+        .with_calling_convention(member_cc())
         .with_arguments([
             SAr::MutSelf,
             SAr::field("arg0", ST::raw("u32")),
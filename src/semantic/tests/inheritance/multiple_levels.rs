@@ -772,6 +772,7 @@ fn b1_d1_dd1_with_associated_functions() {
                             (SV::Public, "base_associated"),
                             SFB::address(0x123),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::MutSelf])]),
                 ),
             ),
@@ -803,8 +804,10 @@ fn b1_d1_dd1_with_associated_functions() {
                                 (SV::Public, "base_associated"),
                                 SFB::field("base", "base_associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             SF::new((SV::Public, "derived_associated"), SFB::address(0x456))
+                                .with_calling_convention(member_cc())
                                 .with_arguments([SAr::MutSelf]),
                         ]),
                 ),
@@ -843,16 +846,19 @@ fn b1_d1_dd1_with_associated_functions() {
                                 (SV::Public, "base_associated"),
                                 SFB::field("derived", "base_associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             SF::new(
                                 (SV::Public, "derived_associated"),
                                 SFB::field("derived", "derived_associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             SF::new(
                                 (SV::Public, "derived_derived_associated"),
                                 SFB::address(0x789),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                         ]),
                 ),
@@ -264,6 +264,7 @@ fn b1_d1_with_associated_functions() {
                             (SV::Public, "base_associated"),
                             SFB::address(0x123),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::MutSelf])]),
                 ),
             ),
@@ -295,8 +296,10 @@ fn b1_d1_with_associated_functions() {
                                 (SV::Public, "base_associated"),
                                 SFB::field("base", "base_associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             SF::new((SV::Public, "derived_associated"), SFB::address(0x456))
+                                .with_calling_convention(member_cc())
                                 .with_arguments([SAr::MutSelf]),
                         ]),
                 ),
@@ -343,12 +346,15 @@ fn b1_d1_without_overlapping_vfuncs_will_fail() {
                 .with_attributes([A::align(8)]),
             ),
         ]),
-        concat!(
-            "while processing `test::Derived`\n",
-            "vftable for `test::Derived` has function ",
-            r#"`pub extern "thiscall" fn not_base_vfunc2(&mut self, arg0: u32, arg1: f32) -> i32 = self.vftable.not_base_vfunc2` "#,
-            "at index 1 but base class `base` has function ",
-            r#"`pub extern "thiscall" fn base_vfunc2(&mut self, arg0: u32, arg1: f32) -> i32 = self.vftable.base_vfunc2`"#
+        ErrorKind::Vftable,
+        &format!(
+            concat!(
+                "vftable for `test::Derived` has function ",
+                r#"`pub extern "{cc}" fn not_base_vfunc2(&mut self, arg0: u32, arg1: f32) -> i32 = self.vftable.not_base_vfunc2` "#,
+                "at index 1 but base class `base` has function ",
+                r#"`pub extern "{cc}" fn base_vfunc2(&mut self, arg0: u32, arg1: f32) -> i32 = self.vftable.base_vfunc2`"#
+            ),
+            cc = member_cc()
         ),
     );
 }
@@ -594,6 +594,7 @@ fn a1_b1_d1_with_associated_functions() {
                             (SV::Public, "base_a_associated"),
                             SFB::address(0x123),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::MutSelf])]),
                 ),
             ),
@@ -628,6 +629,7 @@ fn a1_b1_d1_with_associated_functions() {
                             (SV::Public, "base_b_associated"),
                             SFB::address(0x456),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::MutSelf])]),
                 ),
             ),
@@ -662,15 +664,18 @@ fn a1_b1_d1_with_associated_functions() {
                                 (SV::Public, "base_a_associated"),
                                 SFB::field("base_a", "base_a_associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             SF::new(
                                 (SV::Public, "base_b_associated"),
                                 SFB::field("base_b", "base_b_associated"),
                             )
+                            .with_calling_convention(member_cc())
                             .with_arguments([SAr::MutSelf]),
                             vfunc_semantic("base_b_vfunc")
                                 .with_body(SFB::field("base_b", "base_b_vfunc")),
                             SF::new((SV::Public, "derived_associated"), SFB::address(0x789))
+                                .with_calling_convention(member_cc())
                                 .with_arguments([SAr::MutSelf]),
                         ]),
                 ),
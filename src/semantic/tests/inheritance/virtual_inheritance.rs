@@ -0,0 +1,345 @@
+//! Tests for virtual inheritance, where a base shared by several bases is only stored once,
+//! at the end of the complete type
+
+use super::*;
+
+use crate::semantic::{Config, Target};
+use pretty_assertions::assert_eq;
+
+fn pointer() -> T {
+    T::ident("u8").mut_pointer()
+}
+
+fn pointer_region(name: &str) -> SR {
+    SR::field((SV::Public, name), ST::raw("u8").mut_pointer())
+}
+
+fn vbptr_region() -> SR {
+    SR::field((SV::Private, "vbtable"), ST::raw("i32").const_pointer())
+}
+
+fn non_virtual_doc(name: &str) -> String {
+    format!(" The non-virtual part of `{name}`, as embedded in the types that inherit from it")
+}
+
+#[test]
+fn can_resolve_virtual_diamond_inheritance() {
+    let virtual_base =
+        || TS::field((V::Public, "base"), T::ident("Base")).with_attributes([A::virtual_base()]);
+    let base_vbtable = |base_field: Option<&str>| {
+        STVB::new(
+            [SR::field((SV::Public, "base"), ST::raw("test::Base"))],
+            base_field.map(|f| f.to_string()),
+        )
+    };
+    let base_storage = || SR::field((SV::Private, "base"), ST::raw("test::Base"));
+    let ps = pointer_size();
+
+    let mut definitions = vec![SID::defined_resolved(
+        (SV::Public, "test::Base"),
+        SISR::new((ps, ps), STD::new().with_regions([pointer_region("data")])),
+    )];
+    for name in ["BaseA", "BaseB"] {
+        let field = if name == "BaseA" { "a" } else { "b" };
+        let non_virtual_regions = [vbptr_region(), pointer_region(field)];
+        definitions.extend([
+            SID::defined_resolved(
+                (SV::Public, format!("test::{name}").as_str()),
+                SISR::new(
+                    (3 * ps, ps),
+                    STD::new()
+                        .with_regions([
+                            non_virtual_regions[0].clone(),
+                            non_virtual_regions[1].clone(),
+                            base_storage(),
+                        ])
+                        .with_vbtable(base_vbtable(None)),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, format!("test::{name}NonVirtual").as_str()),
+                SISR::new(
+                    (2 * ps, ps),
+                    STD::new()
+                        .with_regions(non_virtual_regions)
+                        .with_doc(non_virtual_doc(name))
+                        .with_non_virtual_part_of(format!("test::{name}").as_str()),
+                ),
+            ),
+        ]);
+    }
+    let derived_regions = [
+        SR::field((SV::Public, "base_a"), ST::raw("test::BaseANonVirtual")).marked_as_base(),
+        SR::field((SV::Public, "base_b"), ST::raw("test::BaseBNonVirtual")).marked_as_base(),
+        pointer_region("derived"),
+    ];
+    definitions.extend([
+        SID::defined_resolved(
+            (SV::Public, "test::Derived"),
+            SISR::new(
+                (6 * ps, ps),
+                STD::new()
+                    .with_regions(
+                        derived_regions
+                            .iter()
+                            .cloned()
+                            .chain([base_storage()])
+                            .collect::<Vec<_>>(),
+                    )
+                    .with_vbtable(base_vbtable(Some("base_a"))),
+            ),
+        ),
+        SID::defined_resolved(
+            (SV::Public, "test::DerivedNonVirtual"),
+            SISR::new(
+                (5 * ps, ps),
+                STD::new()
+                    .with_regions(derived_regions)
+                    .with_doc(non_virtual_doc("Derived"))
+                    .with_non_virtual_part_of("test::Derived"),
+            ),
+        ),
+    ]);
+
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Base"),
+                TD::new([TS::field((V::Public, "data"), pointer())]),
+            ),
+            ID::new(
+                (V::Public, "BaseA"),
+                TD::new([virtual_base(), TS::field((V::Public, "a"), pointer())]),
+            ),
+            ID::new(
+                (V::Public, "BaseB"),
+                TD::new([virtual_base(), TS::field((V::Public, "b"), pointer())]),
+            ),
+            ID::new(
+                (V::Public, "Derived"),
+                TD::new([
+                    TS::field((V::Public, "base_a"), T::ident("BaseA"))
+                        .with_attributes([A::base()]),
+                    TS::field((V::Public, "base_b"), T::ident("BaseB"))
+                        .with_attributes([A::base()]),
+                    TS::field((V::Public, "derived"), pointer()),
+                ]),
+            ),
+        ]),
+        definitions,
+    );
+}
+
+#[test]
+fn can_lay_out_virtual_bases_after_the_non_virtual_part() {
+    // `Outer` hoists `Inner`'s virtual base ahead of `Inner`, stores only the non-virtual part
+    // of `Inner`, and pads its own non-virtual part to its alignment before the virtual bases.
+    let module = M::new().with_definitions([
+        ID::new(
+            (V::Public, "Root"),
+            TD::new([TS::field((V::Public, "id"), T::ident("u32"))]),
+        ),
+        ID::new(
+            (V::Public, "Inner"),
+            TD::new([
+                TS::field((V::Public, "root"), T::ident("Root"))
+                    .with_attributes([A::virtual_base()]),
+                TS::field((V::Public, "data"), pointer()),
+            ]),
+        ),
+        ID::new(
+            (V::Public, "Outer"),
+            TD::new([
+                TS::field((V::Public, "inner"), T::ident("Inner"))
+                    .with_attributes([A::virtual_base()]),
+                TS::field((V::Public, "flags"), T::ident("u32")),
+            ]),
+        ),
+    ]);
+
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    let resolved = state
+        .type_registry()
+        .get(&IP::from("test::Outer"))
+        .and_then(|d| d.resolved())
+        .unwrap();
+    let outer = resolved.inner.as_type().unwrap();
+
+    let ps = pointer_size();
+    let mut expected_regions = vec![
+        vbptr_region(),
+        SR::field((SV::Public, "flags"), ST::raw("u32")),
+    ];
+    if ps == 8 {
+        expected_regions.push(SR::field((SV::Private, "_field_c"), unknown(4)));
+    }
+    expected_regions.push(SR::field((SV::Private, "root"), ST::raw("test::Root")));
+    if ps == 8 {
+        expected_regions.push(SR::field((SV::Private, "_field_14"), unknown(4)));
+    }
+    expected_regions.push(SR::field(
+        (SV::Private, "inner"),
+        ST::raw("test::InnerNonVirtual"),
+    ));
+
+    assert_eq!(outer.regions, expected_regions);
+    assert_eq!(
+        outer.vbtable,
+        Some(STVB::new(
+            [
+                SR::field((SV::Public, "root"), ST::raw("test::Root")),
+                SR::field((SV::Public, "inner"), ST::raw("test::InnerNonVirtual")),
+            ],
+            None,
+        ))
+    );
+    assert_eq!(resolved.size, if ps == 8 { 0x28 } else { 0x14 });
+}
+
+#[test]
+fn can_inherit_functions_of_bases_with_virtual_bases() {
+    // The non-virtual part of `Inner` only describes its layout, so `Outer` gets `Inner`'s
+    // functions from `Inner` itself, and calls them through the base it stores.
+    let module =
+        M::new()
+            .with_definitions([
+                ID::new(
+                    (V::Public, "Root"),
+                    TD::new([TS::field((V::Public, "id"), T::ident("u32"))]),
+                ),
+                ID::new(
+                    (V::Public, "Inner"),
+                    TD::new([
+                        TS::field((V::Public, "root"), T::ident("Root"))
+                            .with_attributes([A::virtual_base()]),
+                        TS::field((V::Public, "data"), pointer()),
+                    ]),
+                ),
+                ID::new(
+                    (V::Public, "Outer"),
+                    TD::new([TS::field((V::Public, "inner"), T::ident("Inner"))
+                        .with_attributes([A::base()])]),
+                ),
+            ])
+            .with_impls([FB::new(
+                "Inner",
+                [F::new((V::Public, "associated"), [Ar::MutSelf])
+                    .with_attributes([A::address(0x123)])],
+            )]);
+
+    let state = build_state(&module, &IP::from("test")).unwrap();
+    let type_definition = |path: &str| {
+        state
+            .type_registry()
+            .get(&IP::from(path))
+            .and_then(|d| d.resolved())
+            .and_then(|r| r.inner.as_type())
+            .cloned()
+            .unwrap()
+    };
+
+    let inner_non_virtual = type_definition("test::InnerNonVirtual");
+    assert_eq!(inner_non_virtual.associated_functions, []);
+    assert_eq!(inner_non_virtual.vbtable, None);
+    assert_eq!(
+        inner_non_virtual.non_virtual_part_of,
+        Some(IP::from("test::Inner"))
+    );
+
+    let outer = type_definition("test::Outer");
+    assert_eq!(
+        outer.associated_functions,
+        [SF::new(
+            (SV::Public, "associated"),
+            SFB::field("inner", "associated")
+        )
+        .with_calling_convention(member_cc())
+        .with_arguments([SAr::MutSelf])]
+    );
+    assert_eq!(
+        outer.vbtable,
+        Some(STVB::new(
+            [SR::field((SV::Public, "root"), ST::raw("test::Root"))],
+            "inner".to_string(),
+        ))
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_virtual_bases() {
+    let base = || {
+        ID::new(
+            (V::Public, "Base"),
+            TD::new([TS::field((V::Public, "id"), T::ident("u32"))]),
+        )
+    };
+
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            base(),
+            ID::new(
+                (V::Public, "Derived"),
+                TD::new([TS::field((V::Public, "base"), T::ident("Base"))
+                    .with_attributes([A::virtual_base(), A::address(0x8)])]),
+            ),
+        ]),
+        ErrorKind::InvalidAttribute,
+        "virtual base `base` of type `test::Derived` cannot have an `address` or `bits` attribute, as it is laid out after the rest of the type",
+    );
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            base(),
+            ID::new(
+                (V::Public, "Derived"),
+                TD::new([
+                    TS::field((V::Public, "base"), T::ident("Base")).with_attributes([
+                        A::Function("base".into(), vec![E::Ident("shared".into())]),
+                    ]),
+                ]),
+            ),
+        ]),
+        ErrorKind::InvalidAttribute,
+        "`base` attribute for field `base` of type `test::Derived` only takes `virtual`",
+    );
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            base(),
+            ID::new(
+                (V::Public, "Holder"),
+                TD::new([
+                    TS::field((V::Public, "base"), T::ident("Base"))
+                        .with_attributes([A::virtual_base()]),
+                    TS::field((V::Public, "value"), T::ident("T")),
+                ])
+                .with_generics(["T"]),
+            ),
+        ]),
+        ErrorKind::InvalidGeneric,
+        "generic type `test::Holder` cannot have a base type",
+    );
+}
+
+#[test]
+fn will_fail_on_virtual_bases_for_non_msvc_targets() {
+    let module = M::new().with_definitions([
+        ID::new(
+            (V::Public, "Base"),
+            TD::new([TS::field((V::Public, "id"), T::ident("u32"))]),
+        ),
+        ID::new(
+            (V::Public, "Derived"),
+            TD::new([TS::field((V::Public, "base"), T::ident("Base"))
+                .with_attributes([A::virtual_base()])]),
+        ),
+    ]);
+
+    let target = Target::from_preset("x86_64-linux").unwrap();
+    let err = build_state_with(&module, &IP::from("test"), target, Config::new()).unwrap_err();
+    let diagnostics = err.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, ErrorKind::InvalidAttribute);
+    assert_eq!(
+        diagnostics[0].message,
+        "virtual base `base` of type `test::Derived` is not supported by the target `x86_64-linux`"
+    );
+}
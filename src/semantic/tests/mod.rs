@@ -1,14 +1,30 @@
 use crate::{
+    error::ErrorKind,
     grammar::test_aliases::*,
     semantic::{semantic_state::SemanticState, types::test_aliases::*},
+    span::SourceFile,
 };
 
 use anyhow::Context;
 use pretty_assertions::assert_eq;
 
+mod aliases;
 mod alignment;
+mod bitfields;
+mod cfg;
+mod chains;
+mod constants;
+mod expressions;
+mod function_pointers;
+mod generics;
+mod images;
 mod inheritance;
+mod nested;
+mod signatures;
+mod targets;
+mod unions;
 mod util;
+mod versions;
 use util::*;
 
 #[test]
@@ -159,6 +175,7 @@ fn can_resolve_complex_type() {
                             (SV::Public, "test_function"),
                             SFB::address(0x800_000),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([
                             SAr::MutSelf,
                             SAr::field("arg1".to_string(), ST::raw("test::TestType").mut_pointer()),
@@ -180,6 +197,7 @@ fn will_eventually_terminate_with_an_unknown_type() {
             (V::Public, "TestType2"),
             TD::new([TS::field((V::Private, "field_2"), T::ident("TestType1"))]),
         )]),
+        ErrorKind::UnresolvedType,
         r#"type resolution will not terminate, failed on types: ["test::TestType2"] (resolved types: [])"#,
     );
 }
@@ -197,7 +215,7 @@ fn can_use_type_from_another_module() {
         TD::new([TS::field((V::Private, "field"), T::ident("u32"))]),
     )]);
 
-    let mut semantic_state = SemanticState::new(4);
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
     semantic_state
         .add_module(&module1, &IP::from("module1"))
         .unwrap();
@@ -228,14 +246,452 @@ fn can_use_type_from_another_module() {
     );
 }
 
+#[test]
+fn can_use_relative_and_absolute_paths() {
+    let world = M::new().with_definitions([ID::new(
+        (V::Public, "Entity"),
+        TD::new([TS::field((V::Private, "id"), T::ident("u32"))]),
+    )]);
+    let ui = M::new().with_definitions([ID::new(
+        (V::Public, "Widget"),
+        TD::new([TS::field((V::Private, "id"), T::ident("u32"))]),
+    )]);
+    let hud = M::new()
+        .with_uses([IP::from("super::Widget"), IP::from("crate::game::world")])
+        .with_definitions([ID::new(
+            (V::Public, "Hud"),
+            TD::new([
+                TS::field(
+                    (V::Private, "parent_entity"),
+                    T::path("super::super::world::Entity").mut_pointer(),
+                ),
+                TS::field(
+                    (V::Private, "crate_entity"),
+                    T::path("crate::game::world::Entity").mut_pointer(),
+                ),
+                TS::field(
+                    (V::Private, "used_entity"),
+                    T::path("world::Entity").mut_pointer(),
+                ),
+                TS::field((V::Private, "this"), T::path("self::Hud").const_pointer()),
+                TS::field((V::Private, "widget"), T::ident("Widget")),
+            ]),
+        )]);
+
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
+    for (module, path) in [
+        (&world, "game::world"),
+        (&ui, "game::ui"),
+        (&hud, "game::ui::hud"),
+    ] {
+        semantic_state.add_module(module, &IP::from(path)).unwrap();
+    }
+    let semantic_state = semantic_state.build().unwrap();
+
+    let path = IP::from("game::ui::hud::Hud");
+    let resolved_type = semantic_state
+        .type_registry()
+        .get(&path)
+        .cloned()
+        .context("failed to get type")
+        .unwrap();
+    assert_eq!(
+        resolved_type,
+        SID::defined_resolved(
+            (SV::Public, path.clone()),
+            SISR::new(
+                (20, 4),
+                STD::new().with_regions([
+                    SR::field(
+                        (SV::Private, "parent_entity"),
+                        ST::raw("game::world::Entity").mut_pointer()
+                    ),
+                    SR::field(
+                        (SV::Private, "crate_entity"),
+                        ST::raw("game::world::Entity").mut_pointer()
+                    ),
+                    SR::field(
+                        (SV::Private, "used_entity"),
+                        ST::raw("game::world::Entity").mut_pointer()
+                    ),
+                    SR::field(
+                        (SV::Private, "this"),
+                        ST::raw("game::ui::hud::Hud").const_pointer()
+                    ),
+                    SR::field((SV::Private, "widget"), ST::raw("game::ui::Widget")),
+                ])
+            )
+        )
+    );
+}
+
+#[test]
+fn can_use_grouped_glob_and_aliased_imports() {
+    let world = M::new().with_definitions([
+        ID::new(
+            (V::Public, "Entity"),
+            TD::new([TS::field((V::Private, "id"), T::ident("u32"))]),
+        ),
+        ID::new(
+            (V::Public, "Mesh"),
+            TD::new([TS::field((V::Private, "id"), T::ident("u32"))]),
+        ),
+    ]);
+    let math = M::new().with_definitions([ID::new(
+        (V::Public, "Vector"),
+        TD::new([TS::field((V::Private, "x"), T::ident("u32"))]),
+    )]);
+    let player = M::new()
+        .with_uses([
+            U::new(IP::from("game::world::Entity")),
+            U::new(IP::from("game::world::Mesh")).with_alias("PlayerMesh"),
+            U::glob(IP::from("game::math")),
+        ])
+        .with_definitions([ID::new(
+            (V::Public, "Player"),
+            TD::new([
+                TS::field((V::Private, "entity"), T::ident("Entity")),
+                TS::field((V::Private, "mesh"), T::ident("PlayerMesh")),
+                TS::field((V::Private, "position"), T::ident("Vector")),
+            ]),
+        )]);
+
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
+    for (module, path) in [
+        (&world, "game::world"),
+        (&math, "game::math"),
+        (&player, "game::player"),
+    ] {
+        semantic_state.add_module(module, &IP::from(path)).unwrap();
+    }
+    let semantic_state = semantic_state.build().unwrap();
+
+    let path = IP::from("game::player::Player");
+    assert_eq!(
+        semantic_state.type_registry().get(&path).cloned().unwrap(),
+        SID::defined_resolved(
+            (SV::Public, path.clone()),
+            SISR::new(
+                (12, 4),
+                STD::new().with_regions([
+                    SR::field((SV::Private, "entity"), ST::raw("game::world::Entity")),
+                    SR::field((SV::Private, "mesh"), ST::raw("game::world::Mesh")),
+                    SR::field((SV::Private, "position"), ST::raw("game::math::Vector")),
+                ])
+            )
+        )
+    );
+}
+
+#[test]
+fn can_use_types_through_reexports() {
+    let world = M::new().with_definitions([ID::new(
+        (V::Public, "Entity"),
+        TD::new([TS::field((V::Private, "id"), T::ident("u32"))]),
+    )]);
+    let math = M::new().with_definitions([ID::new(
+        (V::Public, "Vector"),
+        TD::new([TS::field((V::Private, "x"), T::ident("u32"))]),
+    )]);
+    let prelude = M::new().with_uses([
+        U::new(IP::from("game::world::Entity"))
+            .with_alias("GameEntity")
+            .with_visibility(V::Public),
+        U::glob(IP::from("game::math")).with_visibility(V::Public),
+    ]);
+    let player = M::new()
+        .with_uses([U::new(IP::from("game::prelude::GameEntity"))])
+        .with_definitions([ID::new(
+            (V::Public, "Player"),
+            TD::new([
+                TS::field((V::Private, "entity"), T::ident("GameEntity")),
+                TS::field((V::Private, "position"), T::path("super::prelude::Vector")),
+            ]),
+        )]);
+
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
+    for (module, path) in [
+        (&world, "game::world"),
+        (&math, "game::math"),
+        (&prelude, "game::prelude"),
+        (&player, "game::player"),
+    ] {
+        semantic_state.add_module(module, &IP::from(path)).unwrap();
+    }
+    let semantic_state = semantic_state.build().unwrap();
+
+    let path = IP::from("game::player::Player");
+    assert_eq!(
+        semantic_state.type_registry().get(&path).cloned().unwrap(),
+        SID::defined_resolved(
+            (SV::Public, path.clone()),
+            SISR::new(
+                (8, 4),
+                STD::new().with_regions([
+                    SR::field((SV::Private, "entity"), ST::raw("game::world::Entity")),
+                    SR::field((SV::Private, "position"), ST::raw("game::math::Vector")),
+                ])
+            )
+        )
+    );
+}
+
+#[test]
+fn will_fail_on_names_provided_by_more_than_one_glob_import() {
+    let item = || {
+        M::new().with_definitions([ID::new(
+            (V::Public, "Item"),
+            TD::new([TS::field((V::Private, "id"), T::ident("u32"))]),
+        )])
+    };
+    let user = |uses: Vec<U>| {
+        M::new().with_uses(uses).with_definitions([ID::new(
+            (V::Public, "User"),
+            TD::new([TS::field((V::Private, "item"), T::ident("Item"))]),
+        )])
+    };
+    let build = |user: M| {
+        let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
+        for (module, path) in [
+            (item(), "game::v"),
+            (item(), "game::w"),
+            (user, "game::user"),
+        ] {
+            semantic_state.add_module(&module, &IP::from(path)).unwrap();
+        }
+        semantic_state.build()
+    };
+
+    let err = build(user(vec![
+        U::glob(IP::from("super::v")),
+        U::glob(IP::from("super::w")),
+    ]))
+    .unwrap_err();
+    assert_eq!(
+        err.diagnostics()
+            .iter()
+            .map(|d| (d.kind, d.message.as_str(), d.notes.clone()))
+            .collect::<Vec<_>>(),
+        [(
+            ErrorKind::AmbiguousImport,
+            "`Item` is ambiguous in module `game::user`, as more than one glob import provides it",
+            vec![
+                "`Item` could refer to `game::v::Item`, imported by `use game::v::*`".to_string(),
+                "`Item` could refer to `game::w::Item`, imported by `use game::w::*`".to_string(),
+                "import `Item` by name to choose one".to_string(),
+            ]
+        )]
+    );
+
+    // Importing the name directly chooses between them, and the same item imported through
+    // more than one glob import is not ambiguous.
+    for uses in [
+        vec![
+            U::glob(IP::from("super::v")),
+            U::glob(IP::from("super::w")),
+            U::new(IP::from("super::w::Item")),
+        ],
+        vec![
+            U::glob(IP::from("super::w")),
+            U::glob(IP::from("crate::game::w")),
+        ],
+    ] {
+        let state = build(user(uses)).unwrap();
+        let user = state
+            .type_registry()
+            .get(&IP::from("game::user::User"))
+            .and_then(|t| t.resolved())
+            .and_then(|r| r.inner.as_type())
+            .cloned()
+            .unwrap();
+        assert_eq!(user.regions[0].type_ref, ST::raw("game::w::Item"));
+    }
+}
+
+#[test]
+fn will_fail_on_use_above_the_root_module() {
+    assert_ast_produces_failure(
+        M::new().with_uses([IP::from("super::super::TestType")]),
+        ErrorKind::UnresolvedType,
+        "failed to resolve `use super::super::TestType;` in module `test`",
+    );
+}
+
+#[test]
+fn will_report_every_invalid_use_with_the_rest_of_the_module() {
+    let mut semantic_state = SemanticState::new(target());
+    let err = semantic_state
+        .add_source_file(
+            SourceFile::new(
+                "test.pyxis",
+                concat!(
+                    "use super::super::A;\n",
+                    "use self::super::B;\n",
+                    "pub extern value: u32;\n",
+                ),
+            ),
+            &IP::from("test"),
+        )
+        .unwrap_err();
+
+    let diagnostics: Vec<_> = err
+        .diagnostics()
+        .iter()
+        .map(|d| {
+            let line = d.location.as_ref().map(|l| l.span.start.line);
+            (d.kind, d.message.as_str(), line)
+        })
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            (
+                ErrorKind::MissingAttribute,
+                "failed to find `address` or `signature` attribute for extern value `value` in module `test`",
+                Some(3),
+            ),
+            (
+                ErrorKind::UnresolvedType,
+                "failed to resolve `use super::super::A;` in module `test`",
+                Some(1),
+            ),
+            (
+                ErrorKind::UnresolvedType,
+                "failed to resolve `use self::super::B;` in module `test`",
+                Some(2),
+            ),
+        ]
+    );
+}
+
 #[test]
 fn will_fail_on_an_extern_without_size() {
     assert_ast_produces_failure(
         M::new().with_extern_types([("TestType".into(), As::default())]),
+        ErrorKind::MissingAttribute,
         "failed to find `size` attribute for extern type `TestType` in module `test`",
     );
 }
 
+#[test]
+fn will_report_all_independent_failures() {
+    assert_ast_produces_failures(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "TestType1"),
+                TD::new([TS::field((V::Public, "field_1"), T::ident("u64"))])
+                    .with_attributes([A::size(4)]),
+            ),
+            ID::new(
+                (V::Public, "TestType2"),
+                TD::new([TS::field((V::Public, "field_1"), T::ident("Unknown"))]),
+            ),
+            ID::new(
+                (V::Public, "TestType3"),
+                TD::new([TS::field(
+                    (V::Private, "field_1"),
+                    T::ident("i32").mut_pointer(),
+                )])
+                .with_attributes([A::defaultable()]),
+            ),
+        ]),
+        [
+            (
+                ErrorKind::SizeMismatch,
+                "calculated size 8 for type `test::TestType1` does not match target size 4; is your target size correct?",
+            ),
+            (
+                ErrorKind::NotDefaultable,
+                "field `field_1` of type `test::TestType3` is not a defaultable type (pointer or function?)",
+            ),
+            (
+                ErrorKind::UnresolvedType,
+                r#"type resolution will not terminate, failed on types: ["test::TestType2"] (resolved types: [])"#,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_not_report_types_that_depend_on_a_failed_type() {
+    assert_ast_produces_failures(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "TestType1"),
+                TD::new([TS::field((V::Public, "field_1"), T::ident("u64"))])
+                    .with_attributes([A::size(4)]),
+            ),
+            ID::new(
+                (V::Public, "TestType2"),
+                TD::new([TS::field((V::Public, "field_1"), T::ident("TestType1"))]),
+            ),
+            ID::new(
+                (V::Public, "TestType3"),
+                TD::new([TS::field(
+                    (V::Public, "field_1"),
+                    T::ident("TestType2").array(2),
+                )]),
+            ),
+        ]),
+        [(
+            ErrorKind::SizeMismatch,
+            "calculated size 8 for type `test::TestType1` does not match target size 4; is your target size correct?",
+        )],
+    );
+}
+
+#[test]
+fn can_resolve_generic_extern_type() {
+    assert_ast_produces_type_definitions(
+        M::new()
+            .with_extern_types([("Map".into(), As::from_iter([A::size(12), A::align(4)]))])
+            .with_definitions([ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field(
+                    (V::Public, "field_1"),
+                    T::generic("Map", [T::ident("u32"), T::ident("TestType").mut_pointer()]),
+                )]),
+            )]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::TestType"),
+                SISR::new(
+                    (12, 4),
+                    STD::new().with_regions([SR::field(
+                        (SV::Public, "field_1"),
+                        ST::generic(
+                            "test::Map",
+                            [ST::raw("u32"), ST::raw("test::TestType").mut_pointer()],
+                        ),
+                    )]),
+                ),
+            ),
+            SID::category_resolved(
+                (SV::Public, "test::Map"),
+                SISR::new((12, 4), STD::new()),
+                SIC::Extern,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_fail_on_generic_type_with_unknown_argument() {
+    assert_ast_produces_failure(
+        M::new()
+            .with_extern_types([("Map".into(), As::from_iter([A::size(12), A::align(4)]))])
+            .with_definitions([ID::new(
+                (V::Public, "TestType"),
+                TD::new([TS::field(
+                    (V::Public, "field_1"),
+                    T::generic("Map", [T::ident("u32"), T::ident("Unknown")]),
+                )]),
+            )]),
+        ErrorKind::UnresolvedType,
+        r#"type resolution will not terminate, failed on types: ["test::TestType"] (resolved types: ["test::Map"])"#,
+    );
+}
+
 #[test]
 fn can_resolve_embed_of_an_extern() {
     assert_ast_produces_type_definitions(
@@ -325,6 +781,7 @@ fn can_generate_vftable() {
                                     (SV::Public, "test_function0"),
                                     SFB::vftable("test_function0"),
                                 )
+                                .with_calling_convention(member_cc())
                                 .with_arguments([
                                     SAr::MutSelf,
                                     SAr::field("arg0", ST::raw("u32")),
@@ -335,6 +792,7 @@ fn can_generate_vftable() {
                                     (SV::Public, "test_function1"),
                                     SFB::vftable("test_function1"),
                                 )
+                                .with_calling_convention(member_cc())
                                 .with_arguments([
                                     SAr::MutSelf,
                                     SAr::field("arg0", ST::raw("u32")),
@@ -357,7 +815,7 @@ fn can_generate_vftable() {
                         SR::field(
                             (SV::Public, "test_function0"),
                             ST::function(
-                                SCC::Thiscall,
+                                member_cc(),
                                 [
                                     ("this", ST::raw("test::TestType").mut_pointer()),
                                     ("arg0", ST::raw("u32")),
@@ -369,7 +827,7 @@ fn can_generate_vftable() {
                         SR::field(
                             (SV::Public, "test_function1"),
                             ST::function(
-                                SCC::Thiscall,
+                                member_cc(),
                                 [
                                     ("this", ST::raw("test::TestType").mut_pointer()),
                                     ("arg0", ST::raw("u32")),
@@ -432,6 +890,7 @@ fn can_generate_vftable_with_indices() {
                                     (SV::Public, "test_function0"),
                                     SFB::vftable("test_function0"),
                                 )
+                                .with_calling_convention(member_cc())
                                 .with_arguments([
                                     SAr::MutSelf,
                                     SAr::field("arg0", ST::raw("u32")),
@@ -444,6 +903,7 @@ fn can_generate_vftable_with_indices() {
                                     (SV::Public, "test_function1"),
                                     SFB::vftable("test_function1"),
                                 )
+                                .with_calling_convention(member_cc())
                                 .with_arguments([
                                     SAr::MutSelf,
                                     SAr::field("arg0", ST::raw("u32")),
@@ -468,7 +928,7 @@ fn can_generate_vftable_with_indices() {
                         SR::field(
                             (SV::Public, "test_function0"),
                             ST::function(
-                                SCC::Thiscall,
+                                member_cc(),
                                 [
                                     ("this", ST::raw("test::TestType").mut_pointer()),
                                     ("arg0", ST::raw("u32")),
@@ -482,7 +942,7 @@ fn can_generate_vftable_with_indices() {
                         SR::field(
                             (SV::Public, "test_function1"),
                             ST::function(
-                                SCC::Thiscall,
+                                member_cc(),
                                 [
                                     ("this", ST::raw("test::TestType").mut_pointer()),
                                     ("arg0", ST::raw("u32")),
@@ -589,14 +1049,16 @@ fn will_propagate_calling_convention_for_impl_and_vftable() {
 
 fn make_vfunc(index: usize) -> SF {
     let name = format!("_vfunc_{index}");
-    SF::new((SV::Private, name.clone()), SFB::vftable(name)).with_arguments([SAr::MutSelf])
+    SF::new((SV::Private, name.clone()), SFB::vftable(name))
+        .with_calling_convention(member_cc())
+        .with_arguments([SAr::MutSelf])
 }
 
 fn make_vfunc_region(index: usize) -> SR {
     SR::field(
         (SV::Private, format!("_vfunc_{}", index)),
         ST::function(
-            SCC::Thiscall,
+            member_cc(),
             [("this", ST::raw("test::TestType").mut_pointer())],
             None,
         ),
@@ -612,7 +1074,7 @@ fn can_define_extern_value() {
         [A::address(0x1337)],
     )]);
 
-    let mut semantic_state = SemanticState::new(4);
+    let mut semantic_state = SemanticState::new(target_for_pointer_size(4));
     semantic_state
         .add_module(&module1, &IP::from("module1"))
         .unwrap();
@@ -632,7 +1094,7 @@ fn can_define_extern_value() {
             visibility: SV::Public,
             name: "test".into(),
             type_: ST::raw("u32").mut_pointer(),
-            address: 0x1337
+            address: SA::Fixed(0x1337)
         }
     );
 }
@@ -643,7 +1105,7 @@ fn can_resolve_enum() {
         M::new().with_definitions([ID::new(
             (V::Public, "TestType"),
             ED::new(
-                T::ident("u32"),
+                T::ident("i32"),
                 [
                     ES::field_with_expr("Item0", E::IntLiteral(-2)),
                     ES::field("Item1"),
@@ -658,7 +1120,7 @@ fn can_resolve_enum() {
             (SV::Public, "test::TestType"),
             SISR::new(
                 (4, 4),
-                SED::new(ST::raw("u32"))
+                SED::new(ST::raw("i32"))
                     .with_fields([
                         ("Item0", -2),
                         ("Item1", -1),
@@ -831,6 +1293,7 @@ fn will_reject_defaultable_on_pointer() {
             )])
             .with_attributes([A::defaultable()]),
         )]),
+        ErrorKind::NotDefaultable,
         "field `field_1` of type `test::TestType` is not a defaultable type (pointer or function?)",
     );
 }
@@ -849,6 +1312,7 @@ fn will_reject_defaultable_on_enum_field() {
                 ED::new(T::ident("u32"), [ES::field("Item1")], []),
             ),
         ]),
+        ErrorKind::NotDefaultable,
         "field `field_1` of type `test::TestType` is not a defaultable type",
     );
 }
@@ -865,6 +1329,7 @@ fn can_handle_defaultable_on_enum_with_default_field() {
             )
             .with_attributes([A::defaultable()]),
         )]),
+        ErrorKind::NotDefaultable,
         "enum `test::TestType` is marked as defaultable but has no default variant set",
     );
 
@@ -881,6 +1346,7 @@ fn can_handle_defaultable_on_enum_with_default_field() {
             )
             .with_attributes([]),
         )]),
+        ErrorKind::NotDefaultable,
         "enum `test::TestType` has a default variant set but is not marked as defaultable",
     );
 
@@ -924,6 +1390,7 @@ fn will_reject_defaultable_on_non_defaultable_type() {
             ),
             ID::new((V::Public, "TestNonDefaultable"), TD::new([])),
         ]),
+        ErrorKind::NotDefaultable,
         "field `field_1` of type `test::TestType` is not a defaultable type",
     );
 }
@@ -945,8 +1412,8 @@ fn will_reject_types_that_are_larger_than_their_specified_size() {
                 .with_attributes([A::size(0x100)]),
             ),
         ]),
+        ErrorKind::SizeMismatch,
         concat!(
-            "while processing `test::TestType`\n",
             "calculated size 512 for type `test::TestType` does not match target size 256; ",
             "is your target size correct?"
         ),
@@ -995,6 +1462,7 @@ fn can_propagate_doc_comments() {
                         .with_vftable(STV::new(
                             [
                                 SF::new((SV::Private, "test_vfunc"), SFB::vftable("test_vfunc"))
+                                    .with_calling_convention(member_cc())
                                     .with_arguments([SAr::ConstSelf])
                                     .with_doc(" My test vfunc!"),
                             ],
@@ -1005,6 +1473,7 @@ fn can_propagate_doc_comments() {
                             (SV::Private, "test_func"),
                             SFB::address(0x123),
                         )
+                        .with_calling_convention(member_cc())
                         .with_arguments([SAr::ConstSelf])
                         .with_doc(" My test func!")]),
                 ),
@@ -1016,7 +1485,7 @@ fn can_propagate_doc_comments() {
                     STD::new().with_regions([SR::field(
                         (SV::Private, "test_vfunc"),
                         ST::function(
-                            SCC::Thiscall,
+                            member_cc(),
                             [("this", ST::raw("test::TestType").const_pointer())],
                             None,
                         ),
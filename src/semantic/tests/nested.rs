@@ -0,0 +1,146 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_resolve_nested_items() {
+    let module = assert_ast_produces_type_definitions(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Entity"),
+            TD::new([
+                TS::nested(
+                    (V::Public, "pos"),
+                    TD::new([
+                        TS::field((V::Public, "x"), T::ident("f32")),
+                        TS::field((V::Public, "y"), T::ident("f32")),
+                        TS::field((V::Public, "z"), T::ident("f32")),
+                    ]),
+                )
+                .with_attributes([A::doc(" Where the entity is")]),
+                TS::nested(
+                    (V::Private, "extra_data"),
+                    UD::new([
+                        TS::field((V::Public, "id"), T::ident("u32")),
+                        TS::nested(
+                            (V::Public, "pair"),
+                            TD::new([
+                                TS::field((V::Public, "low"), T::ident("u16")),
+                                TS::field((V::Public, "high"), T::ident("u16")),
+                            ]),
+                        ),
+                    ]),
+                ),
+            ])
+            .with_attributes([A::align(4), A::copyable()]),
+        )]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Entity"),
+                SISR::new(
+                    (16, 4),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "pos"), ST::raw("test::EntityPos"))
+                                .with_doc(" Where the entity is"),
+                            SR::field(
+                                (SV::Private, "extra_data"),
+                                ST::raw("test::EntityExtraData"),
+                            ),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            // Nested types are only as aligned as their fields need them to be
+            SID::defined_resolved(
+                (SV::Public, "test::EntityPos"),
+                SISR::new(
+                    (12, 4),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "x"), ST::raw("f32")),
+                            SR::field((SV::Public, "y"), ST::raw("f32")),
+                            SR::field((SV::Public, "z"), ST::raw("f32")),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::EntityExtraData"),
+                SISR::new(
+                    (4, 4),
+                    SUD::new()
+                        .with_fields([
+                            SR::field((SV::Public, "id"), ST::raw("u32")),
+                            SR::field((SV::Public, "pair"), ST::raw("test::EntityExtraDataPair")),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::EntityExtraDataPair"),
+                SISR::new(
+                    (4, 2),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "low"), ST::raw("u16")),
+                            SR::field((SV::Public, "high"), ST::raw("u16")),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+        ],
+    );
+
+    let mut definition_paths: Vec<_> = module.definition_paths().iter().cloned().collect();
+    definition_paths.sort();
+    assert_eq!(
+        definition_paths,
+        [
+            SIP::from("test::Entity"),
+            SIP::from("test::EntityExtraData"),
+            SIP::from("test::EntityExtraDataPair"),
+            SIP::from("test::EntityPos"),
+        ]
+    );
+}
+
+#[test]
+fn will_fail_on_nested_item_with_existing_name() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Entity"),
+                TD::new([TS::nested(
+                    (V::Public, "pos"),
+                    TD::new([TS::field((V::Public, "x"), T::ident("f32"))]),
+                )]),
+            ),
+            ID::new(
+                (V::Public, "EntityPos"),
+                TD::new([TS::field((V::Public, "x"), T::ident("f32"))]),
+            ),
+        ]),
+        ErrorKind::DuplicateDefinition,
+        "the nested item `EntityPos` in module `test` has the same name as another item",
+    );
+}
+
+#[test]
+fn will_fail_on_nested_item_in_generic_type() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Wrapper"),
+            TD::new([TS::nested(
+                (V::Public, "inner"),
+                TD::new([TS::field((V::Public, "value"), T::ident("T"))]),
+            )])
+            .with_generics(["T"]),
+        )]),
+        ErrorKind::InvalidGeneric,
+        "generic type `test::Wrapper` cannot have a nested item",
+    );
+}
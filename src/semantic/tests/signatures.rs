@@ -0,0 +1,75 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_resolve_signatures() {
+    let pattern = || [Some(0x48), Some(0x8B), Some(0x05), None, None, None, None];
+    let module = M::new()
+        .with_constants([C::new(
+            (V::Public, "PLAYER_COUNT_OFFSET"),
+            T::ident("u32"),
+            3,
+        )])
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::signature(
+                SG::new(pattern())
+                    .with_offset(E::Ident("PLAYER_COUNT_OFFSET".into()))
+                    .with_rip_relative(true),
+            )],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "World"),
+            TD::new([TS::field((V::Public, "tick"), T::ident("u32"))])
+                .with_attributes([A::signature(SG::new(pattern()).with_offset(3))]),
+        )])
+        .with_impls([FB::new(
+            "World",
+            [F::new((V::Public, "update"), [Ar::MutSelf])
+                .with_attributes([A::signature(SG::new([Some(0x40), Some(0x53)]))])],
+        )]);
+
+    let created_module = assert_ast_produces_type_definitions(
+        module,
+        [SID::defined_resolved(
+            (SV::Public, "test::World"),
+            SISR::new(
+                (4, 4),
+                STD::new()
+                    .with_regions([SR::field((SV::Public, "tick"), ST::raw("u32"))])
+                    .with_singleton(SA::Signature(SSG::new(pattern()).with_offset(3)))
+                    .with_associated_functions([SF::new(
+                        (SV::Public, "update"),
+                        SFB::signature(SSG::new([Some(0x40), Some(0x53)])),
+                    )
+                    .with_calling_convention(member_cc())
+                    .with_arguments([SAr::MutSelf])]),
+            ),
+        )],
+    );
+    assert_eq!(
+        created_module.extern_values,
+        [SEV {
+            visibility: SV::Public,
+            name: "player_count".into(),
+            type_: ST::raw("u32"),
+            address: SA::Signature(SSG::new(pattern()).with_offset(3).with_rip_relative(true)),
+        }]
+    );
+}
+
+#[test]
+fn will_fail_on_signature_for_virtual_function() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new(
+            (V::Public, "World"),
+            TD::new([TS::vftable([F::new((V::Public, "update"), [Ar::MutSelf])
+                .with_attributes([A::signature(SG::new([Some(0x40)]))])])]),
+        )]),
+        ErrorKind::InvalidAttribute,
+        "signature attribute is not supported for virtual function `update`",
+    );
+}
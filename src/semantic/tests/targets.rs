@@ -0,0 +1,227 @@
+use super::*;
+
+use crate::semantic::{Config, Target};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn can_lay_out_primitives_and_c_types_per_target() {
+    let wrapper = |name: &str, type_: &str| {
+        ID::new(
+            (V::Public, name),
+            TD::new([TS::field((V::Public, "value"), T::ident(type_))]),
+        )
+    };
+    let module = M::new().with_definitions([
+        wrapper("Timestamp", "u64"),
+        wrapper("Count", "c_long"),
+        wrapper("Character", "wchar_t"),
+    ]);
+
+    let layouts = |preset| {
+        let state = build_state_with(
+            &module,
+            &IP::from("test"),
+            Target::from_preset(preset).unwrap(),
+            Config::new(),
+        )
+        .unwrap();
+        ["Timestamp", "Count", "Character"].map(|name| {
+            let resolved = state
+                .type_registry()
+                .get(&IP::from(format!("test::{name}").as_str()))
+                .and_then(|d| d.resolved())
+                .unwrap();
+            let type_ = resolved.inner.as_type().unwrap().regions[0]
+                .type_ref
+                .clone();
+            (resolved.size, resolved.alignment, type_)
+        })
+    };
+
+    assert_eq!(
+        layouts("x86-msvc"),
+        [
+            (8, 8, ST::raw("u64")),
+            (4, 4, ST::raw("i32")),
+            (2, 2, ST::raw("u16"))
+        ]
+    );
+    assert_eq!(
+        layouts("x64-msvc"),
+        [
+            (8, 8, ST::raw("u64")),
+            (4, 4, ST::raw("i32")),
+            (2, 2, ST::raw("u16"))
+        ]
+    );
+    // `u64` is only aligned to 4 bytes on i386 System V.
+    assert_eq!(
+        layouts("i686-linux"),
+        [
+            (8, 4, ST::raw("u64")),
+            (4, 4, ST::raw("i32")),
+            (4, 4, ST::raw("i32"))
+        ]
+    );
+    assert_eq!(
+        layouts("x86_64-linux"),
+        [
+            (8, 8, ST::raw("u64")),
+            (8, 8, ST::raw("i64")),
+            (4, 4, ST::raw("i32"))
+        ]
+    );
+}
+
+#[test]
+fn can_default_member_calling_convention_per_target() {
+    let module = M::new().with_impls([FB::new(
+        "Player",
+        [F::new((V::Public, "respawn"), [Ar::MutSelf]).with_attributes([A::address(0x100)])],
+    )]);
+    let module = module.with_definitions([ID::new(
+        (V::Public, "Player"),
+        TD::new([TS::field((V::Public, "health"), T::ident("u32"))]),
+    )]);
+
+    let calling_convention = |preset| {
+        let state = build_state_with(
+            &module,
+            &IP::from("test"),
+            Target::from_preset(preset).unwrap(),
+            Config::new(),
+        )
+        .unwrap();
+        let resolved = state
+            .type_registry()
+            .get(&IP::from("test::Player"))
+            .and_then(|d| d.resolved())
+            .unwrap();
+        resolved.inner.as_type().unwrap().associated_functions[0].calling_convention
+    };
+
+    assert_eq!(calling_convention("x86-msvc"), SCC::Thiscall);
+    assert_eq!(calling_convention("x64-msvc"), SCC::C);
+    assert_eq!(calling_convention("i686-linux"), SCC::C);
+    assert_eq!(calling_convention("x86_64-linux"), SCC::C);
+}
+
+#[test]
+fn will_fail_on_unsupported_calling_convention() {
+    let player = || {
+        ID::new(
+            (V::Public, "Player"),
+            TD::new([TS::field((V::Public, "health"), T::ident("u32"))]),
+        )
+    };
+    let function_pointer = M::new().with_definitions([
+        player(),
+        ID::new(
+            (V::Public, "Callbacks"),
+            TD::new([TS::field(
+                (V::Public, "on_death"),
+                T::Function(
+                    FT::new([(Some("this"), T::ident("Player").mut_pointer())], None)
+                        .with_calling_convention("thiscall"),
+                ),
+            )]),
+        ),
+    ]);
+    let function = M::new().with_definitions([player()]).with_impls([FB::new(
+        "Player",
+        [F::new((V::Public, "respawn"), [Ar::MutSelf])
+            .with_attributes([A::address(0x100), A::calling_convention("thiscall")])],
+    )]);
+
+    let messages = |module| {
+        build_state_with(
+            module,
+            &IP::from("test"),
+            Target::from_preset("x64-msvc").unwrap(),
+            Config::new(),
+        )
+        .unwrap_err()
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind, d.message.clone()))
+        .collect::<Vec<_>>()
+    };
+
+    assert!(build_state_with(
+        &function_pointer,
+        &IP::from("test"),
+        Target::from_preset("x86-msvc").unwrap(),
+        Config::new()
+    )
+    .is_ok());
+    assert_eq!(
+        messages(&function_pointer),
+        [(
+            ErrorKind::UnresolvedType,
+            r#"calling convention "thiscall" in `extern "thiscall" fn(this: *mut Player)` is not supported by the target `x64-msvc`"#.to_string()
+        )]
+    );
+
+    assert!(build_state_with(
+        &function,
+        &IP::from("test"),
+        Target::from_preset("x86-msvc").unwrap(),
+        Config::new()
+    )
+    .is_ok());
+    assert_eq!(
+        messages(&function),
+        [(
+            ErrorKind::InvalidAttribute,
+            "calling convention for function `respawn` is not supported by the target `x64-msvc`: thiscall".to_string()
+        )]
+    );
+}
+
+#[test]
+fn can_test_target_in_cfg() {
+    let module = M::new().with_definitions([ID::new(
+        (V::Public, "Player"),
+        TD::new([
+            TS::field((V::Public, "name"), T::ident("u8").const_pointer()),
+            TS::field((V::Public, "msvc_only"), T::ident("u32"))
+                .with_attributes([A::cfg(CP::assign("abi", "msvc"))]),
+            TS::field((V::Public, "sysv_only"), T::ident("u32"))
+                .with_attributes([A::cfg(CP::assign("abi", "sysv"))]),
+            TS::field((V::Public, "little_endian_only"), T::ident("u32"))
+                .with_attributes([A::cfg(CP::assign("endian", "little"))]),
+        ]),
+    )]);
+
+    let fields = |preset| {
+        let state = build_state_with(
+            &module,
+            &IP::from("test"),
+            Target::from_preset(preset).unwrap(),
+            Config::new(),
+        )
+        .unwrap();
+        let resolved = state
+            .type_registry()
+            .get(&IP::from("test::Player"))
+            .and_then(|d| d.resolved())
+            .unwrap();
+        resolved
+            .inner
+            .as_type()
+            .unwrap()
+            .regions
+            .iter()
+            .filter_map(|r| r.name.clone().filter(|_| r.visibility == SV::Public))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        fields("x64-msvc"),
+        ["name", "msvc_only", "little_endian_only"]
+    );
+    assert_eq!(
+        fields("x86_64-linux"),
+        ["name", "sysv_only", "little_endian_only"]
+    );
+}
@@ -0,0 +1,186 @@
+use super::*;
+
+#[test]
+fn can_resolve_union() {
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Value"),
+                UD::new([
+                    TS::field((V::Public, "int"), T::ident("u32")),
+                    TS::field((V::Public, "bytes"), T::ident("u8").array(6))
+                        .with_attributes([A::doc(" The raw bytes")]),
+                ])
+                .with_attributes([A::copyable()]),
+            ),
+            ID::new(
+                (V::Public, "Padded"),
+                UD::new([
+                    TS::field((V::Public, "value"), T::ident("Value")),
+                    TS::field((V::Public, "wide"), T::ident("u64")),
+                ])
+                .with_attributes([A::size(24)]),
+            ),
+        ]),
+        [
+            // The size is rounded up to the alignment of the most aligned field
+            SID::defined_resolved(
+                (SV::Public, "test::Value"),
+                SISR::new(
+                    (8, 4),
+                    SUD::new()
+                        .with_fields([
+                            SR::field((SV::Public, "int"), ST::raw("u32")),
+                            SR::field((SV::Public, "bytes"), ST::raw("u8").array(6))
+                                .with_doc(" The raw bytes"),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::Padded"),
+                SISR::new(
+                    (24, 8),
+                    SUD::new().with_fields([
+                        SR::field((SV::Public, "value"), ST::raw("test::Value")),
+                        SR::field((SV::Public, "wide"), ST::raw("u64")),
+                        SR::field((SV::Private, "_field_2"), unknown(24)),
+                    ]),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn can_resolve_inline_unions() {
+    assert_ast_produces_type_definitions(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Variant"),
+            TD::new([
+                TS::field((V::Public, "tag"), T::ident("u32")),
+                TS::union(
+                    V::Public,
+                    [
+                        TS::field((V::Public, "int"), T::ident("i64")),
+                        TS::field((V::Public, "float"), T::ident("f64")),
+                    ],
+                )
+                .with_attributes([A::address(8), A::doc(" The payload")]),
+                TS::union(
+                    V::Private,
+                    [TS::field((V::Public, "flags"), T::ident("u32"))],
+                ),
+                TS::field((V::Private, "_"), T::unknown(4)),
+            ])
+            .with_attributes([A::align(8), A::copyable()]),
+        )]),
+        [
+            SID::defined_resolved(
+                (SV::Public, "test::Variant"),
+                SISR::new(
+                    (0x18, 8),
+                    STD::new()
+                        .with_regions([
+                            SR::field((SV::Public, "tag"), ST::raw("u32")),
+                            SR::field((SV::Private, "_field_4"), unknown(4)),
+                            SR::field((SV::Public, "union_0"), ST::raw("test::VariantUnion0"))
+                                .with_doc(" The payload"),
+                            SR::field((SV::Private, "union_1"), ST::raw("test::VariantUnion1")),
+                            SR::field((SV::Private, "_field_14"), unknown(4)),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            // Inline unions can be copied if the type they're in can be
+            SID::defined_resolved(
+                (SV::Public, "test::VariantUnion0"),
+                SISR::new(
+                    (8, 8),
+                    SUD::new()
+                        .with_fields([
+                            SR::field((SV::Public, "int"), ST::raw("i64")),
+                            SR::field((SV::Public, "float"), ST::raw("f64")),
+                        ])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+            SID::defined_resolved(
+                (SV::Public, "test::VariantUnion1"),
+                SISR::new(
+                    (4, 4),
+                    SUD::new()
+                        .with_fields([SR::field((SV::Public, "flags"), ST::raw("u32"))])
+                        .with_copyable(true)
+                        .with_cloneable(true),
+                ),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_unions() {
+    assert_ast_produces_failures(
+        M::new().with_definitions([
+            ID::new(
+                (V::Public, "Cloneable"),
+                UD::new([TS::field((V::Public, "a"), T::ident("u32"))])
+                    .with_attributes([A::cloneable()]),
+            ),
+            ID::new(
+                (V::Public, "Placed"),
+                UD::new([
+                    TS::field((V::Public, "a"), T::ident("u32")).with_attributes([A::address(4)])
+                ]),
+            ),
+            ID::new(
+                (V::Public, "Underaligned"),
+                UD::new([TS::field((V::Public, "a"), T::ident("u64"))])
+                    .with_attributes([A::align(4)]),
+            ),
+            ID::new(
+                (V::Public, "Undersized"),
+                UD::new([TS::field((V::Public, "a"), T::ident("u32").array(3))])
+                    .with_attributes([A::size(8)]),
+            ),
+        ]),
+        [
+            (
+                ErrorKind::InvalidAttribute,
+                "union `test::Cloneable` cannot be `cloneable` without being `copyable`",
+            ),
+            (
+                ErrorKind::InvalidAttribute,
+                "field `a` of union `test::Placed` cannot have the `address` attribute, as every field of a union starts at its beginning",
+            ),
+            (
+                ErrorKind::Alignment,
+                "alignment 4 is less than minimum required alignment 8 for union `test::Underaligned`",
+            ),
+            (
+                ErrorKind::SizeMismatch,
+                "calculated size 12 for union `test::Undersized` is larger than target size 8; is your target size correct?",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn will_fail_on_inline_union_in_generic_type() {
+    assert_ast_produces_failure(
+        M::new().with_definitions([ID::new(
+            (V::Public, "Wrapper"),
+            TD::new([TS::union(
+                V::Public,
+                [TS::field((V::Public, "value"), T::ident("T"))],
+            )])
+            .with_generics(["T"]),
+        )]),
+        ErrorKind::InvalidGeneric,
+        "generic type `test::Wrapper` cannot have an inline union",
+    );
+}
@@ -1,9 +1,10 @@
 use crate::{
+    error::{ErrorKind, Result},
     grammar::test_aliases::*,
     semantic::{
         semantic_state::{ResolvedSemanticState, SemanticState},
         types::test_aliases::*,
-        Module,
+        Config, Module, Target,
     },
 };
 
@@ -22,8 +23,32 @@ pub fn pointer_size() -> usize {
     })
 }
 
-pub fn build_state(module: &M, module_path: &IP) -> anyhow::Result<ResolvedSemanticState> {
-    let mut semantic_state = SemanticState::new(pointer_size());
+/// The MSVC target with the given pointer size
+pub fn target_for_pointer_size(pointer_size: usize) -> Target {
+    let preset = match pointer_size {
+        4 => "x86-msvc",
+        8 => "x64-msvc",
+        _ => panic!("there is no target with a pointer size of {pointer_size}"),
+    };
+    Target::from_preset(preset).unwrap()
+}
+
+pub fn target() -> Target {
+    target_for_pointer_size(pointer_size())
+}
+
+pub fn build_state(module: &M, module_path: &IP) -> Result<ResolvedSemanticState> {
+    build_state_with(module, module_path, target(), Config::new())
+}
+
+/// Builds `module` for `target`, with `config` applied to it
+pub fn build_state_with(
+    module: &M,
+    module_path: &IP,
+    target: Target,
+    config: Config,
+) -> Result<ResolvedSemanticState> {
+    let mut semantic_state = SemanticState::new(target).with_config(config);
     semantic_state.add_module(module, module_path)?;
     semantic_state.build()
 }
@@ -52,16 +77,22 @@ pub fn assert_ast_produces_type_definitions(
 }
 
 #[track_caller]
-pub fn assert_ast_produces_failure(module: M, failure: &str) {
+pub fn assert_ast_produces_failure(module: M, kind: ErrorKind, failure: &str) {
+    assert_ast_produces_failures(module, [(kind, failure)]);
+}
+
+#[track_caller]
+pub fn assert_ast_produces_failures<'a>(
+    module: M,
+    failures: impl IntoIterator<Item = (ErrorKind, &'a str)>,
+) {
     let err = build_state(&module, &IP::from("test")).unwrap_err();
-    let mut msg = err.to_string();
-    let mut next_err = err.source();
-    while let Some(next) = next_err {
-        msg.push('\n');
-        msg.push_str(&next.to_string());
-        next_err = next.source();
-    }
-    assert_eq!(msg, failure);
+    let diagnostics: Vec<_> = err
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind, d.message.as_str()))
+        .collect();
+    assert_eq!(diagnostics, failures.into_iter().collect::<Vec<_>>());
 }
 
 pub fn unknown(size: usize) -> ST {
@@ -81,3 +112,8 @@ pub fn filter_out_empty_regions(regions: impl IntoIterator<Item = SR>) -> Vec<SR
         .filter(|r| !matches!(&r.type_ref, ST::Array(_, 0)))
         .collect()
 }
+
+/// The calling convention of functions that take `self` on the target being tested
+pub fn member_cc() -> SCC {
+    target().member_calling_convention()
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+use crate::semantic::Config;
+use pretty_assertions::assert_eq;
+
+fn versioned(v1_0: usize, v1_1: usize) -> SA {
+    SA::Versioned(vec![("v1_0".into(), v1_0), ("v1_1".into(), v1_1)])
+}
+
+#[test]
+fn can_resolve_versioned_addresses() {
+    // The addresses are given out of order, but are stored in the declared order.
+    let module = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_per_version([("v1_1", 0x2337), ("v1_0", 0x1337)])],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "World"),
+            TD::new([TS::field((V::Public, "tick"), T::ident("u32"))])
+                .with_attributes([A::singleton_per_version([("v1_0", 0x100), ("v1_1", 0x200)])]),
+        )])
+        .with_impls([FB::new(
+            "World",
+            [F::new((V::Public, "update"), [Ar::MutSelf])
+                .with_attributes([A::address_per_version([("v1_0", 0x1000), ("v1_1", 0x2000)])])],
+        )]);
+
+    let config = Config::new().with_versions(["v1_0", "v1_1"]);
+    let state = build_state_with(&module, &IP::from("test"), target(), config).unwrap();
+    let created_module = state.modules().get(&IP::from("test")).unwrap();
+    let created_type_definitions: Vec<_> = created_module
+        .definitions(state.type_registry())
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        created_type_definitions,
+        [SID::defined_resolved(
+            (SV::Public, "test::World"),
+            SISR::new(
+                (4, 4),
+                STD::new()
+                    .with_regions([SR::field((SV::Public, "tick"), ST::raw("u32"))])
+                    .with_singleton(versioned(0x100, 0x200))
+                    .with_associated_functions([SF::new(
+                        (SV::Public, "update"),
+                        SFB::address(versioned(0x1000, 0x2000)),
+                    )
+                    .with_calling_convention(member_cc())
+                    .with_arguments([SAr::MutSelf])]),
+            ),
+        )]
+    );
+    assert_eq!(
+        created_module.extern_values,
+        [SEV {
+            visibility: SV::Public,
+            name: "player_count".into(),
+            type_: ST::raw("u32"),
+            address: versioned(0x1337, 0x2337),
+        }]
+    );
+}
+
+#[test]
+fn will_fail_on_invalid_versioned_addresses() {
+    let module = M::new()
+        .with_extern_values([EV::new(
+            V::Public,
+            "player_count",
+            T::ident("u32"),
+            [A::address_per_version([("v1_0", 0x1337), ("v2_0", 0x2337)])],
+        )])
+        .with_definitions([ID::new(
+            (V::Public, "Player"),
+            TD::new([TS::field((V::Public, "health"), T::ident("u32"))
+                .with_attributes([A::address_per_version([("v1_0", 4), ("v1_1", 8)])])]),
+        )])
+        .with_impls([FB::new(
+            "Player",
+            [F::new((V::Public, "respawn"), [Ar::MutSelf])
+                .with_attributes([A::address_per_version([("v1_0", 0x40_1000)])])],
+        )]);
+
+    let config = Config::new().with_versions(["v1_0", "v1_1"]);
+    let err = build_state_with(&module, &IP::from("test"), target(), config).unwrap_err();
+    let messages: Vec<_> = err
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            (
+                ErrorKind::InvalidAttribute,
+                "extern value `test::player_count` has an address for `v2_0`, which is not a declared version"
+            ),
+            (
+                ErrorKind::MissingAttribute,
+                "extern value `test::player_count` has no address for the version `v1_1`"
+            ),
+            (
+                ErrorKind::InvalidAttribute,
+                "field `health` of type `test::Player` has an address for each version, but it can't change at runtime"
+            ),
+            (
+                ErrorKind::MissingAttribute,
+                "function `test::Player::respawn` has no address for the version `v1_1`"
+            ),
+        ]
+    );
+}
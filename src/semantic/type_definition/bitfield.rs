@@ -0,0 +1,96 @@
+use crate::semantic::types::{Type, Visibility};
+
+/// A field that occupies some of the bits of a storage region (e.g. `#[bits(5)] pub layer: u32`)
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Bitfield {
+    pub visibility: Visibility,
+    pub name: String,
+    pub doc: Option<String>,
+    pub type_ref: Type,
+    /// The offset of the bitfield from the least significant bit of its storage
+    pub offset: usize,
+    pub width: usize,
+}
+impl Bitfield {
+    pub fn new(
+        (visibility, name): (Visibility, impl Into<String>),
+        type_ref: Type,
+        offset: usize,
+        width: usize,
+    ) -> Self {
+        Bitfield {
+            visibility,
+            name: name.into(),
+            doc: None,
+            type_ref,
+            offset,
+            width,
+        }
+    }
+    pub fn with_doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+    /// Whether the bitfield's type is signed, and so should be sign-extended when read
+    pub fn is_signed(&self) -> bool {
+        primitive_name(&self.type_ref).is_some_and(|name| name.starts_with('i'))
+    }
+    /// Whether the bitfield's type is `bool`
+    pub fn is_bool(&self) -> bool {
+        primitive_name(&self.type_ref) == Some("bool")
+    }
+}
+
+/// Returns the number of bits in `type_` if it can be used for a bitfield
+pub(crate) fn type_bits(type_: &Type) -> Option<usize> {
+    Some(match primitive_name(type_)? {
+        "u8" | "i8" | "bool" => 8,
+        "u16" | "i16" => 16,
+        "u32" | "i32" => 32,
+        "u64" | "i64" => 64,
+        _ => return None,
+    })
+}
+
+fn primitive_name(type_: &Type) -> Option<&str> {
+    match type_ {
+        Type::Raw(path) if path.len() == 1 => path.last().map(|s| s.as_str()),
+        _ => None,
+    }
+}
+
+/// The unsigned integer type that stores bitfields of `bits` bits
+pub(crate) fn storage_type(bits: usize) -> Type {
+    Type::raw(format!("u{bits}").as_str())
+}
+
+/// Packs consecutive bitfields into storage units following the MSVC rules. A bitfield shares
+/// the storage unit of the bitfield before it if their types are the same size and it fits in
+/// the bits that remain; otherwise, it starts a new unit.
+#[derive(Default)]
+pub(crate) struct BitfieldPacker {
+    /// The size in bits of the current storage unit, and how many of its bits are used
+    unit: Option<(usize, usize)>,
+}
+impl BitfieldPacker {
+    /// Places a bitfield of `width` bits with a type of `bits` bits. Returns its offset within
+    /// its storage unit, and whether that unit is new.
+    pub(crate) fn pack(&mut self, bits: usize, width: usize) -> (usize, bool) {
+        match &mut self.unit {
+            Some((unit_bits, used)) if *unit_bits == bits && *used + width <= bits => {
+                let offset = *used;
+                *used += width;
+                (offset, false)
+            }
+            _ => {
+                self.unit = Some((bits, width));
+                (0, true)
+            }
+        }
+    }
+    /// Ends the current storage unit, so that the next bitfield starts a new one. This happens
+    /// after a field that isn't a bitfield, or a zero-width bitfield.
+    pub(crate) fn finish(&mut self) {
+        self.unit = None;
+    }
+}
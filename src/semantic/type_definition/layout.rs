@@ -0,0 +1,200 @@
+use crate::{
+    error::Result,
+    grammar::{self, ItemPath},
+    semantic::{
+        target::{AbiFamily, Target},
+        type_definition::{generated_doc, get_region_name_and_type_definition},
+        type_registry::TypeRegistry,
+        types::{
+            ItemCategory, ItemDefinition, ItemState, ItemStateResolved, Region, Type,
+            TypeDefinition, Visibility,
+        },
+    },
+};
+
+/// The C++ ABI whose layout rules a type opts into with `#[layout(...)]`, instead of being laid
+/// out as declared.
+///
+/// Under both, bases are laid out before the fields, empty bases take no space, every region is
+/// padded to its alignment, and the type is aligned to its most-aligned region and padded to
+/// that alignment.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Layout {
+    /// Bases with a vftable are laid out first, in declaration order, followed by the other bases
+    Msvc,
+    /// The primary base is laid out first, followed by the other bases in declaration order.
+    /// Types that inherit from a non-POD type can reuse its tail padding.
+    Itanium,
+}
+impl Layout {
+    pub fn from_expr(expr: &grammar::Expr) -> Option<Self> {
+        match expr {
+            grammar::Expr::Ident(ident) => match ident.as_str() {
+                "msvc" => Some(Layout::Msvc),
+                "itanium" => Some(Layout::Itanium),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// The rules that the C++ compilers for `target` follow
+    pub fn for_target(target: &Target) -> Self {
+        match target.abi() {
+            AbiFamily::Msvc => Layout::Msvc,
+            AbiFamily::SystemV => Layout::Itanium,
+        }
+    }
+}
+
+/// The path of the item that holds the type at `path` without its tail padding, which is what
+/// types that inherit from it under the Itanium rules embed
+pub fn unpadded_path(path: &ItemPath) -> Option<ItemPath> {
+    let name = path.last()?;
+    Some(
+        path.parent()?
+            .join(format!("{}Unpadded", name.as_str()).into()),
+    )
+}
+
+/// The type that a base of type `type_` is embedded as under the Itanium rules: the type without
+/// its tail padding if it has any that can be reused, or itself otherwise. If it was replaced,
+/// the alignment of the original type is returned alongside it, as the replacement is packed.
+///
+/// Returns `None` if the base hasn't been resolved yet.
+pub fn base_storage_type(
+    type_registry: &TypeRegistry,
+    type_: Type,
+) -> Option<(Type, Option<usize>)> {
+    let Type::Raw(path) = &type_ else {
+        return Some((type_, None));
+    };
+    let Some(item) = type_registry.get(path) else {
+        return Some((type_, None));
+    };
+    let resolved = item.resolved()?;
+    let has_tail_padding = resolved
+        .inner
+        .as_type()
+        .is_some_and(|td| td.data_size.is_some());
+    match unpadded_path(path).filter(|_| has_tail_padding) {
+        Some(path) => Some((Type::Raw(path), Some(resolved.alignment))),
+        None => Some((type_, None)),
+    }
+}
+
+/// Whether the type of the base `region` has a vftable, or `None` if it hasn't been resolved yet
+fn is_polymorphic(
+    type_registry: &TypeRegistry,
+    resolvee_path: &ItemPath,
+    region: &Region,
+) -> Result<Option<bool>> {
+    Ok(
+        get_region_name_and_type_definition(type_registry, resolvee_path, region)?
+            .map(|(_, definition)| definition.vftable.is_some()),
+    )
+}
+
+/// Reorders the regions of a type to follow the rules of `layout`: empty bases first, then the
+/// other bases in the order of the ABI, then the fields in declaration order.
+///
+/// Returns `None` if a base hasn't been resolved yet.
+#[allow(clippy::type_complexity)]
+pub fn order_regions(
+    type_registry: &TypeRegistry,
+    resolvee_path: &ItemPath,
+    layout: Layout,
+    regions: Vec<(Option<usize>, Region)>,
+) -> Result<Option<Vec<(Option<usize>, Region)>>> {
+    let primary_base = primary_base(type_registry, resolvee_path, regions.iter().map(|r| &r.1))?
+        .and_then(|r| r.name.clone());
+
+    let mut keyed = Vec::with_capacity(regions.len());
+    for (offset, region) in regions {
+        let key = if !region.is_base {
+            3
+        } else {
+            let (Some(size), Some(is_polymorphic)) = (
+                region.size(type_registry),
+                is_polymorphic(type_registry, resolvee_path, &region)?,
+            ) else {
+                return Ok(None);
+            };
+            let leads = match layout {
+                Layout::Msvc => is_polymorphic,
+                Layout::Itanium => primary_base.is_some() && region.name == primary_base,
+            };
+            if size == 0 {
+                0
+            } else if leads {
+                1
+            } else {
+                2
+            }
+        };
+        keyed.push((key, (offset, region)));
+    }
+
+    // The sort is stable, so each group stays in declaration order.
+    keyed.sort_by_key(|(key, _)| *key);
+    Ok(Some(keyed.into_iter().map(|(_, r)| r).collect()))
+}
+
+/// The primary base of a type: the first of its bases with a vftable, which it shares the
+/// vftable of
+pub fn primary_base<'a>(
+    type_registry: &TypeRegistry,
+    resolvee_path: &ItemPath,
+    regions: impl Iterator<Item = &'a Region>,
+) -> Result<Option<&'a Region>> {
+    for region in regions.filter(|r| r.is_base) {
+        if is_polymorphic(type_registry, resolvee_path, region)? == Some(true) {
+            return Ok(Some(region));
+        }
+    }
+    Ok(None)
+}
+
+/// Given a type laid out with the Itanium rules that has reusable tail padding, and the size and
+/// number of regions that come before it, create the type definition for the type without it.
+///
+/// It is packed so that the regions that follow it can be placed in the tail padding, and shares
+/// the type's functions and vftable, as they are reached through the same `this`. Its regions
+/// aren't bases, as references can't be taken to the fields of a packed type.
+pub fn build_unpadded_type(
+    resolvee_path: &ItemPath,
+    visibility: Visibility,
+    definition: &TypeDefinition,
+    (data_size, region_count): (usize, usize),
+) -> Option<ItemDefinition> {
+    let regions = definition.regions[..region_count]
+        .iter()
+        .cloned()
+        .map(|mut r| {
+            r.is_base = false;
+            r
+        })
+        .collect();
+
+    Some(ItemDefinition {
+        visibility,
+        path: unpadded_path(resolvee_path)?,
+        state: ItemState::Resolved(ItemStateResolved {
+            size: data_size,
+            alignment: 1,
+            inner: TypeDefinition {
+                regions,
+                doc: Some(generated_doc(format_args!(
+                    "`{}` without its tail padding, as embedded in the types that inherit from it",
+                    resolvee_path.last()?.as_str()
+                ))),
+                singleton: None,
+                packed: true,
+                data_size: None,
+                generic_instance: None,
+                ..definition.clone()
+            }
+            .into(),
+        }),
+        category: ItemCategory::Defined,
+    })
+}
@@ -1,18 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Result},
     grammar::{self, ItemPath},
     semantic::{
-        function,
+        expression, function,
+        target::AbiFamily,
         type_registry::TypeRegistry,
-        types::{Function, FunctionBody, ItemState, ItemStateResolved, Type, Visibility},
+        types::{Address, Function, FunctionBody, ItemState, ItemStateResolved, Type, Visibility},
         SemanticState,
     },
+    span::Span,
     util,
 };
 
+mod bitfield;
+mod layout;
+mod vbtable;
 mod vftable;
-use anyhow::Context;
+pub use bitfield::Bitfield;
+use layout::Layout;
+pub use vbtable::TypeVbtable;
 pub use vftable::TypeVftable;
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
@@ -22,6 +30,8 @@ pub struct Region {
     pub doc: Option<String>,
     pub type_ref: Type,
     pub is_base: bool,
+    /// The bitfields stored in this region, if it is the storage for a run of bitfields
+    pub bitfields: Vec<Bitfield>,
 }
 impl Region {
     pub fn field((visibility, name): (Visibility, impl Into<String>), type_ref: Type) -> Self {
@@ -31,6 +41,7 @@ impl Region {
             doc: None,
             type_ref,
             is_base: false,
+            bitfields: vec![],
         }
     }
     pub fn unnamed_field(type_ref: Type) -> Self {
@@ -40,6 +51,7 @@ impl Region {
             doc: None,
             type_ref,
             is_base: false,
+            bitfields: vec![],
         }
     }
     pub fn marked_as_base(mut self) -> Self {
@@ -50,22 +62,60 @@ impl Region {
         self.doc = Some(doc.into());
         self
     }
+    pub fn with_bitfields(mut self, bitfields: impl Into<Vec<Bitfield>>) -> Self {
+        self.bitfields = bitfields.into();
+        self
+    }
     pub fn size(&self, type_registry: &TypeRegistry) -> Option<usize> {
         self.type_ref.size(type_registry)
     }
 }
 
+/// Records that a type is an instantiation of a generic type definition
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct GenericInstance {
+    pub template: ItemPath,
+    pub arguments: Vec<Type>,
+    /// The regions of the instantiation with the generic parameters of the definition left in
+    /// place, or `None` if the definition is monomorphised.
+    pub generic_regions: Option<Vec<Region>>,
+}
+impl GenericInstance {
+    pub fn new(template: impl Into<ItemPath>, arguments: impl Into<Vec<Type>>) -> Self {
+        Self {
+            template: template.into(),
+            arguments: arguments.into(),
+            generic_regions: None,
+        }
+    }
+    pub fn with_generic_regions(mut self, generic_regions: impl Into<Vec<Region>>) -> Self {
+        self.generic_regions = Some(generic_regions.into());
+        self
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, Default, Hash)]
 pub struct TypeDefinition {
     pub regions: Vec<Region>,
     pub doc: Option<String>,
     pub associated_functions: Vec<Function>,
     pub vftable: Option<TypeVftable>,
-    pub singleton: Option<usize>,
+    /// The vftables this type declares for the subobjects of its bases with
+    /// `vftable(base) { ... }`
+    pub base_vftables: Vec<TypeVftable>,
+    pub vbtable: Option<TypeVbtable>,
+    pub singleton: Option<Address>,
     pub copyable: bool,
     pub cloneable: bool,
     pub defaultable: bool,
     pub packed: bool,
+    /// The size of the type without the tail padding that types inheriting from it reuse, if it
+    /// has any; only types laid out with the Itanium rules have one
+    pub data_size: Option<usize>,
+    pub generic_instance: Option<GenericInstance>,
+    /// The type that this is the non-virtual part of, if it is one. It only describes the layout
+    /// of that part; the functions, vftable and vbtable of the base are those of the complete type.
+    pub non_virtual_part_of: Option<ItemPath>,
 }
 impl TypeDefinition {
     pub fn new() -> Self {
@@ -90,8 +140,16 @@ impl TypeDefinition {
         self.vftable = Some(vftable);
         self
     }
-    pub fn with_singleton(mut self, singleton: usize) -> Self {
-        self.singleton = Some(singleton);
+    pub fn with_base_vftables(mut self, base_vftables: impl Into<Vec<TypeVftable>>) -> Self {
+        self.base_vftables = base_vftables.into();
+        self
+    }
+    pub fn with_vbtable(mut self, vbtable: TypeVbtable) -> Self {
+        self.vbtable = Some(vbtable);
+        self
+    }
+    pub fn with_singleton(mut self, singleton: impl Into<Address>) -> Self {
+        self.singleton = Some(singleton.into());
         self
     }
     pub fn with_copyable(mut self, copyable: bool) -> Self {
@@ -110,13 +168,25 @@ impl TypeDefinition {
         self.packed = packed;
         self
     }
+    pub fn with_data_size(mut self, data_size: usize) -> Self {
+        self.data_size = Some(data_size);
+        self
+    }
+    pub fn with_generic_instance(mut self, generic_instance: GenericInstance) -> Self {
+        self.generic_instance = Some(generic_instance);
+        self
+    }
+    pub fn with_non_virtual_part_of(mut self, complete_type: impl Into<ItemPath>) -> Self {
+        self.non_virtual_part_of = Some(complete_type.into());
+        self
+    }
     /// Returns the fields and types of everything in this type's hierarchy, starting from the top
     pub fn dfs_hierarchy(
         &self,
         type_registry: &TypeRegistry,
         type_path: &ItemPath,
         fields: &[&str],
-    ) -> anyhow::Result<Vec<(Vec<String>, Type)>> {
+    ) -> Result<Vec<(Vec<String>, Type)>> {
         let mut output = vec![];
         for region in &self.regions {
             if !region.is_base {
@@ -147,15 +217,58 @@ impl TypeDefinition {
     }
 }
 
+/// Whether instantiations of this generic type definition should each be generated as their
+/// own type, instead of sharing one generic type
+pub(crate) fn is_monomorphised(definition: &grammar::TypeDefinition) -> bool {
+    definition
+        .attributes
+        .iter()
+        .any(|a| matches!(a, grammar::Attribute::Ident(ident) if ident.as_str() == "monomorphise"))
+}
+
+/// The doc comment for a type that is generated alongside the one written, which starts with a
+/// space as the text of a `///` comment does
+fn generated_doc(text: std::fmt::Arguments) -> String {
+    format!(" {text}")
+}
+
+/// Builds the type at `resolvee_path`. If `instance` is provided, `definition` is a generic
+/// type definition, and the type is its instantiation with the given template path and arguments.
 pub fn build(
     semantic: &mut SemanticState,
     resolvee_path: &ItemPath,
     visibility: Visibility,
+    span: Span,
     definition: &grammar::TypeDefinition,
-) -> anyhow::Result<Option<ItemStateResolved>> {
-    let module = semantic
-        .get_module_for_path(resolvee_path)
-        .with_context(|| format!("failed to get module for path `{resolvee_path}`"))?;
+    instance: Option<(&ItemPath, &[Type])>,
+) -> Result<Option<ItemStateResolved>> {
+    let module = semantic.get_module_for_path(resolvee_path).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to get module for path `{resolvee_path}`"),
+        )
+    })?;
+    let mut diagnostics = Diagnostics::new();
+
+    // Map the generic parameters to the arguments of this instantiation. Unless we're
+    // monomorphising, we also resolve each field with the parameters left in place, so that
+    // the generic type can be generated from them.
+    let parameter_names = definition.generics.iter().map(|g| g.0.clone());
+    let parameters: HashMap<String, Type> = instance
+        .map(|(_, arguments)| {
+            parameter_names
+                .clone()
+                .zip(arguments.iter().cloned())
+                .collect()
+        })
+        .unwrap_or_default();
+    let generic_parameters: Option<HashMap<String, Type>> =
+        (instance.is_some() && !is_monomorphised(definition)).then(|| {
+            parameter_names
+                .map(|name| (name.clone(), Type::Parameter(name)))
+                .collect()
+        });
+    let mut generic_field_types: HashMap<String, Type> = HashMap::new();
 
     // Handle attributes
     let mut target_size: Option<usize> = None;
@@ -165,31 +278,78 @@ pub fn build(
     let mut defaultable = false;
     let mut packed = false;
     let mut align = None;
-    let doc = definition.attributes.doc(resolvee_path)?;
+    let mut layout = None;
+    let mut size_span = Span::none();
+    let mut align_span = Span::none();
+    let mut layout_span = Span::none();
+    let doc = diagnostics
+        .record(definition.attributes.doc(resolvee_path))
+        .flatten();
+    let scope = module.scope();
+    let item = format!("type `{resolvee_path}`");
     for attribute in &definition.attributes {
+        if let Some((ident, value)) = expression::evaluate_address_attribute(
+            &semantic.type_registry,
+            &scope,
+            attribute,
+            "singleton",
+            &item,
+        ) {
+            let value = value.map_err(|d| d.with_location(module.location(ident.span())));
+            match diagnostics.record(value) {
+                Some(Some(value)) => singleton = Some(value),
+                // This depends on a type that hasn't been resolved yet, so try again later.
+                Some(None) => return diagnostics.finish(None),
+                None => {}
+            }
+            continue;
+        }
+
         match attribute {
             grammar::Attribute::Function(ident, exprs) => {
-                match (ident.as_str(), exprs.as_slice()) {
-                    ("size", [grammar::Expr::IntLiteral(value)]) => {
-                        target_size = Some(
-                            (*value)
-                                .try_into()
-                                .with_context(|| format!("failed to convert `size` attribute into usize for type `{resolvee_path}`"))?,
+                let [expr] = exprs.as_slice() else {
+                    continue;
+                };
+                if ident.as_str() == "layout" {
+                    layout_span = ident.span();
+                    layout = Layout::from_expr(expr);
+                    if layout.is_none() {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                ErrorKind::InvalidAttribute,
+                                format!(
+                                    "`layout` attribute for type `{resolvee_path}` must be `msvc` or `itanium`, but was `{expr}`"
+                                ),
+                            )
+                            .with_location(module.location(ident.span())),
                         );
                     }
-                    ("singleton", [grammar::Expr::IntLiteral(value)]) => {
-                        singleton = Some((*value).try_into().with_context(|| {
-                            format!(
-                                "failed to convert `singleton` attribute into usize for type `{resolvee_path}`"
-                            )
-                        })?);
+                    continue;
+                }
+                let target = match ident.as_str() {
+                    "size" => {
+                        size_span = ident.span();
+                        &mut target_size
                     }
-                    ("align", [grammar::Expr::IntLiteral(value)]) => {
-                        align = Some((*value).try_into().with_context(|| {
-                            format!("failed to convert `align` attribute into usize for type `{resolvee_path}`")
-                        })?);
+                    "align" => {
+                        align_span = ident.span();
+                        &mut align
                     }
-                    _ => {}
+                    _ => continue,
+                };
+                let value = expression::evaluate_attribute(
+                    &semantic.type_registry,
+                    &scope,
+                    ident,
+                    expr,
+                    &item,
+                )
+                .map_err(|d| d.with_location(module.location(ident.span())));
+                match diagnostics.record(value) {
+                    Some(Some(value)) => *target = Some(value),
+                    // This depends on a type that hasn't been resolved yet, so try again later.
+                    Some(None) => return diagnostics.finish(None),
+                    None => {}
                 }
             }
             grammar::Attribute::Ident(ident) => match ident.as_str() {
@@ -202,36 +362,99 @@ pub fn build(
                 "packed" => packed = true,
                 _ => {}
             },
-            grammar::Attribute::Assign(_, _) => {}
+            grammar::Attribute::Assign(_, _)
+            | grammar::Attribute::Cfg(_, _)
+            | grammar::Attribute::Keyed(_, _)
+            | grammar::Attribute::Signature(_, _)
+            | grammar::Attribute::Image(_, _)
+            | grammar::Attribute::Chain(_, _) => {}
         }
     }
 
+    if packed && layout.is_some() {
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::InvalidAttribute,
+                format!(
+                    "cannot specify both `packed` and `layout` attributes for type `{resolvee_path}`"
+                ),
+            )
+            .with_location(module.location(layout_span)),
+        );
+    }
+    // Where the fields of a generic type fall depends on its arguments, so unless the definition
+    // says otherwise, each instantiation is padded as its target's C++ compiler would pad it.
+    if instance.is_some() && layout.is_none() && !packed {
+        layout = Some(Layout::for_target(semantic.type_registry.target()));
+    }
+
     // Handle fields
     let mut pending_regions: Vec<(Option<usize>, Region)> = vec![];
+    let mut virtual_bases: Vec<Region> = vec![];
+    let mut field_spans: HashMap<String, Span> = HashMap::new();
     let mut vftable_functions = None;
+    let mut base_vftable_functions: Vec<(grammar::Ident, Span, Vec<Function>)> = vec![];
+    // The alignment the type needs beyond that of its regions, as bases that reuse their tail
+    // padding are embedded packed
+    let mut min_alignment = align.unwrap_or(1);
+    let mut bitfield_packer = bitfield::BitfieldPacker::default();
     for (idx, statement) in definition.statements.iter().enumerate() {
-        let grammar::TypeStatement { field, attributes } = statement;
+        let grammar::TypeStatement {
+            field,
+            attributes,
+            span: statement_span,
+        } = statement;
 
         match field {
             grammar::TypeField::Field(visibility, ident, type_) => {
-                // Extract address attribute
+                // Extract address and bits attributes
                 let mut address: Option<usize> = None;
+                let mut bits: Option<usize> = None;
                 let mut is_base = false;
-                let doc: Option<String> = attributes.doc(resolvee_path)?;
+                let mut is_virtual = false;
+                let doc = diagnostics.record(attributes.doc(resolvee_path)).flatten();
                 for attribute in attributes {
                     match attribute {
                         grammar::Attribute::Ident(ident) if ident.as_str() == "base" => {
                             is_base = true
                         }
-                        grammar::Attribute::Function(ident, exprs) => {
-                            if let ("address", [grammar::Expr::IntLiteral(addr)]) =
-                                (ident.as_str(), &exprs[..])
-                            {
-                                address = Some(
-                                    (*addr)
-                                        .try_into()
-                                        .with_context(|| format!("failed to convert `address` attribute into usize for field `{ident}` of type `{resolvee_path}`"))?,
-                                );
+                        grammar::Attribute::Function(attr_ident, exprs)
+                            if attr_ident.as_str() == "base" =>
+                        {
+                            match exprs.as_slice() {
+                                [grammar::Expr::Ident(arg)] if arg.as_str() == "virtual" => {
+                                    is_base = true;
+                                    is_virtual = true;
+                                }
+                                _ => diagnostics.push(
+                                    Diagnostic::error(
+                                        ErrorKind::InvalidAttribute,
+                                        format!(
+                                            "`base` attribute for field `{ident}` of type `{resolvee_path}` only takes `virtual`"
+                                        ),
+                                    )
+                                    .with_location(module.location(attr_ident.span())),
+                                ),
+                            }
+                        }
+                        grammar::Attribute::Function(attr_ident, exprs) => {
+                            let (target, expr) = match (attr_ident.as_str(), &exprs[..]) {
+                                ("address", [expr]) => (&mut address, expr),
+                                ("bits", [expr]) => (&mut bits, expr),
+                                _ => continue,
+                            };
+                            let value = expression::evaluate_attribute(
+                                &semantic.type_registry,
+                                &scope,
+                                attr_ident,
+                                expr,
+                                &format!("field `{ident}` of type `{resolvee_path}`"),
+                            )
+                            .map_err(|d| d.with_location(module.location(attr_ident.span())));
+                            match diagnostics.record(value) {
+                                Some(Some(value)) => *target = Some(value),
+                                Some(None) => return diagnostics.finish(None),
+                                None => {}
                             }
                         }
                         _ => {}
@@ -239,32 +462,186 @@ pub fn build(
                 }
 
                 // Push field
-                let Some(type_) = semantic
-                    .type_registry
-                    .resolve_grammar_type(&module.scope(), type_)
-                else {
-                    return Ok(None);
+                if let Some(generic_type) = generic_parameters.as_ref().and_then(|parameters| {
+                    semantic
+                        .type_registry
+                        .resolve_grammar_type_with_parameters(&scope, parameters, type_)
+                }) {
+                    generic_field_types.insert(ident.0.clone(), generic_type);
+                }
+                let Some(type_) = semantic.type_registry.resolve_grammar_type_with_parameters(
+                    &scope,
+                    &parameters,
+                    type_,
+                ) else {
+                    // Report any errors we've already found, including invalid array lengths
+                    // and calling conventions; they won't go away by waiting.
+                    let checked =
+                        expression::check_type(&semantic.type_registry, &scope, &parameters, type_)
+                            .map_err(|d| d.with_location(module.location(type_.span())));
+                    diagnostics.record(checked);
+                    return diagnostics.finish(None);
+                };
+
+                // Bases with virtual bases of their own only embed their non-virtual part.
+                let type_ = if is_base && !is_virtual {
+                    let Some(type_) = vbtable::base_storage_type(&semantic.type_registry, type_)
+                    else {
+                        return diagnostics.finish(None);
+                    };
+                    type_
+                } else {
+                    type_
                 };
 
-                let ident = (ident.0 != "_").then(|| ident.0.clone());
-                pending_regions.push((
-                    address,
-                    Region {
+                // Under the Itanium rules, bases with tail padding only embed the part before it,
+                // so that it can be reused.
+                let type_ = if is_base && layout == Some(Layout::Itanium) {
+                    let Some((type_, alignment)) =
+                        layout::base_storage_type(&semantic.type_registry, type_)
+                    else {
+                        return diagnostics.finish(None);
+                    };
+                    if let Some(alignment) = alignment {
+                        min_alignment = util::lcm([min_alignment, alignment].into_iter());
+                    }
+                    type_
+                } else {
+                    type_
+                };
+
+                field_spans.insert(ident.0.clone(), ident.span().or(*statement_span));
+                let name = (ident.0 != "_").then(|| ident.0.clone());
+
+                if is_virtual {
+                    // Virtual bases are laid out after the rest of the type, so they can't be
+                    // given an address.
+                    let invalid_virtual_base = |message: String| {
+                        Diagnostic::error(ErrorKind::InvalidAttribute, message)
+                            .with_location(module.location(ident.span().or(*statement_span)))
+                    };
+                    if address.is_some() || bits.is_some() {
+                        diagnostics.push(invalid_virtual_base(format!(
+                            "virtual base `{ident}` of type `{resolvee_path}` cannot have an `address` or `bits` attribute, as it is laid out after the rest of the type"
+                        )));
+                    } else if name.is_none() {
+                        diagnostics.push(invalid_virtual_base(format!(
+                            "virtual base of type `{resolvee_path}` must be named, as it is found through an accessor"
+                        )));
+                    } else if layout == Some(Layout::Itanium) {
+                        diagnostics.push(invalid_virtual_base(format!(
+                            "virtual base `{ident}` of type `{resolvee_path}` is not supported by the `itanium` layout"
+                        )));
+                    } else if semantic.type_registry.target().abi() != AbiFamily::Msvc {
+                        // Only MSVC's vbptr and vbtable are implemented; the Itanium ABI
+                        // keeps the offsets of virtual bases in the vftable instead.
+                        diagnostics.push(
+                            invalid_virtual_base(format!(
+                                "virtual base `{ident}` of type `{resolvee_path}` is not supported by the target `{}`",
+                                semantic.type_registry.target().name()
+                            ))
+                            .with_note("virtual bases are only supported on MSVC targets"),
+                        );
+                    }
+                    virtual_bases.push(Region {
                         visibility: (*visibility).into(),
-                        name: ident,
+                        name,
                         doc,
                         type_ref: type_,
                         is_base,
-                    },
-                ));
+                        bitfields: vec![],
+                    });
+                    continue;
+                }
+
+                let Some(width) = bits else {
+                    bitfield_packer.finish();
+                    pending_regions.push((
+                        address,
+                        Region {
+                            visibility: (*visibility).into(),
+                            name,
+                            doc,
+                            type_ref: type_,
+                            is_base,
+                            bitfields: vec![],
+                        },
+                    ));
+                    continue;
+                };
+
+                // Pack the bitfield into the storage region of the bitfields before it, or
+                // start a new storage region.
+                let invalid_bitfield = |message: String| {
+                    Diagnostic::error(ErrorKind::InvalidAttribute, message)
+                        .with_location(module.location(ident.span().or(*statement_span)))
+                };
+                let Some(type_bits) = bitfield::type_bits(&type_) else {
+                    diagnostics.push(invalid_bitfield(format!(
+                        "bitfield `{ident}` of type `{resolvee_path}` must have an integer or bool type, but has type `{type_}`"
+                    )));
+                    continue;
+                };
+                if width > type_bits {
+                    diagnostics.push(invalid_bitfield(format!(
+                        "bitfield `{ident}` of type `{resolvee_path}` is {width} bits wide, but its type `{type_}` only has {type_bits} bits"
+                    )));
+                    continue;
+                }
+                if address.is_some() {
+                    bitfield_packer.finish();
+                }
+                if width == 0 {
+                    // A zero-width bitfield only ends the current storage unit.
+                    if name.is_some() {
+                        diagnostics.push(invalid_bitfield(format!(
+                            "bitfield `{ident}` of type `{resolvee_path}` has a width of zero, which is only allowed for unnamed bitfields"
+                        )));
+                    }
+                    bitfield_packer.finish();
+                    continue;
+                }
+                let (offset, is_new_unit) = bitfield_packer.pack(type_bits, width);
+                if is_new_unit {
+                    pending_regions.push((
+                        address,
+                        Region::unnamed_field(bitfield::storage_type(type_bits)),
+                    ));
+                }
+                if let (Some(name), Some((_, storage))) = (name, pending_regions.last_mut()) {
+                    storage.bitfields.push(Bitfield {
+                        visibility: (*visibility).into(),
+                        name,
+                        doc,
+                        type_ref: type_,
+                        offset,
+                        width,
+                    });
+                }
             }
-            grammar::TypeField::Vftable(functions) => {
+            grammar::TypeField::Vftable(functions)
+            | grammar::TypeField::BaseVftable(_, functions) => {
+                let base = match field {
+                    grammar::TypeField::BaseVftable(base, _) => Some(base),
+                    _ => None,
+                };
+                let item = match base {
+                    Some(base) => format!("vftable for base `{base}` of type `{resolvee_path}`"),
+                    None => format!("vftable of type `{resolvee_path}`"),
+                };
+
                 // the vftable field is a sentinel field used to ensure that the user has
                 // thought about the presence of vftables in their type. we do not actually
                 // count it as a region; the type will be generated with a vftable field later on
-                if idx != 0 {
-                    anyhow::bail!(
-                        "vftable field of type `{resolvee_path}` must be the first field"
+                if base.is_none() && idx != 0 {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::Vftable,
+                            format!(
+                                "vftable field of type `{resolvee_path}` must be the first field"
+                            ),
+                        )
+                        .with_location(module.location(*statement_span)),
                     );
                 }
 
@@ -274,41 +651,100 @@ pub fn build(
                     let grammar::Attribute::Function(ident, exprs) = attribute else {
                         continue;
                     };
-                    if let ("size", [grammar::Expr::IntLiteral(size_)]) =
-                        (ident.as_str(), exprs.as_slice())
-                    {
-                        size = Some(*size_ as usize);
+                    let ("size", [expr]) = (ident.as_str(), exprs.as_slice()) else {
+                        continue;
+                    };
+                    let value = expression::evaluate_attribute(
+                        &semantic.type_registry,
+                        &scope,
+                        ident,
+                        expr,
+                        &item,
+                    )
+                    .map_err(|d| d.with_location(module.location(ident.span())));
+                    match diagnostics.record(value) {
+                        Some(Some(value)) => size = Some(value),
+                        Some(None) => return diagnostics.finish(None),
+                        None => {}
                     }
                 }
 
-                vftable_functions = Some(
-                    vftable::convert_grammar_functions_to_semantic_functions(
-                        &semantic.type_registry,
-                        module,
-                        size,
-                        functions,
-                    )
-                    .with_context(|| {
-                        format!("while building vftable for type `{resolvee_path}`")
-                    })?,
-                );
+                let functions = vftable::convert_grammar_functions_to_semantic_functions(
+                    &semantic.type_registry,
+                    module,
+                    size,
+                    functions,
+                )
+                .map_err(|e| e.with_note(format!("while building {item}")));
+                let Some(functions) = diagnostics.record(functions) else {
+                    continue;
+                };
+                let Some(base) = base else {
+                    vftable_functions = Some((*statement_span, functions));
+                    continue;
+                };
+                if base_vftable_functions.iter().any(|(b, ..)| b == base) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::DuplicateDefinition,
+                            format!("{item} is defined more than once"),
+                        )
+                        .with_location(module.location(*statement_span)),
+                    );
+                    continue;
+                }
+                base_vftable_functions.push((base.clone(), *statement_span, functions));
+            }
+            grammar::TypeField::Union(..) | grammar::TypeField::Nested(..) => {
+                // Inline unions and nested items are replaced with fields when the module is
+                // added, except in generic types, which have already been reported as unable
+                // to have them.
             }
         }
     }
 
-    let Some((regions, vftable, size)) = resolve_regions(
+    // The layout can't be trusted if any of the attributes or fields were invalid.
+    if !diagnostics.is_empty() {
+        return Err(diagnostics.into());
+    }
+
+    let Some(ResolvedRegions {
+        regions,
+        vftable,
+        vbtable,
+        unpadded,
+        size,
+    }) = resolve_regions(
         semantic,
         resolvee_path,
         visibility,
-        target_size,
+        (target_size, size_span),
+        &field_spans,
         pending_regions,
+        &virtual_bases,
         vftable_functions,
+        layout.map(|layout| (layout, min_alignment)),
     )
-    .with_context(|| format!("while processing `{resolvee_path}`"))?
+    .map_err(|e| e.with_note(format!("while processing `{resolvee_path}`")))?
     else {
         return Ok(None);
     };
 
+    // Build the vftables declared for the subobjects of bases
+    let mut base_vftables = vec![];
+    for (base, span, functions) in base_vftable_functions {
+        let base_vftable = vftable::build_base(
+            semantic,
+            resolvee_path,
+            visibility,
+            (&regions, vftable.as_ref()),
+            base.as_str(),
+            span,
+            functions,
+        );
+        base_vftables.extend(diagnostics.record(base_vftable));
+    }
+
     // Reborrow the module after resolving regions
     let module = semantic.get_module_for_path(resolvee_path).unwrap();
 
@@ -318,7 +754,7 @@ pub fn build(
         .as_ref()
         .map(|v| v.functions.iter().map(|f| f.name.clone()).collect())
         .unwrap_or_default();
-    for (i, base_region) in regions.iter().filter(|r| r.is_base).enumerate() {
+    for base_region in regions.iter().filter(|r| r.is_base) {
         // Inject all base associated functions into the type
         let Some((base_name, base_type)) = get_region_name_and_type_definition(
             &semantic.type_registry,
@@ -329,113 +765,151 @@ pub fn build(
             continue;
         };
 
-        let mut add_functions = |functions: &[Function]| {
-            for function in functions.iter().filter(|f| f.is_public()) {
+        // Functions declared by this type keep their bodies; those of the base are called
+        // through it.
+        let mut add_functions = |functions: &[Function], is_declared: bool| {
+            for function in functions.iter().filter(|f| is_declared || f.is_public()) {
                 let mut function = function.clone();
                 let original_name = function.name.clone();
                 if associated_functions_used_names.contains(&original_name) {
                     function.name = format!("{}_{}", base_name, original_name);
                 }
-                function.body = FunctionBody::field(base_name.clone(), original_name);
+                if !is_declared {
+                    function.body = FunctionBody::field(base_name.clone(), original_name);
+                }
                 associated_functions_used_names.insert(function.name.clone());
                 associated_functions.push(function);
             }
         };
 
         // Push this base's associated functions into the type
-        add_functions(&base_type.associated_functions);
+        add_functions(&base_type.associated_functions, false);
 
-        if i > 0 {
-            // Inject all non-first-base vfuncs into the type
+        if let Some(base_vftable) = base_vftables
+            .iter()
+            .find(|v| v.base_field.as_ref() == Some(&base_name))
+        {
+            // Inject the vfuncs this type declared for the base instead of the base's own
+            add_functions(&base_vftable.functions, true);
+        } else if vftable.as_ref().and_then(|v| v.base_field.as_ref()) != Some(&base_name) {
+            // Inject the vfuncs of all bases whose vftable isn't shared into the type
             if let Some(vftable) = &base_type.vftable {
-                add_functions(&vftable.functions);
+                add_functions(&vftable.functions, false);
             }
         }
     }
     if let Some(type_impl) = module.impls.get(resolvee_path) {
         for function in &type_impl.functions {
             if associated_functions_used_names.contains(&function.name.0) {
-                anyhow::bail!(
-                    "function `{}` is already defined in type `{}` (or a base type)",
-                    function.name,
-                    resolvee_path
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::DuplicateDefinition,
+                        format!(
+                            "function `{}` is already defined in type `{}` (or a base type)",
+                            function.name, resolvee_path
+                        ),
+                    )
+                    .with_location(module.location(function.span())),
                 );
+                continue;
             }
 
-            let function =
-                function::build(&semantic.type_registry, &module.scope(), false, function)
-                    .with_context(|| {
-                        format!(
-                            "while building impl function `{}` for type `{resolvee_path}`",
-                            function.name
-                        )
-                    })?;
-            associated_functions_used_names.insert(function.name.clone());
-            associated_functions.push(function);
+            let result =
+                function::build(&semantic.type_registry, module, false, function).map_err(|e| {
+                    e.with_note(format!(
+                        "while building impl function `{}` for type `{resolvee_path}`",
+                        function.name
+                    ))
+                });
+            if let Some(function) = diagnostics.record(result) {
+                associated_functions_used_names.insert(function.name.clone());
+                associated_functions.push(function);
+            }
         }
     }
 
     // Iterate over all of the regions and ensure their types are defaultable if
     // we have our defaultable attribute set.
+    let field_location =
+        |name: &str| module.location(field_spans.get(name).copied().unwrap_or_default());
     if defaultable {
         for region in &regions {
-            let Region {
-                visibility: _,
-                name,
-                doc: _,
-                type_ref,
-                is_base: _,
-            } = region;
+            let Region { name, type_ref, .. } = region;
             let name = name.as_deref().unwrap_or("unnamed");
             fn get_defaultable_type_path(type_ref: &Type) -> Option<&ItemPath> {
                 match type_ref {
-                    Type::Raw(tp) => Some(tp),
+                    Type::Raw(tp) | Type::Generic(tp, _) => Some(tp),
                     Type::Array(t, _) => get_defaultable_type_path(t),
                     _ => None,
                 }
             }
+            // Nullable function pointers default to null.
+            fn is_nullable_function(type_ref: &Type) -> bool {
+                match type_ref {
+                    Type::Function(.., nullable) => *nullable,
+                    Type::Array(t, _) => is_nullable_function(t),
+                    _ => false,
+                }
+            }
+            if is_nullable_function(type_ref) {
+                continue;
+            }
             let Some(path) = get_defaultable_type_path(type_ref) else {
-                anyhow::bail!("field `{name}` of type `{resolvee_path}` is not a defaultable type (pointer or function?)");
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::NotDefaultable,
+                        format!(
+                            "field `{name}` of type `{resolvee_path}` is not a defaultable type (pointer or function?)"
+                        ),
+                    )
+                    .with_location(field_location(name)),
+                );
+                continue;
             };
 
-            let item = semantic
-                .type_registry
-                .get(path)
-                .with_context(|| {
+            let Some(item) = semantic.type_registry.get(path) else {
+                diagnostics.push(Diagnostic::error(
+                    ErrorKind::UnresolvedType,
                     format!(
                         "failed to get type `{path}` for field `{name}` of type `{resolvee_path}`"
-                    )
-                })?
-                .state
-                .clone();
+                    ),
+                ));
+                continue;
+            };
 
-            let ItemState::Resolved(ItemStateResolved { inner, .. }) = &item else {
+            let ItemState::Resolved(ItemStateResolved { inner, .. }) = &item.state else {
                 continue;
             };
 
             if !inner.defaultable() {
-                anyhow::bail!("field `{name}` of type `{resolvee_path}` is not a defaultable type");
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::NotDefaultable,
+                        format!(
+                            "field `{name}` of type `{resolvee_path}` is not a defaultable type"
+                        ),
+                    )
+                    .with_location(field_location(name)),
+                );
             }
         }
     }
 
     let alignment = if packed {
         if align.is_some() {
-            anyhow::bail!(
-                "cannot specify both `packed` and `align` attributes for type `{resolvee_path}`"
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::InvalidAttribute,
+                    format!(
+                        "cannot specify both `packed` and `align` attributes for type `{resolvee_path}`"
+                    ),
+                )
+                .with_location(module.location(align_span)),
             );
         }
 
         1
     } else {
-        // Determine the final requested alignment.
-        // The requested alignment, the alignment of a single-region type, or the pointer size.
-        let alignment = align
-            .or((regions.len() == 1)
-                .then(|| regions[0].type_ref.alignment(&semantic.type_registry))
-                .flatten())
-            .unwrap_or(semantic.type_registry.pointer_size());
-
         // Calculate the minimum required alignment.
         let required_alignment = util::lcm(
             regions
@@ -443,65 +917,172 @@ pub fn build(
                 .flat_map(|r| r.type_ref.alignment(&semantic.type_registry)),
         );
 
+        // Determine the final requested alignment.
+        // The requested alignment, the alignment of a single-region type, or the pointer size.
+        // Nested types are aligned like the anonymous structs they stand in for, and only
+        // require the alignment of their fields, as are types laid out with C++ rules.
+        let alignment = if layout.is_some() {
+            align.unwrap_or(util::lcm([required_alignment, min_alignment].into_iter()))
+        } else {
+            let default_alignment = if module.nested_definition_paths.contains(resolvee_path) {
+                required_alignment
+            } else {
+                semantic.type_registry.pointer_size()
+            };
+            align
+                .or((regions.len() == 1)
+                    .then(|| regions[0].type_ref.alignment(&semantic.type_registry))
+                    .flatten())
+                .unwrap_or(default_alignment)
+        };
+
         // Ensure that the alignment is at least the minimum required alignment.
         if required_alignment > alignment {
-            anyhow::bail!(
-                "alignment {alignment} is less than minimum required alignment {required_alignment} for type `{resolvee_path}`"
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::Alignment,
+                    format!(
+                        "alignment {alignment} is less than minimum required alignment {required_alignment} for type `{resolvee_path}`"
+                    ),
+                )
+                .with_location(module.location(align_span.or(span))),
             );
         }
 
         // Ensure that all fields are aligned.
+        let mut has_misaligned_field = false;
         {
             let mut last_address = 0;
             for region in &regions {
                 let name = &region.name.as_deref().unwrap_or("unnamed");
                 let alignment = region.type_ref.alignment(&semantic.type_registry).unwrap();
                 if last_address % alignment != 0 {
-                    anyhow::bail!(
-                        "field `{name}` of type `{resolvee_path}` is located at 0x{last_address:X}, which is not divisible by {alignment} (the alignment of the type of the field)"
+                    has_misaligned_field = true;
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ErrorKind::Alignment,
+                            format!(
+                                "field `{name}` of type `{resolvee_path}` is located at 0x{last_address:X}, which is not divisible by {alignment} (the alignment of the type of the field)"
+                            ),
+                        )
+                        .with_location(field_location(name)),
                     );
                 }
                 last_address += region.size(&semantic.type_registry).unwrap();
             }
         }
 
-        // Ensure that the size is a multiple of the alignment.
-        if size % alignment != 0 {
-            anyhow::bail!("the type `{resolvee_path}` has a size of {size}, which is not a multiple of its alignment {alignment}");
+        // Ensure that the size is a multiple of the alignment. A misaligned field throws the
+        // size off as well, so this would only repeat that error.
+        if !has_misaligned_field && size % alignment != 0 {
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::Alignment,
+                    format!(
+                        "the type `{resolvee_path}` has a size of {size}, which is not a multiple of its alignment {alignment}"
+                    ),
+                )
+                .with_location(module.location(size_span.or(span))),
+            );
         }
 
         alignment
     };
 
-    Ok(Some(ItemStateResolved {
+    let generic_instance = instance.map(|(template, arguments)| GenericInstance {
+        template: template.clone(),
+        arguments: arguments.to_vec(),
+        generic_regions: generic_parameters.is_some().then(|| {
+            regions
+                .iter()
+                .map(|r| {
+                    let mut region = r.clone();
+                    if let Some(type_) = r.name.as_ref().and_then(|n| generic_field_types.get(n)) {
+                        region.type_ref = type_.clone();
+                    }
+                    region
+                })
+                .collect()
+        }),
+    });
+
+    let (vbtable, non_virtual_regions) = vbtable.unzip();
+    // Generic types can't be inherited from, so they have no use for their tail padding.
+    let unpadded = unpadded.filter(|_| instance.is_none());
+    let type_definition = TypeDefinition {
+        regions,
+        doc,
+        associated_functions,
+        vftable,
+        base_vftables,
+        vbtable,
+        singleton,
+        copyable,
+        cloneable,
+        defaultable,
+        packed,
+        data_size: unpadded.map(|(data_size, _)| data_size),
+        generic_instance,
+        non_virtual_part_of: None,
+    };
+
+    // Types with reusable tail padding are embedded in the types that inherit from them without
+    // it, so that part needs its own item.
+    if let Some(unpadded) = unpadded.filter(|_| diagnostics.is_empty()) {
+        if let Some(item) =
+            layout::build_unpadded_type(resolvee_path, visibility, &type_definition, unpadded)
+        {
+            semantic.add_item(item)?;
+        }
+    }
+
+    // Types with virtual bases are embedded in the types that inherit from them without their
+    // virtual bases, so their non-virtual part needs its own item.
+    if let Some(region_count) = non_virtual_regions.filter(|_| diagnostics.is_empty()) {
+        if let Some(item) = vbtable::build_non_virtual_type(
+            &semantic.type_registry,
+            resolvee_path,
+            visibility,
+            &type_definition,
+            region_count,
+        ) {
+            semantic.add_item(item)?;
+        }
+    }
+
+    diagnostics.finish(Some(ItemStateResolved {
         size,
         alignment,
-        inner: TypeDefinition {
-            regions,
-            doc,
-            associated_functions,
-            vftable,
-            singleton,
-            copyable,
-            cloneable,
-            defaultable,
-            packed,
-        }
-        .into(),
+        inner: type_definition.into(),
     }))
 }
 
-#[allow(clippy::type_complexity)]
+/// The regions of a type once they've been laid out
+struct ResolvedRegions {
+    regions: Vec<Region>,
+    vftable: Option<TypeVftable>,
+    /// The vbtable, and the number of regions that make up the non-virtual part of the type
+    vbtable: Option<(TypeVbtable, usize)>,
+    /// The size of the type and the number of its regions before its reusable tail padding, if
+    /// it has any
+    unpadded: Option<(usize, usize)>,
+    size: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
 fn resolve_regions(
     semantic: &mut SemanticState,
     resolvee_path: &ItemPath,
     visibility: Visibility,
-    target_size: Option<usize>,
+    (target_size, size_span): (Option<usize>, Span),
+    field_spans: &HashMap<String, Span>,
     regions: Vec<(Option<usize>, Region)>,
-    vftable_functions: Option<Vec<Function>>,
-) -> anyhow::Result<Option<(Vec<Region>, Option<TypeVftable>, usize)>> {
+    virtual_bases: &[Region],
+    vftable_functions: Option<(Span, Vec<Function>)>,
+    layout: Option<(Layout, usize)>,
+) -> Result<Option<ResolvedRegions>> {
     // this resolution algorithm is very simple and doesn't handle overlapping regions
-    // or regions that are out of order
+    // or regions that are out of order; fields that share memory belong in a union
     #[derive(Default)]
     struct Regions {
         regions: Vec<Region>,
@@ -519,11 +1100,76 @@ fn resolve_regions(
             self.last_address += size;
             Some(())
         }
+        fn push_aligned(
+            &mut self,
+            type_registry: &TypeRegistry,
+            region: Region,
+            align: bool,
+        ) -> Option<()> {
+            if align {
+                self.pad_to(type_registry, region.type_ref.alignment(type_registry)?)?;
+            }
+            self.push(type_registry, region)
+        }
+        fn alignment(&self, type_registry: &TypeRegistry) -> usize {
+            util::lcm(
+                self.regions
+                    .iter()
+                    .flat_map(|r| r.type_ref.alignment(type_registry)),
+            )
+        }
+        fn pad_to_alignment(&mut self, type_registry: &TypeRegistry) -> Option<()> {
+            self.pad_to(type_registry, self.alignment(type_registry))
+        }
+        fn pad_to(&mut self, type_registry: &TypeRegistry, alignment: usize) -> Option<()> {
+            let padding = self.last_address.next_multiple_of(alignment) - self.last_address;
+            if padding == 0 {
+                return Some(());
+            }
+            self.push(
+                type_registry,
+                Region::unnamed_field(type_registry.padding_type(padding)),
+            )
+        }
     }
     let mut resolved = Regions::default();
 
-    // Create vftable
-    let first_base = regions.iter().map(|t| &t.1).find(|r| r.is_base);
+    // Reorder the bases to follow the rules of the requested C++ ABI
+    let regions = match layout {
+        Some((layout, _)) => {
+            let Some(regions) =
+                layout::order_regions(&semantic.type_registry, resolvee_path, layout, regions)?
+            else {
+                return Ok(None);
+            };
+            regions
+        }
+        None => regions,
+    };
+    // Types that aren't POD can have their tail padding reused under the Itanium rules.
+    let is_pod = vftable_functions.is_none() && !regions.iter().any(|(_, r)| r.is_base);
+
+    // Create vbtable
+    let non_virtual_bases: Vec<_> = regions.iter().map(|t| &t.1).filter(|r| r.is_base).collect();
+    let Some((vbtable, mut vbtable_region)) = vbtable::build(
+        &semantic.type_registry,
+        resolvee_path,
+        &non_virtual_bases,
+        virtual_bases,
+    )?
+    else {
+        return Ok(None);
+    };
+
+    // Create vftable, sharing the first base's, or the primary base's under C++ rules
+    let first_base = match layout {
+        Some(_) => layout::primary_base(
+            &semantic.type_registry,
+            resolvee_path,
+            regions.iter().map(|t| &t.1),
+        )?,
+        None => regions.iter().map(|t| &t.1).find(|r| r.is_base),
+    };
     let (vftable, vftable_region) = vftable::build(
         semantic,
         resolvee_path,
@@ -541,7 +1187,33 @@ fn resolve_regions(
     }
 
     // Insert each region, including padding if necessary
-    for (offset, region) in regions {
+    let module = semantic.get_module_for_path(resolvee_path).unwrap();
+    let locations: Vec<_> = regions
+        .iter()
+        .map(|(_, r)| {
+            let span = r
+                .name
+                .as_ref()
+                .and_then(|n| field_spans.get(n))
+                .copied()
+                .unwrap_or_default();
+            module.location(span)
+        })
+        .collect();
+    let target_size_location = module.location(size_span);
+    for ((offset, region), location) in regions.into_iter().zip(locations) {
+        // The vbptr follows the leading bases, as in MSVC.
+        if !region.is_base {
+            if let Some(vbtable_region) = vbtable_region.take() {
+                if resolved
+                    .push_aligned(&semantic.type_registry, vbtable_region, layout.is_some())
+                    .is_none()
+                {
+                    return Ok(None);
+                }
+            }
+        }
+
         if let Some(offset) = offset {
             let Some(size) = offset.checked_sub(resolved.last_address) else {
                 let existing_region = resolved
@@ -551,9 +1223,16 @@ fn resolve_regions(
                     .name
                     .as_deref()
                     .unwrap_or_default();
-                anyhow::bail!(
-                    "attempted to insert padding at 0x{offset:X}, but overlapped with existing region `{existing_region}` that ends at 0x{:X}", resolved.last_address
-                );
+                return Err(Diagnostic::error(
+                    ErrorKind::Overlap,
+                    format!(
+                        "attempted to insert padding at 0x{offset:X}, but overlapped with existing region `{existing_region}` that ends at 0x{:X}",
+                        resolved.last_address
+                    ),
+                )
+                .with_location(location)
+                .with_note("fields that share memory can be grouped with `union { ... }`")
+                .into());
             };
             let padding_region = Region::unnamed_field(semantic.type_registry.padding_type(size));
             if resolved
@@ -564,11 +1243,78 @@ fn resolve_regions(
             }
         }
 
-        if resolved.push(&semantic.type_registry, region).is_none() {
+        // Under C++ rules, each region is placed at the next offset that suits its alignment.
+        let align = offset.is_none() && layout.is_some();
+        if resolved
+            .push_aligned(&semantic.type_registry, region, align)
+            .is_none()
+        {
             return Ok(None);
         }
     }
 
+    // Lay out the virtual bases once, after the non-virtual part of the type
+    let vbtable = match vbtable {
+        Some(vbtable) => {
+            if let Some(vbtable_region) = vbtable_region {
+                if resolved
+                    .push_aligned(&semantic.type_registry, vbtable_region, layout.is_some())
+                    .is_none()
+                {
+                    return Ok(None);
+                }
+            }
+            if resolved.pad_to_alignment(&semantic.type_registry).is_none() {
+                return Ok(None);
+            }
+            let non_virtual_regions = resolved.regions.len();
+
+            for base in &vbtable.bases {
+                let Some(alignment) = base.type_ref.alignment(&semantic.type_registry) else {
+                    return Ok(None);
+                };
+                if resolved
+                    .pad_to(&semantic.type_registry, alignment)
+                    .is_none()
+                {
+                    return Ok(None);
+                }
+                let storage = Region::field(
+                    (Visibility::Private, base.name.clone().unwrap_or_default()),
+                    base.type_ref.clone(),
+                );
+                if resolved.push(&semantic.type_registry, storage).is_none() {
+                    return Ok(None);
+                }
+            }
+            if resolved.pad_to_alignment(&semantic.type_registry).is_none() {
+                return Ok(None);
+            }
+
+            Some((vbtable, non_virtual_regions))
+        }
+        None => None,
+    };
+
+    // Under C++ rules, the type is padded to its alignment; the Itanium rules let the types that
+    // inherit from a non-POD type reuse that padding.
+    let mut unpadded = None;
+    if let Some((layout, min_alignment)) = layout {
+        let data_size = resolved.last_address;
+        let region_count = resolved.regions.len();
+        let alignment =
+            util::lcm([resolved.alignment(&semantic.type_registry), min_alignment].into_iter());
+        if resolved
+            .pad_to(&semantic.type_registry, alignment)
+            .is_none()
+        {
+            return Ok(None);
+        }
+        if layout == Layout::Itanium && !is_pod && resolved.last_address > data_size {
+            unpadded = Some((data_size, region_count));
+        }
+    }
+
     // Pad out to target size
     if let Some(target_size) = target_size {
         if resolved.last_address < target_size {
@@ -593,21 +1339,11 @@ fn resolve_regions(
             return Ok(None);
         };
 
-        if let Region {
-            visibility: _,
-            name: None,
-            doc: _,
-            type_ref,
-            is_base: _,
-        } = region
-        {
-            *region = Region {
-                visibility: Visibility::Private,
-                name: Some(format!("_field_{size:x}")),
-                doc: None,
-                type_ref: type_ref.clone(),
-                is_base: false,
-            };
+        if region.name.is_none() {
+            region.visibility = Visibility::Private;
+            region.name = Some(format!("_field_{size:x}"));
+            region.doc = None;
+            region.is_base = false;
         }
 
         size += region_size;
@@ -616,13 +1352,24 @@ fn resolve_regions(
     // Check that the final size is equal to the target size
     if let Some(target_size) = target_size {
         if size != target_size {
-            anyhow::bail!(
-                "calculated size {size} for type `{resolvee_path}` does not match target size {target_size}; is your target size correct?"
-            );
+            return Err(Diagnostic::error(
+                ErrorKind::SizeMismatch,
+                format!(
+                    "calculated size {size} for type `{resolvee_path}` does not match target size {target_size}; is your target size correct?"
+                ),
+            )
+            .with_location(target_size_location)
+            .into());
         }
     }
 
-    Ok(Some((resolved.regions, vftable, size)))
+    Ok(Some(ResolvedRegions {
+        regions: resolved.regions,
+        vftable,
+        vbtable,
+        unpadded,
+        size,
+    }))
 }
 
 /// Given a region, attempt to get the region's name and its type definition if available
@@ -630,36 +1377,67 @@ fn get_region_name_and_type_definition<'a>(
     type_registry: &'a TypeRegistry,
     type_path: &ItemPath,
     region: &Region,
-) -> anyhow::Result<Option<(String, &'a TypeDefinition)>> {
+) -> Result<Option<(String, &'a TypeDefinition)>> {
     let region_name = region
         .name
         .clone()
         .expect("region had no name, this shouldn't be possible");
 
-    let Type::Raw(path) = &region.type_ref else {
-        anyhow::bail!(
-            "expected region field `{}` of type `{}` to be a raw type, but it was a {}",
-            region_name,
-            type_path,
-            region.type_ref.human_friendly_type()
-        );
+    let (path, region_type) = match &region.type_ref {
+        Type::Raw(path) => (path, type_registry.get(path)),
+        Type::Generic(path, arguments) => (path, type_registry.get_generic(path, arguments)),
+        _ => {
+            return Err(Diagnostic::error(
+                ErrorKind::Internal,
+                format!(
+                    "expected region field `{}` of type `{}` to be a raw type, but it was a {}",
+                    region_name,
+                    type_path,
+                    region.type_ref.human_friendly_type()
+                ),
+            )
+            .into());
+        }
     };
 
-    let region_type = type_registry
-        .get(path)
-        .with_context(|| format!("failed to get region type `{path}` for type `{type_path}`"))?;
+    let region_type = region_type.ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::UnresolvedType,
+            format!("failed to get region type `{path}` for type `{type_path}`"),
+        )
+    })?;
 
     let Some(region_type) = region_type.resolved() else {
         return Ok(None);
     };
 
     let Some(region_type) = region_type.inner.as_type() else {
-        anyhow::bail!(
-            "expected region field `{}` of type `{}` to be a type, but it was a {}",
-            region_name,
-            type_path,
-            region_type.inner.human_friendly_type()
-        );
+        return Err(Diagnostic::error(
+            ErrorKind::Internal,
+            format!(
+                "expected region field `{}` of type `{}` to be a type, but it was a {}",
+                region_name,
+                type_path,
+                region_type.inner.human_friendly_type()
+            ),
+        )
+        .into());
+    };
+
+    // The non-virtual part of a type only describes its layout, so the base is described by the
+    // complete type.
+    let region_type = match &region_type.non_virtual_part_of {
+        Some(complete_type) => {
+            let Some(complete_type) = type_registry
+                .get(complete_type)
+                .and_then(|t| t.resolved())
+                .and_then(|r| r.inner.as_type())
+            else {
+                return Ok(None);
+            };
+            complete_type
+        }
+        None => region_type,
     };
 
     Ok(Some((region_name, region_type)))
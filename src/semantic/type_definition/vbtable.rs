@@ -0,0 +1,204 @@
+use crate::{
+    error::{Diagnostic, ErrorKind, Result},
+    grammar::ItemPath,
+    semantic::{
+        type_definition::{generated_doc, get_region_name_and_type_definition},
+        type_registry::TypeRegistry,
+        types::{
+            ItemCategory, ItemDefinition, ItemState, ItemStateResolved, Region, Type,
+            TypeDefinition, Visibility,
+        },
+    },
+    util,
+};
+
+/// The virtual bases of a type, which are laid out once at the end of the complete type and
+/// found at runtime through the offsets in its vbtable
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct TypeVbtable {
+    /// The virtual bases in the order of their entries in the vbtable, each named after its
+    /// accessor and typed as it is stored
+    pub bases: Vec<Region>,
+    /// The base whose vbptr this type shares, if it doesn't have its own
+    pub base_field: Option<String>,
+}
+impl TypeVbtable {
+    pub fn new(bases: impl Into<Vec<Region>>, base_field: impl Into<Option<String>>) -> Self {
+        Self {
+            bases: bases.into(),
+            base_field: base_field.into(),
+        }
+    }
+}
+
+/// The path of the item that holds the non-virtual part of the type at `path`, which is what
+/// types that inherit from it non-virtually embed
+pub fn non_virtual_path(path: &ItemPath) -> Option<ItemPath> {
+    let name = path.last()?;
+    Some(
+        path.parent()?
+            .join(format!("{}NonVirtual", name.as_str()).into()),
+    )
+}
+
+/// The type that a base of type `type_` is embedded as: its non-virtual part if it has virtual
+/// bases, or itself otherwise. Returns `None` if the base hasn't been resolved yet.
+pub fn base_storage_type(type_registry: &TypeRegistry, type_: Type) -> Option<Type> {
+    let Type::Raw(path) = &type_ else {
+        return Some(type_);
+    };
+    let Some(item) = type_registry.get(path) else {
+        return Some(type_);
+    };
+    let has_vbtable = item
+        .resolved()?
+        .inner
+        .as_type()
+        .is_some_and(|td| td.vbtable.is_some());
+    if has_vbtable {
+        non_virtual_path(path).map(Type::Raw)
+    } else {
+        Some(type_)
+    }
+}
+
+/// Builds the vbtable for a type from its non-virtual bases and the virtual bases it declares.
+///
+/// The virtual bases of each non-virtual base come first, so that the vbtable of the first of
+/// them can be shared, followed by the declared virtual bases, each preceded by its own virtual
+/// bases. A virtual base that is inherited more than once is only stored once.
+///
+/// Returns the vbtable and the vbptr region to add to the type, if it needs its own, or `None`
+/// if a base hasn't been resolved yet.
+#[allow(clippy::type_complexity)]
+pub fn build(
+    type_registry: &TypeRegistry,
+    resolvee_path: &ItemPath,
+    non_virtual_bases: &[&Region],
+    virtual_bases: &[Region],
+) -> Result<Option<(Option<TypeVbtable>, Option<Region>)>> {
+    let mut base_field = None;
+    let mut bases: Vec<Region> = vec![];
+    let push = |bases: &mut Vec<Region>, base: Region| {
+        if bases.iter().any(|b| b.type_ref == base.type_ref) {
+            return Ok(());
+        }
+        if bases.iter().any(|b| b.name == base.name) {
+            return Err(Diagnostic::error(
+                ErrorKind::DuplicateDefinition,
+                format!(
+                    "type `{resolvee_path}` has more than one virtual base named `{}`",
+                    base.name.as_deref().unwrap_or_default()
+                ),
+            ));
+        }
+        bases.push(base);
+        Ok(())
+    };
+
+    for region in non_virtual_bases {
+        let Some((name, definition)) =
+            get_region_name_and_type_definition(type_registry, resolvee_path, region)?
+        else {
+            return Ok(None);
+        };
+        let Some(vbtable) = &definition.vbtable else {
+            continue;
+        };
+        base_field.get_or_insert(name);
+        for base in &vbtable.bases {
+            push(&mut bases, base.clone())?;
+        }
+    }
+
+    for region in virtual_bases {
+        let Some((name, definition)) =
+            get_region_name_and_type_definition(type_registry, resolvee_path, region)?
+        else {
+            return Ok(None);
+        };
+        let mut base = region.clone();
+        base.is_base = false;
+        if let Some(vbtable) = &definition.vbtable {
+            for base in &vbtable.bases {
+                push(&mut bases, base.clone())?;
+            }
+            let Type::Raw(path) = &region.type_ref else {
+                return Err(Diagnostic::error(
+                    ErrorKind::Internal,
+                    format!(
+                        "expected virtual base `{name}` of type `{resolvee_path}` to be a raw type"
+                    ),
+                )
+                .into());
+            };
+            base.type_ref = Type::Raw(non_virtual_path(path).unwrap_or_else(|| path.clone()));
+        }
+        push(&mut bases, base)?;
+    }
+
+    if bases.is_empty() {
+        return Ok(Some((None, None)));
+    }
+
+    // Only create a vbptr if there's no base to share one with.
+    let region = base_field.is_none().then(|| {
+        Region::field(
+            (Visibility::Private, "vbtable"),
+            Type::ConstPointer(Box::new(Type::Raw("i32".into()))),
+        )
+    });
+    Ok(Some((Some(TypeVbtable { bases, base_field }), region)))
+}
+
+/// Given a type with virtual bases and the number of regions in its non-virtual part, create
+/// the type definition for that part. It only describes the part's layout; the type's functions,
+/// vftable and vbtable are reached through the complete type.
+pub fn build_non_virtual_type(
+    type_registry: &TypeRegistry,
+    resolvee_path: &ItemPath,
+    visibility: Visibility,
+    definition: &TypeDefinition,
+    region_count: usize,
+) -> Option<ItemDefinition> {
+    let regions = definition.regions[..region_count].to_vec();
+    let size = regions
+        .iter()
+        .map(|r| r.size(type_registry))
+        .sum::<Option<usize>>()?;
+    let alignment = if definition.packed {
+        1
+    } else {
+        util::lcm(
+            regions
+                .iter()
+                .flat_map(|r| r.type_ref.alignment(type_registry)),
+        )
+    };
+
+    Some(ItemDefinition {
+        visibility,
+        path: non_virtual_path(resolvee_path)?,
+        state: ItemState::Resolved(ItemStateResolved {
+            size,
+            alignment,
+            inner: TypeDefinition {
+                regions,
+                doc: Some(generated_doc(format_args!(
+                    "The non-virtual part of `{}`, as embedded in the types that inherit from it",
+                    resolvee_path.last()?.as_str()
+                ))),
+                associated_functions: vec![],
+                vftable: None,
+                base_vftables: vec![],
+                vbtable: None,
+                singleton: None,
+                generic_instance: None,
+                non_virtual_part_of: Some(resolvee_path.clone()),
+                ..definition.clone()
+            }
+            .into(),
+        }),
+        category: ItemCategory::Defined,
+    })
+}
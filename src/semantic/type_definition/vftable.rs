@@ -1,9 +1,8 @@
-use anyhow::Context;
-
 use crate::{
+    error::{Diagnostic, ErrorKind, Result},
     grammar::{self, ItemPath},
     semantic::{
-        function,
+        expression, function,
         module::Module,
         type_definition::get_region_name_and_type_definition,
         type_registry::TypeRegistry,
@@ -13,6 +12,8 @@ use crate::{
         },
         SemanticState,
     },
+    span::Span,
+    util,
 };
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
@@ -41,8 +42,9 @@ pub fn convert_grammar_functions_to_semantic_functions(
     module: &Module,
     size: Option<usize>,
     functions: &[grammar::Function],
-) -> anyhow::Result<Vec<Function>> {
+) -> Result<Vec<Function>> {
     // Insert function, with padding if necessary
+    let calling_convention = type_registry.target().member_calling_convention();
     let mut output = vec![];
     for function in functions {
         let mut index = None;
@@ -50,28 +52,50 @@ pub fn convert_grammar_functions_to_semantic_functions(
             let grammar::Attribute::Function(ident, exprs) = attribute else {
                 continue;
             };
-            match (ident.as_str(), exprs.as_slice()) {
-                ("index", [grammar::Expr::IntLiteral(index_)]) => {
-                    index = Some(*index_ as usize);
+            let ("index", [expr]) = (ident.as_str(), exprs.as_slice()) else {
+                continue;
+            };
+            let item = format!("function `{}`", function.name);
+            let location = module.location(ident.span());
+            index = match expression::evaluate_attribute(
+                type_registry,
+                &module.scope(),
+                ident,
+                expr,
+                &item,
+            ) {
+                Ok(Some(index)) => Some(index),
+                Ok(None) => {
+                    return Err(expression::unresolved_attribute(ident, &item)
+                        .with_location(location)
+                        .into())
                 }
-                _ => continue,
-            }
+                Err(diagnostic) => return Err(diagnostic.with_location(location).into()),
+            };
         }
 
         if let Some(index) = index {
-            make_padding_functions(&mut output, index);
+            make_padding_functions(&mut output, index, calling_convention);
         }
-        let function = function::build(type_registry, &module.scope(), true, function)
-            .with_context(|| format!("while building vftable function `{}`", function.name))?;
+        let function = function::build(type_registry, module, true, function).map_err(|e| {
+            e.with_note(format!(
+                "while building vftable function `{}`",
+                function.name
+            ))
+        })?;
         output.push(function);
     }
 
     // Pad out to target size
     if let Some(size) = size {
-        make_padding_functions(&mut output, size);
+        make_padding_functions(&mut output, size, calling_convention);
     }
 
-    fn make_padding_functions(output: &mut Vec<Function>, target_len: usize) {
+    fn make_padding_functions(
+        output: &mut Vec<Function>,
+        target_len: usize,
+        calling_convention: CallingConvention,
+    ) {
         let functions_to_add = target_len.saturating_sub(output.len());
         for _ in 0..functions_to_add {
             let name = format!("_vfunc_{}", output.len());
@@ -84,7 +108,7 @@ pub fn convert_grammar_functions_to_semantic_functions(
                 body: FunctionBody::Vftable {
                     function_name: name,
                 },
-                calling_convention: CallingConvention::Thiscall,
+                calling_convention,
             });
         }
     }
@@ -97,13 +121,15 @@ pub fn build(
     resolvee_path: &ItemPath,
     visibility: Visibility,
     first_base: Option<&Region>,
-    vftable_functions: Option<Vec<Function>>,
-) -> anyhow::Result<(Option<TypeVftable>, Option<Region>)> {
-    if let Some(vftable_functions) = vftable_functions {
+    vftable_functions: Option<(Span, Vec<Function>)>,
+) -> Result<(Option<TypeVftable>, Option<Region>)> {
+    if let Some((vftable_span, vftable_functions)) = vftable_functions {
         // There are functions defined for this vftable.
         let vftable_item = build_type(
             &semantic.type_registry,
             resolvee_path,
+            "",
+            &Type::Raw(resolvee_path.clone()),
             visibility,
             &vftable_functions,
         );
@@ -124,12 +150,18 @@ pub fn build(
             // There is a base class with a vftable. Let's use its field.
 
             // Ensure that all of the base classes's vfuncs are included in the derived class's vftable
+            let location = semantic
+                .get_module_for_path(resolvee_path)
+                .and_then(|m| m.location(vftable_span));
             if vftable_functions.len() < base_vftable.functions.len() {
-                anyhow::bail!(
-                    "vftable for `{}` is missing functions from base class `{}`",
-                    resolvee_path,
-                    base_name
-                );
+                return Err(Diagnostic::error(
+                    ErrorKind::Vftable,
+                    format!(
+                        "vftable for `{resolvee_path}` is missing functions from base class `{base_name}`"
+                    ),
+                )
+                .with_location(location)
+                .into());
             }
             for (idx, (base_vfunc, derived_vfunc)) in base_vftable
                 .functions
@@ -138,14 +170,14 @@ pub fn build(
                 .enumerate()
             {
                 if base_vfunc != derived_vfunc {
-                    anyhow::bail!(
-                        "vftable for `{}` has function `{}` at index {} but base class `{}` has function `{}`",
-                        resolvee_path,
-                        derived_vfunc,
-                        idx,
-                        base_name,
-                        base_vfunc
-                    );
+                    return Err(Diagnostic::error(
+                        ErrorKind::Vftable,
+                        format!(
+                            "vftable for `{resolvee_path}` has function `{derived_vfunc}` at index {idx} but base class `{base_name}` has function `{base_vfunc}`"
+                        ),
+                    )
+                    .with_location(location)
+                    .into());
                 }
             }
 
@@ -165,6 +197,7 @@ pub fn build(
                 doc: None,
                 type_ref: vftable_pointer_type.clone(),
                 is_base: false,
+                bitfields: vec![],
             };
 
             Ok((
@@ -194,10 +227,110 @@ pub fn build(
     }
 }
 
-/// Given a list of functions, create the type definition for the vftable containing them
+/// Builds the vftable that a type declares for one of its bases with `vftable(base) { ... }`,
+/// which replaces the base's vftable in the base's subobject. It must start with the base's
+/// functions, but can change their signatures and docs, and its functions are called with
+/// `this` pointing to the subobject.
+pub fn build_base(
+    semantic: &mut SemanticState,
+    resolvee_path: &ItemPath,
+    visibility: Visibility,
+    (regions, vftable): (&[Region], Option<&TypeVftable>),
+    base: &str,
+    vftable_span: Span,
+    functions: Vec<Function>,
+) -> Result<TypeVftable> {
+    let location = semantic
+        .get_module_for_path(resolvee_path)
+        .and_then(|m| m.location(vftable_span));
+    let error = |message: String| Diagnostic::error(ErrorKind::Vftable, message);
+
+    let Some(region) = regions
+        .iter()
+        .find(|r| r.is_base && r.name.as_deref() == Some(base))
+    else {
+        return Err(error(format!(
+            "vftable for base `{base}` of type `{resolvee_path}` does not name a non-virtual base of the type"
+        ))
+        .with_location(location)
+        .into());
+    };
+    if vftable.and_then(|v| v.base_field.as_deref()) == Some(base) {
+        return Err(error(format!(
+            "vftable for base `{base}` of type `{resolvee_path}` is shared with the type, so its functions belong in the type's own vftable"
+        ))
+        .with_location(location)
+        .into());
+    }
+    let Some((_, base_vftable)) =
+        get_region_name_and_vftable(&semantic.type_registry, resolvee_path, region)?
+    else {
+        return Err(error(format!(
+            "vftable for base `{base}` of type `{resolvee_path}` overrides a base without a vftable"
+        ))
+        .with_location(location)
+        .into());
+    };
+
+    // Ensure that the base's vfuncs are all overridden, in the same order
+    if functions.len() < base_vftable.functions.len() {
+        return Err(error(format!(
+            "vftable for base `{base}` of type `{resolvee_path}` is missing functions from the base's vftable"
+        ))
+        .with_location(location)
+        .into());
+    }
+    for (idx, (base_vfunc, derived_vfunc)) in
+        base_vftable.functions.iter().zip(&functions).enumerate()
+    {
+        if base_vfunc.name != derived_vfunc.name {
+            return Err(error(format!(
+                "vftable for base `{base}` of type `{resolvee_path}` has function `{}` at index {idx} but the base's vftable has function `{}`",
+                derived_vfunc.name, base_vfunc.name
+            ))
+            .with_location(location)
+            .into());
+        }
+    }
+
+    let functions: Vec<_> = functions
+        .into_iter()
+        .map(|mut f| {
+            f.body = FunctionBody::base_vftable(base, f.name.clone());
+            f
+        })
+        .collect();
+    let Some(vftable_type) = build_type(
+        &semantic.type_registry,
+        resolvee_path,
+        &util::to_pascal_case(base),
+        &region.type_ref,
+        visibility,
+        &functions,
+    ) else {
+        return Err(Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to build vftable for base `{base}` of type `{resolvee_path}`"),
+        )
+        .into());
+    };
+    let vftable_path = vftable_type.path.clone();
+    semantic.add_item(vftable_type)?;
+
+    Ok(TypeVftable {
+        functions,
+        base_field: Some(base.to_string()),
+        type_: Type::ConstPointer(Box::new(Type::Raw(vftable_path))),
+    })
+}
+
+/// Given a list of functions, create the type definition for the vftable containing them, named
+/// after the type and `infix` (e.g. `DerivedBaseBVftable`), with `this` pointing to `this_type`
 fn build_type(
     type_registry: &TypeRegistry,
     resolvee_path: &ItemPath,
+    infix: &str,
+    this_type: &Type,
     visibility: Visibility,
     functions: &[Function],
 ) -> Option<ItemDefinition> {
@@ -205,11 +338,11 @@ fn build_type(
 
     let resolvee_vtable_path = resolvee_path
         .parent()?
-        .join(format!("{}Vftable", name.as_str()).into());
+        .join(format!("{}{infix}Vftable", name.as_str()).into());
 
     let regions: Vec<_> = functions
         .iter()
-        .map(|f| function_to_region(resolvee_path, f))
+        .map(|f| function_to_region(this_type, f))
         .collect();
 
     Some(ItemDefinition {
@@ -223,11 +356,16 @@ fn build_type(
                 doc: None,
                 associated_functions: vec![],
                 vftable: None,
+                vbtable: None,
                 singleton: None,
                 cloneable: false,
                 copyable: false,
                 defaultable: false,
                 packed: false,
+                data_size: None,
+                base_vftables: vec![],
+                generic_instance: None,
+                non_virtual_part_of: None,
             }
             .into(),
         }),
@@ -236,20 +374,18 @@ fn build_type(
 }
 
 /// Given a function, create a region representing it
-fn function_to_region(resolvee_path: &ItemPath, function: &Function) -> Region {
+fn function_to_region(this_type: &Type, function: &Function) -> Region {
     let arguments = function
         .arguments
         .iter()
         .map(|a| match a {
             Argument::ConstSelf => (
                 "this".to_string(),
-                Box::new(Type::ConstPointer(Box::new(Type::Raw(
-                    resolvee_path.clone(),
-                )))),
+                Box::new(Type::ConstPointer(Box::new(this_type.clone()))),
             ),
             Argument::MutSelf => (
                 "this".to_string(),
-                Box::new(Type::MutPointer(Box::new(Type::Raw(resolvee_path.clone())))),
+                Box::new(Type::MutPointer(Box::new(this_type.clone()))),
             ),
             Argument::Field(name, type_ref) => (name.clone(), Box::new(type_ref.clone())),
         })
@@ -260,8 +396,9 @@ fn function_to_region(resolvee_path: &ItemPath, function: &Function) -> Region {
         visibility: function.visibility,
         name: Some(function.name.clone()),
         doc: function.doc.clone(),
-        type_ref: Type::Function(function.calling_convention, arguments, return_type),
+        type_ref: Type::Function(function.calling_convention, arguments, return_type, false),
         is_base: false,
+        bitfields: vec![],
     }
 }
 
@@ -270,7 +407,7 @@ fn get_optional_region_name_and_vftable<'a>(
     type_registry: &'a TypeRegistry,
     resolvee_path: &ItemPath,
     region: Option<&Region>,
-) -> anyhow::Result<Option<(String, &'a TypeVftable)>> {
+) -> Result<Option<(String, &'a TypeVftable)>> {
     Ok(region
         .map(|b| get_region_name_and_vftable(type_registry, resolvee_path, b))
         .transpose()?
@@ -282,7 +419,7 @@ fn get_region_name_and_vftable<'a>(
     type_registry: &'a TypeRegistry,
     resolvee_path: &ItemPath,
     region: &Region,
-) -> anyhow::Result<Option<(String, &'a TypeVftable)>> {
+) -> Result<Option<(String, &'a TypeVftable)>> {
     Ok(
         get_region_name_and_type_definition(type_registry, resolvee_path, region)?
             .and_then(|(name, td)| td.vftable.as_ref().map(|vftable| (name, vftable))),
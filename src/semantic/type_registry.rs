@@ -1,32 +1,87 @@
 use std::collections::HashMap;
 
 use crate::{
-    grammar::{self, ItemPath},
-    semantic::types::{ItemDefinition, Type},
+    grammar::{self, ItemPath, ItemPathSegment},
+    semantic::{
+        expression,
+        module::{self, Import, Scope},
+        target::Target,
+        type_definition,
+        types::{CallingConvention, ItemDefinition, ItemState, Type},
+    },
 };
 
+/// Types and constants are named separately, so a type and a constant can share a name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Namespace {
+    Types,
+    Constants,
+    Any,
+}
+
 #[derive(Debug)]
 pub struct TypeRegistry {
     types: HashMap<ItemPath, ItemDefinition>,
-    pointer_size: usize,
+    /// Items re-exported by a `pub use`, mapped from the path they're re-exported at to the
+    /// path they refer to
+    reexports: HashMap<ItemPath, ItemPath>,
+    /// Modules mapped to the modules they re-export everything from with a `pub use`
+    glob_reexports: HashMap<ItemPath, Vec<ItemPath>>,
+    /// Constants mapped to their values, or `None` if they have not been evaluated yet
+    constants: HashMap<ItemPath, Option<i128>>,
+    target: Target,
 }
 
 impl TypeRegistry {
-    pub(crate) fn new(pointer_size: usize) -> TypeRegistry {
+    pub(crate) fn new(target: Target) -> TypeRegistry {
         TypeRegistry {
             types: HashMap::new(),
-            pointer_size,
+            reexports: HashMap::new(),
+            glob_reexports: HashMap::new(),
+            constants: HashMap::new(),
+            target,
         }
     }
 
+    pub fn target(&self) -> &Target {
+        &self.target
+    }
+
     pub fn pointer_size(&self) -> usize {
-        self.pointer_size
+        self.target.pointer_size()
     }
 
     pub fn get(&self, item_path: &ItemPath) -> Option<&ItemDefinition> {
         self.types.get(item_path)
     }
 
+    /// Returns the definition that determines the layout of `path` instantiated with
+    /// `arguments`: the instantiation for generic type definitions, or the type itself
+    /// otherwise (e.g. for extern types).
+    pub fn get_generic(&self, path: &ItemPath, arguments: &[Type]) -> Option<&ItemDefinition> {
+        let item = self.get(path)?;
+        match &item.state {
+            ItemState::Generic(_) => self.get(&instance_path(path, arguments)),
+            _ => Some(item),
+        }
+    }
+
+    /// Returns the instantiations of the generic type definition at `template`, sorted by path
+    pub fn instances(&self, template: &ItemPath) -> Vec<&ItemDefinition> {
+        let mut instances: Vec<_> = self
+            .types
+            .values()
+            .filter(|t| {
+                t.resolved()
+                    .and_then(|r| r.inner.as_type())
+                    .and_then(|td| td.generic_instance.as_ref())
+                    .is_some_and(|i| &i.template == template)
+            })
+            .collect();
+        instances.sort_by_key(|t| &t.path);
+        instances
+    }
+
     pub fn get_mut(&mut self, item_path: &ItemPath) -> Option<&mut ItemDefinition> {
         self.types.get_mut(item_path)
     }
@@ -39,11 +94,24 @@ impl TypeRegistry {
             .collect()
     }
 
+    /// Returns the types and constants that have not been resolved yet
     pub(crate) fn unresolved(&self) -> Vec<ItemPath> {
         self.types
             .iter()
-            .filter(|(_, t)| !t.is_predefined() && !t.is_resolved())
+            .filter(|(_, t)| {
+                !t.is_predefined()
+                    && matches!(
+                        t.state,
+                        ItemState::Unresolved(_) | ItemState::Instance(_, _)
+                    )
+            })
             .map(|(k, _)| k.clone())
+            .chain(
+                self.constants
+                    .iter()
+                    .filter(|(_, v)| v.is_none())
+                    .map(|(k, _)| k.clone()),
+            )
             .collect()
     }
 
@@ -51,50 +119,379 @@ impl TypeRegistry {
         self.types.insert(type_.path.clone(), type_);
     }
 
-    pub(crate) fn resolve_string(&self, scope: &[ItemPath], name: &str) -> Option<Type> {
-        // todo: take scope_modules and scope_types instead of scope so that we don't need
-        // to do this partitioning
-        let (scope_types, scope_modules): (Vec<&ItemPath>, Vec<&ItemPath>) =
-            scope.iter().partition(|ip| self.types.contains_key(ip));
+    /// Declares the constant at `path`, which is given its value once it has been evaluated
+    pub(crate) fn add_constant(&mut self, path: ItemPath) {
+        self.constants.insert(path, None);
+    }
 
-        // If we find the relevant type within our scope, take the last one
-        scope_types
-            .into_iter()
+    pub(crate) fn is_constant(&self, path: &ItemPath) -> bool {
+        self.constants.contains_key(path)
+    }
+
+    /// Returns the value of the constant at `path`, or `None` if it has not been evaluated yet
+    pub fn constant(&self, path: &ItemPath) -> Option<i128> {
+        self.constants.get(path).copied().flatten()
+    }
+
+    pub(crate) fn set_constant(&mut self, path: &ItemPath, value: i128) {
+        self.constants.insert(path.clone(), Some(value));
+    }
+
+    /// Records the re-exports made by a `pub use` in `module`, so that the items can be
+    /// found through it.
+    pub(crate) fn add_reexport(&mut self, module: &ItemPath, import: &Import) {
+        match &import.name {
+            Some(name) => {
+                self.reexports
+                    .insert(module.join(name.as_str().into()), import.path.clone());
+            }
+            None => self
+                .glob_reexports
+                .entry(module.clone())
+                .or_default()
+                .push(import.path.clone()),
+        }
+    }
+
+    /// Returns the path of the item that `path` refers to, following any re-exports.
+    pub fn canonical_path(&self, path: &ItemPath) -> Option<ItemPath> {
+        self.canonical_path_in(Namespace::Any, path)
+    }
+
+    /// Returns the path of the item in `namespace` that `path` refers to, following any
+    /// re-exports.
+    fn canonical_path_in(&self, namespace: Namespace, path: &ItemPath) -> Option<ItemPath> {
+        // Re-exports can refer to each other, so limit how many we follow.
+        const MAX_REEXPORT_DEPTH: usize = 16;
+
+        fn canonical_path(
+            type_registry: &TypeRegistry,
+            namespace: Namespace,
+            path: &ItemPath,
+            depth: usize,
+        ) -> Option<ItemPath> {
+            let is_type = type_registry.types.contains_key(path);
+            let is_constant = type_registry.constants.contains_key(path);
+            let exists = match namespace {
+                Namespace::Types => is_type,
+                Namespace::Constants => is_constant,
+                Namespace::Any => is_type || is_constant,
+            };
+            if exists {
+                return Some(path.clone());
+            }
+            if depth == MAX_REEXPORT_DEPTH {
+                return None;
+            }
+            if let Some(target) = type_registry.reexports.get(path) {
+                return canonical_path(type_registry, namespace, target, depth + 1);
+            }
+            let name = path.last()?;
+            type_registry
+                .glob_reexports
+                .get(&path.parent()?)?
+                .iter()
+                .find_map(|m| {
+                    canonical_path(type_registry, namespace, &m.join(name.clone()), depth + 1)
+                })
+        }
+
+        canonical_path(self, namespace, path, 0)
+    }
+
+    /// Resolves a name to a type. C types whose layout depends on the target (e.g. `c_long`)
+    /// resolve to the predefined type with the same layout, unless another type shadows them.
+    pub(crate) fn resolve_string(&self, scope: &Scope, name: &str) -> Option<Type> {
+        self.find_in_scope(Namespace::Types, scope, &[name])
+            .or_else(|| self.target.c_type(name).map(ItemPath::from))
+            .map(Type::Raw)
+    }
+
+    /// Resolves a path to a type. Paths starting with `crate`, `self` or `super` are resolved
+    /// from the scope's module; other paths are resolved through the scope.
+    pub(crate) fn resolve_path(&self, scope: &Scope, path: &[grammar::Ident]) -> Option<Type> {
+        self.find_path(Namespace::Types, scope, path).map(Type::Raw)
+    }
+
+    /// Resolves a path to a constant, in the same way as [`Self::resolve_path`].
+    pub(crate) fn resolve_constant(
+        &self,
+        scope: &Scope,
+        path: &[grammar::Ident],
+    ) -> Option<ItemPath> {
+        self.find_path(Namespace::Constants, scope, path)
+    }
+
+    fn find_path(
+        &self,
+        namespace: Namespace,
+        scope: &Scope,
+        path: &[grammar::Ident],
+    ) -> Option<ItemPath> {
+        let segments: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+        if ItemPathSegment::from(*segments.first()?).is_relative() {
+            return module::resolve_relative_path(&scope.module, segments)
+                .and_then(|ip| self.canonical_path_in(namespace, &ip));
+        }
+        self.find_in_scope(namespace, scope, &segments)
+    }
+
+    /// Finds the item in `namespace` named by a path that starts with a name in scope. The
+    /// first segment is looked up in order from the module's named imports (the most recent
+    /// first), the module itself, its glob imports, and then the root.
+    ///
+    /// Returns `None` if more than one glob import provides the item, as in Rust; this is
+    /// reported by [`Self::ambiguous_imports`].
+    fn find_in_scope(
+        &self,
+        namespace: Namespace,
+        scope: &Scope,
+        segments: &[&str],
+    ) -> Option<ItemPath> {
+        if let Some(path) = self.find_in_named_scope(namespace, scope, segments) {
+            return Some(path);
+        }
+        match self
+            .find_in_glob_imports(namespace, scope, segments)
+            .as_slice()
+        {
+            [] => self.canonical_path_in(namespace, &join(&ItemPath::empty(), segments)),
+            [(_, path)] => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    /// Finds the item in `namespace` named by `segments` through the module's named imports
+    /// or the module itself, which take precedence over its glob imports.
+    fn find_in_named_scope(
+        &self,
+        namespace: Namespace,
+        scope: &Scope,
+        segments: &[&str],
+    ) -> Option<ItemPath> {
+        let (first, rest) = segments.split_first()?;
+        scope
+            .imports
+            .iter()
             .rev()
-            .find(|st| st.last().map(|i| i.as_str()) == Some(name))
-            .map(|ip| Type::Raw(ip.clone()))
-            .or_else(|| {
-                // Otherwise, search our scopes
-                std::iter::once(&ItemPath::empty())
-                    .chain(scope_modules.iter().copied())
-                    .map(|ip| ip.join(name.into()))
-                    .find(|ip| self.types.contains_key(ip))
-                    .map(Type::Raw)
-            })
+            .filter(|i| i.name.as_deref() == Some(*first))
+            .map(|i| join(&i.path, rest))
+            .chain(std::iter::once(join(&scope.module, segments)))
+            .find_map(|ip| self.canonical_path_in(namespace, &ip))
+    }
+
+    /// Returns each distinct item in `namespace` named by `segments` through the module's glob
+    /// imports, alongside the first import that provides it.
+    fn find_in_glob_imports<'a>(
+        &self,
+        namespace: Namespace,
+        scope: &'a Scope,
+        segments: &[&str],
+    ) -> Vec<(&'a Import, ItemPath)> {
+        let mut found: Vec<(&Import, ItemPath)> = vec![];
+        for import in scope.imports.iter().filter(|i| i.name.is_none()) {
+            let Some(path) = self.canonical_path_in(namespace, &join(&import.path, segments))
+            else {
+                continue;
+            };
+            if found.iter().all(|(_, p)| p != &path) {
+                found.push((import, path));
+            }
+        }
+        found
+    }
+
+    /// Returns the glob imports that each provide a different item in `namespace` named by
+    /// `segments`, if there's more than one and the name isn't otherwise in scope.
+    pub(crate) fn ambiguous_imports<'a>(
+        &self,
+        namespace: Namespace,
+        scope: &'a Scope,
+        segments: &[&str],
+    ) -> Option<Vec<(&'a Import, ItemPath)>> {
+        if self
+            .find_in_named_scope(namespace, scope, segments)
+            .is_some()
+        {
+            return None;
+        }
+        let found = self.find_in_glob_imports(namespace, scope, segments);
+        (found.len() > 1).then_some(found)
     }
 
     pub(crate) fn resolve_grammar_type(
         &self,
-        scope: &[ItemPath],
+        scope: &Scope,
+        type_: &grammar::Type,
+    ) -> Option<Type> {
+        self.resolve_grammar_type_with_parameters(scope, &HashMap::new(), type_)
+    }
+
+    /// Resolves `type_`, substituting any generic parameters named in `parameters`.
+    pub(crate) fn resolve_grammar_type_with_parameters(
+        &self,
+        scope: &Scope,
+        parameters: &HashMap<String, Type>,
         type_: &grammar::Type,
     ) -> Option<Type> {
         // todo: consider building a better module import/scope system
+        let resolve =
+            |t: &grammar::Type| self.resolve_grammar_type_with_parameters(scope, parameters, t);
         match type_ {
-            grammar::Type::ConstPointer(t) => self
-                .resolve_grammar_type(scope, t.as_ref())
-                .map(|t| Type::ConstPointer(Box::new(t))),
-            grammar::Type::MutPointer(t) => self
-                .resolve_grammar_type(scope, t.as_ref())
-                .map(|t| Type::MutPointer(Box::new(t))),
-            grammar::Type::Array(t, size) => self
-                .resolve_grammar_type(scope, t.as_ref())
-                .map(|t| Type::Array(Box::new(t), *size)),
-            grammar::Type::Ident(ident) => self.resolve_string(scope, ident.as_str()),
+            grammar::Type::ConstPointer(t) => resolve(t).map(|t| Type::ConstPointer(Box::new(t))),
+            grammar::Type::MutPointer(t) => resolve(t).map(|t| Type::MutPointer(Box::new(t))),
+            grammar::Type::Array(t, length) => {
+                let length =
+                    expression::evaluate_array_length(self, scope, parameters, length).ok()??;
+                resolve(t).map(|t| Type::Array(Box::new(t), length))
+            }
+            grammar::Type::Ident(ident) => parameters
+                .get(ident.as_str())
+                .cloned()
+                .or_else(|| self.resolve_string(scope, ident.as_str())),
+            grammar::Type::Path(path) => self.resolve_path(scope, path),
+            grammar::Type::Generic(path, arguments) => {
+                let Some(Type::Raw(path)) = self.resolve_path(scope, path) else {
+                    return None;
+                };
+                let arguments = arguments.iter().map(resolve).collect::<Option<Vec<_>>>()?;
+                if let Some(ItemState::Generic(definition)) = self.get(&path).map(|t| &t.state) {
+                    let grammar::ItemDefinitionInner::Type(td) = &definition.inner else {
+                        return None;
+                    };
+                    if td.generics.len() != arguments.len() {
+                        return None;
+                    }
+                    // Monomorphised instantiations are types in their own right.
+                    if type_definition::is_monomorphised(td)
+                        && !arguments.iter().any(Type::has_parameters)
+                    {
+                        return Some(Type::Raw(instance_path(&path, &arguments)));
+                    }
+                }
+                Some(Type::Generic(path, arguments))
+            }
+            grammar::Type::Function(function) => {
+                // As with free functions, assume the system calling convention if none is given.
+                let calling_convention = match &function.calling_convention {
+                    Some(calling_convention) => calling_convention
+                        .parse()
+                        .ok()
+                        .filter(|cc| self.target.supports(*cc))?,
+                    None => CallingConvention::System,
+                };
+                let arguments = function
+                    .arguments
+                    .iter()
+                    .map(|(name, type_)| {
+                        let name = name.as_ref().map_or("_", |n| n.as_str()).to_string();
+                        Some((name, Box::new(resolve(type_)?)))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let return_type = match &function.return_type {
+                    Some(return_type) => Some(Box::new(resolve(return_type)?)),
+                    None => None,
+                };
+                Some(Type::Function(
+                    calling_convention,
+                    arguments,
+                    return_type,
+                    function.nullable,
+                ))
+            }
             grammar::Type::Unknown(size) => Some(self.padding_type(*size)),
         }
     }
 
     pub(crate) fn padding_type(&self, bytes: usize) -> Type {
-        Type::Array(Box::new(self.resolve_string(&[], "u8").unwrap()), bytes)
+        Type::Array(
+            Box::new(self.resolve_string(&Scope::default(), "u8").unwrap()),
+            bytes,
+        )
+    }
+}
+
+/// Appends `segments` to `base`
+fn join(base: &ItemPath, segments: &[&str]) -> ItemPath {
+    let mut path = base.clone();
+    for segment in segments {
+        path.push((*segment).into());
+    }
+    path
+}
+
+/// Returns the path of the instantiation of the generic type definition at `template` with
+/// `arguments`, which lives alongside the definition (e.g. `test::Array<*mut u8>` is
+/// `test::Array_mut_u8`).
+///
+/// Types from the definition's module are named by their name alone, and types from other
+/// modules by their full path with `__` between segments (e.g. `test::Array<other::Item>` is
+/// `test::Array_other__Item`). Names that still collide are reported when the instantiations
+/// are registered.
+pub(crate) fn instance_path(template: &ItemPath, arguments: &[Type]) -> ItemPath {
+    fn mangle_path(out: &mut String, module: &ItemPath, path: &ItemPath) {
+        let skip = match path.parent() {
+            Some(parent) if &parent == module => module.len(),
+            _ => 0,
+        };
+        for (index, segment) in path.iter().skip(skip).enumerate() {
+            if index > 0 {
+                out.push_str("__");
+            }
+            out.push_str(segment.as_str());
+        }
+    }
+
+    fn mangle(out: &mut String, module: &ItemPath, type_: &Type) {
+        match type_ {
+            Type::Unresolved(_) => out.push_str("unresolved"),
+            Type::Raw(path) => mangle_path(out, module, path),
+            Type::Generic(path, arguments) => {
+                mangle_path(out, module, path);
+                for argument in arguments {
+                    out.push('_');
+                    mangle(out, module, argument);
+                }
+            }
+            Type::Parameter(name) => out.push_str(name),
+            Type::ConstPointer(t) => {
+                out.push_str("const_");
+                mangle(out, module, t);
+            }
+            Type::MutPointer(t) => {
+                out.push_str("mut_");
+                mangle(out, module, t);
+            }
+            Type::Array(t, size) => {
+                mangle(out, module, t);
+                out.push_str(&format!("_{size}"));
+            }
+            Type::Function(calling_convention, arguments, return_type, nullable) => {
+                if *nullable {
+                    out.push_str("opt_");
+                }
+                out.push_str("fn_");
+                out.push_str(&calling_convention.as_str().to_lowercase());
+                for (_, argument) in arguments {
+                    out.push('_');
+                    mangle(out, module, argument);
+                }
+                if let Some(return_type) = return_type {
+                    out.push_str("_ret_");
+                    mangle(out, module, return_type);
+                }
+            }
+        }
+    }
+
+    let module = template.parent().unwrap_or_else(ItemPath::empty);
+    let mut name = template
+        .last()
+        .map(|s| s.as_str().to_string())
+        .unwrap_or_default();
+    for argument in arguments {
+        name.push('_');
+        mangle(&mut name, &module, argument);
     }
+    module.join(name.into())
 }
@@ -6,9 +6,14 @@ use crate::{
 };
 
 pub use crate::semantic::{
+    alias_definition::AliasDefinition,
+    constant::Constant,
     enum_definition::EnumDefinition,
     function::{Argument, CallingConvention, Function, FunctionBody},
-    type_definition::{Region, TypeDefinition, TypeVftable},
+    type_definition::{
+        Bitfield, GenericInstance, Region, TypeDefinition, TypeVbtable, TypeVftable,
+    },
+    union_definition::UnionDefinition,
 };
 
 #[allow(dead_code, clippy::upper_case_acronyms)]
@@ -16,6 +21,8 @@ pub mod test_aliases {
     pub type SID = super::ItemDefinition;
     pub type STD = super::TypeDefinition;
     pub type SED = super::EnumDefinition;
+    pub type SUD = super::UnionDefinition;
+    pub type SAD = super::AliasDefinition;
     pub type ST = super::Type;
     pub type SAr = super::Argument;
     pub type SF = super::Function;
@@ -28,8 +35,14 @@ pub mod test_aliases {
     pub type SCC = super::CallingConvention;
     pub type SV = super::Visibility;
     pub type SEV = super::ExternValue;
+    pub type SA = super::Address;
+    pub type SSG = super::Signature;
     pub type STV = super::TypeVftable;
+    pub type STVB = super::TypeVbtable;
     pub type SFB = super::FunctionBody;
+    pub type SGI = super::GenericInstance;
+    pub type SC = super::Constant;
+    pub type SBF = super::Bitfield;
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -46,44 +59,109 @@ impl From<grammar::Visibility> for Visibility {
     }
 }
 
+/// The largest array the standard library implements `Default` for.
+const MAX_DEFAULTABLE_ARRAY_SIZE: usize = 32;
+
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 pub enum Type {
     Unresolved(grammar::Type),
     Raw(ItemPath),
+    /// An instantiation of a generic type. If the path is a generic type definition, its
+    /// layout is that of the instantiation; otherwise, it is that of the type at the path.
+    Generic(ItemPath, Vec<Type>),
+    /// A generic parameter of the type definition it appears in; it has no layout.
+    Parameter(String),
     ConstPointer(Box<Type>),
     MutPointer(Box<Type>),
     Array(Box<Type>, usize),
+    /// A function pointer, with its calling convention, arguments and return type. The last
+    /// field is whether the pointer may be null.
     Function(
         CallingConvention,
         Vec<(String, Box<Type>)>,
         Option<Box<Type>>,
+        bool,
     ),
 }
 impl Type {
     /// Returns `None` if this type is unresolved
     pub(crate) fn size(&self, type_registry: &type_registry::TypeRegistry) -> Option<usize> {
         match self {
-            Type::Unresolved(_) => None,
+            Type::Unresolved(_) | Type::Parameter(_) => None,
             Type::Raw(path) => type_registry.get(path).and_then(|t| t.size()),
+            Type::Generic(path, arguments) => type_registry
+                .get_generic(path, arguments)
+                .and_then(|t| t.size()),
             Type::ConstPointer(_) => Some(type_registry.pointer_size()),
             Type::MutPointer(_) => Some(type_registry.pointer_size()),
             Type::Array(tr, count) => tr.size(type_registry).map(|s| s * count),
-            Type::Function(_, _, _) => Some(type_registry.pointer_size()),
+            Type::Function(..) => Some(type_registry.pointer_size()),
         }
     }
     pub(crate) fn alignment(&self, type_registry: &type_registry::TypeRegistry) -> Option<usize> {
         match self {
-            Type::Unresolved(_) => None,
+            Type::Unresolved(_) | Type::Parameter(_) => None,
             Type::Raw(path) => type_registry.get(path).and_then(|t| t.alignment()),
+            Type::Generic(path, arguments) => type_registry
+                .get_generic(path, arguments)
+                .and_then(|t| t.alignment()),
             Type::ConstPointer(_) => Some(type_registry.pointer_size()),
             Type::MutPointer(_) => Some(type_registry.pointer_size()),
             Type::Array(tr, _) => Some(tr.alignment(type_registry)?),
-            Type::Function(_, _, _) => Some(type_registry.pointer_size()),
+            Type::Function(..) => Some(type_registry.pointer_size()),
         }
     }
+    /// Whether this type is `Copy` in Rust. Types that aren't resolved or don't opt in with
+    /// `copyable` are assumed not to be.
+    pub(crate) fn is_copyable(&self, type_registry: &type_registry::TypeRegistry) -> bool {
+        self.implements(type_registry, ItemDefinitionInner::copyable, true)
+    }
+    /// Whether this type is `Clone` in Rust
+    pub(crate) fn is_cloneable(&self, type_registry: &type_registry::TypeRegistry) -> bool {
+        self.implements(type_registry, ItemDefinitionInner::cloneable, true)
+    }
+    /// Whether this type is `Default` in Rust; of the pointers, only nullable function
+    /// pointers are, and the standard library only implements it for arrays of up to 32
+    /// elements.
+    pub(crate) fn is_defaultable(&self, type_registry: &type_registry::TypeRegistry) -> bool {
+        match self {
+            Type::Array(tr, size) => {
+                *size <= MAX_DEFAULTABLE_ARRAY_SIZE && tr.is_defaultable(type_registry)
+            }
+            _ => self.implements(type_registry, ItemDefinitionInner::defaultable, false),
+        }
+    }
+    /// Whether this type implements a trait in Rust, given whether items implement it and
+    /// whether pointers do
+    fn implements(
+        &self,
+        type_registry: &type_registry::TypeRegistry,
+        item_implements: fn(&ItemDefinitionInner) -> bool,
+        pointers_implement: bool,
+    ) -> bool {
+        let resolved = match self {
+            Type::Unresolved(_) | Type::Parameter(_) => return false,
+            Type::Raw(path) => type_registry.get(path),
+            Type::Generic(path, arguments) => type_registry.get_generic(path, arguments),
+            Type::ConstPointer(_) | Type::MutPointer(_) => return pointers_implement,
+            Type::Function(.., nullable) => return pointers_implement || *nullable,
+            Type::Array(tr, _) => {
+                return tr.implements(type_registry, item_implements, pointers_implement)
+            }
+        };
+        resolved
+            .and_then(|t| t.resolved())
+            .is_some_and(|t| item_implements(&t.inner))
+    }
     pub fn raw(path: impl Into<ItemPath>) -> Self {
         Type::Raw(path.into())
     }
+    pub fn generic(path: impl Into<ItemPath>, arguments: impl Into<Vec<Type>>) -> Self {
+        Type::Generic(path.into(), arguments.into())
+    }
+    pub fn parameter(name: impl Into<String>) -> Self {
+        Type::Parameter(name.into())
+    }
     pub fn const_pointer(self) -> Self {
         Type::ConstPointer(Box::new(self))
     }
@@ -105,8 +183,31 @@ impl Type {
                 .map(|(name, tr)| (name.to_string(), Box::new(tr)))
                 .collect(),
             return_type.into().map(Box::new),
+            false,
         )
     }
+    /// Makes a function pointer type nullable
+    pub fn nullable(self) -> Self {
+        match self {
+            Type::Function(calling_convention, args, return_type, _) => {
+                Type::Function(calling_convention, args, return_type, true)
+            }
+            type_ => type_,
+        }
+    }
+    /// Whether this type refers to any generic parameters
+    pub fn has_parameters(&self) -> bool {
+        match self {
+            Type::Parameter(_) => true,
+            Type::Unresolved(_) | Type::Raw(_) => false,
+            Type::Generic(_, arguments) => arguments.iter().any(Type::has_parameters),
+            Type::ConstPointer(t) | Type::MutPointer(t) | Type::Array(t, _) => t.has_parameters(),
+            Type::Function(_, arguments, return_type, _) => {
+                arguments.iter().any(|(_, t)| t.has_parameters())
+                    || return_type.as_ref().is_some_and(|t| t.has_parameters())
+            }
+        }
+    }
     pub fn is_array(&self) -> bool {
         matches!(self, Type::Array(_, _))
     }
@@ -117,10 +218,12 @@ impl Type {
         match self {
             Type::Unresolved(_) => "an unresolved type",
             Type::Raw(_) => "a type",
+            Type::Generic(_, _) => "a generic type",
+            Type::Parameter(_) => "a generic parameter",
             Type::ConstPointer(_) => "a const pointer",
             Type::MutPointer(_) => "a mut pointer",
             Type::Array(_, _) => "an array",
-            Type::Function(_, _, _) => "a function",
+            Type::Function(..) => "a function",
         }
     }
 }
@@ -129,6 +232,17 @@ impl fmt::Display for Type {
         match self {
             Type::Unresolved(tr) => write!(f, "unresolved:{:?}", tr),
             Type::Raw(path) => write!(f, "{}", path),
+            Type::Generic(path, arguments) => {
+                write!(f, "{path}<")?;
+                for (index, argument) in arguments.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    argument.fmt(f)?;
+                }
+                write!(f, ">")
+            }
+            Type::Parameter(name) => write!(f, "{name}"),
             Type::ConstPointer(tr) => {
                 write!(f, "*const ")?;
                 tr.fmt(f)
@@ -142,30 +256,40 @@ impl fmt::Display for Type {
                 tr.fmt(f)?;
                 write!(f, "; {}]", size)
             }
-            Type::Function(calling_convention, args, return_type) => {
+            Type::Function(calling_convention, args, return_type, nullable) => {
+                if *nullable {
+                    write!(f, "Option<")?;
+                }
                 write!(f, "extern \"{calling_convention}\" fn (")?;
                 for (index, (field, type_ref)) in args.iter().enumerate() {
-                    write!(f, "{field}: ")?;
-                    type_ref.fmt(f)?;
                     if index > 0 {
                         write!(f, ", ")?;
                     }
+                    write!(f, "{field}: ")?;
+                    type_ref.fmt(f)?;
                 }
                 write!(f, ")")?;
                 if let Some(type_ref) = return_type {
                     write!(f, " -> ")?;
                     type_ref.fmt(f)?;
                 }
+                if *nullable {
+                    write!(f, ">")?;
+                }
                 Ok(())
             }
         }
     }
 }
 
+// Most items are types, so they're kept inline rather than boxed.
+#[allow(clippy::large_enum_variant)]
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 pub enum ItemDefinitionInner {
     Type(TypeDefinition),
     Enum(EnumDefinition),
+    Union(UnionDefinition),
+    Alias(AliasDefinition),
 }
 impl From<TypeDefinition> for ItemDefinitionInner {
     fn from(td: TypeDefinition) -> Self {
@@ -177,11 +301,24 @@ impl From<EnumDefinition> for ItemDefinitionInner {
         ItemDefinitionInner::Enum(ed)
     }
 }
+impl From<UnionDefinition> for ItemDefinitionInner {
+    fn from(ud: UnionDefinition) -> Self {
+        ItemDefinitionInner::Union(ud)
+    }
+}
+impl From<AliasDefinition> for ItemDefinitionInner {
+    fn from(ad: AliasDefinition) -> Self {
+        ItemDefinitionInner::Alias(ad)
+    }
+}
 impl ItemDefinitionInner {
     pub fn defaultable(&self) -> bool {
         match self {
             ItemDefinitionInner::Type(td) => td.defaultable,
             ItemDefinitionInner::Enum(ed) => ed.defaultable && ed.default_index.is_some(),
+            // Rust can't derive `Default` for a union.
+            ItemDefinitionInner::Union(_) => false,
+            ItemDefinitionInner::Alias(ad) => ad.defaultable,
         }
     }
     pub fn as_type(&self) -> Option<&TypeDefinition> {
@@ -196,16 +333,50 @@ impl ItemDefinitionInner {
             _ => None,
         }
     }
+    pub fn as_union(&self) -> Option<&UnionDefinition> {
+        match self {
+            Self::Union(v) => Some(v),
+            _ => None,
+        }
+    }
+    pub fn as_alias(&self) -> Option<&AliasDefinition> {
+        match self {
+            Self::Alias(v) => Some(v),
+            _ => None,
+        }
+    }
+    /// Whether the item is generated as a Rust type that is `Copy`
+    pub fn copyable(&self) -> bool {
+        match self {
+            ItemDefinitionInner::Type(td) => td.copyable,
+            ItemDefinitionInner::Enum(ed) => ed.copyable,
+            ItemDefinitionInner::Union(ud) => ud.copyable,
+            ItemDefinitionInner::Alias(ad) => ad.copyable,
+        }
+    }
+    /// Whether the item is generated as a Rust type that is `Clone`
+    pub fn cloneable(&self) -> bool {
+        match self {
+            ItemDefinitionInner::Type(td) => td.cloneable,
+            ItemDefinitionInner::Enum(ed) => ed.cloneable,
+            ItemDefinitionInner::Union(ud) => ud.cloneable,
+            ItemDefinitionInner::Alias(ad) => ad.cloneable,
+        }
+    }
     pub fn human_friendly_type(&self) -> &'static str {
         match self {
             ItemDefinitionInner::Type(_) => "a type",
             ItemDefinitionInner::Enum(_) => "an enum",
+            ItemDefinitionInner::Union(_) => "a union",
+            ItemDefinitionInner::Alias(_) => "a type alias",
         }
     }
     pub fn doc(&self) -> Option<&str> {
         match self {
             ItemDefinitionInner::Type(t) => t.doc(),
             ItemDefinitionInner::Enum(e) => e.doc(),
+            ItemDefinitionInner::Union(u) => u.doc(),
+            ItemDefinitionInner::Alias(a) => a.doc(),
         }
     }
 }
@@ -231,10 +402,18 @@ impl ItemStateResolved {
     }
 }
 
+// Resolved items are the common case, so they're kept inline rather than boxed.
+#[allow(clippy::large_enum_variant)]
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 pub enum ItemState {
     Unresolved(grammar::ItemDefinition),
     Resolved(ItemStateResolved),
+    /// A generic type definition. It is never resolved itself; instead, each of its
+    /// instantiations is resolved separately.
+    Generic(grammar::ItemDefinition),
+    /// An instantiation of the generic type definition at the path with the given arguments
+    /// that has not been resolved yet.
+    Instance(ItemPath, Vec<Type>),
 }
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
@@ -312,5 +491,128 @@ pub struct ExternValue {
     pub visibility: Visibility,
     pub name: String,
     pub type_: Type,
-    pub address: usize,
+    pub address: Address,
+}
+
+/// The address of a function, extern value or singleton
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub enum Address {
+    Fixed(usize),
+    /// An address for each version of the executable, in the order the versions were
+    /// declared in. The address is looked up when the version is selected at runtime.
+    Versioned(Vec<(String, usize)>),
+    /// A pattern to scan memory for at runtime
+    Signature(Signature),
+    /// An offset from wherever the image (e.g. `game.exe`) was loaded, which is looked up at
+    /// runtime. The offset is either fixed or versioned.
+    Relative {
+        image: String,
+        rva: Box<Address>,
+    },
+    /// A chain of pointers to follow from the starting address, adding each offset to the
+    /// pointer read before it. Only singletons and extern values can be found this way, as any
+    /// of the pointers can be null.
+    ///
+    /// Each offset reads one pointer, so a chain without offsets is just its starting address:
+    /// unlike `singleton(X)`, `singleton(chain(X))` is the item at `X`, not the item that the
+    /// pointer at `X` points to.
+    Chain {
+        start: Box<Address>,
+        offsets: Vec<isize>,
+    },
+}
+impl Address {
+    pub fn chain(start: impl Into<Address>, offsets: impl IntoIterator<Item = isize>) -> Self {
+        Address::Chain {
+            start: Box::new(start.into()),
+            offsets: offsets.into_iter().collect(),
+        }
+    }
+    pub fn relative(image: impl Into<String>, rva: impl Into<Address>) -> Self {
+        Address::Relative {
+            image: image.into(),
+            rva: Box::new(rva.into()),
+        }
+    }
+}
+impl From<usize> for Address {
+    fn from(address: usize) -> Self {
+        Address::Fixed(address)
+    }
+}
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Fixed(address) => write!(f, "0x{address:X}"),
+            Address::Versioned(addresses) => {
+                write!(f, "(")?;
+                for (i, (version, address)) in addresses.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{version} = 0x{address:X}")?;
+                }
+                write!(f, ")")
+            }
+            Address::Signature(signature) => write!(f, "{signature}"),
+            Address::Relative { image, rva } => write!(f, "{image:?} + {rva}"),
+            Address::Chain { start, offsets } => {
+                write!(f, "chain({start}")?;
+                for offset in offsets {
+                    let sign = if *offset < 0 { "-" } else { "" };
+                    write!(f, ", {sign}0x{:X}", offset.unsigned_abs())?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// A byte pattern that locates an item in memory, and how to get the item's address from
+/// where it matches
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Signature {
+    /// The bytes to match, where `None` matches any byte
+    pub pattern: Vec<Option<u8>>,
+    /// The offset from the start of the match to the item, or to its displacement
+    pub offset: usize,
+    /// Whether the item is located by a 32-bit displacement at the offset, relative to the
+    /// end of the displacement
+    pub rip_relative: bool,
+}
+impl Signature {
+    pub fn new(pattern: impl IntoIterator<Item = Option<u8>>) -> Self {
+        Signature {
+            pattern: pattern.into_iter().collect(),
+            offset: 0,
+            rip_relative: false,
+        }
+    }
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+    pub fn with_rip_relative(mut self, rip_relative: bool) -> Self {
+        self.rip_relative = rip_relative;
+        self
+    }
+}
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "signature(\"")?;
+        for (i, byte) in self.pattern.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            match byte {
+                Some(byte) => write!(f, "{byte:02X}")?,
+                None => write!(f, "??")?,
+            }
+        }
+        write!(f, "\", offset = {}", self.offset)?;
+        if self.rip_relative {
+            write!(f, ", rip_relative")?;
+        }
+        write!(f, ")")
+    }
 }
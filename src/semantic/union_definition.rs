@@ -0,0 +1,275 @@
+use crate::{
+    error::{Diagnostic, Diagnostics, ErrorKind, Result},
+    grammar::{self, ItemPath},
+    semantic::{
+        expression,
+        types::{ItemStateResolved, Region, Visibility},
+        SemanticState,
+    },
+    span::Span,
+};
+
+#[derive(PartialEq, Eq, Debug, Clone, Default, Hash)]
+pub struct UnionDefinition {
+    /// The fields of the union, all of which start at its beginning
+    pub fields: Vec<Region>,
+    pub doc: Option<String>,
+    pub copyable: bool,
+    pub cloneable: bool,
+}
+impl UnionDefinition {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    pub fn with_fields(mut self, fields: impl Into<Vec<Region>>) -> Self {
+        self.fields = fields.into();
+        self
+    }
+    pub fn with_doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+    pub fn with_copyable(mut self, copyable: bool) -> Self {
+        self.copyable = copyable;
+        self
+    }
+    pub fn with_cloneable(mut self, cloneable: bool) -> Self {
+        self.cloneable = cloneable;
+        self
+    }
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+}
+
+pub fn build(
+    semantic: &SemanticState,
+    resolvee_path: &ItemPath,
+    span: Span,
+    definition: &grammar::UnionDefinition,
+) -> Result<Option<ItemStateResolved>> {
+    let module = semantic.get_module_for_path(resolvee_path).ok_or_else(|| {
+        Diagnostic::error(
+            ErrorKind::Internal,
+            format!("failed to get module for path `{resolvee_path}`"),
+        )
+    })?;
+    let scope = module.scope();
+    let mut diagnostics = Diagnostics::new();
+
+    // Handle attributes
+    let mut target_size: Option<usize> = None;
+    let mut align: Option<usize> = None;
+    let mut copyable = false;
+    let mut cloneable = false;
+    let mut size_span = Span::none();
+    let mut align_span = Span::none();
+    let doc = diagnostics
+        .record(definition.attributes.doc(resolvee_path))
+        .flatten();
+    for attribute in &definition.attributes {
+        match attribute {
+            grammar::Attribute::Function(ident, exprs) => {
+                let [expr] = exprs.as_slice() else {
+                    continue;
+                };
+                let target = match ident.as_str() {
+                    "size" => {
+                        size_span = ident.span();
+                        &mut target_size
+                    }
+                    "align" => {
+                        align_span = ident.span();
+                        &mut align
+                    }
+                    _ => continue,
+                };
+                let value = expression::evaluate_attribute(
+                    &semantic.type_registry,
+                    &scope,
+                    ident,
+                    expr,
+                    &format!("union `{resolvee_path}`"),
+                )
+                .map_err(|d| d.with_location(module.location(ident.span())));
+                match diagnostics.record(value) {
+                    Some(Some(value)) => *target = Some(value),
+                    // This depends on a type that hasn't been resolved yet, so try again later.
+                    Some(None) => return diagnostics.finish(None),
+                    None => {}
+                }
+            }
+            grammar::Attribute::Ident(ident) => match ident.as_str() {
+                "copyable" => {
+                    copyable = true;
+                    cloneable = true;
+                }
+                "cloneable" => cloneable = true,
+                "defaultable" | "packed" => diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::InvalidAttribute,
+                        format!("union `{resolvee_path}` cannot be `{ident}`"),
+                    )
+                    .with_location(module.location(ident.span())),
+                ),
+                _ => {}
+            },
+            grammar::Attribute::Assign(_, _)
+            | grammar::Attribute::Cfg(_, _)
+            | grammar::Attribute::Keyed(_, _)
+            | grammar::Attribute::Signature(_, _)
+            | grammar::Attribute::Image(_, _)
+            | grammar::Attribute::Chain(_, _) => {}
+        }
+    }
+    // Rust can only clone a union by copying it.
+    if cloneable && !copyable {
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::InvalidAttribute,
+                format!("union `{resolvee_path}` cannot be `cloneable` without being `copyable`"),
+            )
+            .with_location(module.location(span)),
+        );
+    }
+
+    // Handle fields
+    let mut fields = vec![];
+    for statement in &definition.statements {
+        let grammar::TypeStatement {
+            field, attributes, ..
+        } = statement;
+        // The parser only allows fields in unions.
+        let grammar::TypeField::Field(visibility, ident, type_) = field else {
+            continue;
+        };
+
+        for attribute in attributes {
+            let name = match attribute {
+                grammar::Attribute::Ident(name) => name,
+                grammar::Attribute::Function(name, _) => name,
+                grammar::Attribute::Keyed(name, _) => name,
+                grammar::Attribute::Signature(name, _) => name,
+                grammar::Attribute::Image(name, _) => name,
+                grammar::Attribute::Chain(name, _) => name,
+                grammar::Attribute::Assign(_, _) | grammar::Attribute::Cfg(_, _) => continue,
+            };
+            if ["address", "bits", "base"].contains(&name.as_str()) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorKind::InvalidAttribute,
+                        format!(
+                            "field `{ident}` of union `{resolvee_path}` cannot have the `{name}` attribute, as every field of a union starts at its beginning"
+                        ),
+                    )
+                    .with_location(module.location(name.span())),
+                );
+            }
+        }
+
+        let Some(type_) = semantic.type_registry.resolve_grammar_type(&scope, type_) else {
+            // Report any errors we've already found; they won't go away by waiting.
+            let checked =
+                expression::check_type(&semantic.type_registry, &scope, &Default::default(), type_)
+                    .map_err(|d| d.with_location(module.location(type_.span())));
+            diagnostics.record(checked);
+            return diagnostics.finish(None);
+        };
+        let doc = diagnostics.record(attributes.doc(resolvee_path)).flatten();
+        fields.push(Region {
+            visibility: (*visibility).into(),
+            name: (ident.0 != "_").then(|| ident.0.clone()),
+            doc,
+            type_ref: type_,
+            is_base: false,
+            bitfields: vec![],
+        });
+    }
+
+    // The layout can't be trusted if any of the attributes or fields were invalid.
+    if !diagnostics.is_empty() {
+        return Err(diagnostics.into());
+    }
+
+    // The union is as large as its largest field, and as aligned as its most aligned field.
+    let mut size = 0;
+    let mut required_alignment = 1;
+    for region in &fields {
+        let (Some(field_size), Some(field_alignment)) = (
+            region.size(&semantic.type_registry),
+            region.type_ref.alignment(&semantic.type_registry),
+        ) else {
+            return Ok(None);
+        };
+        size = size.max(field_size);
+        required_alignment = required_alignment.max(field_alignment);
+    }
+    let alignment = align.unwrap_or(required_alignment);
+    if required_alignment > alignment {
+        diagnostics.push(
+            Diagnostic::error(
+                ErrorKind::Alignment,
+                format!(
+                    "alignment {alignment} is less than minimum required alignment {required_alignment} for union `{resolvee_path}`"
+                ),
+            )
+            .with_location(module.location(align_span.or(span))),
+        );
+    }
+    // As in C, the size is rounded up to the alignment, so that the union can be put in an array.
+    size = size.next_multiple_of(alignment);
+
+    if let Some(target_size) = target_size {
+        if target_size < size {
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::SizeMismatch,
+                    format!(
+                        "calculated size {size} for union `{resolvee_path}` is larger than target size {target_size}; is your target size correct?"
+                    ),
+                )
+                .with_location(module.location(size_span)),
+            );
+        } else if target_size % alignment != 0 {
+            diagnostics.push(
+                Diagnostic::error(
+                    ErrorKind::Alignment,
+                    format!(
+                        "the union `{resolvee_path}` has a size of {target_size}, which is not a multiple of its alignment {alignment}"
+                    ),
+                )
+                .with_location(module.location(size_span)),
+            );
+        } else if target_size > size {
+            fields.push(Region::unnamed_field(
+                semantic.type_registry.padding_type(target_size),
+            ));
+            size = target_size;
+        }
+    }
+
+    // Give every unnamed field a name
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut region)| {
+            if region.name.is_none() {
+                region.visibility = Visibility::Private;
+                region.name = Some(format!("_field_{index}"));
+            }
+            region
+        })
+        .collect();
+
+    diagnostics.finish(Some(ItemStateResolved {
+        size,
+        alignment,
+        inner: UnionDefinition {
+            fields,
+            doc,
+            copyable,
+            cloneable,
+        }
+        .into(),
+    }))
+}
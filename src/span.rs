@@ -0,0 +1,122 @@
+use std::{fmt, hash, path::PathBuf};
+
+/// A line/column position within a source file. Lines are 1-indexed and columns are 0-indexed,
+/// matching [`proc_macro2::LineColumn`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+impl From<proc_macro2::LineColumn> for LineColumn {
+    fn from(lc: proc_macro2::LineColumn) -> Self {
+        LineColumn {
+            line: lc.line,
+            column: lc.column,
+        }
+    }
+}
+
+/// The location of a grammar node within its source file.
+///
+/// Spans are deliberately ignored for equality and hashing so that ASTs constructed
+/// by hand (e.g. in tests) compare equal to ASTs produced by the parser.
+#[derive(Copy, Clone, Default)]
+pub struct Span {
+    pub start: LineColumn,
+    pub end: LineColumn,
+}
+impl Span {
+    pub fn new(start: LineColumn, end: LineColumn) -> Self {
+        Span { start, end }
+    }
+
+    /// A span that does not point anywhere.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.start.line == 0
+    }
+
+    /// Returns the first of `self` and `other` that is not [`Span::none`].
+    pub fn or(self, other: Span) -> Span {
+        if self.is_none() {
+            other
+        } else {
+            self
+        }
+    }
+}
+impl From<proc_macro2::Span> for Span {
+    fn from(span: proc_macro2::Span) -> Self {
+        Span::new(span.start().into(), span.end().into())
+    }
+}
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_none() {
+            write!(f, "Span(none)")
+        } else {
+            write!(
+                f,
+                "Span({}:{}..{}:{})",
+                self.start.line, self.start.column, self.end.line, self.end.column
+            )
+        }
+    }
+}
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+impl Eq for Span {}
+impl hash::Hash for Span {
+    fn hash<H: hash::Hasher>(&self, _state: &mut H) {}
+}
+
+/// A source file that grammar nodes can point into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+impl SourceFile {
+    pub fn new(path: impl Into<PathBuf>, contents: impl Into<String>) -> Self {
+        SourceFile {
+            path: path.into(),
+            contents: contents.into(),
+        }
+    }
+
+    /// Renders a snippet of the source pointing at `span`, or `None` if the span does not
+    /// point anywhere within this file.
+    ///
+    /// ```text
+    ///   --> types/test.pyxis:3:4
+    ///    | pub field: Foo,
+    ///    |     ^^^^^
+    /// ```
+    pub fn render(&self, span: Span) -> Option<String> {
+        if span.is_none() {
+            return None;
+        }
+        let line = self.contents.lines().nth(span.start.line - 1)?;
+        let width = if span.end.line == span.start.line {
+            span.end.column.saturating_sub(span.start.column).max(1)
+        } else {
+            line.len().saturating_sub(span.start.column).max(1)
+        };
+
+        Some(format!(
+            "  --> {}:{}:{}\n   | {}\n   | {}{}",
+            self.path.display(),
+            span.start.line,
+            span.start.column + 1,
+            line,
+            " ".repeat(span.start.column),
+            "^".repeat(width)
+        ))
+    }
+}
@@ -10,3 +10,17 @@ pub fn gcd(mut a: usize, mut b: usize) -> usize {
     }
     a
 }
+
+/// Converts a `snake_case` name to `PascalCase` (e.g. `world_pos` to `WorldPos`)
+pub fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}